@@ -1,17 +1,20 @@
-use std::{fs::read, path::PathBuf};
+use std::{
+    fs::{read, File},
+    path::{Path, PathBuf},
+};
 
 use clap::{command, Parser};
 use eyre::Result;
 use openvm_build::{build_guest_package, get_package, guest_methods, GuestOptions};
 use openvm_circuit::arch::{
-    instructions::exe::VmExe, DefaultSegmentationStrategy, VirtualMachine, VmConfig,
+    instructions::exe::VmExe, AirCostShape, DefaultSegmentationStrategy, VirtualMachine, VmConfig,
 };
 use openvm_native_circuit::NativeConfig;
 use openvm_native_compiler::conversion::CompilerOptions;
 use openvm_sdk::{
     commit::commit_app_exe,
     config::{
-        AggConfig, AggStarkConfig, AppConfig, Halo2Config, DEFAULT_APP_LOG_BLOWUP,
+        AggConfig, AggStarkConfig, AppConfig, Halo2Config, WrapperBackend, DEFAULT_APP_LOG_BLOWUP,
         DEFAULT_INTERNAL_LOG_BLOWUP, DEFAULT_LEAF_LOG_BLOWUP, DEFAULT_ROOT_LOG_BLOWUP,
     },
     keygen::{leaf_keygen, AppProvingKey},
@@ -72,6 +75,11 @@ pub struct BenchmarkCli {
     /// Whether to execute with additional profiling metric collection
     #[arg(long)]
     pub profiling: bool,
+
+    /// Path to write the `AirCostShape`s of the app VM to, as JSON. Together with the metrics
+    /// collected with `--profiling`, this is the input of `openvm-prof calibrate`.
+    #[arg(long)]
+    pub air_shapes_output: Option<PathBuf>,
 }
 
 impl BenchmarkCli {
@@ -130,6 +138,7 @@ impl BenchmarkCli {
                 verifier_k: self.halo2_outer_k.unwrap_or(23),
                 wrapper_k: self.halo2_wrapper_k,
                 profiling: self.profiling,
+                wrapper_backend: WrapperBackend::Halo2,
             },
         }
     }
@@ -153,6 +162,7 @@ impl BenchmarkCli {
     ) -> Result<()>
     where
         VC: VmConfig<F>,
+        VC: Sync,
         VC::Executor: Chip<SC>,
         VC::Periphery: Chip<SC>,
     {
@@ -162,6 +172,7 @@ impl BenchmarkCli {
             app_config,
             exe,
             input_stream,
+            self.air_shapes_output.as_deref(),
             #[cfg(not(feature = "aggregation"))]
             None,
             #[cfg(feature = "aggregation")]
@@ -195,7 +206,7 @@ pub fn build_bench_program(program_name: &str, profile: impl ToString) -> Result
     Elf::decode(&data, MEM_SIZE as u32)
 }
 
-/// 1. Generate proving key from config.
+/// 1. Generate proving key from config, and write the AIR shapes to `air_shapes_output` if set.
 /// 2. Commit to the exe by generating cached trace for program.
 /// 3. Executes runtime
 /// 4. Generate trace
@@ -208,10 +219,12 @@ pub fn bench_from_exe<VC>(
     app_config: AppConfig<VC>,
     exe: impl Into<VmExe<F>>,
     input_stream: StdIn,
+    air_shapes_output: Option<&Path>,
     leaf_vm_config: Option<NativeConfig>,
 ) -> Result<()>
 where
     VC: VmConfig<F>,
+    VC: Sync,
     VC::Executor: Chip<SC>,
     VC::Periphery: Chip<SC>,
 {
@@ -224,6 +237,10 @@ where
             AppProvingKey::keygen(app_config.clone())
         })
     });
+    if let Some(path) = air_shapes_output {
+        let shapes = AirCostShape::from_pk(&app_pk.app_vm_pk.vm_pk);
+        serde_json::to_writer_pretty(File::create(path)?, &shapes)?;
+    }
     // 2. Commit to the exe by generating cached trace for program.
     let committed_exe = info_span!("commit_exe", group = &bench_name).in_scope(|| {
         metrics_span("commit_exe_time_ms", || {
@@ -554,6 +554,32 @@ impl FieldExpr {
             .collect()
     }
 
+    /// Names of the columns in the layout of [Self::load_vars], e.g. `vars[2][5]` for the limb 5
+    /// of variable 2.
+    pub fn column_names(&self) -> Vec<String> {
+        assert!(self.builder.is_finalized());
+        let limbs = |name: &str, i: usize, num_limbs: usize| {
+            (0..num_limbs)
+                .map(|j| format!("{name}[{i}][{j}]"))
+                .collect::<Vec<_>>()
+        };
+        let mut names = vec!["is_valid".to_string()];
+        for i in 0..self.num_input {
+            names.extend(limbs("inputs", i, self.num_limbs));
+        }
+        for i in 0..self.num_variables {
+            names.extend(limbs("vars", i, self.num_limbs));
+        }
+        for (i, &q) in self.q_limbs.iter().enumerate() {
+            names.extend(limbs("q_limbs", i, q));
+        }
+        for (i, &c) in self.carry_limbs.iter().enumerate() {
+            names.extend(limbs("carry_limbs", i, c));
+        }
+        names.extend((0..self.num_flags).map(|i| format!("flags[{i}]")));
+        names
+    }
+
     pub fn load_vars<T: Clone>(&self, arr: &[T]) -> FieldExprCols<T> {
         assert!(self.builder.is_finalized());
         let is_valid = arr[0].clone();
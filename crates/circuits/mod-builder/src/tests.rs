@@ -61,6 +61,9 @@ fn test_div() {
     let builder = builder.borrow().clone();
     let expr = FieldExpr::new(builder, range_checker.bus(), false);
     let width = BaseAir::<BabyBear>::width(&expr);
+    let column_names = expr.column_names();
+    assert_eq!(column_names.len(), width);
+    assert_eq!(column_names[1 + 2 * expr.num_limbs], "vars[0][0]");
 
     let x = generate_random_biguint(&prime);
     let y = generate_random_biguint(&prime);
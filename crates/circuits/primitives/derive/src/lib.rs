@@ -5,7 +5,7 @@ extern crate proc_macro;
 use itertools::multiunzip;
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Fields, GenericParam, LitStr, Meta};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, GenericParam, LitStr, Member, Meta};
 
 #[proc_macro_derive(AlignedBorrow)]
 pub fn aligned_borrow_derive(input: TokenStream) -> TokenStream {
@@ -41,6 +41,22 @@ pub fn aligned_borrow_derive(input: TokenStream) -> TokenStream {
     // Get impl generics (`<T, N: NumLimbs, const M: usize>`), type generics (`<T, N>`), where clause (`where T: Clone`)
     let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
 
+    // Field names and members in declaration order, which is the layout order of the `repr(C)`
+    // struct, to name columns.
+    let fields = match &ast.data {
+        Data::Struct(data) => &data.fields,
+        _ => panic!("AlignedBorrow can only be derived for structs"),
+    };
+    let field_members: Vec<_> = fields.members().collect();
+    let field_names: Vec<_> = field_members
+        .iter()
+        .map(|member| match member {
+            Member::Named(ident) => ident.to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        })
+        .collect();
+    let u8_type = quote! { #name<u8 #(, #non_first_generics)*> };
+
     let methods = quote! {
         impl #impl_generics core::borrow::Borrow<#name #type_generics> for [#type_generic] #where_clause {
             fn borrow(&self) -> &#name #type_generics {
@@ -64,7 +80,26 @@ pub fn aligned_borrow_derive(input: TokenStream) -> TokenStream {
 
         impl #impl_generics #name #type_generics {
             pub const fn width() -> usize {
-                std::mem::size_of::<#name<u8 #(, #non_first_generics)*>>()
+                std::mem::size_of::<#u8_type>()
+            }
+
+            /// Names of the columns in layout order. A field spanning several columns, e.g. an
+            /// array or a nested struct, is named with the index of the column within the field,
+            /// e.g. `a[2]`.
+            pub fn column_names() -> ::std::vec::Vec<::std::string::String> {
+                let fields: &[(&str, usize)] = &[
+                    #((#field_names, core::mem::offset_of!(#u8_type, #field_members))),*
+                ];
+                let mut names = ::std::vec::Vec::with_capacity(Self::width());
+                for (i, &(field, start)) in fields.iter().enumerate() {
+                    let end = fields.get(i + 1).map_or(Self::width(), |&(_, offset)| offset);
+                    if end - start == 1 {
+                        names.push(::std::string::ToString::to_string(field));
+                    } else {
+                        names.extend((0..end - start).map(|j| ::std::format!("{}[{}]", field, j)));
+                    }
+                }
+                names
             }
         }
     };
@@ -1,5 +1,8 @@
 use cargo_openvm::{
-    commands::{BenchCmd, BuildCmd, EvmProvingSetupCmd, KeygenCmd, ProveCmd, RunCmd, VerifyCmd},
+    commands::{
+        BenchCmd, BuildCmd, CommitCmd, DisasmCmd, EvmProvingSetupCmd, KeygenCmd, ProveCmd, RunCmd,
+        VerifyCmd,
+    },
     OPENVM_VERSION_MESSAGE,
 };
 use clap::{Parser, Subcommand};
@@ -25,6 +28,8 @@ pub struct VmCli {
 pub enum VmCliCommands {
     Bench(BenchCmd),
     Build(BuildCmd),
+    Commit(CommitCmd),
+    Disasm(DisasmCmd),
     Keygen(KeygenCmd),
     Prove(ProveCmd),
     Run(RunCmd),
@@ -40,6 +45,8 @@ async fn main() -> Result<()> {
     match command {
         VmCliCommands::Bench(cmd) => cmd.run(),
         VmCliCommands::Build(cmd) => cmd.run(),
+        VmCliCommands::Commit(cmd) => cmd.run(),
+        VmCliCommands::Disasm(cmd) => cmd.run(),
         VmCliCommands::Run(cmd) => cmd.run(),
         VmCliCommands::Keygen(cmd) => cmd.run(),
         VmCliCommands::Prove(cmd) => cmd.run(),
@@ -98,7 +98,7 @@ where
     // 4. Generate STARK proofs for each segment (segmentation is determined by `config`), with timer.
     // vm.prove will emit metrics for proof time of each segment
     let start = Instant::now();
-    let proofs = vm.prove(&pk, results);
+    let proofs = vm.prove(&pk, results.per_segment);
     let proving_time_ms = start.elapsed().as_millis();
 
     let total_proving_time_ms = execute_and_trace_gen_time_ms + proving_time_ms;
@@ -0,0 +1,63 @@
+use std::{
+    fs::{read_to_string, write},
+    path::PathBuf,
+};
+
+use clap::Parser;
+use eyre::Result;
+use openvm_sdk::{
+    commit::{AppExecutionCommit, AppExecutionCommitJson},
+    config::SdkVmConfig,
+    fs::{read_app_pk_from_file, read_exe_from_file},
+    keygen::AppProvingKey,
+    Sdk,
+};
+
+use crate::default::{DEFAULT_APP_EXE_PATH, DEFAULT_APP_PK_PATH};
+
+#[derive(Parser)]
+#[command(
+    name = "commit",
+    about = "Compute the executable and app config commitments without proving"
+)]
+pub struct CommitCmd {
+    #[clap(long, action, help = "Path to app proving key", default_value = DEFAULT_APP_PK_PATH)]
+    app_pk: PathBuf,
+
+    #[clap(long, action, help = "Path to OpenVM executable", default_value = DEFAULT_APP_EXE_PATH)]
+    exe: PathBuf,
+
+    #[clap(long, action, help = "Path to write the commitments as JSON")]
+    output: Option<PathBuf>,
+
+    #[clap(
+        long,
+        action,
+        help = "Path to a JSON file of expected commitments to check the executable against"
+    )]
+    expected: Option<PathBuf>,
+}
+
+impl CommitCmd {
+    pub fn run(&self) -> Result<()> {
+        let app_pk: AppProvingKey<SdkVmConfig> = read_app_pk_from_file(&self.app_pk)?;
+        let exe = read_exe_from_file(&self.exe)?;
+
+        if let Some(expected) = &self.expected {
+            let expected: AppExecutionCommitJson =
+                serde_json::from_str(&read_to_string(expected)?)?;
+            let expected = AppExecutionCommit::try_from(&expected)?;
+            Sdk.verify_app_execution_commit(&app_pk, exe, &expected)?;
+            println!("Executable matches the expected commitments");
+            return Ok(());
+        }
+
+        let commit = Sdk.compute_app_execution_commit(&app_pk, exe)?;
+        let json = serde_json::to_string_pretty(&AppExecutionCommitJson::from(&commit))?;
+        if let Some(output) = &self.output {
+            write(output, &json)?;
+        }
+        println!("{}", json);
+        Ok(())
+    }
+}
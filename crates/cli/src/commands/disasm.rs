@@ -0,0 +1,47 @@
+use std::{fs::write, path::PathBuf};
+
+use clap::Parser;
+use eyre::Result;
+use openvm_sdk::fs::read_exe_from_file;
+
+use crate::{
+    default::{DEFAULT_APP_CONFIG_PATH, DEFAULT_APP_EXE_PATH},
+    util::read_config_toml_or_default,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "disasm",
+    about = "Print an OpenVM executable as text, with opcode and function names"
+)]
+pub struct DisasmCmd {
+    #[clap(action, help = "Path to OpenVM executable", default_value = DEFAULT_APP_EXE_PATH)]
+    exe: PathBuf,
+
+    #[clap(long, action, help = "Path to app config TOML file", default_value = DEFAULT_APP_CONFIG_PATH)]
+    config: PathBuf,
+
+    #[clap(
+        long,
+        action,
+        help = "Path to write the disassembly to instead of stdout"
+    )]
+    output: Option<PathBuf>,
+}
+
+impl DisasmCmd {
+    pub fn run(&self) -> Result<()> {
+        let exe = read_exe_from_file(&self.exe)?;
+        let app_config = read_config_toml_or_default(&self.config)?;
+        let text = app_config
+            .app_vm_config
+            .opcode_table()
+            .disassemble_exe(&exe);
+        if let Some(output) = &self.output {
+            write(output, text)?;
+        } else {
+            print!("{text}");
+        }
+        Ok(())
+    }
+}
@@ -4,6 +4,12 @@ pub use bench::*;
 mod build;
 pub use build::*;
 
+mod commit;
+pub use commit::*;
+
+mod disasm;
+pub use disasm::*;
+
 mod keygen;
 pub use keygen::*;
 
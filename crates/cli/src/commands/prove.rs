@@ -7,7 +7,8 @@ use openvm_sdk::{
     commit::AppExecutionCommit,
     config::SdkVmConfig,
     fs::{
-        read_agg_pk_from_file, read_app_pk_from_file, read_exe_from_file, write_app_proof_to_file,
+        read_agg_pk_from_file, read_agg_stark_pk_from_file, read_app_pk_from_file,
+        read_exe_from_file, write_agg_stark_proof_to_file, write_app_proof_to_file,
         write_evm_proof_to_file,
     },
     keygen::AppProvingKey,
@@ -16,8 +17,9 @@ use openvm_sdk::{
 
 use crate::{
     default::{
-        DEFAULT_AGG_PK_PATH, DEFAULT_APP_EXE_PATH, DEFAULT_APP_PK_PATH, DEFAULT_APP_PROOF_PATH,
-        DEFAULT_EVM_PROOF_PATH, DEFAULT_PARAMS_DIR,
+        DEFAULT_AGG_PK_PATH, DEFAULT_AGG_STARK_PK_PATH, DEFAULT_APP_EXE_PATH, DEFAULT_APP_PK_PATH,
+        DEFAULT_APP_PROOF_PATH, DEFAULT_EVM_PROOF_PATH, DEFAULT_PARAMS_DIR,
+        DEFAULT_STARK_PROOF_PATH,
     },
     util::{read_to_stdin, Input},
 };
@@ -44,6 +46,19 @@ enum ProveSubCommand {
         #[clap(long, action, help = "Path to output proof", default_value = DEFAULT_APP_PROOF_PATH)]
         output: PathBuf,
     },
+    Stark {
+        #[clap(long, action, help = "Path to app proving key", default_value = DEFAULT_APP_PK_PATH)]
+        app_pk: PathBuf,
+
+        #[clap(long, action, help = "Path to OpenVM executable", default_value = DEFAULT_APP_EXE_PATH)]
+        exe: PathBuf,
+
+        #[clap(long, value_parser, help = "Input to OpenVM program")]
+        input: Option<Input>,
+
+        #[clap(long, action, help = "Path to output proof", default_value = DEFAULT_STARK_PROOF_PATH)]
+        output: PathBuf,
+    },
     Evm {
         #[clap(long, action, help = "Path to app proving key", default_value = DEFAULT_APP_PK_PATH)]
         app_pk: PathBuf,
@@ -72,6 +87,20 @@ impl ProveCmd {
                 let app_proof = Sdk.generate_app_proof(app_pk, committed_exe, input)?;
                 write_app_proof_to_file(app_proof, output)?;
             }
+            ProveSubCommand::Stark {
+                app_pk,
+                exe,
+                input,
+                output,
+            } => {
+                let (app_pk, committed_exe, input) = Self::prepare_execution(app_pk, exe, input)?;
+                let agg_stark_pk = read_agg_stark_pk_from_file(DEFAULT_AGG_STARK_PK_PATH).map_err(|e| {
+                    eyre::eyre!("Failed to read STARK aggregation proving key: {}\nPlease run 'cargo openvm setup --stark-only' first", e)
+                })?;
+                let stark_proof =
+                    Sdk.generate_agg_stark_proof(app_pk, committed_exe, agg_stark_pk, input)?;
+                write_agg_stark_proof_to_file(stark_proof, output)?;
+            }
             ProveSubCommand::Evm {
                 app_pk,
                 exe,
@@ -2,7 +2,10 @@ use std::path::PathBuf;
 
 use clap::Parser;
 use eyre::Result;
-use openvm_sdk::{fs::read_exe_from_file, Sdk};
+use openvm_sdk::{
+    fs::{read_exe_from_file, read_journal_from_file, write_journal_to_file},
+    Sdk,
+};
 
 use crate::{
     default::{DEFAULT_APP_CONFIG_PATH, DEFAULT_APP_EXE_PATH},
@@ -20,13 +23,55 @@ pub struct RunCmd {
 
     #[clap(long, value_parser, help = "Input to OpenVM program")]
     input: Option<Input>,
+
+    #[clap(
+        long,
+        action,
+        conflicts_with = "replay",
+        help = "Path to write the journal of all inputs and hints consumed by the execution to"
+    )]
+    record: Option<PathBuf>,
+
+    #[clap(
+        long,
+        action,
+        conflicts_with = "input",
+        help = "Path to a journal written with --record, to replay the recorded execution"
+    )]
+    replay: Option<PathBuf>,
+
+    #[cfg(feature = "aot")]
+    #[clap(
+        long,
+        action,
+        conflicts_with_all = ["record", "replay"],
+        help = "Run the RV32IM instructions as native code compiled ahead of time"
+    )]
+    aot: bool,
 }
 
 impl RunCmd {
     pub fn run(&self) -> Result<()> {
         let exe = read_exe_from_file(&self.exe)?;
         let app_config = read_config_toml_or_default(&self.config)?;
-        let output = Sdk.execute(exe, app_config.app_vm_config, read_to_stdin(&self.input)?)?;
+        let vm_config = app_config.app_vm_config;
+        let output = if let Some(replay) = &self.replay {
+            let journal = read_journal_from_file(replay)?;
+            Sdk.replay(exe, vm_config, journal)?
+        } else if let Some(record) = &self.record {
+            let (output, journal) =
+                Sdk.execute_and_record(exe, vm_config, read_to_stdin(&self.input)?);
+            write_journal_to_file(journal, record)?;
+            output?
+        } else {
+            #[cfg(feature = "aot")]
+            if self.aot {
+                let output = Sdk.execute_aot(exe, vm_config, read_to_stdin(&self.input)?)?;
+                println!("Execution output: {:?}", output);
+                return Ok(());
+            }
+            Sdk.execute(exe, vm_config, read_to_stdin(&self.input)?)?
+        };
         println!("Execution output: {:?}", output);
         Ok(())
     }
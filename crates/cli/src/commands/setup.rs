@@ -9,23 +9,42 @@ use clap::Parser;
 use eyre::{eyre, Result};
 use openvm_native_recursion::halo2::utils::CacheHalo2ParamsReader;
 use openvm_sdk::{
-    config::AggConfig,
-    fs::{write_agg_pk_to_file, write_evm_verifier_to_file},
+    config::{AggConfig, AggStarkConfig, WrapperBackend},
+    fs::{write_agg_pk_to_file, write_agg_stark_pk_to_file, write_evm_verifier_to_file},
     keygen::RootVerifierProvingKey,
     Sdk,
 };
 
-use crate::default::{DEFAULT_AGG_PK_PATH, DEFAULT_PARAMS_DIR, DEFAULT_VERIFIER_PATH};
+use crate::default::{
+    DEFAULT_AGG_PK_PATH, DEFAULT_AGG_STARK_PK_PATH, DEFAULT_PARAMS_DIR, DEFAULT_VERIFIER_PATH,
+};
 
 #[derive(Parser)]
 #[command(
     name = "evm-proving-setup",
     about = "Set up for generating EVM proofs. ATTENTION: this requires large amounts of computation and memory. "
 )]
-pub struct EvmProvingSetupCmd {}
+pub struct EvmProvingSetupCmd {
+    #[clap(
+        long,
+        action,
+        help = "Only generate the STARK aggregation proving key, which is much cheaper and does not need solc or KZG parameters"
+    )]
+    stark_only: bool,
+
+    #[clap(
+        long,
+        action,
+        help = "Wrap the STARK proof with Groth16 instead of Halo2. Skips the KZG parameters download, but the CRS is generated locally and is only suitable for testing"
+    )]
+    groth16: bool,
+}
 
 impl EvmProvingSetupCmd {
     pub async fn run(&self) -> Result<()> {
+        if self.stark_only {
+            return Self::stark_setup();
+        }
         if PathBuf::from(DEFAULT_AGG_PK_PATH).exists()
             && PathBuf::from(DEFAULT_VERIFIER_PATH).exists()
         {
@@ -37,9 +56,13 @@ impl EvmProvingSetupCmd {
             ));
         }
 
-        Self::download_params(10, 24).await?;
+        let mut agg_config = AggConfig::default();
+        if self.groth16 {
+            agg_config.halo2_config.wrapper_backend = WrapperBackend::Groth16;
+        } else {
+            Self::download_params(10, 24).await?;
+        }
         let params_reader = CacheHalo2ParamsReader::new(DEFAULT_PARAMS_DIR);
-        let agg_config = AggConfig::default();
 
         println!("Generating proving key...");
         let agg_pk = Sdk.agg_keygen(agg_config, &params_reader, None::<&RootVerifierProvingKey>)?;
@@ -48,6 +71,7 @@ impl EvmProvingSetupCmd {
         let verifier = Sdk.generate_snark_verifier_contract(&params_reader, &agg_pk)?;
 
         println!("Writing proving key to file...");
+        write_agg_stark_pk_to_file(agg_pk.agg_stark_pk.clone(), DEFAULT_AGG_STARK_PK_PATH)?;
         write_agg_pk_to_file(agg_pk, DEFAULT_AGG_PK_PATH)?;
 
         println!("Writing verifier contract to file...");
@@ -56,6 +80,21 @@ impl EvmProvingSetupCmd {
         Ok(())
     }
 
+    fn stark_setup() -> Result<()> {
+        if PathBuf::from(DEFAULT_AGG_STARK_PK_PATH).exists() {
+            println!("STARK aggregation proving key already exists");
+            return Ok(());
+        }
+
+        println!("Generating STARK aggregation proving key...");
+        let agg_stark_pk = Sdk.agg_stark_keygen(AggStarkConfig::default())?;
+
+        println!("Writing proving key to file...");
+        write_agg_stark_pk_to_file(agg_stark_pk, DEFAULT_AGG_STARK_PK_PATH)?;
+
+        Ok(())
+    }
+
     fn check_solc_installed() -> bool {
         std::process::Command::new("solc")
             .arg("--version")
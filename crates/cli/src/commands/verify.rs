@@ -3,15 +3,20 @@ use std::path::PathBuf;
 use clap::Parser;
 use eyre::Result;
 use openvm_sdk::{
+    commit::AppExecutionCommit,
+    config::SdkVmConfig,
     fs::{
+        read_agg_stark_pk_from_file, read_agg_stark_proof_from_file, read_app_pk_from_file,
         read_app_proof_from_file, read_app_vk_from_file, read_evm_proof_from_file,
-        read_evm_verifier_from_file,
+        read_evm_verifier_from_file, read_exe_from_file,
     },
+    keygen::AppProvingKey,
     Sdk,
 };
 
 use crate::default::{
-    DEFAULT_APP_PROOF_PATH, DEFAULT_APP_VK_PATH, DEFAULT_EVM_PROOF_PATH, DEFAULT_VERIFIER_PATH,
+    DEFAULT_AGG_STARK_PK_PATH, DEFAULT_APP_EXE_PATH, DEFAULT_APP_PK_PATH, DEFAULT_APP_PROOF_PATH,
+    DEFAULT_APP_VK_PATH, DEFAULT_EVM_PROOF_PATH, DEFAULT_STARK_PROOF_PATH, DEFAULT_VERIFIER_PATH,
 };
 
 #[derive(Parser)]
@@ -30,6 +35,16 @@ enum VerifySubCommand {
         #[clap(long, action, help = "Path to app proof", default_value = DEFAULT_APP_PROOF_PATH)]
         proof: PathBuf,
     },
+    Stark {
+        #[clap(long, action, help = "Path to app proving key", default_value = DEFAULT_APP_PK_PATH)]
+        app_pk: PathBuf,
+
+        #[clap(long, action, help = "Path to OpenVM executable", default_value = DEFAULT_APP_EXE_PATH)]
+        exe: PathBuf,
+
+        #[clap(long, action, help = "Path to STARK proof", default_value = DEFAULT_STARK_PROOF_PATH)]
+        proof: PathBuf,
+    },
     Evm {
         #[clap(long, action, help = "Path to EVM proof", default_value = DEFAULT_EVM_PROOF_PATH)]
         proof: PathBuf,
@@ -44,6 +59,29 @@ impl VerifyCmd {
                 let app_proof = read_app_proof_from_file(proof)?;
                 Sdk.verify_app_proof(&app_vk, &app_proof)?;
             }
+            VerifySubCommand::Stark { app_pk, exe, proof } => {
+                let app_pk: AppProvingKey<SdkVmConfig> = read_app_pk_from_file(app_pk)?;
+                let app_exe = read_exe_from_file(exe)?;
+                let committed_exe = Sdk.commit_app_exe(app_pk.app_fri_params(), app_exe)?;
+                let expected_commit = AppExecutionCommit::compute(
+                    &app_pk.app_vm_pk.vm_config,
+                    &committed_exe,
+                    &app_pk.leaf_committed_exe,
+                );
+                let agg_stark_pk = read_agg_stark_pk_from_file(DEFAULT_AGG_STARK_PK_PATH).map_err(|e| {
+                    eyre::eyre!("Failed to read STARK aggregation proving key: {}\nPlease run 'cargo openvm setup --stark-only' first", e)
+                })?;
+                let stark_proof = read_agg_stark_proof_from_file(proof)?;
+                let pvs =
+                    Sdk.verify_agg_stark_proof(&agg_stark_pk, &expected_commit, &stark_proof)?;
+                if let Some(input_commit) = pvs.input_commit {
+                    println!("Input commit: {:?}", input_commit);
+                }
+                if let Some(data_segments_commit) = pvs.data_segments_commit {
+                    println!("Data segments commit: {:?}", data_segments_commit);
+                }
+                println!("Public values: {:?}", pvs.public_values);
+            }
             VerifySubCommand::Evm { proof } => {
                 let evm_verifier = read_evm_verifier_from_file(DEFAULT_VERIFIER_PATH).map_err(|e| {
                     eyre::eyre!("Failed to read EVM verifier: {}\nPlease run 'cargo openvm evm-proving-setup' first", e)
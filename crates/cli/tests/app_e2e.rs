@@ -26,6 +26,17 @@ fn test_cli_app_e2e() -> Result<()> {
         ],
     )?;
 
+    run_cmd(
+        "cargo",
+        &[
+            "openvm",
+            "disasm",
+            temp_exe.to_str().unwrap(),
+            "--config",
+            "example/openvm.toml",
+        ],
+    )?;
+
     run_cmd(
         "cargo",
         &[
@@ -87,6 +98,7 @@ fn test_cli_app_e2e() -> Result<()> {
 fn test_cli_app_e2e_default_paths() -> Result<()> {
     run_cmd("cargo", &["install", "--path", ".", "--force"])?;
     run_cmd("cargo", &["openvm", "build", "--manifest-dir", "example"])?;
+    run_cmd("cargo", &["openvm", "disasm"])?;
     run_cmd("cargo", &["openvm", "keygen"])?;
     run_cmd("cargo", &["openvm", "run"])?;
     run_cmd("cargo", &["openvm", "prove", "app"])?;
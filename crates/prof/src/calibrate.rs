@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use eyre::{bail, Result};
+use openvm_circuit::arch::{AirCostShape, ProvingCostModel};
+
+use crate::types::{Labels, MetricDb};
+
+/// Per-segment, per-AIR trace heights are emitted under this name with a `chip_name` label when
+/// the VM config has `profiling` enabled.
+pub const ROWS_USED_LABEL: &str = "rows_used";
+const CHIP_NAME_LABEL: &str = "chip_name";
+
+const NUM_FEATURES: usize = ProvingCostModel::NUM_FEATURES;
+
+/// A segment observed in the metrics: its features summed over AIRs and the measured cost.
+#[derive(Clone, Debug)]
+pub struct CostSample {
+    pub features: [f64; NUM_FEATURES],
+    pub cost: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct Calibration {
+    pub model: ProvingCostModel,
+    pub samples: Vec<CostSample>,
+    /// Coefficient of determination of the fit.
+    pub r_squared: f64,
+}
+
+/// Collects one sample per segment, i.e. per label set, that has both `rows_used` metrics and
+/// the `target` metric. Only segments with the given `group` label are used if it is set.
+pub fn collect_samples(
+    db: &MetricDb,
+    shapes: &[AirCostShape],
+    target: &str,
+    group: Option<&str>,
+) -> Result<Vec<CostSample>> {
+    let shapes: HashMap<&str, &AirCostShape> =
+        shapes.iter().map(|s| (s.air_name.as_str(), s)).collect();
+    let mut segments: HashMap<Labels, CostSample> = HashMap::new();
+    let mut costs: HashMap<Labels, f64> = HashMap::new();
+    for (labels, metrics) in &db.flat_dict {
+        if labels.get("segment").is_none() {
+            continue;
+        }
+        if group.is_some() && labels.get("group") != group {
+            continue;
+        }
+        if let Some(chip_name) = labels.get(CHIP_NAME_LABEL) {
+            let Some(height) = metrics.iter().find(|m| m.name == ROWS_USED_LABEL) else {
+                continue;
+            };
+            let Some(shape) = shapes.get(chip_name) else {
+                bail!("no AIR shape for chip {chip_name}");
+            };
+            let mut segment_labels = labels.clone();
+            segment_labels.remove(CHIP_NAME_LABEL);
+            let sample = segments.entry(segment_labels).or_insert(CostSample {
+                features: [0.0; NUM_FEATURES],
+                cost: 0.0,
+            });
+            for (total, f) in sample
+                .features
+                .iter_mut()
+                .zip(ProvingCostModel::features(shape, height.value as usize))
+            {
+                *total += f;
+            }
+        } else if let Some(cost) = metrics.iter().find(|m| m.name == target) {
+            costs.insert(labels.clone(), cost.value);
+        }
+    }
+    Ok(segments
+        .into_iter()
+        .filter_map(|(labels, mut sample)| {
+            sample.cost = *costs.get(&labels)?;
+            Some(sample)
+        })
+        .collect())
+}
+
+/// Fits a [ProvingCostModel] with non-negative weights to the samples by least squares.
+pub fn fit(samples: Vec<CostSample>) -> Result<Calibration> {
+    if samples.is_empty() {
+        bail!(
+            "no segment has both `{ROWS_USED_LABEL}` and target metrics; was `profiling` enabled?"
+        );
+    }
+    // Features with a negative fitted weight are dropped and the rest refitted, until all
+    // remaining weights are non-negative.
+    let mut active = [true; NUM_FEATURES];
+    let weights = loop {
+        let weights = least_squares(&samples, &active);
+        let mut all_non_negative = true;
+        for (is_active, w) in active.iter_mut().zip(&weights) {
+            if *is_active && *w < 0.0 {
+                *is_active = false;
+                all_non_negative = false;
+            }
+        }
+        if all_non_negative {
+            break weights;
+        }
+    };
+
+    let predict =
+        |s: &CostSample| -> f64 { s.features.iter().zip(&weights).map(|(f, w)| f * w).sum() };
+    let mean = samples.iter().map(|s| s.cost).sum::<f64>() / samples.len() as f64;
+    let ss_tot: f64 = samples.iter().map(|s| (s.cost - mean).powi(2)).sum();
+    let ss_res: f64 = samples.iter().map(|s| (s.cost - predict(s)).powi(2)).sum();
+    let r_squared = if ss_tot == 0.0 {
+        1.0
+    } else {
+        1.0 - ss_res / ss_tot
+    };
+    Ok(Calibration {
+        model: ProvingCostModel::from_weights(weights),
+        samples,
+        r_squared,
+    })
+}
+
+/// Solves the ridge-regularized normal equations restricted to the active features.
+fn least_squares(samples: &[CostSample], active: &[bool; NUM_FEATURES]) -> [f64; NUM_FEATURES] {
+    let idx: Vec<usize> = (0..NUM_FEATURES).filter(|&i| active[i]).collect();
+    let n = idx.len();
+    let mut weights = [0.0; NUM_FEATURES];
+    if n == 0 {
+        return weights;
+    }
+    // Augmented matrix [X^T X | X^T y].
+    let mut a = vec![vec![0.0; n + 1]; n];
+    for s in samples {
+        for (r, &i) in idx.iter().enumerate() {
+            for (c, &j) in idx.iter().enumerate() {
+                a[r][c] += s.features[i] * s.features[j];
+            }
+            a[r][n] += s.features[i] * s.cost;
+        }
+    }
+    // Features are often collinear (e.g. only one AIR shape), so regularize slightly.
+    for (r, row) in a.iter_mut().enumerate() {
+        row[r] += row[r].abs() * 1e-9 + f64::MIN_POSITIVE;
+    }
+    // Gaussian elimination with partial pivoting.
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&x, &y| a[x][col].abs().total_cmp(&a[y][col].abs()))
+            .unwrap();
+        a.swap(col, pivot);
+        for row in col + 1..n {
+            let factor = a[row][col] / a[col][col];
+            for k in col..=n {
+                a[row][k] -= factor * a[col][k];
+            }
+        }
+    }
+    let mut solution = vec![0.0; n];
+    for row in (0..n).rev() {
+        let rest: f64 = (row + 1..n).map(|k| a[row][k] * solution[k]).sum();
+        solution[row] = (a[row][n] - rest) / a[row][row];
+    }
+    for (&i, w) in idx.iter().zip(solution) {
+        weights[i] = w;
+    }
+    weights
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_recovers_model() {
+        let true_weights = [3.0, 0.0, 0.5, 0.25, 0.0, 2.0];
+        let samples = (1..20)
+            .map(|i| {
+                let features = [
+                    i as f64 * 100.0,
+                    0.0,
+                    (i * i) as f64 * 40.0,
+                    (i % 7) as f64 * 1000.0,
+                    0.0,
+                    ((i * 3) % 5) as f64 * 300.0,
+                ];
+                let cost = features.iter().zip(&true_weights).map(|(f, w)| f * w).sum();
+                CostSample { features, cost }
+            })
+            .collect();
+        let calibration = fit(samples).unwrap();
+        let model = calibration.model;
+        assert!((model.per_row - 3.0).abs() < 1e-6);
+        assert!((model.per_main_cell - 0.5).abs() < 1e-6);
+        assert!((model.per_after_challenge_cell - 0.25).abs() < 1e-6);
+        assert!((model.per_interaction - 2.0).abs() < 1e-6);
+        assert!(calibration.r_squared > 0.999);
+    }
+}
@@ -7,8 +7,10 @@ use std::{
 use clap::{Parser, Subcommand};
 use eyre::Result;
 use itertools::Itertools;
+use openvm_circuit::arch::AirCostShape;
 use openvm_prof::{
-    aggregate::{GroupedMetrics, VM_METRIC_NAMES},
+    aggregate::{GroupedMetrics, PROVE_EXCL_TRACE_TIME_LABEL, VM_METRIC_NAMES},
+    calibrate::{collect_samples, fit},
     summary::GithubSummary,
     types::{BenchmarkOutput, MetricDb},
 };
@@ -37,6 +39,9 @@ struct Cli {
 #[derive(Subcommand, Debug)]
 enum Commands {
     Summary(SummaryCmd),
+    /// Fit a proving cost model for cost-model segmentation to the per-segment metrics in
+    /// `json-paths`, which must have been collected with `profiling` enabled.
+    Calibrate(CalibrateCmd),
 }
 
 #[derive(Parser, Debug)]
@@ -47,8 +52,55 @@ struct SummaryCmd {
     summary_md_path: Option<PathBuf>,
 }
 
+#[derive(Parser, Debug)]
+struct CalibrateCmd {
+    /// JSON file with the `AirCostShape`s of the proven VM, in AIR ID order, e.g. as written by
+    /// the `--air-shapes-output` option of the benchmarks.
+    #[arg(long)]
+    air_shapes: PathBuf,
+    /// Metric to model the cost of.
+    #[arg(long, default_value = PROVE_EXCL_TRACE_TIME_LABEL)]
+    target: String,
+    /// Only use segments with this `group` label.
+    #[arg(long)]
+    group: Option<String>,
+    /// Path to write the fitted `ProvingCostModel` JSON to. Printed to stdout if not set.
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+fn calibrate(json_paths: &[PathBuf], cmd: CalibrateCmd) -> Result<()> {
+    let shapes: Vec<AirCostShape> = serde_json::from_reader(fs::File::open(&cmd.air_shapes)?)?;
+    let mut samples = Vec::new();
+    for path in json_paths {
+        let db = MetricDb::new(path)?;
+        samples.extend(collect_samples(
+            &db,
+            &shapes,
+            &cmd.target,
+            cmd.group.as_deref(),
+        )?);
+    }
+    let calibration = fit(samples)?;
+    eprintln!(
+        "Fitted {} segments, R^2 = {:.4}",
+        calibration.samples.len(),
+        calibration.r_squared
+    );
+    let model = serde_json::to_string_pretty(&calibration.model)?;
+    if let Some(path) = cmd.output {
+        fs::write(&path, model)?;
+    } else {
+        println!("{model}");
+    }
+    Ok(())
+}
+
 fn main() -> Result<()> {
     let args = Cli::parse();
+    if let Some(Commands::Calibrate(cmd)) = args.command {
+        return calibrate(&args.json_paths, cmd);
+    }
 
     let prev_json_paths = if let Some(paths) = args.prev_json_paths {
         paths.into_iter().map(Some).collect()
@@ -94,6 +146,7 @@ fn main() -> Result<()> {
     }
     if let Some(command) = args.command {
         match command {
+            Commands::Calibrate(_) => unreachable!(),
             Commands::Summary(cmd) => {
                 let summary =
                     GithubSummary::new(&aggregated_metrics, &md_paths, &cmd.benchmark_results_link);
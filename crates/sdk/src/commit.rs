@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use eyre::bail;
 use openvm_circuit::{
     arch::{
         hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
@@ -7,12 +8,15 @@ use openvm_circuit::{
         VmConfig,
     },
     system::{
-        memory::{paged_vec::AddressMap, tree::MemoryNode},
+        memory::{initial_memory_image, tree::MemoryNode},
         program::trace::VmCommittedExe,
     },
 };
 use openvm_native_compiler::{conversion::CompilerOptions, ir::DIGEST_SIZE};
-use openvm_stark_backend::{config::StarkGenericConfig, p3_field::PrimeField32};
+use openvm_stark_backend::{
+    config::StarkGenericConfig,
+    p3_field::{PrimeField, PrimeField32},
+};
 use openvm_stark_sdk::{
     config::{baby_bear_poseidon2::BabyBearPoseidon2Engine, FriParameters},
     engine::StarkFriEngine,
@@ -20,12 +24,14 @@ use openvm_stark_sdk::{
     p3_baby_bear::BabyBear,
     p3_bn254_fr::Bn254Fr,
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{
     keygen::AppProvingKey, verifier::leaf::LeafVmVerifierConfig, NonRootCommittedExe, F, SC,
 };
 
 /// `AppExecutionCommit` has all the commitments users should check against the final proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct AppExecutionCommit<T> {
     /// Commitment of the leaf VM verifier program which commits the VmConfig of App VM.
     /// Internal verifier will verify `leaf_vm_verifier_commit`.
@@ -54,34 +60,26 @@ impl AppExecutionCommit<F> {
             app_exe.exe.program.max_num_public_values <= app_vm_config.system().num_public_values
         );
         let hasher = vm_poseidon2_hasher();
-        let memory_dimensions = app_vm_config.system().memory_config.memory_dimensions();
+        let mem_config = app_vm_config.resolved_system_config().memory_config;
+        let memory_dimensions = mem_config.memory_dimensions();
         let app_program_commit: [F; DIGEST_SIZE] = app_exe.committed_program.commitment.into();
         let leaf_verifier_program_commit: [F; DIGEST_SIZE] =
             leaf_vm_verifier_exe.committed_program.commitment.into();
 
-        let mem_config = app_vm_config.system().memory_config;
         let init_memory_commit = MemoryNode::tree_from_memory(
             memory_dimensions,
-            &AddressMap::from_iter(
-                mem_config.as_offset,
-                1 << mem_config.as_height,
-                1 << mem_config.pointer_max_bits,
-                app_exe.exe.init_memory.clone(),
-            ),
+            &initial_memory_image(&mem_config, &app_exe.exe.init_memory),
             &hasher,
         )
         .hash();
-        let mut padded_pc_start = [F::ZERO; DIGEST_SIZE];
-        padded_pc_start[0] = F::from_canonical_u32(app_exe.exe.pc_start);
-        let app_hash = hasher.hash(&app_program_commit);
-        let init_memory_hash = hasher.hash(&init_memory_commit);
-        let pc_start_hash = hasher.hash(&padded_pc_start);
-        let compress_1 = hasher.compress(&app_hash, &init_memory_hash);
-        let user_commit = hasher.compress(&compress_1, &pc_start_hash);
 
         Self {
             leaf_vm_verifier_commit: leaf_verifier_program_commit,
-            exe_commit: user_commit,
+            exe_commit: compute_exe_commit(
+                &app_program_commit,
+                &init_memory_commit,
+                F::from_canonical_u32(app_exe.exe.pc_start),
+            ),
         }
     }
 
@@ -94,6 +92,90 @@ impl AppExecutionCommit<F> {
     }
 }
 
+/// Serializable form of [AppExecutionCommit], with the commitments both as BabyBear digests and
+/// in the Bn254 form exposed by the EVM verifier. Bn254 values are 0x-prefixed big-endian hex.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppExecutionCommitJson {
+    pub exe_commit: [u32; DIGEST_SIZE],
+    pub leaf_vm_verifier_commit: [u32; DIGEST_SIZE],
+    pub exe_commit_bn254: String,
+    pub app_config_commit_bn254: String,
+}
+
+impl From<&AppExecutionCommit<F>> for AppExecutionCommitJson {
+    fn from(commit: &AppExecutionCommit<F>) -> Self {
+        Self {
+            exe_commit: commit.exe_commit.map(|x| x.as_canonical_u32()),
+            leaf_vm_verifier_commit: commit.leaf_vm_verifier_commit.map(|x| x.as_canonical_u32()),
+            exe_commit_bn254: bn254_to_hex(&commit.exe_commit_to_bn254()),
+            app_config_commit_bn254: bn254_to_hex(&commit.app_config_commit_to_bn254()),
+        }
+    }
+}
+
+impl TryFrom<&AppExecutionCommitJson> for AppExecutionCommit<F> {
+    type Error = eyre::Report;
+
+    /// Fails if a BabyBear digest is not canonical or doesn't match its Bn254 form.
+    fn try_from(json: &AppExecutionCommitJson) -> Result<Self, Self::Error> {
+        let to_digest = |name: &str, digest: &[u32; DIGEST_SIZE]| {
+            if digest.iter().any(|&x| x >= BabyBear::ORDER_U32) {
+                bail!("{name} is not a BabyBear digest");
+            }
+            Ok(digest.map(F::from_canonical_u32))
+        };
+        let commit = Self {
+            leaf_vm_verifier_commit: to_digest(
+                "leaf_vm_verifier_commit",
+                &json.leaf_vm_verifier_commit,
+            )?,
+            exe_commit: to_digest("exe_commit", &json.exe_commit)?,
+        };
+        let expected = AppExecutionCommitJson::from(&commit);
+        if !json
+            .exe_commit_bn254
+            .eq_ignore_ascii_case(&expected.exe_commit_bn254)
+        {
+            bail!(
+                "exe_commit_bn254 {} does not match exe_commit, expected {}",
+                json.exe_commit_bn254,
+                expected.exe_commit_bn254
+            );
+        }
+        if !json
+            .app_config_commit_bn254
+            .eq_ignore_ascii_case(&expected.app_config_commit_bn254)
+        {
+            bail!(
+                "app_config_commit_bn254 {} does not match leaf_vm_verifier_commit, expected {}",
+                json.app_config_commit_bn254,
+                expected.app_config_commit_bn254
+            );
+        }
+        Ok(commit)
+    }
+}
+
+/// Computes `exe_commit` as documented in [AppExecutionCommit].
+pub(crate) fn compute_exe_commit(
+    app_program_commit: &[F; DIGEST_SIZE],
+    init_memory_commit: &[F; DIGEST_SIZE],
+    pc_start: F,
+) -> [F; DIGEST_SIZE] {
+    let hasher = vm_poseidon2_hasher();
+    let mut padded_pc_start = [F::ZERO; DIGEST_SIZE];
+    padded_pc_start[0] = pc_start;
+    let app_hash = hasher.hash(app_program_commit);
+    let init_memory_hash = hasher.hash(init_memory_commit);
+    let pc_start_hash = hasher.hash(&padded_pc_start);
+    let compress_1 = hasher.compress(&app_hash, &init_memory_hash);
+    hasher.compress(&compress_1, &pc_start_hash)
+}
+
+fn bn254_to_hex(x: &Bn254Fr) -> String {
+    format!("0x{:064x}", x.as_canonical_biguint())
+}
+
 pub(crate) fn babybear_digest_to_bn254(digest: &[F; DIGEST_SIZE]) -> Bn254Fr {
     let mut ret = Bn254Fr::ZERO;
     let order = Bn254Fr::from_canonical_u32(BabyBear::ORDER_U32);
@@ -114,7 +196,7 @@ pub fn generate_leaf_committed_exe<VC: VmConfig<F>>(
     let leaf_engine = BabyBearPoseidon2Engine::new(leaf_fri_params);
     let leaf_program = LeafVmVerifierConfig {
         app_fri_params: app_pk.app_vm_pk.fri_params,
-        app_system_config: app_pk.app_vm_pk.vm_config.system().clone(),
+        app_system_config: app_pk.app_vm_pk.vm_config.resolved_system_config(),
         compiler_options,
     }
     .build_program(&app_vm_vk);
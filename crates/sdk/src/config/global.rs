@@ -4,12 +4,19 @@ use openvm_algebra_circuit::{
     Fp2Extension, Fp2ExtensionExecutor, Fp2ExtensionPeriphery, ModularExtension,
     ModularExtensionExecutor, ModularExtensionPeriphery,
 };
-use openvm_algebra_transpiler::{Fp2TranspilerExtension, ModularTranspilerExtension};
+use openvm_algebra_transpiler::{
+    Fp2Opcode, Fp2TranspilerExtension, ModularTranspilerExtension, Rv32ModularArithmeticOpcode,
+};
 use openvm_bigint_circuit::{Int256, Int256Executor, Int256Periphery};
-use openvm_bigint_transpiler::Int256TranspilerExtension;
+use openvm_bigint_transpiler::{
+    Int256TranspilerExtension, Rv32BaseAlu256Opcode, Rv32BranchEqual256Opcode,
+    Rv32BranchLessThan256Opcode, Rv32LessThan256Opcode, Rv32Mul256Opcode, Rv32Shift256Opcode,
+};
 use openvm_circuit::{
     arch::{
-        SystemConfig, SystemExecutor, SystemPeriphery, VmChipComplex, VmConfig, VmInventoryError,
+        instructions::{disasm::OpcodeTable, LocalOpcode},
+        MemoryConfig, SystemConfig, SystemExecutor, SystemPeriphery, VmChipComplex, VmConfig,
+        VmInventoryError,
     },
     circuit_derive::{Chip, ChipUsageGetter},
     derive::{AnyEnum, InstructionExecutor},
@@ -17,29 +24,34 @@ use openvm_circuit::{
 use openvm_ecc_circuit::{
     WeierstrassExtension, WeierstrassExtensionExecutor, WeierstrassExtensionPeriphery,
 };
-use openvm_ecc_transpiler::EccTranspilerExtension;
+use openvm_ecc_transpiler::{EccTranspilerExtension, Rv32WeierstrassOpcode};
 use openvm_keccak256_circuit::{Keccak256, Keccak256Executor, Keccak256Periphery};
-use openvm_keccak256_transpiler::Keccak256TranspilerExtension;
+use openvm_keccak256_transpiler::{Keccak256TranspilerExtension, Rv32KeccakOpcode};
 use openvm_native_circuit::{
     CastFExtension, CastFExtensionExecutor, CastFExtensionPeriphery, Native, NativeExecutor,
     NativePeriphery,
 };
+use openvm_native_compiler::{with_native_opcodes, CastfOpcode};
 use openvm_pairing_circuit::{
     PairingExtension, PairingExtensionExecutor, PairingExtensionPeriphery,
 };
-use openvm_pairing_transpiler::PairingTranspilerExtension;
+use openvm_pairing_transpiler::{Fp12Opcode, PairingOpcode, PairingTranspilerExtension};
 use openvm_rv32im_circuit::{
     Rv32I, Rv32IExecutor, Rv32IPeriphery, Rv32Io, Rv32IoExecutor, Rv32IoPeriphery, Rv32M,
     Rv32MExecutor, Rv32MPeriphery,
 };
 use openvm_rv32im_transpiler::{
-    Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
+    BaseAluOpcode, BranchEqualOpcode, BranchLessThanOpcode, DivRemOpcode, LessThanOpcode,
+    MulHOpcode, MulOpcode, Rv32AuipcOpcode, Rv32HintStoreOpcode, Rv32ITranspilerExtension,
+    Rv32IoTranspilerExtension, Rv32JalLuiOpcode, Rv32JalrOpcode, Rv32LoadStoreOpcode,
+    Rv32MTranspilerExtension, ShiftOpcode,
 };
 use openvm_sha256_circuit::{Sha256, Sha256Executor, Sha256Periphery};
-use openvm_sha256_transpiler::Sha256TranspilerExtension;
+use openvm_sha256_transpiler::{Rv32Sha256Opcode, Sha256TranspilerExtension};
 use openvm_stark_backend::p3_field::PrimeField32;
 use openvm_transpiler::transpiler::Transpiler;
 use serde::{Deserialize, Serialize};
+use strum::EnumCount;
 
 use crate::F;
 
@@ -158,6 +170,102 @@ impl SdkVmConfig {
         }
         transpiler
     }
+
+    /// The names of the opcodes this config supports, to disassemble and assemble its programs.
+    /// Opcode classes of the modular, complex and elliptic curve extensions are numbered by the
+    /// index of their modulus or curve, e.g. `modular_1.ADD`.
+    pub fn opcode_table(&self) -> OpcodeTable {
+        let mut table = OpcodeTable::new();
+        if self.rv32i.is_some() {
+            table = table
+                .with_opcodes::<BaseAluOpcode>("rv32")
+                .with_opcodes::<ShiftOpcode>("rv32")
+                .with_opcodes::<LessThanOpcode>("rv32")
+                .with_opcodes::<Rv32LoadStoreOpcode>("rv32")
+                .with_opcodes::<BranchEqualOpcode>("rv32")
+                .with_opcodes::<BranchLessThanOpcode>("rv32")
+                .with_opcodes::<Rv32JalLuiOpcode>("rv32")
+                .with_opcodes::<Rv32JalrOpcode>("rv32")
+                .with_opcodes::<Rv32AuipcOpcode>("rv32");
+        }
+        if self.io.is_some() {
+            table = table.with_opcodes::<Rv32HintStoreOpcode>("rv32");
+        }
+        if self.keccak.is_some() {
+            table = table.with_opcodes::<Rv32KeccakOpcode>("keccak");
+        }
+        if self.sha256.is_some() {
+            table = table.with_opcodes::<Rv32Sha256Opcode>("sha256");
+        }
+        if self.native.is_some() {
+            table = with_native_opcodes(table);
+        }
+        if self.rv32m.is_some() {
+            table = table
+                .with_opcodes::<MulOpcode>("rv32")
+                .with_opcodes::<MulHOpcode>("rv32")
+                .with_opcodes::<DivRemOpcode>("rv32");
+        }
+        if self.bigint.is_some() {
+            table = table
+                .with_opcodes_at::<BaseAluOpcode>("int256", Rv32BaseAlu256Opcode::CLASS_OFFSET)
+                .with_opcodes_at::<ShiftOpcode>("int256", Rv32Shift256Opcode::CLASS_OFFSET)
+                .with_opcodes_at::<LessThanOpcode>("int256", Rv32LessThan256Opcode::CLASS_OFFSET)
+                .with_opcodes_at::<BranchEqualOpcode>(
+                    "int256",
+                    Rv32BranchEqual256Opcode::CLASS_OFFSET,
+                )
+                .with_opcodes_at::<BranchLessThanOpcode>(
+                    "int256",
+                    Rv32BranchLessThan256Opcode::CLASS_OFFSET,
+                )
+                .with_opcodes_at::<MulOpcode>("int256", Rv32Mul256Opcode::CLASS_OFFSET);
+        }
+        if let Some(modular) = &self.modular {
+            for i in 0..modular.supported_modulus.len() {
+                table = table.with_opcodes_at::<Rv32ModularArithmeticOpcode>(
+                    &format!("modular_{i}"),
+                    Rv32ModularArithmeticOpcode::CLASS_OFFSET
+                        + i * Rv32ModularArithmeticOpcode::COUNT,
+                );
+            }
+        }
+        if let Some(fp2) = &self.fp2 {
+            for i in 0..fp2.supported_modulus.len() {
+                table = table.with_opcodes_at::<Fp2Opcode>(
+                    &format!("fp2_{i}"),
+                    Fp2Opcode::CLASS_OFFSET + i * Fp2Opcode::COUNT,
+                );
+            }
+        }
+        if let Some(pairing) = &self.pairing {
+            for curve in &pairing.supported_curves {
+                let name = format!("{curve:?}").to_lowercase();
+                let pairing_idx = *curve as usize;
+                table = table
+                    .with_opcodes_at::<PairingOpcode>(
+                        &name,
+                        PairingOpcode::CLASS_OFFSET + pairing_idx * PairingOpcode::COUNT,
+                    )
+                    .with_opcodes_at::<Fp12Opcode>(
+                        &format!("{name}_fp12"),
+                        Fp12Opcode::CLASS_OFFSET + pairing_idx * Fp12Opcode::COUNT,
+                    );
+            }
+        }
+        if let Some(ecc) = &self.ecc {
+            for i in 0..ecc.supported_curves.len() {
+                table = table.with_opcodes_at::<Rv32WeierstrassOpcode>(
+                    &format!("ecc_{i}"),
+                    Rv32WeierstrassOpcode::CLASS_OFFSET + i * Rv32WeierstrassOpcode::COUNT,
+                );
+            }
+        }
+        if self.castf.is_some() {
+            table = table.with_opcodes::<CastfOpcode>("native");
+        }
+        table
+    }
 }
 
 impl<F: PrimeField32> VmConfig<F> for SdkVmConfig {
@@ -231,6 +339,36 @@ impl<F: PrimeField32> VmConfig<F> for SdkVmConfig {
 
         Ok(complex)
     }
+
+    fn resolved_system_config(&self) -> SystemConfig {
+        let mut config = self.system.config.clone();
+        self.add_address_spaces::<F>(&mut config.memory_config)
+            .expect("invalid extension address space");
+        config
+    }
+}
+
+impl SdkVmConfig {
+    /// Registers the address spaces of the enabled extensions, in the order
+    /// [VmConfig::create_chip_complex] extends the chip complex with them.
+    fn add_address_spaces<F: PrimeField32>(
+        &self,
+        memory_config: &mut MemoryConfig,
+    ) -> Result<(), VmInventoryError> {
+        memory_config.add_extension_address_spaces::<F>(&self.rv32i.map(|_| Rv32I))?;
+        memory_config.add_extension_address_spaces::<F>(&self.io.map(|_| Rv32Io))?;
+        memory_config.add_extension_address_spaces::<F>(&self.keccak.map(|_| Keccak256))?;
+        memory_config.add_extension_address_spaces::<F>(&self.sha256.map(|_| Sha256))?;
+        memory_config.add_extension_address_spaces::<F>(&self.native.map(|_| Native))?;
+        memory_config.add_extension_address_spaces::<F>(&self.rv32m)?;
+        memory_config.add_extension_address_spaces::<F>(&self.bigint)?;
+        memory_config.add_extension_address_spaces::<F>(&self.modular.clone())?;
+        memory_config.add_extension_address_spaces::<F>(&self.fp2.clone())?;
+        memory_config.add_extension_address_spaces::<F>(&self.pairing.clone())?;
+        memory_config.add_extension_address_spaces::<F>(&self.ecc.clone())?;
+        memory_config.add_extension_address_spaces::<F>(&self.castf.clone())?;
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
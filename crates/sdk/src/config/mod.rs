@@ -43,6 +43,19 @@ pub struct AggStarkConfig {
     pub compiler_options: CompilerOptions,
     /// Max constraint degree for FRI logup chunking
     pub root_max_constraint_degree: usize,
+    /// How the root verifier exposes the user public values.
+    #[serde(default)]
+    pub public_values_mode: PublicValuesMode,
+    /// Whether the root verifier exposes the input commitment of the App VM, see
+    /// [SystemConfig::commit_input_stream](openvm_circuit::arch::SystemConfig::commit_input_stream).
+    /// It is only useful if the App VM commits to its input stream.
+    #[serde(default)]
+    pub expose_input_commit: bool,
+    /// Whether the root verifier exposes the merkle root of the data segments mapped into App VM
+    /// memory, see [StdIn::data_segments_commit](crate::StdIn::data_segments_commit). It is only
+    /// useful if the App VM is executed with data segments.
+    #[serde(default)]
+    pub expose_data_segments_commit: bool,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -53,6 +66,36 @@ pub struct Halo2Config {
     pub wrapper_k: Option<usize>,
     /// Sets the profiling mode of halo2 VM
     pub profiling: bool,
+    /// Proof system wrapping the static verifier for the EVM.
+    #[serde(default)]
+    pub wrapper_backend: WrapperBackend,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WrapperBackend {
+    /// Static verifier and wrapper circuits proven with Halo2 over KZG.
+    #[default]
+    Halo2,
+    /// Static verifier compiled to R1CS and proven with Groth16. `verifier_k` and `wrapper_k`
+    /// are ignored and no KZG parameters are needed.
+    ///
+    /// Attention: the CRS is generated locally during keygen, so whoever runs keygen can forge
+    /// proofs. Only use this for testing.
+    Groth16,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PublicValuesMode {
+    /// The root verifier exposes every user public value as a separate public value.
+    #[default]
+    Raw,
+    /// The root verifier exposes the 32 bytes of the Keccak256 digest of the user public values
+    /// instead. Every user public value must be a byte.
+    ///
+    /// A 32 byte digest doesn't fit in a single Bn254 element, so the static verifier and the
+    /// EVM verifier take it as two instances holding its big-endian high and low 128 bits. An
+    /// on-chain consumer recovers `bytes32 publicValuesHash` as `bytes32((hi << 128) | lo)`.
+    Keccak256,
 }
 
 impl<VC> AppConfig<VC> {
@@ -95,6 +138,9 @@ impl Default for AggStarkConfig {
             profiling: false,
             compiler_options: Default::default(),
             root_max_constraint_degree: (1 << DEFAULT_ROOT_LOG_BLOWUP) + 1,
+            public_values_mode: PublicValuesMode::default(),
+            expose_input_commit: false,
+            expose_data_segments_commit: false,
         }
     }
 }
@@ -107,6 +153,7 @@ impl Default for AggConfig {
                 verifier_k: 24,
                 wrapper_k: None,
                 profiling: false,
+                wrapper_backend: WrapperBackend::default(),
             },
         }
     }
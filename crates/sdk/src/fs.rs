@@ -4,14 +4,18 @@ use std::{
 };
 
 use eyre::Result;
-use openvm_circuit::arch::{instructions::exe::VmExe, VmConfig};
+use openvm_circuit::arch::{instructions::exe::VmExe, ExecutionJournal, VmConfig};
 use openvm_native_recursion::halo2::{wrapper::EvmVerifier, EvmProof};
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
-    keygen::{AggProvingKey, AppProvingKey, AppVerifyingKey},
+    keygen::{
+        AggProvingKey, AggStarkProvingKey, AppProvingKey, AppVerifyingKey,
+        CompressedAppVerifyingKey, LegacyAggProvingKey,
+    },
     prover::vm::ContinuationVmProof,
-    F, SC,
+    verifier::{internal::types::CompressedAppProof, root::types::AggStarkProof},
+    RootSC, F, SC,
 };
 
 pub fn read_exe_from_file<P: AsRef<Path>>(path: P) -> Result<VmExe<F>> {
@@ -22,6 +26,14 @@ pub fn write_exe_to_file<P: AsRef<Path>>(exe: VmExe<F>, path: P) -> Result<()> {
     write_to_file_bitcode(path, exe)
 }
 
+pub fn read_journal_from_file<P: AsRef<Path>>(path: P) -> Result<ExecutionJournal<F>> {
+    read_from_file_bitcode(path)
+}
+
+pub fn write_journal_to_file<P: AsRef<Path>>(journal: ExecutionJournal<F>, path: P) -> Result<()> {
+    write_to_file_bitcode(path, journal)
+}
+
 pub fn read_app_pk_from_file<VC: VmConfig<F>, P: AsRef<Path>>(
     path: P,
 ) -> Result<AppProvingKey<VC>> {
@@ -54,14 +66,70 @@ pub fn write_app_proof_to_file<P: AsRef<Path>>(
     write_to_file_bitcode(path, proof)
 }
 
-pub fn read_agg_pk_from_file<P: AsRef<Path>>(path: P) -> Result<AggProvingKey> {
+pub fn read_compressed_app_vk_from_file<P: AsRef<Path>>(
+    path: P,
+) -> Result<CompressedAppVerifyingKey> {
+    read_from_file_bitcode(path)
+}
+
+pub fn write_compressed_app_vk_to_file<P: AsRef<Path>>(
+    compressed_app_vk: CompressedAppVerifyingKey,
+    path: P,
+) -> Result<()> {
+    write_to_file_bitcode(path, compressed_app_vk)
+}
+
+pub fn read_compressed_app_proof_from_file<P: AsRef<Path>>(
+    path: P,
+) -> Result<CompressedAppProof<SC>> {
     read_from_file_bitcode(path)
 }
 
+pub fn write_compressed_app_proof_to_file<P: AsRef<Path>>(
+    proof: CompressedAppProof<SC>,
+    path: P,
+) -> Result<()> {
+    write_to_file_bitcode(path, proof)
+}
+
+/// Reads an aggregation proving key. Keys written before the SNARK wrapper backend was
+/// configurable are read as keys with a Halo2 wrapper.
+pub fn read_agg_pk_from_file<P: AsRef<Path>>(path: P) -> Result<AggProvingKey> {
+    let data = std::fs::read(path)?;
+    match bitcode::deserialize::<AggProvingKey>(&data) {
+        Ok(agg_pk) => Ok(agg_pk),
+        Err(err) => bitcode::deserialize::<LegacyAggProvingKey>(&data)
+            .map(AggProvingKey::from)
+            .map_err(|_| err.into()),
+    }
+}
+
 pub fn write_agg_pk_to_file<P: AsRef<Path>>(agg_pk: AggProvingKey, path: P) -> Result<()> {
     write_to_file_bitcode(path, agg_pk)
 }
 
+pub fn read_agg_stark_pk_from_file<P: AsRef<Path>>(path: P) -> Result<AggStarkProvingKey> {
+    read_from_file_bitcode(path)
+}
+
+pub fn write_agg_stark_pk_to_file<P: AsRef<Path>>(
+    agg_stark_pk: AggStarkProvingKey,
+    path: P,
+) -> Result<()> {
+    write_to_file_bitcode(path, agg_stark_pk)
+}
+
+pub fn read_agg_stark_proof_from_file<P: AsRef<Path>>(path: P) -> Result<AggStarkProof<RootSC>> {
+    read_from_file_bitcode(path)
+}
+
+pub fn write_agg_stark_proof_to_file<P: AsRef<Path>>(
+    proof: AggStarkProof<RootSC>,
+    path: P,
+) -> Result<()> {
+    write_to_file_bitcode(path, proof)
+}
+
 pub fn read_evm_proof_from_file<P: AsRef<Path>>(path: P) -> Result<EvmProof> {
     read_from_file_bitcode(path)
 }
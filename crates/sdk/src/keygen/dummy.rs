@@ -12,7 +12,6 @@ use openvm_circuit::{
     utils::next_power_of_two_or_zero,
 };
 use openvm_native_circuit::NativeConfig;
-use openvm_native_compiler::ir::DIGEST_SIZE;
 use openvm_native_recursion::hints::Hintable;
 use openvm_rv32im_circuit::Rv32ImConfig;
 use openvm_stark_sdk::{
@@ -27,14 +26,15 @@ use openvm_stark_sdk::{
 };
 
 use crate::{
+    keygen::AppProvingKey,
     prover::vm::{
         local::VmLocalProver, types::VmProvingKey, ContinuationVmProof, ContinuationVmProver,
         SingleSegmentVmProver,
     },
     verifier::{
-        internal::types::InternalVmVerifierInput,
+        internal::types::{InternalVmVerifierInput, LeafVerifierCommitProof},
         leaf::{types::LeafVmVerifierInput, LeafVmVerifierConfig},
-        root::types::RootVmVerifierInput,
+        root::{types::RootVmVerifierInput, RootVmConfig},
     },
     NonRootCommittedExe, F, SC,
 };
@@ -45,14 +45,16 @@ use crate::{
 ///
 /// All trace heights are rounded to the next power of two (or 0 -> 0).
 pub(super) fn compute_root_proof_heights(
-    root_vm_config: NativeConfig,
+    root_vm_config: RootVmConfig,
     root_exe: VmExe<F>,
     dummy_internal_proof: &Proof<SC>,
+    leaf_verifier_commit_proof: Option<LeafVerifierCommitProof<F>>,
 ) -> (Vec<usize>, VmComplexTraceHeights) {
-    let num_user_public_values = root_vm_config.system.num_public_values - 2 * DIGEST_SIZE;
+    let num_user_public_values = root_vm_config.num_user_public_values;
     let root_input = RootVmVerifierInput {
         proofs: vec![dummy_internal_proof.clone()],
         public_values: vec![F::ZERO; num_user_public_values],
+        leaf_verifier_commit_proof,
     };
     let vm = SingleSegmentVmExecutor::new(root_vm_config);
     let res = vm
@@ -72,11 +74,13 @@ pub(super) fn dummy_internal_proof(
     internal_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
     internal_exe: Arc<NonRootCommittedExe>,
     leaf_proof: Proof<SC>,
+    leaf_verifier_commit_proof: Option<&LeafVerifierCommitProof<F>>,
 ) -> Proof<SC> {
     let mut internal_inputs = InternalVmVerifierInput::chunk_leaf_or_internal_proofs(
         internal_exe.get_program_commit().into(),
         &[leaf_proof],
         1,
+        leaf_verifier_commit_proof,
     );
     let internal_input = internal_inputs.pop().unwrap();
     let internal_prover = VmLocalProver::<SC, NativeConfig, BabyBearPoseidon2Engine>::new(
@@ -86,17 +90,6 @@ pub(super) fn dummy_internal_proof(
     SingleSegmentVmProver::prove(&internal_prover, internal_input.write())
 }
 
-pub(super) fn dummy_internal_proof_riscv_app_vm(
-    leaf_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
-    internal_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
-    internal_exe: Arc<NonRootCommittedExe>,
-    num_public_values: usize,
-) -> Proof<SC> {
-    let fri_params = standard_fri_params_with_100_bits_conjectured_security(1);
-    let leaf_proof = dummy_leaf_proof_riscv_app_vm(leaf_vm_pk, num_public_values, fri_params);
-    dummy_internal_proof(internal_vm_pk, internal_exe, leaf_proof)
-}
-
 #[allow(dead_code)]
 pub fn dummy_leaf_proof<VC: VmConfig<F>>(
     leaf_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
@@ -104,6 +97,7 @@ pub fn dummy_leaf_proof<VC: VmConfig<F>>(
     overridden_heights: Option<VmComplexTraceHeights>,
 ) -> Proof<SC>
 where
+    VC: Sync,
     VC::Executor: Chip<SC>,
     VC::Periphery: Chip<SC>,
 {
@@ -121,6 +115,20 @@ pub(super) fn dummy_leaf_proof_riscv_app_vm(
     dummy_leaf_proof_impl(leaf_vm_pk, app_vm_pk, &app_proof)
 }
 
+/// Dummy proof of the leaf verifier of `app_pk`.
+pub(super) fn dummy_leaf_proof_for_app<VC: VmConfig<F>>(
+    leaf_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
+    app_pk: &AppProvingKey<VC>,
+) -> Proof<SC>
+where
+    VC: Sync,
+    VC::Executor: Chip<SC>,
+    VC::Periphery: Chip<SC>,
+{
+    let app_proof = dummy_app_proof_impl(app_pk.app_vm_pk.clone(), None);
+    prove_dummy_leaf(leaf_vm_pk, app_pk.leaf_committed_exe.clone(), &app_proof)
+}
+
 fn dummy_leaf_proof_impl<VC: VmConfig<F>>(
     leaf_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
     app_vm_pk: Arc<VmProvingKey<SC, VC>>,
@@ -128,7 +136,7 @@ fn dummy_leaf_proof_impl<VC: VmConfig<F>>(
 ) -> Proof<SC> {
     let leaf_program = LeafVmVerifierConfig {
         app_fri_params: app_vm_pk.fri_params,
-        app_system_config: app_vm_pk.vm_config.system().clone(),
+        app_system_config: app_vm_pk.vm_config.resolved_system_config(),
         compiler_options: Default::default(),
     }
     .build_program(&app_vm_pk.vm_pk.get_vk());
@@ -142,6 +150,14 @@ fn dummy_leaf_proof_impl<VC: VmConfig<F>>(
         leaf_program.into(),
         e.config.pcs(),
     ));
+    prove_dummy_leaf(leaf_vm_pk, leaf_exe, app_proof)
+}
+
+fn prove_dummy_leaf(
+    leaf_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
+    leaf_exe: Arc<NonRootCommittedExe>,
+    app_proof: &ContinuationVmProof<SC>,
+) -> Proof<SC> {
     let leaf_prover =
         VmLocalProver::<SC, NativeConfig, BabyBearPoseidon2Engine>::new(leaf_vm_pk, leaf_exe);
     let mut leaf_inputs = LeafVmVerifierInput::chunk_continuation_vm_proof(app_proof, 1);
@@ -168,6 +184,7 @@ fn dummy_app_proof_impl<VC: VmConfig<F>>(
     overridden_heights: Option<VmComplexTraceHeights>,
 ) -> ContinuationVmProof<SC>
 where
+    VC: Sync,
     VC::Executor: Chip<SC>,
     VC::Periphery: Chip<SC>,
 {
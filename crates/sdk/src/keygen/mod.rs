@@ -1,20 +1,28 @@
 use std::sync::Arc;
 
 use derivative::Derivative;
-use dummy::{compute_root_proof_heights, dummy_internal_proof_riscv_app_vm};
+use dummy::{
+    compute_root_proof_heights, dummy_internal_proof, dummy_leaf_proof_for_app,
+    dummy_leaf_proof_riscv_app_vm,
+};
 use openvm_circuit::{
-    arch::{VirtualMachine, VmConfig},
+    arch::{VirtualMachine, VmConfig, PROGRAM_CACHED_TRACE_INDEX},
     system::program::trace::VmCommittedExe,
 };
 use openvm_native_circuit::NativeConfig;
 use openvm_native_compiler::ir::DIGEST_SIZE;
-use openvm_native_recursion::halo2::{
-    utils::Halo2ParamsReader, verifier::Halo2VerifierProvingKey, wrapper::Halo2WrapperProvingKey,
+use openvm_native_recursion::{
+    groth16::Groth16WrapperProvingKey,
+    halo2::{
+        utils::Halo2ParamsReader, verifier::Halo2VerifierProvingKey,
+        wrapper::Halo2WrapperProvingKey,
+    },
 };
 use openvm_stark_sdk::{
     config::{
         baby_bear_poseidon2::BabyBearPoseidon2Engine,
-        baby_bear_poseidon2_root::BabyBearPoseidon2RootEngine, FriParameters,
+        baby_bear_poseidon2_root::BabyBearPoseidon2RootEngine,
+        fri_params::standard_fri_params_with_100_bits_conjectured_security, FriParameters,
     },
     engine::StarkFriEngine,
     openvm_stark_backend::{
@@ -30,12 +38,14 @@ use tracing::info_span;
 
 use crate::{
     commit::babybear_digest_to_bn254,
-    config::{AggConfig, AggStarkConfig, AppConfig},
+    config::{AggConfig, AggStarkConfig, AppConfig, Halo2Config, PublicValuesMode, WrapperBackend},
     keygen::perm::AirIdPermutation,
     prover::vm::types::VmProvingKey,
     static_verifier::StaticVerifierPvHandler,
     verifier::{
-        internal::InternalVmVerifierConfig, leaf::LeafVmVerifierConfig, root::RootVmVerifierConfig,
+        internal::{types::LeafVerifierCommitSet, InternalVmVerifierConfig},
+        leaf::LeafVmVerifierConfig,
+        root::{RootVmConfig, RootVmVerifierConfig},
     },
     NonRootCommittedExe, RootSC, F, SC,
 };
@@ -56,18 +66,59 @@ pub struct AppVerifyingKey {
     pub app_vm_vk: MultiStarkVerifyingKey<SC>,
 }
 
+/// Verifying key of compressed app proofs, i.e. proofs of the internal verifier.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CompressedAppVerifyingKey {
+    pub fri_params: FriParameters,
+    pub internal_vm_vk: MultiStarkVerifyingKey<SC>,
+    pub internal_program_commit: [F; DIGEST_SIZE],
+    /// How the root verifier exposes the user public values, so that compressed proofs are
+    /// verified to the same public values as aggregated proofs.
+    #[serde(default)]
+    pub public_values_mode: PublicValuesMode,
+    /// Whether the root verifier exposes the input commitment of the App VM.
+    #[serde(default)]
+    pub expose_input_commit: bool,
+    /// Whether the root verifier exposes the data segments commitment of the App VM.
+    #[serde(default)]
+    pub expose_data_segments_commit: bool,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct AggProvingKey {
+    pub agg_stark_pk: AggStarkProvingKey,
+    #[serde(alias = "halo2_pk")]
+    pub snark_pk: SnarkProvingKey,
+}
+
+/// [AggProvingKey] as serialized before the SNARK wrapper backend was configurable, when it
+/// always had a Halo2 wrapper. Only used to read existing keys, see
+/// [read_agg_pk_from_file](crate::fs::read_agg_pk_from_file).
+#[derive(Serialize, Deserialize)]
+pub(crate) struct LegacyAggProvingKey {
     pub agg_stark_pk: AggStarkProvingKey,
     pub halo2_pk: Halo2ProvingKey,
 }
 
+impl From<LegacyAggProvingKey> for AggProvingKey {
+    fn from(legacy: LegacyAggProvingKey) -> Self {
+        Self {
+            agg_stark_pk: legacy.agg_stark_pk,
+            snark_pk: SnarkProvingKey::Halo2(legacy.halo2_pk),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct AggStarkProvingKey {
     pub leaf_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
     pub internal_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
     pub internal_committed_exe: Arc<NonRootCommittedExe>,
     pub root_verifier_pk: RootVerifierProvingKey,
+    /// If set, the internal and root verifiers only accept proofs of these leaf verifiers.
+    /// Otherwise they accept proofs of any leaf verifier.
+    #[serde(default)]
+    pub leaf_verifier_commits: Option<LeafVerifierCommitSet>,
 }
 
 /// Attention: the size of this struct is VERY large, usually >10GB.
@@ -81,6 +132,13 @@ pub struct Halo2ProvingKey {
     pub profiling: bool,
 }
 
+/// Proving key of the SNARK wrapping the root verifier proof for the EVM.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum SnarkProvingKey {
+    Halo2(Halo2ProvingKey),
+    Groth16(Groth16WrapperProvingKey),
+}
+
 impl<VC: VmConfig<F>> AppProvingKey<VC>
 where
     VC::Executor: Chip<SC>,
@@ -105,7 +163,7 @@ where
             let leaf_engine = BabyBearPoseidon2Engine::new(config.leaf_fri_params.fri_params);
             let leaf_program = LeafVmVerifierConfig {
                 app_fri_params: config.app_fri_params.fri_params,
-                app_system_config: config.app_vm_config.system().clone(),
+                app_system_config: config.app_vm_config.resolved_system_config(),
                 compiler_options: config.compiler_options,
             }
             .build_program(&app_vm_pk.vm_pk.get_vk());
@@ -152,6 +210,69 @@ impl AggStarkProvingKey {
     }
 
     pub fn dummy_proof_and_keygen(config: AggStarkConfig) -> (Self, Proof<SC>) {
+        Self::dummy_proof_and_keygen_impl(config, None, |leaf_vm_pk| {
+            let fri_params = standard_fri_params_with_100_bits_conjectured_security(1);
+            dummy_leaf_proof_riscv_app_vm(leaf_vm_pk, config.max_num_user_public_values, fri_params)
+        })
+    }
+
+    /// Like [Self::keygen], but the internal and root verifiers only accept proofs of the leaf
+    /// verifiers in `leaf_verifier_commits`, so that one set of aggregation keys serves all the
+    /// registered App VM configs. The dummy proofs for keygen are generated with `dummy_app_pk`,
+    /// which must be one of them.
+    pub fn keygen_with_leaf_verifier_commits<VC: VmConfig<F>>(
+        config: AggStarkConfig,
+        leaf_verifier_commits: LeafVerifierCommitSet,
+        dummy_app_pk: &AppProvingKey<VC>,
+    ) -> Self
+    where
+        VC: Sync,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        tracing::info_span!("agg_stark_keygen", group = "agg_stark_keygen").in_scope(|| {
+            Self::dummy_proof_and_keygen_with_leaf_verifier_commits(
+                config,
+                leaf_verifier_commits,
+                dummy_app_pk,
+            )
+            .0
+        })
+    }
+
+    pub fn dummy_proof_and_keygen_with_leaf_verifier_commits<VC: VmConfig<F>>(
+        config: AggStarkConfig,
+        leaf_verifier_commits: LeafVerifierCommitSet,
+        dummy_app_pk: &AppProvingKey<VC>,
+    ) -> (Self, Proof<SC>)
+    where
+        VC: Sync,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        assert!(
+            leaf_verifier_commits.contains(&dummy_app_pk.commit_in_babybear()),
+            "Leaf verifier of the dummy App VM is not in the leaf verifier commits"
+        );
+        assert_eq!(
+            dummy_app_pk.leaf_fri_params, config.leaf_fri_params,
+            "Dummy App VM is incompatible with Agg VM because of leaf FRI parameters"
+        );
+        assert_eq!(
+            dummy_app_pk.num_public_values(),
+            config.max_num_user_public_values,
+            "Dummy App VM is incompatible with Agg VM because of the number of public values"
+        );
+        Self::dummy_proof_and_keygen_impl(config, Some(leaf_verifier_commits), |leaf_vm_pk| {
+            dummy_leaf_proof_for_app(leaf_vm_pk, dummy_app_pk)
+        })
+    }
+
+    fn dummy_proof_and_keygen_impl(
+        config: AggStarkConfig,
+        leaf_verifier_commits: Option<LeafVerifierCommitSet>,
+        dummy_leaf_proof: impl FnOnce(Arc<VmProvingKey<SC, NativeConfig>>) -> Proof<SC>,
+    ) -> (Self, Proof<SC>) {
         let leaf_vm_config = config.leaf_vm_config();
         let internal_vm_config = config.internal_vm_config();
         let root_vm_config = config.root_verifier_vm_config();
@@ -188,6 +309,7 @@ impl AggStarkProvingKey {
             leaf_fri_params: config.leaf_fri_params,
             internal_fri_params: config.internal_fri_params,
             compiler_options: config.compiler_options,
+            leaf_verifier_commits: leaf_verifier_commits.clone(),
         }
         .build_program(&leaf_vm_vk, &internal_vm_vk);
         let internal_committed_exe = Arc::new(VmCommittedExe::<SC>::commit(
@@ -195,11 +317,17 @@ impl AggStarkProvingKey {
             internal_vm.engine.config.pcs(),
         ));
 
-        let internal_proof = dummy_internal_proof_riscv_app_vm(
-            leaf_vm_pk.clone(),
+        let leaf_proof = dummy_leaf_proof(leaf_vm_pk.clone());
+        let leaf_verifier_commit_proof = leaf_verifier_commits.as_ref().map(|commits| {
+            let leaf_verifier_commit =
+                leaf_proof.commitments.main_trace[PROGRAM_CACHED_TRACE_INDEX].into();
+            commits.prove(&leaf_verifier_commit).unwrap()
+        });
+        let internal_proof = dummy_internal_proof(
             internal_vm_pk.clone(),
             internal_committed_exe.clone(),
-            config.max_num_user_public_values,
+            leaf_proof,
+            leaf_verifier_commit_proof.as_ref(),
         );
 
         let root_verifier_pk = {
@@ -209,8 +337,12 @@ impl AggStarkProvingKey {
                 leaf_fri_params: config.leaf_fri_params,
                 internal_fri_params: config.internal_fri_params,
                 num_public_values: config.max_num_user_public_values,
+                public_values_mode: config.public_values_mode,
+                expose_input_commit: config.expose_input_commit,
+                expose_data_segments_commit: config.expose_data_segments_commit,
                 internal_vm_verifier_commit: internal_committed_exe.get_program_commit().into(),
                 compiler_options: config.compiler_options,
+                leaf_verifier_commits: leaf_verifier_commits.clone(),
             }
             .build_program(&leaf_vm_vk, &internal_vm_vk);
             let root_committed_exe = Arc::new(VmCommittedExe::<RootSC>::commit(
@@ -226,6 +358,7 @@ impl AggStarkProvingKey {
                 root_vm_config.clone(),
                 root_committed_exe.exe.clone(),
                 &internal_proof,
+                leaf_verifier_commit_proof,
             );
             let root_air_perm = AirIdPermutation::compute(&air_heights);
             root_air_perm.permute(&mut vm_pk.per_air);
@@ -247,6 +380,7 @@ impl AggStarkProvingKey {
                 internal_vm_pk,
                 internal_committed_exe,
                 root_verifier_pk,
+                leaf_verifier_commits,
             },
             internal_proof,
         )
@@ -256,13 +390,24 @@ impl AggStarkProvingKey {
         self.internal_committed_exe.get_program_commit().into()
     }
 
+    pub fn get_compressed_app_vk(&self) -> CompressedAppVerifyingKey {
+        CompressedAppVerifyingKey {
+            fri_params: self.internal_vm_pk.fri_params,
+            internal_vm_vk: self.internal_vm_pk.vm_pk.get_vk(),
+            internal_program_commit: self.internal_program_commit(),
+            public_values_mode: self.root_verifier_pk.vm_pk.vm_config.public_values_mode(),
+            expose_input_commit: self.root_verifier_pk.vm_pk.vm_config.expose_input_commit,
+            expose_data_segments_commit: self
+                .root_verifier_pk
+                .vm_pk
+                .vm_config
+                .expose_data_segments_commit,
+        }
+    }
+
+    /// Number of user public values the app must expose.
     pub fn num_public_values(&self) -> usize {
-        self.root_verifier_pk
-            .vm_pk
-            .vm_config
-            .system
-            .num_public_values
-            - (2 * DIGEST_SIZE)
+        self.root_verifier_pk.vm_pk.vm_config.num_user_public_values
     }
 }
 
@@ -277,13 +422,13 @@ pub struct RootVerifierProvingKey {
     /// - AIR proving key in `MultiStarkProvingKey` is ordered by trace height.
     /// - `VmConfig.overridden_executor_heights` is specified and is in the original AIR order.
     /// - `VmConfig.memory_config.boundary_air_height` is specified.
-    pub vm_pk: Arc<VmProvingKey<RootSC, NativeConfig>>,
+    pub vm_pk: Arc<VmProvingKey<RootSC, RootVmConfig>>,
     /// Committed executable for the root VM.
     pub root_committed_exe: Arc<VmCommittedExe<RootSC>>,
     /// The constant trace heights, ordered by AIR ID.
     pub air_heights: Vec<usize>,
     // The following is currently not used:
-    // The constant trace heights, ordered according to an internal ordering determined by the `NativeConfig`.
+    // The constant trace heights, ordered according to an internal ordering determined by the `RootVmConfig`.
     // pub internal_heights: VmComplexTraceHeights,
 }
 
@@ -296,7 +441,8 @@ impl RootVerifierProvingKey {
 impl AggProvingKey {
     /// Attention:
     /// - This function is very expensive. Usually it requires >64GB memory and takes >10 minutes.
-    /// - Please make sure SRS(KZG parameters) is already downloaded.
+    /// - Please make sure SRS(KZG parameters) is already downloaded. The Groth16 backend does not
+    ///   read any KZG parameters.
     #[tracing::instrument(level = "info", fields(group = "agg_keygen"), skip_all)]
     pub fn keygen(
         config: AggConfig,
@@ -311,26 +457,90 @@ impl AggProvingKey {
             AggStarkProvingKey::dummy_proof_and_keygen(agg_stark_config);
         let dummy_root_proof = agg_stark_pk
             .root_verifier_pk
-            .generate_dummy_root_proof(dummy_internal_proof);
-        let verifier = agg_stark_pk.root_verifier_pk.keygen_static_verifier(
-            &reader.read_params(halo2_config.verifier_k),
+            .generate_dummy_root_proof(dummy_internal_proof, None);
+        Self::keygen_snark(
+            agg_stark_pk,
             dummy_root_proof,
+            halo2_config,
+            reader,
             pv_handler,
-        );
-        let dummy_snark = verifier.generate_dummy_snark(reader);
-        let wrapper = if let Some(wrapper_k) = halo2_config.wrapper_k {
-            Halo2WrapperProvingKey::keygen(&reader.read_params(wrapper_k), dummy_snark)
-        } else {
-            Halo2WrapperProvingKey::keygen_auto_tune(reader, dummy_snark)
-        };
-        let halo2_pk = Halo2ProvingKey {
-            verifier,
-            wrapper,
-            profiling: halo2_config.profiling,
+        )
+    }
+
+    /// Like [Self::keygen], but the aggregation keys only accept proofs of the leaf verifiers in
+    /// `leaf_verifier_commits`. See [AggStarkProvingKey::keygen_with_leaf_verifier_commits].
+    #[tracing::instrument(level = "info", fields(group = "agg_keygen"), skip_all)]
+    pub fn keygen_with_leaf_verifier_commits<VC: VmConfig<F>>(
+        config: AggConfig,
+        leaf_verifier_commits: LeafVerifierCommitSet,
+        dummy_app_pk: &AppProvingKey<VC>,
+        reader: &impl Halo2ParamsReader,
+        pv_handler: Option<&impl StaticVerifierPvHandler>,
+    ) -> Self
+    where
+        VC: Sync,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let AggConfig {
+            agg_stark_config,
+            halo2_config,
+        } = config;
+        let leaf_verifier_commit_proof =
+            leaf_verifier_commits.prove(&dummy_app_pk.commit_in_babybear());
+        let (agg_stark_pk, dummy_internal_proof) =
+            AggStarkProvingKey::dummy_proof_and_keygen_with_leaf_verifier_commits(
+                agg_stark_config,
+                leaf_verifier_commits,
+                dummy_app_pk,
+            );
+        let dummy_root_proof = agg_stark_pk
+            .root_verifier_pk
+            .generate_dummy_root_proof(dummy_internal_proof, leaf_verifier_commit_proof);
+        Self::keygen_snark(
+            agg_stark_pk,
+            dummy_root_proof,
+            halo2_config,
+            reader,
+            pv_handler,
+        )
+    }
+
+    fn keygen_snark(
+        agg_stark_pk: AggStarkProvingKey,
+        dummy_root_proof: Proof<RootSC>,
+        halo2_config: Halo2Config,
+        reader: &impl Halo2ParamsReader,
+        pv_handler: Option<&impl StaticVerifierPvHandler>,
+    ) -> Self {
+        let snark_pk = match halo2_config.wrapper_backend {
+            WrapperBackend::Halo2 => {
+                let verifier = agg_stark_pk.root_verifier_pk.keygen_static_verifier(
+                    &reader.read_params(halo2_config.verifier_k),
+                    dummy_root_proof,
+                    pv_handler,
+                );
+                let dummy_snark = verifier.generate_dummy_snark(reader);
+                let wrapper = if let Some(wrapper_k) = halo2_config.wrapper_k {
+                    Halo2WrapperProvingKey::keygen(&reader.read_params(wrapper_k), dummy_snark)
+                } else {
+                    Halo2WrapperProvingKey::keygen_auto_tune(reader, dummy_snark)
+                };
+                SnarkProvingKey::Halo2(Halo2ProvingKey {
+                    verifier,
+                    wrapper,
+                    profiling: halo2_config.profiling,
+                })
+            }
+            WrapperBackend::Groth16 => SnarkProvingKey::Groth16(
+                agg_stark_pk
+                    .root_verifier_pk
+                    .keygen_groth16_wrapper(&dummy_root_proof, pv_handler),
+            ),
         };
         Self {
             agg_stark_pk,
-            halo2_pk,
+            snark_pk,
         }
     }
 }
@@ -1,18 +1,25 @@
 extern crate core;
 
-use std::{fs::read, path::Path, sync::Arc};
+use std::{borrow::Borrow, fs::read, path::Path, sync::Arc};
 
-use commit::commit_app_exe;
-use config::AppConfig;
-use eyre::Result;
-use keygen::{AppProvingKey, AppVerifyingKey};
+use commit::{commit_app_exe, compute_exe_commit, AppExecutionCommit};
+use config::{AggStarkConfig, AppConfig, PublicValuesMode};
+use eyre::{bail, eyre, Result};
+use keygen::{AppProvingKey, AppVerifyingKey, CompressedAppVerifyingKey};
 use openvm_build::{
     build_guest_package, find_unique_executable, get_package, GuestOptions, TargetFilter,
 };
 use openvm_circuit::{
-    arch::{instructions::exe::VmExe, ExecutionError, VmConfig, VmExecutor},
-    system::{memory::tree::public_values::extract_public_values, program::trace::VmCommittedExe},
+    arch::{
+        hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+        instructions::exe::VmExe,
+        ExecutionError, ExecutionJournal, ExitCode, VmConfig, VmExecuteOnlyResult, VmExecutor,
+        CONNECTOR_AIR_ID, PROGRAM_CACHED_TRACE_INDEX, PUBLIC_VALUES_AIR_ID,
+    },
+    system::{connector::VmConnectorPvs, program::trace::VmCommittedExe},
 };
+use openvm_keccak256_circuit::utils::keccak256;
+use openvm_native_compiler::ir::DIGEST_SIZE;
 use openvm_native_recursion::{
     halo2::{
         utils::Halo2ParamsReader,
@@ -25,11 +32,15 @@ use openvm_stark_backend::{engine::StarkEngine, proof::Proof};
 use openvm_stark_sdk::{
     config::{
         baby_bear_poseidon2::{BabyBearPoseidon2Config, BabyBearPoseidon2Engine},
-        baby_bear_poseidon2_root::BabyBearPoseidon2RootConfig,
+        baby_bear_poseidon2_root::{BabyBearPoseidon2RootConfig, BabyBearPoseidon2RootEngine},
         FriParameters,
     },
     engine::StarkFriEngine,
-    openvm_stark_backend::{verifier::VerificationError, Chip},
+    openvm_stark_backend::{
+        p3_field::{FieldAlgebra, PrimeField32},
+        verifier::VerificationError,
+        Chip,
+    },
     p3_baby_bear::BabyBear,
 };
 use openvm_transpiler::{
@@ -39,7 +50,10 @@ use openvm_transpiler::{
     FromElf,
 };
 use prover::vm::ContinuationVmProof;
-use verifier::root::types::RootVmVerifierInput;
+use verifier::{
+    internal::types::{CompressedAppProof, InternalVmVerifierPvs, LeafVerifierCommitSet},
+    root::types::{AggStarkProof, RootVmVerifierInput, RootVmVerifierPvs},
+};
 
 pub mod commit;
 pub mod config;
@@ -55,7 +69,7 @@ pub mod fs;
 
 use crate::{
     config::AggConfig,
-    keygen::{AggProvingKey, AggStarkProvingKey},
+    keygen::{AggProvingKey, AggStarkProvingKey, SnarkProvingKey},
     prover::{AppProver, ContinuationProver, StarkProver},
 };
 
@@ -111,13 +125,62 @@ impl Sdk {
         VC::Periphery: Chip<SC>,
     {
         let vm = VmExecutor::new(vm_config);
-        let final_memory = vm.execute(exe, inputs)?;
-        let public_values = extract_public_values(
-            &vm.config.system().memory_config.memory_dimensions(),
-            vm.config.system().num_public_values,
-            final_memory.as_ref().unwrap(),
-        );
-        Ok(public_values)
+        Self::public_values_on_success(vm.execute_only(exe, inputs)?)
+    }
+
+    /// Executes like [Self::execute], but runs the RV32IM instructions of `exe` as native code
+    /// compiled ahead of time, see [openvm_rv32im_aot].
+    #[cfg(feature = "aot")]
+    pub fn execute_aot<VC: VmConfig<F>>(
+        &self,
+        exe: VmExe<F>,
+        vm_config: VC,
+        inputs: StdIn,
+    ) -> Result<Vec<F>> {
+        let executor = openvm_rv32im_aot::AotExecutor::new(vm_config, exe)?;
+        Ok(Self::public_values_on_success(
+            executor.execute(inputs)?.result,
+        )?)
+    }
+
+    /// Executes like [Self::execute], and also returns the journal of the non-deterministic
+    /// inputs of the execution. The journal is returned even if execution fails, so that the
+    /// failure can be reproduced with [Self::replay].
+    pub fn execute_and_record<VC: VmConfig<F>>(
+        &self,
+        exe: VmExe<F>,
+        vm_config: VC,
+        inputs: StdIn,
+    ) -> (Result<Vec<F>, ExecutionError>, ExecutionJournal<F>)
+    where
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let vm = VmExecutor::new(vm_config);
+        let (result, journal) = vm.execute_only_and_record(exe, inputs);
+        (result.and_then(Self::public_values_on_success), journal)
+    }
+
+    /// Executes `exe` again with the inputs recorded by [Self::execute_and_record].
+    pub fn replay<VC: VmConfig<F>>(
+        &self,
+        exe: VmExe<F>,
+        vm_config: VC,
+        journal: ExecutionJournal<F>,
+    ) -> Result<Vec<F>, ExecutionError>
+    where
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let vm = VmExecutor::new(vm_config);
+        Self::public_values_on_success(vm.replay(exe, journal)?)
+    }
+
+    fn public_values_on_success(result: VmExecuteOnlyResult<F>) -> Result<Vec<F>, ExecutionError> {
+        if result.exit_code != ExitCode::Success as u32 {
+            return Err(ExecutionError::FailedWithExitCode(result.exit_code));
+        }
+        Ok(result.public_values)
     }
 
     pub fn commit_app_exe(
@@ -129,6 +192,46 @@ impl Sdk {
         Ok(committed_exe)
     }
 
+    /// Computes the commitments of `exe` run on the App VM of `app_pk`, which are what the
+    /// final proof of an execution is checked against.
+    pub fn compute_app_execution_commit<VC: VmConfig<F>>(
+        &self,
+        app_pk: &AppProvingKey<VC>,
+        exe: VmExe<F>,
+    ) -> Result<AppExecutionCommit<F>> {
+        let committed_exe = commit_app_exe(app_pk.app_fri_params(), exe);
+        Ok(AppExecutionCommit::compute(
+            &app_pk.app_vm_pk.vm_config,
+            &committed_exe,
+            &app_pk.leaf_committed_exe,
+        ))
+    }
+
+    /// Checks that `exe` run on the App VM of `app_pk` has the commitments `expected_commit`.
+    pub fn verify_app_execution_commit<VC: VmConfig<F>>(
+        &self,
+        app_pk: &AppProvingKey<VC>,
+        exe: VmExe<F>,
+        expected_commit: &AppExecutionCommit<F>,
+    ) -> Result<()> {
+        let commit = self.compute_app_execution_commit(app_pk, exe)?;
+        if commit.exe_commit != expected_commit.exe_commit {
+            bail!(
+                "Exe commit mismatch: expected {:?}, computed {:?}",
+                expected_commit.exe_commit,
+                commit.exe_commit
+            );
+        }
+        if commit.leaf_vm_verifier_commit != expected_commit.leaf_vm_verifier_commit {
+            bail!(
+                "Leaf verifier commit mismatch: expected {:?}, computed {:?}",
+                expected_commit.leaf_vm_verifier_commit,
+                commit.leaf_vm_verifier_commit
+            );
+        }
+        Ok(())
+    }
+
     pub fn app_keygen<VC: VmConfig<F>>(&self, config: AppConfig<VC>) -> Result<AppProvingKey<VC>>
     where
         VC::Executor: Chip<SC>,
@@ -145,6 +248,7 @@ impl Sdk {
         inputs: StdIn,
     ) -> Result<ContinuationVmProof<SC>>
     where
+        VC: Sync,
         VC::Executor: Chip<SC>,
         VC::Periphery: Chip<SC>,
     {
@@ -189,6 +293,57 @@ impl Sdk {
         Ok(agg_pk)
     }
 
+    pub fn agg_stark_keygen(&self, config: AggStarkConfig) -> Result<AggStarkProvingKey> {
+        let agg_stark_pk = AggStarkProvingKey::keygen(config);
+        Ok(agg_stark_pk)
+    }
+
+    /// Aggregation keygen for several App VM configs, whose leaf verifier commits are in
+    /// `leaf_verifier_commits`. `dummy_app_pk` must be one of them.
+    pub fn agg_keygen_with_leaf_verifier_commits<VC: VmConfig<F>>(
+        &self,
+        config: AggConfig,
+        leaf_verifier_commits: LeafVerifierCommitSet,
+        dummy_app_pk: &AppProvingKey<VC>,
+        reader: &impl Halo2ParamsReader,
+        pv_handler: Option<&impl StaticVerifierPvHandler>,
+    ) -> Result<AggProvingKey>
+    where
+        VC: Sync,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let agg_pk = AggProvingKey::keygen_with_leaf_verifier_commits(
+            config,
+            leaf_verifier_commits,
+            dummy_app_pk,
+            reader,
+            pv_handler,
+        );
+        Ok(agg_pk)
+    }
+
+    /// Aggregation STARK keygen for several App VM configs, whose leaf verifier commits are in
+    /// `leaf_verifier_commits`. `dummy_app_pk` must be one of them.
+    pub fn agg_stark_keygen_with_leaf_verifier_commits<VC: VmConfig<F>>(
+        &self,
+        config: AggStarkConfig,
+        leaf_verifier_commits: LeafVerifierCommitSet,
+        dummy_app_pk: &AppProvingKey<VC>,
+    ) -> Result<AggStarkProvingKey>
+    where
+        VC: Sync,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let agg_stark_pk = AggStarkProvingKey::keygen_with_leaf_verifier_commits(
+            config,
+            leaf_verifier_commits,
+            dummy_app_pk,
+        );
+        Ok(agg_stark_pk)
+    }
+
     pub fn generate_root_verifier_input<VC: VmConfig<F>>(
         &self,
         app_pk: Arc<AppProvingKey<VC>>,
@@ -197,6 +352,7 @@ impl Sdk {
         inputs: StdIn,
     ) -> Result<RootVmVerifierInput<SC>>
     where
+        VC: Sync,
         VC::Executor: Chip<SC>,
         VC::Periphery: Chip<SC>,
     {
@@ -205,6 +361,230 @@ impl Sdk {
         Ok(proof)
     }
 
+    /// Generates an aggregated STARK proof of the app execution. Unlike the EVM proof, this does
+    /// not require Halo2 proving keys or KZG parameters.
+    pub fn generate_agg_stark_proof<VC: VmConfig<F>>(
+        &self,
+        app_pk: Arc<AppProvingKey<VC>>,
+        app_exe: Arc<NonRootCommittedExe>,
+        agg_stark_pk: AggStarkProvingKey,
+        inputs: StdIn,
+    ) -> Result<AggStarkProof<RootSC>>
+    where
+        VC: Sync,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let stark_prover = StarkProver::new(app_pk, app_exe, agg_stark_pk);
+        let proof = stark_prover.generate_proof_for_outer_recursion(inputs);
+        Ok(AggStarkProof { proof })
+    }
+
+    /// Verifies an aggregated STARK proof against the root verifier of `agg_stark_pk` and checks
+    /// that it commits to `expected_app_commit`. Returns the public values of the root verifier,
+    /// including the input commitment, the data segments commitment and the user public values,
+    /// on success. In [PublicValuesMode::Keccak256], the returned user
+    /// public values are the bytes of their Keccak256 digest.
+    ///
+    /// This performs the same checks as the static verifier circuit.
+    pub fn verify_agg_stark_proof(
+        &self,
+        agg_stark_pk: &AggStarkProvingKey,
+        expected_app_commit: &AppExecutionCommit<F>,
+        proof: &AggStarkProof<RootSC>,
+    ) -> Result<RootVmVerifierPvs<F>> {
+        let root_verifier_pk = &agg_stark_pk.root_verifier_pk;
+        let proof = &proof.proof;
+        let e = BabyBearPoseidon2RootEngine::new(root_verifier_pk.vm_pk.fri_params);
+        e.verify(&root_verifier_pk.vm_pk.vm_pk.get_vk(), proof)
+            .map_err(|reason| eyre!("Sdk::verify_agg_stark_proof: {reason:?}"))?;
+
+        // Program AIR is the only AIR with a cached trace. The cached trace index doesn't
+        // change after reordering.
+        let program_commit = proof
+            .commitments
+            .main_trace
+            .get(PROGRAM_CACHED_TRACE_INDEX)
+            .ok_or_else(|| eyre!("Missing program commitment in root proof"))?;
+        if *program_commit != root_verifier_pk.root_committed_exe.get_program_commit() {
+            bail!("Root verifier program commitment mismatch");
+        }
+
+        let special_air_ids = root_verifier_pk.air_id_permutation().get_special_air_ids();
+        let air_public_values = |air_id: usize| {
+            proof
+                .per_air
+                .iter()
+                .find(|air_proof_data| air_proof_data.air_id == air_id)
+                .map(|air_proof_data| air_proof_data.public_values.as_slice())
+                .ok_or_else(|| eyre!("Missing AIR {air_id} in root proof"))
+        };
+
+        let connector_pvs: &VmConnectorPvs<F> =
+            air_public_values(special_air_ids.connector_air_id)?.borrow();
+        if connector_pvs.initial_pc != F::ZERO
+            || connector_pvs.is_terminate != F::ONE
+            || connector_pvs.exit_code != F::ZERO
+        {
+            bail!("Root verifier did not exit successfully");
+        }
+
+        let pvs = RootVmVerifierPvs::from_flatten(
+            air_public_values(special_air_ids.public_values_air_id)?.to_vec(),
+            &root_verifier_pk.vm_pk.vm_config,
+        );
+        if pvs.exe_commit != expected_app_commit.exe_commit {
+            bail!(
+                "Exe commit mismatch: expected {:?}, proof has {:?}",
+                expected_app_commit.exe_commit,
+                pvs.exe_commit
+            );
+        }
+        if pvs.leaf_verifier_commit != expected_app_commit.leaf_vm_verifier_commit {
+            bail!(
+                "Leaf verifier commit mismatch: expected {:?}, proof has {:?}",
+                expected_app_commit.leaf_vm_verifier_commit,
+                pvs.leaf_verifier_commit
+            );
+        }
+        Ok(pvs)
+    }
+
+    /// Generates a compressed app proof, i.e. a single proof of the internal verifier which
+    /// aggregates all app segment proofs. It is much smaller and faster to verify than the app
+    /// proof, and it can still be aggregated further by the root verifier.
+    pub fn generate_compressed_proof<VC: VmConfig<F>>(
+        &self,
+        app_pk: Arc<AppProvingKey<VC>>,
+        app_exe: Arc<NonRootCommittedExe>,
+        agg_stark_pk: AggStarkProvingKey,
+        inputs: StdIn,
+    ) -> Result<CompressedAppProof<SC>>
+    where
+        VC: Sync,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let stark_prover = StarkProver::new(app_pk, app_exe, agg_stark_pk);
+        let proof = stark_prover.generate_compressed_proof(inputs);
+        Ok(proof)
+    }
+
+    /// Verifies a compressed app proof against `compressed_app_vk` and checks that it commits to
+    /// `expected_app_commit` and to the user public values in the proof. Returns the same public
+    /// values as [Self::verify_agg_stark_proof] on success, so in
+    /// [PublicValuesMode::Keccak256] the returned user public values are the bytes of their
+    /// Keccak256 digest.
+    pub fn verify_compressed_proof(
+        &self,
+        compressed_app_vk: &CompressedAppVerifyingKey,
+        expected_app_commit: &AppExecutionCommit<F>,
+        proof: &CompressedAppProof<SC>,
+    ) -> Result<RootVmVerifierPvs<F>> {
+        let CompressedAppProof {
+            proof,
+            user_public_values,
+        } = proof;
+        let e = BabyBearPoseidon2Engine::new(compressed_app_vk.fri_params);
+        e.verify(&compressed_app_vk.internal_vm_vk, proof)
+            .map_err(|reason| eyre!("Sdk::verify_compressed_proof: {reason:?}"))?;
+
+        let program_commit: [F; DIGEST_SIZE] = (*proof
+            .commitments
+            .main_trace
+            .get(PROGRAM_CACHED_TRACE_INDEX)
+            .ok_or_else(|| eyre!("Missing program commitment in compressed proof"))?)
+        .into();
+        if program_commit != compressed_app_vk.internal_program_commit {
+            bail!("Internal verifier program commitment mismatch");
+        }
+
+        let air_public_values = |air_id: usize| {
+            proof
+                .per_air
+                .iter()
+                .find(|air_proof_data| air_proof_data.air_id == air_id)
+                .map(|air_proof_data| air_proof_data.public_values.as_slice())
+                .ok_or_else(|| eyre!("Missing AIR {air_id} in compressed proof"))
+        };
+        let connector_pvs: &VmConnectorPvs<F> = air_public_values(CONNECTOR_AIR_ID)?.borrow();
+        if connector_pvs.initial_pc != F::ZERO
+            || connector_pvs.is_terminate != F::ONE
+            || connector_pvs.exit_code != F::ZERO
+        {
+            bail!("Internal verifier did not exit successfully");
+        }
+
+        let internal_pvs = air_public_values(PUBLIC_VALUES_AIR_ID)?;
+        if internal_pvs.len() != InternalVmVerifierPvs::<u8>::width() {
+            bail!("Unexpected number of internal verifier public values");
+        }
+        let internal_pvs: &InternalVmVerifierPvs<F> = internal_pvs.borrow();
+        // The internal verifier reads its own program commit from its input.
+        if internal_pvs.extra_pvs.internal_program_commit != program_commit {
+            bail!("Internal verifier committed to a different internal program");
+        }
+        let app_pvs = &internal_pvs.vm_verifier_pvs;
+        // The same checks as the root verifier.
+        if app_pvs.connector.is_terminate != F::ONE || app_pvs.connector.exit_code != F::ZERO {
+            bail!("App program did not exit successfully");
+        }
+        if app_pvs.connector.initial_input_commit != [F::ZERO; DIGEST_SIZE] {
+            bail!("App input commitment does not start from zero");
+        }
+        let num_chunks = user_public_values.len() / DIGEST_SIZE;
+        if user_public_values.len() % DIGEST_SIZE != 0 || !num_chunks.is_power_of_two() {
+            bail!("Invalid number of user public values");
+        }
+        if vm_poseidon2_hasher().merkle_root(user_public_values) != app_pvs.public_values_commit {
+            bail!("User public values do not match the public values commitment");
+        }
+
+        let exe_commit = compute_exe_commit(
+            &app_pvs.app_commit,
+            &app_pvs.exe_memory_root,
+            app_pvs.connector.initial_pc,
+        );
+        if exe_commit != expected_app_commit.exe_commit {
+            bail!(
+                "Exe commit mismatch: expected {:?}, proof has {:?}",
+                expected_app_commit.exe_commit,
+                exe_commit
+            );
+        }
+        let leaf_verifier_commit = internal_pvs.extra_pvs.leaf_verifier_commit;
+        if leaf_verifier_commit != expected_app_commit.leaf_vm_verifier_commit {
+            bail!(
+                "Leaf verifier commit mismatch: expected {:?}, proof has {:?}",
+                expected_app_commit.leaf_vm_verifier_commit,
+                leaf_verifier_commit
+            );
+        }
+        let public_values = match compressed_app_vk.public_values_mode {
+            PublicValuesMode::Raw => user_public_values.clone(),
+            PublicValuesMode::Keccak256 => {
+                // The root verifier only accepts bytes.
+                let bytes = user_public_values
+                    .iter()
+                    .map(|x| u8::try_from(x.as_canonical_u32()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| eyre!("User public values must be bytes"))?;
+                keccak256(&bytes).map(F::from_canonical_u8).to_vec()
+            }
+        };
+        Ok(RootVmVerifierPvs {
+            exe_commit,
+            leaf_verifier_commit,
+            input_commit: compressed_app_vk
+                .expose_input_commit
+                .then_some(app_pvs.connector.final_input_commit),
+            data_segments_commit: compressed_app_vk
+                .expose_data_segments_commit
+                .then_some(app_pvs.data_segments_commit),
+            public_values,
+        })
+    }
+
     pub fn generate_evm_proof<VC: VmConfig<F>>(
         &self,
         reader: &impl Halo2ParamsReader,
@@ -214,6 +594,7 @@ impl Sdk {
         inputs: StdIn,
     ) -> Result<EvmProof>
     where
+        VC: Sync,
         VC::Executor: Chip<SC>,
         VC::Periphery: Chip<SC>,
     {
@@ -227,8 +608,13 @@ impl Sdk {
         reader: &impl Halo2ParamsReader,
         agg_pk: &AggProvingKey,
     ) -> Result<EvmVerifier> {
-        let params = reader.read_params(agg_pk.halo2_pk.wrapper.pinning.metadata.config_params.k);
-        let evm_verifier = agg_pk.halo2_pk.wrapper.generate_evm_verifier(&params);
+        let evm_verifier = match &agg_pk.snark_pk {
+            SnarkProvingKey::Halo2(halo2_pk) => {
+                let params = reader.read_params(halo2_pk.wrapper.pinning.metadata.config_params.k);
+                halo2_pk.wrapper.generate_evm_verifier(&params)
+            }
+            SnarkProvingKey::Groth16(groth16_pk) => groth16_pk.generate_evm_verifier(),
+        };
         Ok(evm_verifier)
     }
 
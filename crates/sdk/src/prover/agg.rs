@@ -1,4 +1,8 @@
-use std::sync::Arc;
+use std::{
+    collections::BTreeMap,
+    sync::{mpsc, Arc},
+    thread,
+};
 
 use openvm_native_circuit::NativeConfig;
 use openvm_native_recursion::hints::Hintable;
@@ -14,7 +18,8 @@ use crate::{
         RootVerifierLocalProver,
     },
     verifier::{
-        internal::types::InternalVmVerifierInput, leaf::types::LeafVmVerifierInput,
+        internal::types::{CompressedAppProof, InternalVmVerifierInput, LeafVerifierCommitProof},
+        leaf::types::{DataSegmentsRootProof, LeafVmVerifierInput, UserPublicValuesRootProof},
         root::types::RootVmVerifierInput,
     },
     NonRootCommittedExe, RootSC, F, SC,
@@ -30,6 +35,9 @@ pub struct AggStarkProver {
 
     internal_prover: VmLocalProver<SC, NativeConfig, BabyBearPoseidon2Engine>,
     root_prover: RootVerifierLocalProver,
+    /// Proof that the leaf verifier is allowed, if the aggregation VMs only accept some leaf
+    /// verifiers.
+    leaf_verifier_commit_proof: Option<LeafVerifierCommitProof<F>>,
 
     pub num_children_internal: usize,
     pub max_internal_wrapper_layers: usize,
@@ -45,6 +53,15 @@ impl AggStarkProver {
         agg_stark_pk: AggStarkProvingKey,
         leaf_committed_exe: Arc<NonRootCommittedExe>,
     ) -> Self {
+        let leaf_verifier_commit_proof =
+            agg_stark_pk
+                .leaf_verifier_commits
+                .as_ref()
+                .map(|leaf_verifier_commits| {
+                    leaf_verifier_commits
+                        .prove(&leaf_committed_exe.get_program_commit().into())
+                        .expect("Leaf verifier is not accepted by Agg VM")
+                });
         let leaf_prover = VmLocalProver::<SC, NativeConfig, BabyBearPoseidon2Engine>::new(
             agg_stark_pk.leaf_vm_pk,
             leaf_committed_exe,
@@ -62,6 +79,7 @@ impl AggStarkProver {
             leaf_controller,
             internal_prover,
             root_prover,
+            leaf_verifier_commit_proof,
             num_children_internal: DEFAULT_NUM_CHILDREN_INTERNAL,
             max_internal_wrapper_layers: DEFAULT_MAX_INTERNAL_WRAPPER_LAYERS,
         }
@@ -85,7 +103,7 @@ impl AggStarkProver {
     /// Generate a proof to aggregate app proofs.
     pub fn generate_agg_proof(&self, app_proofs: ContinuationVmProof<SC>) -> Proof<RootSC> {
         let root_verifier_input = self.generate_root_verifier_input(app_proofs);
-        self.generate_root_proof_impl(root_verifier_input)
+        self.generate_root_proof(root_verifier_input)
     }
 
     pub fn generate_root_verifier_input(
@@ -100,6 +118,25 @@ impl AggStarkProver {
         RootVmVerifierInput {
             proofs: vec![internal_proof],
             public_values,
+            leaf_verifier_commit_proof: self.leaf_verifier_commit_proof.clone(),
+        }
+    }
+
+    /// Like [Self::generate_root_verifier_input], but starts leaf aggregation while `prove_app` is
+    /// still proving app segments. See [LeafProvingController::generate_proof_streaming].
+    pub fn generate_root_verifier_input_streaming(
+        &self,
+        prove_app: impl FnOnce(&mut dyn FnMut(usize, Proof<SC>)) -> ContinuationVmProof<SC>,
+    ) -> RootVmVerifierInput<SC> {
+        let (app_proofs, leaf_proofs) = self
+            .leaf_controller
+            .generate_proof_streaming(&self.leaf_prover, prove_app);
+        let public_values = app_proofs.user_public_values.public_values;
+        let internal_proof = self.generate_internal_proof_impl(leaf_proofs, &public_values);
+        RootVmVerifierInput {
+            proofs: vec![internal_proof],
+            public_values,
+            leaf_verifier_commit_proof: self.leaf_verifier_commit_proof.clone(),
         }
     }
 
@@ -119,6 +156,7 @@ impl AggStarkProver {
                         .execute_for_air_heights(RootVmVerifierInput {
                             proofs: vec![proofs[0].clone()],
                             public_values: public_values.to_vec(),
+                            leaf_verifier_commit_proof: self.leaf_verifier_commit_proof.clone(),
                         });
                 // Root verifier can handle the internal proof. We can stop here.
                 if heights_le(
@@ -132,41 +170,99 @@ impl AggStarkProver {
                 }
                 wrapper_layers += 1;
             }
-            let internal_inputs = InternalVmVerifierInput::chunk_leaf_or_internal_proofs(
-                self.internal_prover
-                    .committed_exe
-                    .get_program_commit()
-                    .into(),
-                &proofs,
-                self.num_children_internal,
-            );
-            proofs = info_span!(
-                "agg_layer",
-                group = format!("internal.{internal_node_height}")
-            )
-            .in_scope(|| {
-                #[cfg(feature = "bench-metrics")]
-                {
-                    metrics::counter!("fri.log_blowup")
-                        .absolute(self.internal_prover.fri_params().log_blowup as u64);
-                    metrics::counter!("num_children").absolute(self.num_children_internal as u64);
-                }
-                internal_inputs
-                    .into_iter()
-                    .map(|input| {
-                        internal_node_idx += 1;
-                        info_span!("single_internal_agg", idx = internal_node_idx,).in_scope(|| {
-                            SingleSegmentVmProver::prove(&self.internal_prover, input.write())
-                        })
-                    })
-                    .collect()
-            });
+            proofs =
+                self.generate_internal_layer(&proofs, internal_node_height, &mut internal_node_idx);
             internal_node_height += 1;
         }
         proofs.pop().unwrap()
     }
 
-    fn generate_root_proof_impl(&self, root_input: RootVmVerifierInput<SC>) -> Proof<RootSC> {
+    /// Proves one layer of internal verifiers, each aggregating `num_children_internal` proofs.
+    fn generate_internal_layer(
+        &self,
+        proofs: &[Proof<SC>],
+        internal_node_height: usize,
+        internal_node_idx: &mut isize,
+    ) -> Vec<Proof<SC>> {
+        let internal_inputs = InternalVmVerifierInput::chunk_leaf_or_internal_proofs(
+            self.internal_prover
+                .committed_exe
+                .get_program_commit()
+                .into(),
+            proofs,
+            self.num_children_internal,
+            self.leaf_verifier_commit_proof.as_ref(),
+        );
+        info_span!(
+            "agg_layer",
+            group = format!("internal.{internal_node_height}")
+        )
+        .in_scope(|| {
+            #[cfg(feature = "bench-metrics")]
+            {
+                metrics::counter!("fri.log_blowup")
+                    .absolute(self.internal_prover.fri_params().log_blowup as u64);
+                metrics::counter!("num_children").absolute(self.num_children_internal as u64);
+            }
+            internal_inputs
+                .into_iter()
+                .map(|input| {
+                    *internal_node_idx += 1;
+                    info_span!("single_internal_agg", idx = *internal_node_idx).in_scope(|| {
+                        SingleSegmentVmProver::prove(&self.internal_prover, input.write())
+                    })
+                })
+                .collect()
+        })
+    }
+
+    /// Generate a compressed app proof, i.e. a single internal verifier proof of the app proofs.
+    /// Unlike [Self::generate_agg_proof], this stops before the root verifier.
+    pub fn generate_compressed_proof(
+        &self,
+        app_proofs: ContinuationVmProof<SC>,
+    ) -> CompressedAppProof<SC> {
+        let leaf_proofs = self
+            .leaf_controller
+            .generate_proof(&self.leaf_prover, &app_proofs);
+        self.compress_leaf_proofs(leaf_proofs, app_proofs.user_public_values.public_values)
+    }
+
+    /// Like [Self::generate_compressed_proof], but starts leaf aggregation while `prove_app` is
+    /// still proving app segments. See [LeafProvingController::generate_proof_streaming].
+    pub fn generate_compressed_proof_streaming(
+        &self,
+        prove_app: impl FnOnce(&mut dyn FnMut(usize, Proof<SC>)) -> ContinuationVmProof<SC>,
+    ) -> CompressedAppProof<SC> {
+        let (app_proofs, leaf_proofs) = self
+            .leaf_controller
+            .generate_proof_streaming(&self.leaf_prover, prove_app);
+        self.compress_leaf_proofs(leaf_proofs, app_proofs.user_public_values.public_values)
+    }
+
+    fn compress_leaf_proofs(
+        &self,
+        leaf_proofs: Vec<Proof<SC>>,
+        user_public_values: Vec<F>,
+    ) -> CompressedAppProof<SC> {
+        let mut internal_node_idx = -1;
+        let mut internal_node_height = 0;
+        let mut proofs = leaf_proofs;
+        // A single leaf proof still needs to be wrapped because compressed proofs are always
+        // internal verifier proofs.
+        while internal_node_height == 0 || proofs.len() > 1 {
+            proofs =
+                self.generate_internal_layer(&proofs, internal_node_height, &mut internal_node_idx);
+            internal_node_height += 1;
+        }
+        CompressedAppProof {
+            proof: proofs.pop().unwrap(),
+            user_public_values,
+        }
+    }
+
+    /// Generate the root proof from the root verifier input.
+    pub fn generate_root_proof(&self, root_input: RootVmVerifierInput<SC>) -> Proof<RootSC> {
         info_span!("agg_layer", group = "root", idx = 0).in_scope(|| {
             let input = root_input.write();
             #[cfg(feature = "bench-metrics")]
@@ -207,6 +303,124 @@ impl LeafProvingController {
                 .collect::<Vec<_>>()
         })
     }
+
+    /// Generates leaf proofs while app segments are still being proven. `prove_app` must move each
+    /// app segment proof into its argument as soon as it is generated, in any order, and return
+    /// the rest of the app proof. The `per_segment` proofs of the returned proof are ignored.
+    ///
+    /// Leaf proofs are generated on a separate thread. A chunk of `num_children` segments is sent
+    /// to it as soon as all of its segments are proven and a later segment exists, i.e. it is
+    /// known not to be the last chunk, which also needs the public values. The first chunk is
+    /// sent after all app segments are proven, because it also needs the data segments proof.
+    pub fn generate_proof_streaming(
+        &self,
+        prover: &VmLocalProver<SC, NativeConfig, BabyBearPoseidon2Engine>,
+        prove_app: impl FnOnce(&mut dyn FnMut(usize, Proof<SC>)) -> ContinuationVmProof<SC>,
+    ) -> (ContinuationVmProof<SC>, Vec<Proof<SC>>) {
+        thread::scope(|s| {
+            let (leaf_sender, leaf_receiver) = mpsc::channel::<(usize, LeafVmVerifierInput<SC>)>();
+            let leaf_worker = s.spawn(move || {
+                info_span!("agg_layer", group = "leaf").in_scope(|| {
+                    #[cfg(feature = "bench-metrics")]
+                    {
+                        metrics::counter!("fri.log_blowup")
+                            .absolute(prover.fri_params().log_blowup as u64);
+                        metrics::counter!("num_children").absolute(self.num_children as u64);
+                    }
+                    let mut leaf_proofs = leaf_receiver
+                        .into_iter()
+                        .map(|(leaf_node_idx, input)| {
+                            let proof = info_span!("single_leaf_agg", idx = leaf_node_idx)
+                                .in_scope(|| {
+                                    SingleSegmentVmProver::prove(prover, input.write_to_stream())
+                                });
+                            (leaf_node_idx, proof)
+                        })
+                        .collect::<Vec<_>>();
+                    leaf_proofs.sort_by_key(|(leaf_node_idx, _)| *leaf_node_idx);
+                    leaf_proofs
+                        .into_iter()
+                        .map(|(_, proof)| proof)
+                        .collect::<Vec<_>>()
+                })
+            });
+
+            let mut next_chunk = 0;
+            let mut first_chunk = None;
+            let mut pending = BTreeMap::new();
+            let app_proofs = prove_app(&mut |seg_idx: usize, proof: Proof<SC>| {
+                pending.insert(seg_idx, proof);
+                loop {
+                    let start = next_chunk * self.num_children;
+                    let end = start + self.num_children;
+                    let is_ready = pending
+                        .last_key_value()
+                        .is_some_and(|(&last, _)| last >= end)
+                        && (start..end).all(|i| pending.contains_key(&i));
+                    if !is_ready {
+                        break;
+                    }
+                    let proofs = (start..end).map(|i| pending.remove(&i).unwrap()).collect();
+                    if next_chunk == 0 {
+                        first_chunk = Some(proofs);
+                    } else {
+                        leaf_sender
+                            .send((
+                                next_chunk,
+                                LeafVmVerifierInput {
+                                    proofs,
+                                    public_values_root_proof: None,
+                                    data_segments_root_proof: None,
+                                },
+                            ))
+                            .expect("leaf proving thread stopped");
+                    }
+                    next_chunk += 1;
+                }
+            });
+
+            // All segments are proven, so the proofs that were not sent yet are contiguous.
+            let mut pending = pending.into_values().peekable();
+            let mut remaining = Vec::new();
+            while pending.peek().is_some() {
+                remaining.push(LeafVmVerifierInput {
+                    proofs: pending.by_ref().take(self.num_children).collect(),
+                    public_values_root_proof: None,
+                    data_segments_root_proof: None,
+                });
+            }
+            remaining.last_mut().unwrap().public_values_root_proof = Some(
+                UserPublicValuesRootProof::extract(&app_proofs.user_public_values),
+            );
+            let data_segments_root_proof = app_proofs
+                .data_segments
+                .as_ref()
+                .map(DataSegmentsRootProof::extract);
+            if let Some(proofs) = first_chunk {
+                leaf_sender
+                    .send((
+                        0,
+                        LeafVmVerifierInput {
+                            proofs,
+                            public_values_root_proof: None,
+                            data_segments_root_proof,
+                        },
+                    ))
+                    .expect("leaf proving thread stopped");
+            } else {
+                remaining[0].data_segments_root_proof = data_segments_root_proof;
+            }
+            for input in (next_chunk..).zip(remaining) {
+                leaf_sender
+                    .send(input)
+                    .expect("leaf proving thread stopped");
+            }
+            drop(leaf_sender);
+            let leaf_proofs = leaf_worker.join().unwrap();
+            tracing::info!("num_leaf_proofs={}", leaf_proofs.len());
+            (app_proofs, leaf_proofs)
+        })
+    }
 }
 
 fn heights_le(a: &[usize], b: &[usize]) -> bool {
@@ -1,16 +1,14 @@
 use std::sync::Arc;
 
 use getset::Getters;
-use openvm_circuit::arch::VmConfig;
+use openvm_circuit::arch::{ParallelProvingConfig, VmConfig};
 use openvm_stark_backend::{proof::Proof, Chip};
 use openvm_stark_sdk::config::baby_bear_poseidon2::BabyBearPoseidon2Engine;
 use tracing::info_span;
 
-use super::vm::SingleSegmentVmProver;
+use super::vm::{ContinuationVmProver, SingleSegmentVmProver};
 use crate::{
-    prover::vm::{
-        local::VmLocalProver, types::VmProvingKey, ContinuationVmProof, ContinuationVmProver,
-    },
+    prover::vm::{local::VmLocalProver, types::VmProvingKey, ContinuationVmProof},
     NonRootCommittedExe, StdIn, F, SC,
 };
 
@@ -46,12 +44,52 @@ impl<VC> AppProver<VC> {
         self
     }
 
+    /// Proves continuation segments concurrently. See [ParallelProvingConfig].
+    pub fn set_parallel_proving(&mut self, parallel_proving: ParallelProvingConfig) -> &mut Self {
+        self.app_prover.set_parallel_proving(parallel_proving);
+        self
+    }
+    pub fn with_parallel_proving(mut self, parallel_proving: ParallelProvingConfig) -> Self {
+        self.set_parallel_proving(parallel_proving);
+        self
+    }
+
     /// Generates proof for every continuation segment
     pub fn generate_app_proof(&self, input: StdIn) -> ContinuationVmProof<SC>
     where
         VC: VmConfig<F>,
+        VC: Sync,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        self.generate_app_proof_impl(|app_prover| ContinuationVmProver::prove(app_prover, input))
+    }
+
+    /// Generates proof for every continuation segment, moving each segment proof into
+    /// `on_segment_proof` as soon as it is generated. The `per_segment` proofs of the returned
+    /// proof are empty.
+    pub fn generate_app_proof_with_callback(
+        &self,
+        input: StdIn,
+        on_segment_proof: impl FnMut(usize, Proof<SC>),
+    ) -> ContinuationVmProof<SC>
+    where
+        VC: VmConfig<F>,
+        VC: Sync,
         VC::Executor: Chip<SC>,
         VC::Periphery: Chip<SC>,
+    {
+        self.generate_app_proof_impl(|app_prover| {
+            app_prover.prove_with_callback(input, on_segment_proof)
+        })
+    }
+
+    fn generate_app_proof_impl(
+        &self,
+        prove: impl FnOnce(&VmLocalProver<SC, VC, BabyBearPoseidon2Engine>) -> ContinuationVmProof<SC>,
+    ) -> ContinuationVmProof<SC>
+    where
+        VC: VmConfig<F>,
     {
         assert!(
             self.vm_config().system().continuation_enabled,
@@ -68,7 +106,7 @@ impl<VC> AppProver<VC> {
             #[cfg(feature = "bench-metrics")]
             metrics::counter!("fri.log_blowup")
                 .absolute(self.app_prover.pk.fri_params.log_blowup as u64);
-            ContinuationVmProver::prove(&self.app_prover, input)
+            prove(&self.app_prover)
         })
     }
 
@@ -0,0 +1,23 @@
+use openvm_native_compiler::prelude::Witness;
+use openvm_native_recursion::{
+    groth16::Groth16WrapperProvingKey, halo2::EvmProof, witness::Witnessable,
+};
+use openvm_stark_sdk::openvm_stark_backend::proof::Proof;
+use tracing::info_span;
+
+use crate::RootSC;
+pub struct Groth16Prover {
+    groth16_pk: Groth16WrapperProvingKey,
+}
+
+impl Groth16Prover {
+    pub fn new(groth16_pk: Groth16WrapperProvingKey) -> Self {
+        Self { groth16_pk }
+    }
+    pub fn prove_for_evm(&self, root_proof: &Proof<RootSC>) -> EvmProof {
+        let mut witness = Witness::default();
+        root_proof.write(&mut witness);
+        info_span!("prove_for_evm", group = "groth16_wrapper")
+            .in_scope(|| self.groth16_pk.prove_for_evm(witness))
+    }
+}
@@ -2,9 +2,9 @@ use std::sync::Arc;
 
 use openvm_circuit::arch::VmConfig;
 use openvm_native_recursion::halo2::EvmProof;
-use openvm_stark_sdk::openvm_stark_backend::Chip;
+use openvm_stark_sdk::openvm_stark_backend::{proof::Proof, Chip};
 
-use crate::{keygen::AppProvingKey, stdin::StdIn, NonRootCommittedExe, F, SC};
+use crate::{keygen::AppProvingKey, stdin::StdIn, NonRootCommittedExe, RootSC, F, SC};
 
 mod agg;
 pub use agg::*;
@@ -12,6 +12,8 @@ mod app;
 pub use app::*;
 use openvm_native_recursion::halo2::utils::Halo2ParamsReader;
 
+mod groth16;
+pub use groth16::*;
 mod halo2;
 #[allow(unused_imports)]
 pub use halo2::*;
@@ -23,11 +25,36 @@ pub mod vm;
 #[allow(unused_imports)]
 pub use stark::*;
 
-use crate::{keygen::AggProvingKey, prover::halo2::Halo2Prover};
+use crate::{
+    keygen::{AggProvingKey, SnarkProvingKey},
+    prover::halo2::Halo2Prover,
+};
 
 pub struct ContinuationProver<VC> {
     stark_prover: StarkProver<VC>,
-    halo2_prover: Halo2Prover,
+    snark_prover: SnarkProver,
+}
+
+/// Prover for the SNARK wrapping the root verifier proof.
+pub enum SnarkProver {
+    Halo2(Halo2Prover),
+    Groth16(Groth16Prover),
+}
+
+impl SnarkProver {
+    pub fn new(reader: &impl Halo2ParamsReader, snark_pk: SnarkProvingKey) -> Self {
+        match snark_pk {
+            SnarkProvingKey::Halo2(halo2_pk) => Self::Halo2(Halo2Prover::new(reader, halo2_pk)),
+            SnarkProvingKey::Groth16(groth16_pk) => Self::Groth16(Groth16Prover::new(groth16_pk)),
+        }
+    }
+
+    pub fn prove_for_evm(&self, root_proof: &Proof<RootSC>) -> EvmProof {
+        match self {
+            Self::Halo2(prover) => prover.prove_for_evm(root_proof),
+            Self::Groth16(prover) => prover.prove_for_evm(root_proof),
+        }
+    }
 }
 
 impl<VC> ContinuationProver<VC> {
@@ -42,12 +69,12 @@ impl<VC> ContinuationProver<VC> {
     {
         let AggProvingKey {
             agg_stark_pk,
-            halo2_pk,
+            snark_pk,
         } = agg_pk;
         let stark_prover = StarkProver::new(app_pk, app_committed_exe, agg_stark_pk);
         Self {
             stark_prover,
-            halo2_prover: Halo2Prover::new(reader, halo2_pk),
+            snark_prover: SnarkProver::new(reader, snark_pk),
         }
     }
 
@@ -59,10 +86,11 @@ impl<VC> ContinuationProver<VC> {
     pub fn generate_proof_for_evm(&self, input: StdIn) -> EvmProof
     where
         VC: VmConfig<F>,
+        VC: Sync,
         VC::Executor: Chip<SC>,
         VC::Periphery: Chip<SC>,
     {
         let root_proof = self.stark_prover.generate_proof_for_outer_recursion(input);
-        self.halo2_prover.prove_for_evm(&root_proof)
+        self.snark_prover.prove_for_evm(&root_proof)
     }
 }
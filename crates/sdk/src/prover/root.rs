@@ -1,6 +1,5 @@
 use async_trait::async_trait;
 use openvm_circuit::arch::{SingleSegmentVmExecutor, Streams};
-use openvm_native_circuit::NativeConfig;
 use openvm_native_recursion::hints::Hintable;
 use openvm_stark_sdk::{
     config::{baby_bear_poseidon2_root::BabyBearPoseidon2RootEngine, FriParameters},
@@ -11,14 +10,14 @@ use openvm_stark_sdk::{
 use crate::{
     keygen::RootVerifierProvingKey,
     prover::vm::{AsyncSingleSegmentVmProver, SingleSegmentVmProver},
-    verifier::root::types::RootVmVerifierInput,
+    verifier::root::{types::RootVmVerifierInput, RootVmConfig},
     RootSC, F, SC,
 };
 
 /// Local prover for a root verifier.
 pub struct RootVerifierLocalProver {
     pub root_verifier_pk: RootVerifierProvingKey,
-    executor_for_heights: SingleSegmentVmExecutor<F, NativeConfig>,
+    executor_for_heights: SingleSegmentVmExecutor<F, RootVmConfig>,
 }
 
 impl RootVerifierLocalProver {
@@ -40,7 +39,7 @@ impl RootVerifierLocalProver {
             .unwrap();
         result.air_heights
     }
-    pub fn vm_config(&self) -> &NativeConfig {
+    pub fn vm_config(&self) -> &RootVmConfig {
         &self.root_verifier_pk.vm_pk.vm_config
     }
     #[allow(dead_code)]
@@ -1,12 +1,12 @@
 use std::sync::Arc;
 
-use openvm_circuit::arch::VmConfig;
+use openvm_circuit::arch::{ParallelProvingConfig, VmConfig};
 use openvm_stark_backend::{proof::Proof, Chip};
 
 use crate::{
     keygen::{AggStarkProvingKey, AppProvingKey},
     prover::{agg::AggStarkProver, app::AppProver},
-    verifier::root::types::RootVmVerifierInput,
+    verifier::{internal::types::CompressedAppProof, root::types::RootVmVerifierInput},
     NonRootCommittedExe, RootSC, StdIn, F, SC,
 };
 
@@ -42,23 +42,60 @@ impl<VC> StarkProver<VC> {
         self.app_prover.set_program_name(program_name);
         self
     }
+    /// Proves app segments concurrently, and starts leaf aggregation of finished segments while
+    /// the later ones are still being proven.
+    pub fn set_parallel_proving(&mut self, parallel_proving: ParallelProvingConfig) -> &mut Self {
+        self.app_prover.set_parallel_proving(parallel_proving);
+        self
+    }
     pub fn generate_proof_for_outer_recursion(&self, input: StdIn) -> Proof<RootSC>
     where
         VC: VmConfig<F>,
+        VC: Sync,
         VC::Executor: Chip<SC>,
         VC::Periphery: Chip<SC>,
     {
-        let app_proof = self.app_prover.generate_app_proof(input);
-        self.agg_prover.generate_agg_proof(app_proof)
+        let root_verifier_input = self.generate_root_verifier_input(input);
+        self.agg_prover.generate_root_proof(root_verifier_input)
     }
 
     pub fn generate_root_verifier_input(&self, input: StdIn) -> RootVmVerifierInput<SC>
     where
         VC: VmConfig<F>,
+        VC: Sync,
         VC::Executor: Chip<SC>,
         VC::Periphery: Chip<SC>,
     {
-        let app_proof = self.app_prover.generate_app_proof(input);
-        self.agg_prover.generate_root_verifier_input(app_proof)
+        if self.app_prover.app_prover().parallel_proving().is_some() {
+            self.agg_prover
+                .generate_root_verifier_input_streaming(|on_segment_proof| {
+                    self.app_prover
+                        .generate_app_proof_with_callback(input, on_segment_proof)
+                })
+        } else {
+            let app_proof = self.app_prover.generate_app_proof(input);
+            self.agg_prover.generate_root_verifier_input(app_proof)
+        }
+    }
+
+    /// Proves the app execution and compresses the app proofs into a single internal verifier
+    /// proof. See [AggStarkProver::generate_compressed_proof].
+    pub fn generate_compressed_proof(&self, input: StdIn) -> CompressedAppProof<SC>
+    where
+        VC: VmConfig<F>,
+        VC: Sync,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        if self.app_prover.app_prover().parallel_proving().is_some() {
+            self.agg_prover
+                .generate_compressed_proof_streaming(|on_segment_proof| {
+                    self.app_prover
+                        .generate_app_proof_with_callback(input, on_segment_proof)
+                })
+        } else {
+            let app_proof = self.app_prover.generate_app_proof(input);
+            self.agg_prover.generate_compressed_proof(app_proof)
+        }
     }
 }
@@ -3,15 +3,21 @@ use std::{marker::PhantomData, sync::Arc};
 use async_trait::async_trait;
 use openvm_circuit::{
     arch::{
-        hasher::poseidon2::vm_poseidon2_hasher, SingleSegmentVmExecutor, Streams, VirtualMachine,
-        VmComplexTraceHeights, VmConfig,
+        hasher::poseidon2::vm_poseidon2_hasher, ParallelProvingConfig, SegmentScheduler,
+        SingleSegmentVmExecutor, Streams, VirtualMachine, VmComplexTraceHeights, VmConfig,
+        DEFAULT_MAX_IN_FLIGHT_SEGMENTS,
+    },
+    system::{
+        memory::tree::{data_segments::DataSegmentsProof, public_values::UserPublicValuesProof},
+        program::trace::VmCommittedExe,
     },
-    system::{memory::tree::public_values::UserPublicValuesProof, program::trace::VmCommittedExe},
 };
 use openvm_stark_backend::{
     config::{StarkGenericConfig, Val},
+    keygen::types::MultiStarkProvingKey,
     p3_field::PrimeField32,
     proof::Proof,
+    prover::types::{CommittedTraceData, ProofInput},
     Chip,
 };
 use openvm_stark_sdk::{config::FriParameters, engine::StarkFriEngine};
@@ -25,6 +31,10 @@ pub struct VmLocalProver<SC: StarkGenericConfig, VC, E: StarkFriEngine<SC>> {
     pub pk: Arc<VmProvingKey<SC, VC>>,
     pub committed_exe: Arc<VmCommittedExe<SC>>,
     overridden_heights: Option<VmComplexTraceHeights>,
+    /// Maximum number of segment proof inputs alive at once during continuation proving.
+    max_in_flight_segments: usize,
+    /// If set, continuation segments are proven concurrently instead of one at a time.
+    parallel_proving: Option<ParallelProvingConfig>,
     _marker: PhantomData<E>,
 }
 
@@ -34,6 +44,8 @@ impl<SC: StarkGenericConfig, VC, E: StarkFriEngine<SC>> VmLocalProver<SC, VC, E>
             pk,
             committed_exe,
             overridden_heights: None,
+            max_in_flight_segments: DEFAULT_MAX_IN_FLIGHT_SEGMENTS,
+            parallel_proving: None,
             _marker: PhantomData,
         }
     }
@@ -47,6 +59,8 @@ impl<SC: StarkGenericConfig, VC, E: StarkFriEngine<SC>> VmLocalProver<SC, VC, E>
             pk,
             committed_exe,
             overridden_heights,
+            max_in_flight_segments: DEFAULT_MAX_IN_FLIGHT_SEGMENTS,
+            parallel_proving: None,
             _marker: PhantomData,
         }
     }
@@ -55,6 +69,23 @@ impl<SC: StarkGenericConfig, VC, E: StarkFriEngine<SC>> VmLocalProver<SC, VC, E>
         self.overridden_heights = Some(overridden_heights);
     }
 
+    /// Sets the maximum number of segments whose proof inputs are kept in memory at once while
+    /// the next segments are executed. Lower values reduce peak memory usage.
+    pub fn set_max_in_flight_segments(&mut self, max_in_flight_segments: usize) {
+        assert!(max_in_flight_segments > 0);
+        self.max_in_flight_segments = max_in_flight_segments;
+    }
+
+    /// Proves continuation segments concurrently, splitting the available threads as specified by
+    /// `parallel_proving`.
+    pub fn set_parallel_proving(&mut self, parallel_proving: ParallelProvingConfig) {
+        self.parallel_proving = Some(parallel_proving);
+    }
+
+    pub fn parallel_proving(&self) -> Option<&ParallelProvingConfig> {
+        self.parallel_proving.as_ref()
+    }
+
     pub fn vm_config(&self) -> &VC {
         &self.pk.vm_config
     }
@@ -64,14 +95,25 @@ impl<SC: StarkGenericConfig, VC, E: StarkFriEngine<SC>> VmLocalProver<SC, VC, E>
     }
 }
 
-impl<SC: StarkGenericConfig, VC: VmConfig<Val<SC>>, E: StarkFriEngine<SC>> ContinuationVmProver<SC>
-    for VmLocalProver<SC, VC, E>
+impl<SC: StarkGenericConfig, VC: VmConfig<Val<SC>>, E: StarkFriEngine<SC>> VmLocalProver<SC, VC, E>
 where
     Val<SC>: PrimeField32,
     VC::Executor: Chip<SC>,
     VC::Periphery: Chip<SC>,
+    VirtualMachine<SC, E, VC>: Sync,
+    MultiStarkProvingKey<SC>: Sync,
+    ProofInput<SC>: Send,
+    CommittedTraceData<SC>: Send,
+    Proof<SC>: Send,
 {
-    fn prove(&self, input: impl Into<Streams<Val<SC>>>) -> ContinuationVmProof<SC> {
+    /// Proves every continuation segment, moving each segment proof into `on_segment_proof` as
+    /// soon as it is generated. With parallel proving, segment proofs may complete out of order.
+    /// The `per_segment` proofs of the returned proof are empty.
+    pub fn prove_with_callback(
+        &self,
+        input: impl Into<Streams<Val<SC>>>,
+        mut on_segment_proof: impl FnMut(usize, Proof<SC>),
+    ) -> ContinuationVmProof<SC> {
         assert!(self.pk.vm_config.system().continuation_enabled);
         let e = E::new(self.pk.fri_params);
         let vm = VirtualMachine::new_with_overridden_trace_heights(
@@ -79,23 +121,82 @@ where
             self.pk.vm_config.clone(),
             self.overridden_heights.clone(),
         );
-        let results = vm
-            .execute_and_generate_with_cached_program(self.committed_exe.clone(), input)
-            .unwrap();
+        let system_config = self.pk.vm_config.resolved_system_config();
+        let input = input.into();
+        let data_segments = (!input.data_segments.is_empty()).then(|| {
+            let initial_memory = vm
+                .executor
+                .initial_memory(&self.committed_exe.exe, &input.data_segments)
+                .unwrap();
+            DataSegmentsProof::compute(
+                system_config.memory_config.memory_dimensions(),
+                &vm_poseidon2_hasher(),
+                &initial_memory,
+            )
+        });
+        let final_memory = if let Some(parallel_proving) = self.parallel_proving {
+            vm.execute_and_prove_parallel(
+                &self.pk.vm_pk,
+                self.committed_exe.clone(),
+                input,
+                &SegmentScheduler::new(parallel_proving),
+                on_segment_proof,
+            )
+        } else {
+            let results = vm.executor.execute_and_generate_pipelined(
+                self.committed_exe.exe.clone(),
+                Some(self.committed_exe.committed_program.clone()),
+                input,
+                self.max_in_flight_segments,
+                |seg_idx, proof_input| {
+                    let proof = vm.prove_segment(&self.pk.vm_pk, seg_idx, proof_input);
+                    on_segment_proof(seg_idx, proof);
+                },
+            );
+            #[cfg(feature = "bench-metrics")]
+            if let Ok(results) = &results {
+                metrics::counter!("num_segments").absolute(results.per_segment.len() as u64);
+            }
+            results.map(|results| results.final_memory)
+        }
+        .unwrap();
         let user_public_values = UserPublicValuesProof::compute(
-            self.pk.vm_config.system().memory_config.memory_dimensions(),
-            self.pk.vm_config.system().num_public_values,
+            system_config.memory_config.memory_dimensions(),
+            system_config.num_public_values,
             &vm_poseidon2_hasher(),
-            results.final_memory.as_ref().unwrap(),
+            final_memory.as_ref().unwrap(),
         );
-        let per_segment = vm.prove(&self.pk.vm_pk, results);
         ContinuationVmProof {
-            per_segment,
+            per_segment: vec![],
             user_public_values,
+            data_segments,
         }
     }
 }
 
+impl<SC: StarkGenericConfig, VC: VmConfig<Val<SC>>, E: StarkFriEngine<SC>> ContinuationVmProver<SC>
+    for VmLocalProver<SC, VC, E>
+where
+    Val<SC>: PrimeField32,
+    VC::Executor: Chip<SC>,
+    VC::Periphery: Chip<SC>,
+    VirtualMachine<SC, E, VC>: Sync,
+    MultiStarkProvingKey<SC>: Sync,
+    ProofInput<SC>: Send,
+    CommittedTraceData<SC>: Send,
+    Proof<SC>: Send,
+{
+    fn prove(&self, input: impl Into<Streams<Val<SC>>>) -> ContinuationVmProof<SC> {
+        let mut per_segment = Vec::new();
+        let mut proof = self.prove_with_callback(input, |seg_idx, proof| {
+            per_segment.push((seg_idx, proof));
+        });
+        per_segment.sort_by_key(|(seg_idx, _)| *seg_idx);
+        proof.per_segment = per_segment.into_iter().map(|(_, proof)| proof).collect();
+        proof
+    }
+}
+
 #[async_trait]
 impl<SC: StarkGenericConfig, VC: VmConfig<Val<SC>>, E: StarkFriEngine<SC>>
     AsyncContinuationVmProver<SC> for VmLocalProver<SC, VC, E>
@@ -104,6 +205,11 @@ where
     Val<SC>: PrimeField32,
     VC::Executor: Chip<SC>,
     VC::Periphery: Chip<SC>,
+    VirtualMachine<SC, E, VC>: Sync,
+    MultiStarkProvingKey<SC>: Sync,
+    ProofInput<SC>: Send,
+    CommittedTraceData<SC>: Send,
+    Proof<SC>: Send,
 {
     async fn prove(
         &self,
@@ -2,7 +2,10 @@ use async_trait::async_trait;
 use derivative::Derivative;
 use openvm_circuit::{
     arch::Streams,
-    system::memory::{tree::public_values::UserPublicValuesProof, CHUNK},
+    system::memory::{
+        tree::{data_segments::DataSegmentsProof, public_values::UserPublicValuesProof},
+        CHUNK,
+    },
 };
 use openvm_stark_backend::{
     config::{Com, StarkGenericConfig, Val},
@@ -22,6 +25,9 @@ pub mod types;
 pub struct ContinuationVmProof<SC: StarkGenericConfig> {
     pub per_segment: Vec<Proof<SC>>,
     pub user_public_values: UserPublicValuesProof<{ CHUNK }, Val<SC>>,
+    /// Proof of the data segments region in the initial memory, if there are data segments.
+    #[serde(default)]
+    pub data_segments: Option<DataSegmentsProof<{ CHUNK }, Val<SC>>>,
 }
 
 /// Prover for a specific exe in a specific continuation VM using a specific Stark config.
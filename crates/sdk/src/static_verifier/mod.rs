@@ -1,3 +1,5 @@
+use std::iter;
+
 use openvm_circuit::arch::PROGRAM_CACHED_TRACE_INDEX;
 use openvm_native_compiler::prelude::*;
 use openvm_native_recursion::{
@@ -5,6 +7,7 @@ use openvm_native_recursion::{
     config::outer::{new_from_outer_multi_vk, OuterConfig},
     digest::DigestVariable,
     fri::TwoAdicFriPcsVariable,
+    groth16::Groth16WrapperProvingKey,
     halo2::{verifier::Halo2VerifierProvingKey, DslOperations, Halo2Params, Halo2Prover},
     hints::Hintable,
     stark::StarkVerifier,
@@ -16,16 +19,19 @@ use openvm_stark_sdk::{
     openvm_stark_backend::{p3_field::FieldAlgebra, proof::Proof},
     p3_bn254_fr::Bn254Fr,
 };
+use rand::rngs::OsRng;
 
 use crate::{
+    config::PublicValuesMode,
     keygen::RootVerifierProvingKey,
     prover::{vm::SingleSegmentVmProver, RootVerifierLocalProver},
     verifier::{
         common::{
             assert_single_segment_vm_exit_successfully_with_connector_air_id, types::SpecialAirIds,
         },
+        internal::types::LeafVerifierCommitProof,
         root::types::{RootVmVerifierInput, RootVmVerifierPvs},
-        utils::compress_babybear_var_to_bn254,
+        utils::{compress_babybear_var_to_bn254, compress_be_bytes_var_to_bn254},
     },
     RootSC, F, SC,
 };
@@ -47,21 +53,30 @@ impl RootVerifierProvingKey {
         }
     }
 
-    pub fn generate_dummy_root_proof(&self, dummy_internal_proof: Proof<SC>) -> Proof<RootSC> {
+    /// Keygen the Groth16 wrapper for this root verifier. The CRS is sampled locally, so the
+    /// resulting key is only suitable for testing.
+    pub fn keygen_groth16_wrapper(
+        &self,
+        root_proof: &Proof<RootSC>,
+        pv_handler: Option<&impl StaticVerifierPvHandler>,
+    ) -> Groth16WrapperProvingKey {
+        let dsl_operations = build_static_verifier_operations(self, root_proof, pv_handler);
+        Groth16WrapperProvingKey::keygen(dsl_operations, &mut OsRng)
+    }
+
+    pub fn generate_dummy_root_proof(
+        &self,
+        dummy_internal_proof: Proof<SC>,
+        leaf_verifier_commit_proof: Option<LeafVerifierCommitProof<F>>,
+    ) -> Proof<RootSC> {
         let prover = RootVerifierLocalProver::new(self.clone());
-        // 2 * DIGEST_SIZE for exe_commit and leaf_commit
-        let num_public_values = prover
-            .root_verifier_pk
-            .vm_pk
-            .vm_config
-            .system
-            .num_public_values
-            - 2 * DIGEST_SIZE;
+        let num_public_values = prover.vm_config().num_user_public_values;
         SingleSegmentVmProver::prove(
             &prover,
             RootVmVerifierInput {
                 proofs: vec![dummy_internal_proof],
                 public_values: vec![F::ZERO; num_public_values],
+                leaf_verifier_commit_proof,
             }
             .write(),
         )
@@ -95,16 +110,31 @@ impl StaticVerifierPvHandler for RootVerifierProvingKey {
             .into_iter()
             .map(|x| builder.cast_felt_to_var(x))
             .collect();
-        let pvs = RootVmVerifierPvs::from_flatten(public_values);
-        let exe_commit = compress_babybear_var_to_bn254(builder, pvs.exe_commit);
-        let leaf_commit = compress_babybear_var_to_bn254(builder, pvs.leaf_verifier_commit);
-        let num_public_values = 2 + pvs.public_values.len();
-        builder.static_commit_public_value(0, exe_commit);
-        builder.static_commit_public_value(1, leaf_commit);
-        for (i, x) in pvs.public_values.into_iter().enumerate() {
-            builder.static_commit_public_value(i + 2, x);
+        let vm_config = &self.vm_pk.vm_config;
+        let pvs = RootVmVerifierPvs::from_flatten(public_values, vm_config);
+        // The input and data segments commitments are only exposed if the root verifier exposes
+        // them, so that the instances of existing EVM verifiers don't change.
+        let commits: Vec<_> = iter::empty()
+            .chain([pvs.exe_commit, pvs.leaf_verifier_commit])
+            .chain(pvs.input_commit)
+            .chain(pvs.data_segments_commit)
+            .map(|commit| compress_babybear_var_to_bn254(builder, commit))
+            .collect();
+        let public_values = match vm_config.public_values_mode() {
+            PublicValuesMode::Raw => pvs.public_values,
+            // A Keccak256 digest doesn't fit in a Bn254 element, so it's exposed as its high and
+            // low 128 bits.
+            PublicValuesMode::Keccak256 => pvs
+                .public_values
+                .chunks(KECCAK_DIGEST_BYTES / 2)
+                .map(|bytes| compress_be_bytes_var_to_bn254(builder, bytes))
+                .collect(),
+        };
+        let static_public_values: Vec<_> = commits.into_iter().chain(public_values).collect();
+        for (i, &x) in static_public_values.iter().enumerate() {
+            builder.static_commit_public_value(i, x);
         }
-        num_public_values
+        static_public_values.len()
     }
 }
 
@@ -1,7 +1,20 @@
 use std::collections::VecDeque;
 
-use openvm_circuit::arch::Streams;
+use bytemuck::Pod;
+use eyre::{bail, Result};
+use openvm_circuit::{
+    arch::{
+        hasher::poseidon2::vm_poseidon2_hasher, instructions::exe::VmExe, Streams, VmConfig,
+        VmExecutor,
+    },
+    system::memory::{
+        tree::data_segments::{data_segments_commit, DataSegment},
+        CHUNK,
+    },
+};
+use openvm_rv32im_circuit::rv32_input_commitment;
 use openvm_stark_backend::p3_field::FieldAlgebra;
+use rkyv::{api::high::HighSerializer, rancor, ser::allocator::ArenaHandle, util::AlignedVec};
 use serde::{Deserialize, Serialize};
 
 use crate::F;
@@ -9,6 +22,10 @@ use crate::F;
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct StdIn {
     pub buffer: VecDeque<Vec<F>>,
+    /// Data segments mapped into guest memory before execution, see
+    /// [Self::add_data_segment].
+    #[serde(default)]
+    pub data_segments: Vec<DataSegment>,
 }
 
 impl StdIn {
@@ -33,9 +50,65 @@ impl StdIn {
         self.buffer.push_back(field_data);
     }
 
+    /// Writes the raw bytes of a plain-old-data value, to be read in the guest with
+    /// `openvm::io::read_aligned`.
+    pub fn write_pod<T: Pod>(&mut self, data: &T) {
+        self.write_bytes(bytemuck::bytes_of(data));
+    }
+
+    /// Writes an rkyv archive of `data`, to be accessed in the guest without deserialization via
+    /// `openvm::io::read_archived`.
+    pub fn write_archived<T>(&mut self, data: &T)
+    where
+        T: for<'a> rkyv::Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, rancor::Error>>,
+    {
+        let bytes = rkyv::to_bytes::<rancor::Error>(data).unwrap();
+        self.write_bytes(&bytes);
+    }
+
     pub fn write_field(&mut self, data: &[F]) {
         self.buffer.push_back(data.to_vec());
     }
+
+    /// Adds a data segment, to be accessed in the guest with `openvm::io::data_segment`.
+    /// Unlike the input stream, the data is committed by its merkle root in the initial memory,
+    /// see [Self::data_segments_commit]. Requires continuations.
+    pub fn add_data_segment(&mut self, name: impl Into<String>, data: Vec<u8>) {
+        self.data_segments.push(DataSegment::new(name, data));
+    }
+
+    /// Returns the data segments commitment exposed by the verifiers. It is zero if there are no
+    /// data segments. Fails if the data segments can't be laid out, in which case execution fails
+    /// too.
+    pub fn data_segments_commit(&self) -> Result<[F; CHUNK]> {
+        Ok(data_segments_commit(
+            &self.data_segments,
+            &vm_poseidon2_hasher(),
+        )?)
+    }
+
+    /// Returns the input commitment that an RV32 App VM with
+    /// [commit_input_stream](openvm_circuit::arch::SystemConfig::commit_input_stream) exposes
+    /// after executing `exe` on these inputs. Fails if it is not the commitment to the inputs,
+    /// because the guest hints other words, which fails execution, or doesn't read each input
+    /// completely.
+    pub fn input_commit<VC: VmConfig<F>>(
+        &self,
+        exe: VmExe<F>,
+        vm_config: VC,
+    ) -> Result<[F; CHUNK]> {
+        if !vm_config.system().commit_input_stream {
+            bail!("the VM does not commit to its input stream");
+        }
+        let result = VmExecutor::new(vm_config).execute_only(exe, self.clone())?;
+        let input_commitment = result
+            .input_commitment
+            .expect("input commitment is kept when committing to the input stream");
+        if input_commitment.digest != rv32_input_commitment(&self.buffer) {
+            bail!("the guest does not read each input completely");
+        }
+        Ok(input_commitment.digest)
+    }
 }
 
 impl From<StdIn> for Streams<F> {
@@ -44,7 +117,9 @@ impl From<StdIn> for Streams<F> {
         while let Some(input) = std_in.read() {
             data.push(input);
         }
-        Streams::new(data)
+        let mut streams = Streams::new(data);
+        streams.data_segments = std_in.data_segments;
+        streams
     }
 }
 
@@ -55,16 +55,24 @@ pub fn assert_or_assign_connector_pvs<C: Config>(
     builder.if_eq(proof_idx, RVar::zero()).then_or_else(
         |builder| {
             builder.assign(&dst.initial_pc, proof_pvs.initial_pc);
+            builder.assign(&dst.initial_input_commit, proof_pvs.initial_input_commit);
         },
         |builder| {
             // assert prev.final_pc == curr.initial_pc
             builder.assert_felt_eq(dst.final_pc, proof_pvs.initial_pc);
             // assert prev.is_terminate == 0
             builder.assert_felt_eq(dst.is_terminate, C::F::ZERO);
+            // assert prev.final_input_commit == curr.initial_input_commit
+            builder.assert_eq::<[_; DIGEST_SIZE]>(
+                dst.final_input_commit,
+                proof_pvs.initial_input_commit,
+            );
         },
     );
     // Update final_pc
     builder.assign(&dst.final_pc, proof_pvs.final_pc);
+    // Update final_input_commit
+    builder.assign(&dst.final_input_commit, proof_pvs.final_input_commit);
     // Update is_terminate
     builder.assign(&dst.is_terminate, proof_pvs.is_terminate);
     // Update exit_code
@@ -124,6 +132,8 @@ fn get_connector_pvs_impl<C: Config>(
         final_pc: builder.get(proof_pvs, 1),
         exit_code: builder.get(proof_pvs, 2),
         is_terminate: builder.get(proof_pvs, 3),
+        initial_input_commit: array::from_fn(|i| builder.get(proof_pvs, 4 + i)),
+        final_input_commit: array::from_fn(|i| builder.get(proof_pvs, 4 + DIGEST_SIZE + i)),
     }
 }
 
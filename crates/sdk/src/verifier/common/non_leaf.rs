@@ -57,6 +57,14 @@ impl<C: Config> NonLeafVerifierVariables<C> {
                         &leaf_verifier_commit,
                         proof_vm_pvs.extra_pvs.leaf_verifier_commit,
                     );
+                    builder.assign(
+                        &pvs.exe_memory_root,
+                        proof_vm_pvs.vm_verifier_pvs.exe_memory_root,
+                    );
+                    builder.assign(
+                        &pvs.data_segments_commit,
+                        proof_vm_pvs.vm_verifier_pvs.data_segments_commit,
+                    );
                 },
                 |builder| {
                     builder.assert_eq::<[_; DIGEST_SIZE]>(
@@ -26,6 +26,12 @@ pub struct VmVerifierPvs<T> {
     /// The merkle root of all public values. This is only meaningful when the last segment is
     /// aggregated by this circuit.
     pub public_values_commit: [T; DIGEST_SIZE],
+    /// The initial memory root with the data segments region zeroed, i.e. the memory root of the
+    /// app program. This is only meaningful when the first segment is aggregated by this circuit.
+    pub exe_memory_root: [T; DIGEST_SIZE],
+    /// The merkle root of the data segments region, or zero if there are no data segments. This
+    /// is only meaningful when the first segment is aggregated by this circuit.
+    pub data_segments_commit: [T; DIGEST_SIZE],
 }
 
 impl<F: PrimeField32> VmVerifierPvs<Felt<F>> {
@@ -37,12 +43,16 @@ impl<F: PrimeField32> VmVerifierPvs<Felt<F>> {
                 final_pc: builder.uninit(),
                 exit_code: builder.uninit(),
                 is_terminate: builder.uninit(),
+                initial_input_commit: array::from_fn(|_| builder.uninit()),
+                final_input_commit: array::from_fn(|_| builder.uninit()),
             },
             memory: MemoryMerklePvs {
                 initial_root: array::from_fn(|_| builder.uninit()),
                 final_root: array::from_fn(|_| builder.uninit()),
             },
             public_values_commit: array::from_fn(|_| builder.uninit()),
+            exe_memory_root: array::from_fn(|_| builder.uninit()),
+            data_segments_commit: array::from_fn(|_| builder.uninit()),
         }
     }
 }
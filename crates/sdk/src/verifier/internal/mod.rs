@@ -1,3 +1,5 @@
+use std::array;
+
 use openvm_circuit::arch::instructions::program::Program;
 use openvm_native_compiler::{conversion::CompilerOptions, prelude::*};
 use openvm_native_recursion::{
@@ -13,9 +15,13 @@ use crate::{
     verifier::{
         common::non_leaf::NonLeafVerifierVariables,
         internal::{
-            types::{InternalVmVerifierExtraPvs, InternalVmVerifierInput, InternalVmVerifierPvs},
+            types::{
+                InternalVmVerifierExtraPvs, InternalVmVerifierInput, InternalVmVerifierPvs,
+                LeafVerifierCommitProof, LeafVerifierCommitSet,
+            },
             vars::InternalVmVerifierInputVariable,
         },
+        utils::VariableP2Hasher,
     },
     C, F,
 };
@@ -28,6 +34,8 @@ pub struct InternalVmVerifierConfig {
     pub leaf_fri_params: FriParameters,
     pub internal_fri_params: FriParameters,
     pub compiler_options: CompilerOptions,
+    /// If set, only proofs of these leaf verifiers are accepted.
+    pub leaf_verifier_commits: Option<LeafVerifierCommitSet>,
 }
 
 impl InternalVmVerifierConfig {
@@ -64,6 +72,13 @@ impl InternalVmVerifierConfig {
             builder.cycle_tracker_start("VerifyProofs");
             let (vm_verifier_pvs, leaf_verifier_commit) =
                 non_leaf_verifier.verify_internal_or_leaf_verifier_proofs(&mut builder, &proofs);
+            if let Some(leaf_verifier_commits) = &self.leaf_verifier_commits {
+                assert_leaf_verifier_commit_allowed(
+                    &mut builder,
+                    leaf_verifier_commits,
+                    leaf_verifier_commit,
+                );
+            }
             builder.cycle_tracker_end("VerifyProofs");
             let pvs = InternalVmVerifierPvs {
                 vm_verifier_pvs,
@@ -82,3 +97,38 @@ impl InternalVmVerifierConfig {
         builder.compile_isa_with_options(self.compiler_options)
     }
 }
+
+/// Reads a [LeafVerifierCommitProof] from the input stream and asserts that it proves
+/// `leaf_verifier_commit` is in `leaf_verifier_commits`.
+pub(crate) fn assert_leaf_verifier_commit_allowed(
+    builder: &mut Builder<C>,
+    leaf_verifier_commits: &LeafVerifierCommitSet,
+    leaf_verifier_commit: [Felt<F>; DIGEST_SIZE],
+) {
+    let height = leaf_verifier_commits.height();
+    let proof = LeafVerifierCommitProof::<F>::read(builder);
+    builder.assert_eq::<Usize<_>>(proof.sibling_hashes.len(), Usize::from(height));
+
+    let hasher = VariableP2Hasher::new(builder);
+    let mut curr_hash = hasher.hash(builder, &leaf_verifier_commit);
+    for i in 0..height {
+        let sibling_hash = builder.get(&proof.sibling_hashes, i);
+        let is_right_child = builder.get(&proof.is_right_child, i);
+        let l_hash: [Felt<F>; DIGEST_SIZE] = array::from_fn(|_| builder.uninit());
+        let r_hash: [Felt<F>; DIGEST_SIZE] = array::from_fn(|_| builder.uninit());
+        builder.if_eq(is_right_child, RVar::one()).then_or_else(
+            |builder| {
+                builder.assign(&l_hash, sibling_hash);
+                builder.assign(&r_hash, curr_hash);
+            },
+            |builder| {
+                builder.assign(&l_hash, curr_hash);
+                builder.assign(&r_hash, sibling_hash);
+            },
+        );
+        curr_hash = hasher.compressor.compress(builder, &l_hash, &r_hash);
+    }
+    let root = leaf_verifier_commits.root();
+    let expected_root: [Felt<F>; DIGEST_SIZE] = array::from_fn(|i| builder.eval(root[i]));
+    builder.assert_eq::<[_; DIGEST_SIZE]>(curr_hash, expected_root);
+}
@@ -1,7 +1,10 @@
 use std::{array, borrow::BorrowMut};
 
 use derivative::Derivative;
-use openvm_circuit::circuit_derive::AlignedBorrow;
+use openvm_circuit::{
+    arch::hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+    circuit_derive::AlignedBorrow,
+};
 use openvm_native_compiler::{
     ir::{Builder, Config, Felt},
     prelude::DIGEST_SIZE,
@@ -10,14 +13,16 @@ use openvm_stark_sdk::{
     config::baby_bear_poseidon2::BabyBearPoseidon2Config,
     openvm_stark_backend::{
         config::{Com, StarkGenericConfig, Val},
-        p3_field::PrimeField32,
+        p3_field::{FieldAlgebra, PrimeField32},
+        p3_util::log2_strict_usize,
         proof::Proof,
     },
+    p3_baby_bear::BabyBear,
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use static_assertions::assert_impl_all;
 
-use crate::{verifier::common::types::VmVerifierPvs, SC};
+use crate::{verifier::common::types::VmVerifierPvs, F, SC};
 
 /// Input for the leaf VM verifier.
 #[derive(Serialize, Deserialize, Derivative)]
@@ -27,24 +32,113 @@ pub struct InternalVmVerifierInput<SC: StarkGenericConfig> {
     pub self_program_commit: [Val<SC>; DIGEST_SIZE],
     /// The proofs of leaf verifier or internal verifier in the execution order.
     pub proofs: Vec<Proof<SC>>,
+    /// Proof that the leaf verifier commit of `proofs` is allowed. Internal VM verifier only needs
+    /// this when it is built with a [LeafVerifierCommitSet].
+    #[serde(default)]
+    pub leaf_verifier_commit_proof: Option<LeafVerifierCommitProof<Val<SC>>>,
 }
 assert_impl_all!(InternalVmVerifierInput<BabyBearPoseidon2Config>: Serialize, DeserializeOwned);
 
+/// App proof compressed into a single proof of the internal verifier. Its public values are
+/// [InternalVmVerifierPvs], which only commit to the user public values, so they are included.
+#[derive(Serialize, Deserialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Clone(bound = "Com<SC>: Clone"))]
+pub struct CompressedAppProof<SC: StarkGenericConfig> {
+    pub proof: Proof<SC>,
+    pub user_public_values: Vec<Val<SC>>,
+}
+assert_impl_all!(CompressedAppProof<BabyBearPoseidon2Config>: Serialize, DeserializeOwned);
+
 impl InternalVmVerifierInput<SC> {
     pub fn chunk_leaf_or_internal_proofs(
         self_program_commit: [Val<SC>; DIGEST_SIZE],
         proofs: &[Proof<SC>],
         chunk: usize,
+        leaf_verifier_commit_proof: Option<&LeafVerifierCommitProof<Val<SC>>>,
     ) -> Vec<Self> {
         proofs
             .chunks(chunk)
             .map(|chunk| Self {
                 self_program_commit,
                 proofs: chunk.to_vec(),
+                leaf_verifier_commit_proof: leaf_verifier_commit_proof.cloned(),
             })
             .collect()
     }
 }
+
+/// Leaf verifier commits accepted by the internal and root VM verifiers, one for each registered
+/// App VM config. The verifiers are built with the root of the Merkle tree whose leaves are the
+/// hashes of the commits, so that one set of aggregation keys serves all registered App VMs.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LeafVerifierCommitSet {
+    /// Padded with zero commits to a power of two. No leaf verifier program has a zero commit.
+    commits: Vec<[F; DIGEST_SIZE]>,
+}
+
+impl LeafVerifierCommitSet {
+    pub fn new(commits: impl IntoIterator<Item = [F; DIGEST_SIZE]>) -> Self {
+        let mut commits: Vec<_> = commits.into_iter().collect();
+        assert!(
+            !commits.is_empty(),
+            "At least 1 leaf verifier commit is required"
+        );
+        assert!(
+            !commits.contains(&[F::ZERO; DIGEST_SIZE]),
+            "Leaf verifier commit cannot be zero"
+        );
+        commits.resize(commits.len().next_power_of_two(), [F::ZERO; DIGEST_SIZE]);
+        Self { commits }
+    }
+
+    pub fn contains(&self, commit: &[F; DIGEST_SIZE]) -> bool {
+        *commit != [F::ZERO; DIGEST_SIZE] && self.commits.contains(commit)
+    }
+
+    /// Height of the Merkle tree, i.e. the length of every [LeafVerifierCommitProof].
+    pub fn height(&self) -> usize {
+        log2_strict_usize(self.commits.len())
+    }
+
+    pub fn root(&self) -> [F; DIGEST_SIZE] {
+        vm_poseidon2_hasher().merkle_root(&self.commits.concat())
+    }
+
+    /// Returns the proof that `commit` is in the set, or `None` if it is not.
+    pub fn prove(&self, commit: &[F; DIGEST_SIZE]) -> Option<LeafVerifierCommitProof<F>> {
+        if !self.contains(commit) {
+            return None;
+        }
+        let mut index = self.commits.iter().position(|c| c == commit).unwrap();
+        let hasher = vm_poseidon2_hasher();
+        let mut layer: Vec<_> = self.commits.iter().map(|c| hasher.hash(c)).collect();
+        let mut proof = LeafVerifierCommitProof {
+            is_right_child: vec![],
+            sibling_hashes: vec![],
+        };
+        while layer.len() > 1 {
+            proof.is_right_child.push(index & 1 == 1);
+            proof.sibling_hashes.push(layer[index ^ 1]);
+            layer = layer
+                .chunks_exact(2)
+                .map(|c| hasher.compress(&c[0], &c[1]))
+                .collect();
+            index >>= 1;
+        }
+        Some(proof)
+    }
+}
+
+/// Proof that a leaf verifier commit is in a [LeafVerifierCommitSet].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LeafVerifierCommitProof<F> {
+    /// Whether the node on the path is a right child, from the leaf up.
+    pub is_right_child: Vec<bool>,
+    /// Sibling hashes of the nodes on the path, from the leaf up.
+    pub sibling_hashes: Vec<[F; DIGEST_SIZE]>,
+}
+assert_impl_all!(LeafVerifierCommitProof<BabyBear>: Serialize, DeserializeOwned);
 /// Aggregated state of all segments
 #[derive(Debug, Clone, Copy, AlignedBorrow)]
 #[repr(C)]
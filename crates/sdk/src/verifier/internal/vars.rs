@@ -2,11 +2,14 @@ use std::array;
 
 use openvm_native_compiler::{ir::DIGEST_SIZE, prelude::*};
 use openvm_native_recursion::{hints::Hintable, vars::StarkProofVariable};
-use openvm_stark_sdk::openvm_stark_backend::proof::Proof;
+use openvm_stark_sdk::openvm_stark_backend::{p3_field::FieldAlgebra, proof::Proof};
 
 use crate::{
-    verifier::{internal::types::InternalVmVerifierInput, utils::write_field_slice},
-    C, SC,
+    verifier::{
+        internal::types::{InternalVmVerifierInput, LeafVerifierCommitProof},
+        utils::write_field_slice,
+    },
+    C, F, SC,
 };
 
 #[derive(DslVariable, Clone)]
@@ -16,6 +19,14 @@ pub struct InternalVmVerifierInputVariable<C: Config> {
     pub proofs: Array<C, StarkProofVariable<C>>,
 }
 
+#[derive(DslVariable, Clone)]
+pub struct LeafVerifierCommitProofVariable<C: Config> {
+    /// 1 if the node on the path is a right child, from the leaf up.
+    pub is_right_child: Array<C, Var<C::N>>,
+    /// Sibling hashes of the nodes on the path, from the leaf up.
+    pub sibling_hashes: Array<C, [Felt<C::F>; DIGEST_SIZE]>,
+}
+
 impl Hintable<C> for InternalVmVerifierInput<SC> {
     type HintVariable = InternalVmVerifierInputVariable<C>;
 
@@ -31,6 +42,40 @@ impl Hintable<C> for InternalVmVerifierInput<SC> {
     fn write(&self) -> Vec<Vec<<C as Config>::N>> {
         let mut stream = write_field_slice(&self.self_program_commit);
         stream.extend(self.proofs.write());
+        // Only read by internal VM verifiers built with a leaf verifier commit set.
+        if let Some(leaf_verifier_commit_proof) = &self.leaf_verifier_commit_proof {
+            stream.extend(leaf_verifier_commit_proof.write());
+        }
+        stream
+    }
+}
+
+impl Hintable<C> for LeafVerifierCommitProof<F> {
+    type HintVariable = LeafVerifierCommitProofVariable<C>;
+
+    fn read(builder: &mut Builder<C>) -> Self::HintVariable {
+        let len = builder.hint_var();
+        let is_right_child = builder.array(len);
+        let sibling_hashes = builder.array(len);
+        builder.range(0, len).for_each(|i_vec, builder| {
+            let is_right = builder.hint_var();
+            builder.set_value(&is_right_child, i_vec[0], is_right);
+            let hash = array::from_fn(|_| builder.hint_felt());
+            builder.set_value(&sibling_hashes, i_vec[0], hash);
+        });
+        Self::HintVariable {
+            is_right_child,
+            sibling_hashes,
+        }
+    }
+
+    fn write(&self) -> Vec<Vec<<C as Config>::N>> {
+        let len = <<C as Config>::N>::from_canonical_usize(self.sibling_hashes.len());
+        let mut stream = len.write();
+        for (is_right, hash) in self.is_right_child.iter().zip(&self.sibling_hashes) {
+            stream.extend(<<C as Config>::N>::from_bool(*is_right).write());
+            stream.extend(write_field_slice(hash));
+        }
         stream
     }
 }
@@ -1,6 +1,14 @@
+use std::array;
+
 use openvm_circuit::{
-    arch::{instructions::program::Program, SystemConfig},
-    system::memory::tree::public_values::PUBLIC_VALUES_ADDRESS_SPACE_OFFSET,
+    arch::{hasher::poseidon2::vm_poseidon2_hasher, instructions::program::Program, SystemConfig},
+    system::memory::tree::{
+        data_segments::{
+            region_height, supports_data_segments, zero_data_segments_root,
+            DATA_SEGMENTS_ADDRESS_SPACE_OFFSET, DATA_SEGMENTS_START,
+        },
+        public_values::PUBLIC_VALUES_ADDRESS_SPACE_OFFSET,
+    },
 };
 use openvm_native_compiler::{conversion::CompilerOptions, prelude::*};
 use openvm_native_recursion::{
@@ -23,7 +31,7 @@ use crate::{
             assert_required_air_for_app_vm_present, get_connector_pvs, get_memory_pvs,
             get_program_commit, types::VmVerifierPvs,
         },
-        leaf::types::UserPublicValuesRootProof,
+        leaf::types::{DataSegmentsRootProof, UserPublicValuesRootProof},
         utils::VariableP2Compressor,
     },
     C, F,
@@ -86,6 +94,9 @@ impl LeafVmVerifierConfig {
                 assert_or_assign_memory_pvs(builder, &pvs.memory, i, &proof_memory_pvs);
             });
             builder.cycle_tracker_end("VerifyProofs");
+            builder.cycle_tracker_start("ExtractDataSegmentsCommit");
+            self.verify_data_segments_root(&mut builder, &pvs);
+            builder.cycle_tracker_end("ExtractDataSegmentsCommit");
             builder.cycle_tracker_start("ExtractPublicValuesCommit");
             let is_terminate = builder.cast_felt_to_var(pvs.connector.is_terminate);
             builder.if_eq(is_terminate, F::ONE).then(|builder| {
@@ -105,6 +116,59 @@ impl LeafVmVerifierConfig {
         builder.compile_isa_with_options(self.compiler_options)
     }
 
+    /// Read the data segments root proof from the input stream if it is present and verify it
+    /// against the initial memory root. This verification must be consistent with
+    /// `openvm_circuit::system::memory::tree::data_segments`.
+    /// Assigns the memory root of the app program, which is the initial memory root with the data
+    /// segments region zeroed, and the data segments commit, which is zero without the proof.
+    ///
+    /// The proof is optional so that segments other than the first don't need it. Skipping it in
+    /// the first segment makes the initial memory root the memory root of the app program, which
+    /// changes the exe commit unless there are no data segments.
+    fn verify_data_segments_root(&self, builder: &mut Builder<C>, pvs: &VmVerifierPvs<Felt<F>>) {
+        builder.assign(&pvs.exe_memory_root, pvs.memory.initial_root);
+        let zero: [Felt<F>; DIGEST_SIZE] = array::from_fn(|_| builder.eval(F::ZERO));
+        builder.assign(&pvs.data_segments_commit, zero);
+
+        let has_proof = <usize as Hintable<C>>::read(builder);
+        let mem_config = &self.app_system_config.memory_config;
+        if !supports_data_segments(mem_config) {
+            builder.assert_var_eq(has_proof, F::ZERO);
+            return;
+        }
+        let memory_dimensions = mem_config.memory_dimensions();
+        let data_as = DATA_SEGMENTS_ADDRESS_SPACE_OFFSET + memory_dimensions.as_offset;
+        let region_start_idx =
+            memory_dimensions.label_to_index((data_as, DATA_SEGMENTS_START / DIGEST_SIZE as u32));
+        let region_height = region_height::<DIGEST_SIZE>();
+        let proof_len = memory_dimensions.overall_height() - region_height;
+        let idx_prefix = region_start_idx >> region_height;
+        let zero_region_root = zero_data_segments_root::<DIGEST_SIZE, F>(&vm_poseidon2_hasher());
+
+        builder.if_eq(has_proof, F::ONE).then(|builder| {
+            let root_proof = DataSegmentsRootProof::<F>::read(builder);
+            builder.assert_eq::<Usize<_>>(root_proof.sibling_hashes.len(), Usize::from(proof_len));
+            let mut curr_root = root_proof.data_segments_commit;
+            let mut curr_exe_root: [Felt<F>; DIGEST_SIZE] =
+                zero_region_root.map(|x| builder.eval(x));
+            // Share the same state array to avoid unnecessary allocations.
+            let compressor = VariableP2Compressor::new(builder);
+            for i in 0..proof_len {
+                let sibling_hash = builder.get(&root_proof.sibling_hashes, i);
+                if idx_prefix & (1 << i) != 0 {
+                    curr_root = compressor.compress(builder, &sibling_hash, &curr_root);
+                    curr_exe_root = compressor.compress(builder, &sibling_hash, &curr_exe_root);
+                } else {
+                    curr_root = compressor.compress(builder, &curr_root, &sibling_hash);
+                    curr_exe_root = compressor.compress(builder, &curr_exe_root, &sibling_hash);
+                }
+            }
+            builder.assert_eq::<[_; DIGEST_SIZE]>(pvs.memory.initial_root, curr_root);
+            builder.assign(&pvs.exe_memory_root, curr_exe_root);
+            builder.assign(&pvs.data_segments_commit, root_proof.data_segments_commit);
+        });
+    }
+
     /// Read the public values root proof from the input stream and verify it.
     /// This verification must be consistent `openvm_circuit::system::memory::tree::public_values`.
     /// Returns the public values commit and the corresponding memory state root.
@@ -1,5 +1,7 @@
 use derivative::Derivative;
-use openvm_circuit::system::memory::tree::public_values::UserPublicValuesProof;
+use openvm_circuit::system::memory::tree::{
+    data_segments::DataSegmentsProof, public_values::UserPublicValuesProof,
+};
 use openvm_native_compiler::ir::DIGEST_SIZE;
 use openvm_stark_sdk::{
     config::baby_bear_poseidon2::BabyBearPoseidon2Config,
@@ -24,6 +26,10 @@ pub struct LeafVmVerifierInput<SC: StarkGenericConfig> {
     /// The public values root proof. Leaf VM verifier only needs this when verifying the last
     /// segment.
     pub public_values_root_proof: Option<UserPublicValuesRootProof<Val<SC>>>,
+    /// The data segments root proof. Leaf VM verifier only needs this when verifying the first
+    /// segment of an execution with data segments.
+    #[serde(default)]
+    pub data_segments_root_proof: Option<DataSegmentsRootProof<Val<SC>>>,
 }
 assert_impl_all!(LeafVmVerifierInput<BabyBearPoseidon2Config>: Serialize, DeserializeOwned);
 
@@ -39,19 +45,34 @@ pub struct UserPublicValuesRootProof<F> {
 }
 assert_impl_all!(UserPublicValuesRootProof<BabyBear>: Serialize, DeserializeOwned);
 
+/// Proof that the merkle root of the data segments region is in the initial memory state. Can be
+/// extracted from `openvm_circuit::system::memory::tree::data_segments::DataSegmentsProof`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DataSegmentsRootProof<F> {
+    /// Sibling hashes for proving the merkle root of the data segments region. The path is
+    /// constant for a specific VM, as in [UserPublicValuesRootProof].
+    pub sibling_hashes: Vec<[F; DIGEST_SIZE]>,
+    pub data_segments_commit: [F; DIGEST_SIZE],
+}
+assert_impl_all!(DataSegmentsRootProof<BabyBear>: Serialize, DeserializeOwned);
+
 impl<SC: StarkGenericConfig> LeafVmVerifierInput<SC> {
     pub fn chunk_continuation_vm_proof(proof: &ContinuationVmProof<SC>, chunk: usize) -> Vec<Self> {
         let ContinuationVmProof {
             per_segment,
             user_public_values,
+            data_segments,
         } = proof;
         let mut ret: Vec<Self> = per_segment
             .chunks(chunk)
             .map(|proof| Self {
                 proofs: proof.to_vec(),
                 public_values_root_proof: None,
+                data_segments_root_proof: None,
             })
             .collect();
+        ret[0].data_segments_root_proof =
+            data_segments.as_ref().map(DataSegmentsRootProof::extract);
         ret.last_mut().unwrap().public_values_root_proof =
             Some(UserPublicValuesRootProof::extract(user_public_values));
         ret
@@ -71,3 +92,16 @@ impl<F: Clone> UserPublicValuesRootProof<F> {
         }
     }
 }
+
+impl<F: Clone> DataSegmentsRootProof<F> {
+    pub fn extract(data_segments_proof: &DataSegmentsProof<{ DIGEST_SIZE }, F>) -> Self {
+        Self {
+            sibling_hashes: data_segments_proof
+                .proof
+                .iter()
+                .map(|(_, hash)| hash.clone())
+                .collect(),
+            data_segments_commit: data_segments_proof.data_segments_commit.clone(),
+        }
+    }
+}
@@ -10,7 +10,7 @@ use openvm_stark_sdk::openvm_stark_backend::{
 
 use crate::{
     verifier::{
-        leaf::types::{LeafVmVerifierInput, UserPublicValuesRootProof},
+        leaf::types::{DataSegmentsRootProof, LeafVmVerifierInput, UserPublicValuesRootProof},
         utils,
     },
     C, F,
@@ -25,13 +25,29 @@ pub struct UserPublicValuesRootProofVariable<const CHUNK: usize, C: Config> {
     pub public_values_commit: [Felt<C::F>; CHUNK],
 }
 
+#[derive(DslVariable, Clone)]
+pub struct DataSegmentsRootProofVariable<const CHUNK: usize, C: Config> {
+    /// Sibling hashes for proving the merkle root of the data segments region. The path is
+    /// constant for a specific VM.
+    pub sibling_hashes: Array<C, [Felt<C::F>; CHUNK]>,
+    pub data_segments_commit: [Felt<C::F>; CHUNK],
+}
+
 impl<SC: StarkGenericConfig> LeafVmVerifierInput<SC> {
     pub fn write_to_stream<C: Config<N = Val<SC>>>(&self) -> Vec<Vec<Val<SC>>>
     where
         Vec<Proof<SC>>: Hintable<C>,
         UserPublicValuesRootProof<Val<SC>>: Hintable<C>,
+        DataSegmentsRootProof<Val<SC>>: Hintable<C>,
     {
         let mut ret = Hintable::<C>::write(&self.proofs);
+        // The leaf VM verifier reads a flag for whether the data segments root proof is present.
+        if let Some(data_segments_root_proof) = &self.data_segments_root_proof {
+            ret.extend(<usize as Hintable<C>>::write(&1));
+            ret.extend(Hintable::<C>::write(data_segments_root_proof));
+        } else {
+            ret.extend(<usize as Hintable<C>>::write(&0));
+        }
         if let Some(pvs_root_proof) = &self.public_values_root_proof {
             ret.extend(Hintable::<C>::write(pvs_root_proof));
         }
@@ -66,3 +82,31 @@ impl Hintable<C> for UserPublicValuesRootProof<F> {
         stream
     }
 }
+
+impl Hintable<C> for DataSegmentsRootProof<F> {
+    type HintVariable = DataSegmentsRootProofVariable<{ DIGEST_SIZE }, C>;
+    fn read(builder: &mut Builder<C>) -> Self::HintVariable {
+        let len = builder.hint_var();
+        let sibling_hashes = builder.array(len);
+        builder.range(0, len).for_each(|i_vec, builder| {
+            let hash = array::from_fn(|_| builder.hint_felt());
+            builder.set_value(&sibling_hashes, i_vec[0], hash);
+        });
+        let data_segments_commit = array::from_fn(|_| builder.hint_felt());
+        Self::HintVariable {
+            sibling_hashes,
+            data_segments_commit,
+        }
+    }
+    fn write(&self) -> Vec<Vec<<C as Config>::N>> {
+        let len = <<C as Config>::N>::from_canonical_usize(self.sibling_hashes.len());
+        let mut stream = len.write();
+        stream.extend(
+            self.sibling_hashes
+                .iter()
+                .flat_map(utils::write_field_slice),
+        );
+        stream.extend(utils::write_field_slice(&self.data_segments_commit));
+        stream
+    }
+}
@@ -1,8 +1,17 @@
 use internal::types::InternalVmVerifierPvs;
+use openvm_circuit::arch::instructions::riscv::RV32_REGISTER_NUM_LIMBS;
+use openvm_keccak256_circuit::Keccak256InAddressSpace;
 use openvm_native_circuit::NativeConfig;
-use openvm_native_compiler::ir::DIGEST_SIZE;
+use openvm_native_compiler::{
+    conversion::AS,
+    ir::{DIGEST_SIZE, KECCAK_DIGEST_BYTES},
+};
+use root::RootVmConfig;
 
-use crate::{config::AggStarkConfig, verifier::common::types::VmVerifierPvs};
+use crate::{
+    config::{AggStarkConfig, PublicValuesMode},
+    verifier::common::types::VmVerifierPvs,
+};
 
 pub mod common;
 pub mod internal;
@@ -29,13 +38,39 @@ impl AggStarkConfig {
         config.system.profiling = self.profiling;
         config
     }
-    pub fn root_verifier_vm_config(&self) -> NativeConfig {
-        let mut config = NativeConfig::aggregation(
-            // app_commit + leaf_verifier_commit + public_values
-            DIGEST_SIZE * 2 + self.max_num_user_public_values,
+    pub fn root_verifier_vm_config(&self) -> RootVmConfig {
+        let (num_exposed_user_public_values, keccak) = match self.public_values_mode {
+            PublicValuesMode::Raw => (self.max_num_user_public_values, None),
+            PublicValuesMode::Keccak256 => {
+                assert_eq!(
+                    self.max_num_user_public_values % RV32_REGISTER_NUM_LIMBS,
+                    0,
+                    "Keccak256 public values mode requires a multiple of {} user public values",
+                    RV32_REGISTER_NUM_LIMBS
+                );
+                (
+                    KECCAK_DIGEST_BYTES,
+                    Some(Keccak256InAddressSpace {
+                        address_space: AS::Native as u32,
+                    }),
+                )
+            }
+        };
+        // exe_commit + leaf_verifier_commit, and input_commit and data_segments_commit if exposed
+        let num_commits = 2
+            + usize::from(self.expose_input_commit)
+            + usize::from(self.expose_data_segments_commit);
+        let mut native = NativeConfig::aggregation(
+            DIGEST_SIZE * num_commits + num_exposed_user_public_values,
             SBOX_SIZE.min(self.root_fri_params.max_constraint_degree()),
         );
-        config.system.profiling = self.profiling;
-        config
+        native.system.profiling = self.profiling;
+        RootVmConfig {
+            native,
+            keccak,
+            num_user_public_values: self.max_num_user_public_values,
+            expose_input_commit: self.expose_input_commit,
+            expose_data_segments_commit: self.expose_data_segments_commit,
+        }
     }
 }
@@ -1,6 +1,15 @@
 use std::array;
 
-use openvm_circuit::arch::instructions::program::Program;
+use derive_more::derive::From;
+use openvm_circuit::{
+    arch::{
+        instructions::program::Program, SystemConfig, VmChipComplex, VmConfig, VmInventoryError,
+    },
+    circuit_derive::{Chip, ChipUsageGetter},
+    derive::{AnyEnum, InstructionExecutor, VmConfig},
+};
+use openvm_keccak256_circuit::{Keccak256Executor, Keccak256InAddressSpace, Keccak256Periphery};
+use openvm_native_circuit::{NativeConfig, NativeConfigExecutor, NativeConfigPeriphery};
 use openvm_native_compiler::{conversion::CompilerOptions, prelude::*};
 use openvm_native_recursion::{
     fri::TwoAdicFriPcsVariable, hints::Hintable, types::new_from_inner_multi_vk,
@@ -8,12 +17,18 @@ use openvm_native_recursion::{
 };
 use openvm_stark_sdk::{
     config::FriParameters,
-    openvm_stark_backend::{keygen::types::MultiStarkVerifyingKey, p3_field::FieldAlgebra},
+    openvm_stark_backend::{
+        keygen::types::MultiStarkVerifyingKey,
+        p3_field::{FieldAlgebra, PrimeField32},
+    },
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{
+    config::PublicValuesMode,
     verifier::{
         common::non_leaf::NonLeafVerifierVariables,
+        internal::{assert_leaf_verifier_commit_allowed, types::LeafVerifierCommitSet},
         root::{
             types::{RootVmVerifierInput, RootVmVerifierPvs},
             vars::RootVmVerifierInputVariable,
@@ -26,13 +41,47 @@ use crate::{
 pub mod types;
 mod vars;
 
+/// VM config of the root verifier.
+#[derive(Clone, Debug, VmConfig, Serialize, Deserialize)]
+pub struct RootVmConfig {
+    #[config]
+    pub native: NativeConfig,
+    /// Only needed in [PublicValuesMode::Keccak256].
+    #[extension(executor = Keccak256Executor, periphery = Keccak256Periphery)]
+    pub keccak: Option<Keccak256InAddressSpace>,
+    /// Number of user public values read by the root verifier program. In
+    /// [PublicValuesMode::Keccak256], the root verifier exposes their digest instead.
+    pub num_user_public_values: usize,
+    /// Whether the root verifier exposes the input commitment of the App VM.
+    #[serde(default)]
+    pub expose_input_commit: bool,
+    /// Whether the root verifier exposes the data segments commitment of the App VM.
+    #[serde(default)]
+    pub expose_data_segments_commit: bool,
+}
+
+impl RootVmConfig {
+    pub fn public_values_mode(&self) -> PublicValuesMode {
+        if self.keccak.is_some() {
+            PublicValuesMode::Keccak256
+        } else {
+            PublicValuesMode::Raw
+        }
+    }
+}
+
 /// Config to generate Root VM verifier program.
 pub struct RootVmVerifierConfig {
     pub leaf_fri_params: FriParameters,
     pub internal_fri_params: FriParameters,
     pub num_public_values: usize,
+    pub public_values_mode: PublicValuesMode,
+    pub expose_input_commit: bool,
+    pub expose_data_segments_commit: bool,
     pub internal_vm_verifier_commit: [F; DIGEST_SIZE],
     pub compiler_options: CompilerOptions,
+    /// If set, only proofs of these leaf verifiers are accepted.
+    pub leaf_verifier_commits: Option<LeafVerifierCommitSet>,
 }
 impl RootVmVerifierConfig {
     pub fn build_program(
@@ -71,12 +120,25 @@ impl RootVmVerifierConfig {
             };
             let (merged_pvs, expected_leaf_commit) =
                 non_leaf_verifier.verify_internal_or_leaf_verifier_proofs(&mut builder, &proofs);
+            // The proof may come directly from a leaf verifier, so this cannot be left to the
+            // internal verifier.
+            if let Some(leaf_verifier_commits) = &self.leaf_verifier_commits {
+                assert_leaf_verifier_commit_allowed(
+                    &mut builder,
+                    leaf_verifier_commits,
+                    expected_leaf_commit,
+                );
+            }
             builder.cycle_tracker_end("VerifyProofs");
 
             // App Program should terminate
             builder.assert_felt_eq(merged_pvs.connector.is_terminate, F::ONE);
             // App Program should exit successfully
             builder.assert_felt_eq(merged_pvs.connector.exit_code, F::ZERO);
+            // The input commitment starts from zero
+            for x in merged_pvs.connector.initial_input_commit {
+                builder.assert_felt_eq(x, F::ZERO);
+            }
 
             builder.cycle_tracker_start("ExtractPublicValues");
             builder.assert_usize_eq(public_values.len(), RVar::from(self.num_public_values));
@@ -86,6 +148,15 @@ impl RootVmVerifierConfig {
             let hasher = VariableP2Hasher::new(&mut builder);
             let pv_commit = hasher.merkle_root(&mut builder, &public_values_vec);
             builder.assert_eq::<[_; DIGEST_SIZE]>(merged_pvs.public_values_commit, pv_commit);
+            let public_values_vec = match self.public_values_mode {
+                PublicValuesMode::Raw => public_values_vec,
+                PublicValuesMode::Keccak256 => {
+                    let digest = builder.keccak256(&public_values);
+                    (0..KECCAK_DIGEST_BYTES)
+                        .map(|i| builder.get(&digest, i))
+                        .collect()
+                }
+            };
             builder.cycle_tracker_end("ExtractPublicValues");
 
             let pvs = RootVmVerifierPvs {
@@ -93,10 +164,16 @@ impl RootVmVerifierConfig {
                     &mut builder,
                     &hasher,
                     merged_pvs.app_commit,
-                    merged_pvs.memory.initial_root,
+                    merged_pvs.exe_memory_root,
                     merged_pvs.connector.initial_pc,
                 ),
                 leaf_verifier_commit: expected_leaf_commit,
+                input_commit: self
+                    .expose_input_commit
+                    .then_some(merged_pvs.connector.final_input_commit),
+                data_segments_commit: self
+                    .expose_data_segments_commit
+                    .then_some(merged_pvs.data_segments_commit),
                 public_values: public_values_vec,
             };
             pvs.flatten()
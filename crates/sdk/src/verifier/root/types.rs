@@ -3,7 +3,10 @@ use std::array;
 use derivative::Derivative;
 use openvm_native_compiler::ir::{Builder, Config, Felt, DIGEST_SIZE};
 use openvm_stark_sdk::{
-    config::baby_bear_poseidon2::BabyBearPoseidon2Config,
+    config::{
+        baby_bear_poseidon2::BabyBearPoseidon2Config,
+        baby_bear_poseidon2_root::BabyBearPoseidon2RootConfig,
+    },
     openvm_stark_backend::{
         config::{Com, StarkGenericConfig, Val},
         p3_field::PrimeField32,
@@ -13,12 +16,21 @@ use openvm_stark_sdk::{
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use static_assertions::assert_impl_all;
 
+use crate::verifier::{internal::types::LeafVerifierCommitProof, root::RootVmConfig};
+
 #[derive(Debug)]
 pub struct RootVmVerifierPvs<T> {
     /// The commitment of the App VM executable.
     pub exe_commit: [T; DIGEST_SIZE],
     /// The commitment of the leaf verifier program, which commits the VM config of App VM.
     pub leaf_verifier_commit: [T; DIGEST_SIZE],
+    /// The commitment to the words the App VM execution wrote to memory through hint
+    /// instructions, or zero if the App VM does not commit to its input stream. Only exposed if
+    /// [RootVmConfig::expose_input_commit] is set.
+    pub input_commit: Option<[T; DIGEST_SIZE]>,
+    /// The merkle root of the data segments mapped into App VM memory, or zero if there are no
+    /// data segments. Only exposed if [RootVmConfig::expose_data_segments_commit] is set.
+    pub data_segments_commit: Option<[T; DIGEST_SIZE]>,
     /// Raw public values from App VM execution.
     pub public_values: Vec<T>,
 }
@@ -34,14 +46,40 @@ pub struct RootVmVerifierInput<SC: StarkGenericConfig> {
     pub proofs: Vec<Proof<SC>>,
     /// Public values to expose directly
     pub public_values: Vec<Val<SC>>,
+    /// Proof that the leaf verifier commit of `proofs` is allowed. Root VM verifier only needs
+    /// this when it is built with a
+    /// [LeafVerifierCommitSet](crate::verifier::internal::types::LeafVerifierCommitSet).
+    #[serde(default)]
+    pub leaf_verifier_commit_proof: Option<LeafVerifierCommitProof<Val<SC>>>,
 }
 assert_impl_all!(RootVmVerifierInput<BabyBearPoseidon2Config>: Serialize, DeserializeOwned);
 
+/// Aggregated STARK proof of an App VM execution, i.e. a proof of the root verifier.
+/// The public values of the proof are [RootVmVerifierPvs], which commit `exe_commit`, the leaf
+/// verifier commit, the input commitment and the user public values.
+#[derive(Serialize, Deserialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Clone(bound = "Com<SC>: Clone"))]
+pub struct AggStarkProof<SC: StarkGenericConfig> {
+    pub proof: Proof<SC>,
+}
+assert_impl_all!(AggStarkProof<BabyBearPoseidon2RootConfig>: Serialize, DeserializeOwned);
+
 impl<F: PrimeField32> RootVmVerifierPvs<Felt<F>> {
-    pub fn uninit<C: Config<F = F>>(builder: &mut Builder<C>, num_public_values: usize) -> Self {
+    pub fn uninit<C: Config<F = F>>(
+        builder: &mut Builder<C>,
+        num_public_values: usize,
+        config: &RootVmConfig,
+    ) -> Self {
         Self {
             exe_commit: array::from_fn(|_| builder.uninit()),
             leaf_verifier_commit: array::from_fn(|_| builder.uninit()),
+            input_commit: config
+                .expose_input_commit
+                .then(|| array::from_fn(|_| builder.uninit())),
+            data_segments_commit: config
+                .expose_data_segments_commit
+                .then(|| array::from_fn(|_| builder.uninit())),
             public_values: (0..num_public_values).map(|_| builder.uninit()).collect(),
         }
     }
@@ -51,16 +89,29 @@ impl<F: Copy> RootVmVerifierPvs<F> {
     pub fn flatten(self) -> Vec<F> {
         let mut ret = self.exe_commit.to_vec();
         ret.extend(self.leaf_verifier_commit);
+        ret.extend(self.input_commit.into_iter().flatten());
+        ret.extend(self.data_segments_commit.into_iter().flatten());
         ret.extend(self.public_values);
         ret
     }
-    pub fn from_flatten(flatten: Vec<F>) -> Self {
-        let exe_commit = flatten[..DIGEST_SIZE].try_into().unwrap();
-        let leaf_verifier_commit = flatten[DIGEST_SIZE..2 * DIGEST_SIZE].try_into().unwrap();
-        let public_values = flatten[2 * DIGEST_SIZE..].to_vec();
+    /// Inverse of [Self::flatten] for the public values of the root verifier with `config`.
+    pub fn from_flatten(flatten: Vec<F>, config: &RootVmConfig) -> Self {
+        let mut offset = 0;
+        let mut next_digest = || -> [F; DIGEST_SIZE] {
+            let digest = flatten[offset..offset + DIGEST_SIZE].try_into().unwrap();
+            offset += DIGEST_SIZE;
+            digest
+        };
+        let exe_commit = next_digest();
+        let leaf_verifier_commit = next_digest();
+        let input_commit = config.expose_input_commit.then(&mut next_digest);
+        let data_segments_commit = config.expose_data_segments_commit.then(&mut next_digest);
+        let public_values = flatten[offset..].to_vec();
         Self {
             exe_commit,
             leaf_verifier_commit,
+            input_commit,
+            data_segments_commit,
             public_values,
         }
     }
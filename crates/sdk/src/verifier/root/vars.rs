@@ -27,6 +27,10 @@ impl Hintable<C> for RootVmVerifierInput<SC> {
     fn write(&self) -> Vec<Vec<<C as Config>::N>> {
         let mut stream = self.proofs.write();
         stream.extend(self.public_values.write());
+        // Only read by root VM verifiers built with a leaf verifier commit set.
+        if let Some(leaf_verifier_commit_proof) = &self.leaf_verifier_commit_proof {
+            stream.extend(leaf_verifier_commit_proof.write());
+        }
         stream
     }
 }
@@ -21,6 +21,21 @@ pub fn compress_babybear_var_to_bn254(
     builder.eval(ret)
 }
 
+/// Packs big-endian bytes into a single Bn254 variable. `bytes` must be at most 31 bytes long.
+pub fn compress_be_bytes_var_to_bn254(
+    builder: &mut Builder<OuterConfig>,
+    bytes: &[Var<Bn254Fr>],
+) -> Var<Bn254Fr> {
+    assert!(bytes.len() < 32);
+    let mut ret = SymbolicVar::ZERO;
+    let mut base = Bn254Fr::ONE;
+    bytes.iter().rev().for_each(|&x| {
+        ret += x * base;
+        base *= Bn254Fr::from_canonical_u32(1 << 8);
+    });
+    builder.eval(ret)
+}
+
 pub(crate) fn assign_array_to_slice<C: Config>(
     builder: &mut Builder<C>,
     dst_slice: &[Felt<C::F>],
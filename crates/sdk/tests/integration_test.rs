@@ -8,6 +8,7 @@ use openvm_circuit::{
     },
     system::{memory::tree::public_values::UserPublicValuesProof, program::trace::VmCommittedExe},
 };
+use openvm_keccak256_circuit::utils::keccak256;
 use openvm_native_circuit::{Native, NativeConfig};
 use openvm_native_compiler::{conversion::CompilerOptions, prelude::*};
 use openvm_native_recursion::{
@@ -16,12 +17,13 @@ use openvm_native_recursion::{
 };
 use openvm_rv32im_transpiler::{Rv32ITranspilerExtension, Rv32MTranspilerExtension};
 use openvm_sdk::{
-    commit::AppExecutionCommit,
-    config::{AggConfig, AggStarkConfig, AppConfig, Halo2Config},
-    keygen::{AppProvingKey, RootVerifierProvingKey},
+    commit::{AppExecutionCommit, AppExecutionCommitJson},
+    config::{AggConfig, AggStarkConfig, AppConfig, Halo2Config, PublicValuesMode, WrapperBackend},
+    keygen::{AppProvingKey, RootVerifierProvingKey, SnarkProvingKey},
     static_verifier::StaticVerifierPvHandler,
     verifier::{
         common::types::{SpecialAirIds, VmVerifierPvs},
+        internal::types::LeafVerifierCommitSet,
         leaf::types::{LeafVmVerifierInput, UserPublicValuesRootProof},
         root::types::RootVmVerifierPvs,
         utils::compress_babybear_var_to_bn254,
@@ -101,6 +103,7 @@ fn agg_config_for_test() -> AggConfig {
             verifier_k: 24,
             wrapper_k: None,
             profiling: false,
+            wrapper_backend: WrapperBackend::Halo2,
         },
     }
 }
@@ -119,6 +122,9 @@ fn agg_stark_config_for_test() -> AggStarkConfig {
             ..Default::default()
         },
         root_max_constraint_degree: (1 << ROOT_LOG_BLOWUP) + 1,
+        public_values_mode: PublicValuesMode::Raw,
+        expose_input_commit: false,
+        expose_data_segments_commit: false,
     }
 }
 
@@ -178,6 +184,7 @@ fn test_public_values_and_leaf_verification() {
             LeafVmVerifierInput {
                 proofs: app_vm_seg_proofs.clone(),
                 public_values_root_proof: None,
+                data_segments_root_proof: None,
             },
         )
         .expect("failed to verify the first segment");
@@ -187,6 +194,9 @@ fn test_public_values_and_leaf_verification() {
         assert_eq!(leaf_vm_pvs.app_commit, expected_app_commit);
         assert_eq!(leaf_vm_pvs.connector.is_terminate, F::ZERO);
         assert_eq!(leaf_vm_pvs.connector.initial_pc, F::ZERO);
+        // Without data segments, the initial memory is the memory of the app program.
+        assert_eq!(leaf_vm_pvs.exe_memory_root, leaf_vm_pvs.memory.initial_root);
+        assert_eq!(leaf_vm_pvs.data_segments_commit, [F::ZERO; DIGEST_SIZE]);
         (
             leaf_vm_pvs.connector.final_pc,
             leaf_vm_pvs.memory.final_root,
@@ -209,6 +219,7 @@ fn test_public_values_and_leaf_verification() {
             LeafVmVerifierInput {
                 proofs: vec![app_last_proof.clone()],
                 public_values_root_proof: Some(pv_root_proof.clone()),
+                data_segments_root_proof: None,
             },
         )
         .expect("failed to verify the second segment");
@@ -235,6 +246,7 @@ fn test_public_values_and_leaf_verification() {
             LeafVmVerifierInput {
                 proofs: vec![app_last_proof.clone()],
                 public_values_root_proof: Some(wrong_pv_root_proof),
+                data_segments_root_proof: None,
             },
         );
         assert!(
@@ -254,6 +266,7 @@ fn test_public_values_and_leaf_verification() {
             LeafVmVerifierInput {
                 proofs: vec![app_last_proof.clone()],
                 public_values_root_proof: Some(wrong_pv_root_proof),
+                data_segments_root_proof: None,
             },
         );
         assert!(
@@ -277,7 +290,7 @@ fn test_static_verifier_custom_pv_handler() {
             &self,
             builder: &mut Builder<OuterConfig>,
             input: &StarkProofVariable<OuterConfig>,
-            _root_verifier_pk: &RootVerifierProvingKey,
+            root_verifier_pk: &RootVerifierProvingKey,
             special_air_ids: &SpecialAirIds,
         ) -> usize {
             let pv_air = builder.get(&input.per_air, special_air_ids.public_values_air_id);
@@ -287,7 +300,8 @@ fn test_static_verifier_custom_pv_handler() {
                 .into_iter()
                 .map(|x| builder.cast_felt_to_var(x))
                 .collect();
-            let pvs = RootVmVerifierPvs::from_flatten(public_values);
+            let pvs =
+                RootVmVerifierPvs::from_flatten(public_values, &root_verifier_pk.vm_pk.vm_config);
             let exe_commit = compress_babybear_var_to_bn254(builder, pvs.exe_commit);
             let leaf_commit = compress_babybear_var_to_bn254(builder, pvs.leaf_verifier_commit);
             let num_public_values = pvs.public_values.len();
@@ -357,6 +371,281 @@ fn test_static_verifier_custom_pv_handler() {
     assert!(Sdk.verify_evm_proof(&evm_verifier, &evm_proof).is_ok());
 }
 
+#[test]
+fn test_app_execution_commit() {
+    let app_log_blowup = 1;
+    let app_pk = Sdk
+        .app_keygen(small_test_app_config(app_log_blowup))
+        .unwrap();
+    let exe = app_committed_exe_for_test(app_log_blowup).exe.clone();
+
+    let commit = Sdk
+        .compute_app_execution_commit(&app_pk, exe.clone())
+        .unwrap();
+    let json = AppExecutionCommitJson::from(&commit);
+    assert_eq!(AppExecutionCommit::try_from(&json).unwrap(), commit);
+    // A JSON whose Bn254 form doesn't match the BabyBear digests is rejected.
+    let mut tampered_json = json.clone();
+    tampered_json.exe_commit[0] ^= 1;
+    assert!(AppExecutionCommit::try_from(&tampered_json).is_err());
+    Sdk.verify_app_execution_commit(&app_pk, exe.clone(), &commit)
+        .unwrap();
+
+    let mut wrong_commit = commit;
+    wrong_commit.exe_commit[0] += F::ONE;
+    assert!(Sdk
+        .verify_app_execution_commit(&app_pk, exe, &wrong_commit)
+        .is_err());
+}
+
+#[test]
+fn test_agg_stark_proof_generation_and_verification() {
+    let app_log_blowup = 1;
+    let app_config = small_test_app_config(app_log_blowup);
+    let app_pk = Arc::new(Sdk.app_keygen(app_config).unwrap());
+    let agg_stark_pk = Sdk.agg_stark_keygen(agg_stark_config_for_test()).unwrap();
+    let app_committed_exe = app_committed_exe_for_test(app_log_blowup);
+    let expected_commit = AppExecutionCommit::compute(
+        &app_pk.app_vm_pk.vm_config,
+        &app_committed_exe,
+        &app_pk.leaf_committed_exe,
+    );
+
+    let proof = Sdk
+        .generate_agg_stark_proof(
+            app_pk.clone(),
+            app_committed_exe,
+            agg_stark_pk.clone(),
+            StdIn::default(),
+        )
+        .unwrap();
+    let pvs = Sdk
+        .verify_agg_stark_proof(&agg_stark_pk, &expected_commit, &proof)
+        .unwrap();
+    assert_eq!(pvs.public_values, vec![F::ZERO; NUM_PUB_VALUES]);
+    // The root verifier does not expose the input and data segments commitments by default.
+    assert_eq!(pvs.input_commit, None);
+    assert_eq!(pvs.data_segments_commit, None);
+
+    // A proof must not verify against a different executable.
+    let mut wrong_commit = expected_commit;
+    wrong_commit.exe_commit[0] += F::ONE;
+    assert!(Sdk
+        .verify_agg_stark_proof(&agg_stark_pk, &wrong_commit, &proof)
+        .is_err());
+}
+
+#[test]
+fn test_agg_stark_proof_with_data_segments() {
+    let app_log_blowup = 1;
+    let app_config = small_test_app_config(app_log_blowup);
+    let app_pk = Arc::new(Sdk.app_keygen(app_config).unwrap());
+    let agg_stark_pk = Sdk
+        .agg_stark_keygen(AggStarkConfig {
+            expose_data_segments_commit: true,
+            ..agg_stark_config_for_test()
+        })
+        .unwrap();
+    let app_committed_exe = app_committed_exe_for_test(app_log_blowup);
+    let expected_commit = AppExecutionCommit::compute(
+        &app_pk.app_vm_pk.vm_config,
+        &app_committed_exe,
+        &app_pk.leaf_committed_exe,
+    );
+
+    let mut stdin = StdIn::default();
+    stdin.add_data_segment("table", (0..=255).collect());
+    stdin.add_data_segment("genesis", vec![7; 100]);
+    let proof = Sdk
+        .generate_agg_stark_proof(
+            app_pk.clone(),
+            app_committed_exe,
+            agg_stark_pk.clone(),
+            stdin.clone(),
+        )
+        .unwrap();
+    // Data segments don't change the exe commit.
+    let pvs = Sdk
+        .verify_agg_stark_proof(&agg_stark_pk, &expected_commit, &proof)
+        .unwrap();
+    assert_eq!(
+        pvs.data_segments_commit,
+        Some(stdin.data_segments_commit().unwrap())
+    );
+    assert_ne!(pvs.data_segments_commit, Some([F::ZERO; DIGEST_SIZE]));
+}
+
+#[test]
+fn test_compressed_proof_generation_and_verification() {
+    let app_log_blowup = 1;
+    let app_config = small_test_app_config(app_log_blowup);
+    let app_pk = Arc::new(Sdk.app_keygen(app_config).unwrap());
+    let agg_stark_pk = Sdk.agg_stark_keygen(agg_stark_config_for_test()).unwrap();
+    let compressed_app_vk = agg_stark_pk.get_compressed_app_vk();
+    let app_committed_exe = app_committed_exe_for_test(app_log_blowup);
+    let expected_commit = AppExecutionCommit::compute(
+        &app_pk.app_vm_pk.vm_config,
+        &app_committed_exe,
+        &app_pk.leaf_committed_exe,
+    );
+
+    let proof = Sdk
+        .generate_compressed_proof(
+            app_pk.clone(),
+            app_committed_exe,
+            agg_stark_pk,
+            StdIn::default(),
+        )
+        .unwrap();
+    let pvs = Sdk
+        .verify_compressed_proof(&compressed_app_vk, &expected_commit, &proof)
+        .unwrap();
+    assert_eq!(pvs.exe_commit, expected_commit.exe_commit);
+    assert_eq!(pvs.public_values, vec![F::ZERO; NUM_PUB_VALUES]);
+
+    // A proof must not verify against a different executable.
+    let mut wrong_commit = expected_commit;
+    wrong_commit.exe_commit[0] += F::ONE;
+    assert!(Sdk
+        .verify_compressed_proof(&compressed_app_vk, &wrong_commit, &proof)
+        .is_err());
+
+    // The user public values must match the commitment in the proof.
+    let mut wrong_proof = proof.clone();
+    wrong_proof.user_public_values[0] += F::ONE;
+    assert!(Sdk
+        .verify_compressed_proof(&compressed_app_vk, &expected_commit, &wrong_proof)
+        .is_err());
+}
+
+#[test]
+fn test_agg_stark_proof_with_input_commit() {
+    let app_log_blowup = 1;
+    let app_config = small_test_app_config(app_log_blowup);
+    let app_pk = Arc::new(Sdk.app_keygen(app_config).unwrap());
+    let agg_stark_pk = Sdk
+        .agg_stark_keygen(AggStarkConfig {
+            expose_input_commit: true,
+            ..agg_stark_config_for_test()
+        })
+        .unwrap();
+    let app_committed_exe = app_committed_exe_for_test(app_log_blowup);
+    let expected_commit = AppExecutionCommit::compute(
+        &app_pk.app_vm_pk.vm_config,
+        &app_committed_exe,
+        &app_pk.leaf_committed_exe,
+    );
+
+    let proof = Sdk
+        .generate_agg_stark_proof(
+            app_pk,
+            app_committed_exe,
+            agg_stark_pk.clone(),
+            StdIn::default(),
+        )
+        .unwrap();
+    let pvs = Sdk
+        .verify_agg_stark_proof(&agg_stark_pk, &expected_commit, &proof)
+        .unwrap();
+    // The app VM does not commit to its input stream.
+    assert_eq!(pvs.input_commit, Some([F::ZERO; DIGEST_SIZE]));
+    assert_eq!(pvs.public_values, vec![F::ZERO; NUM_PUB_VALUES]);
+}
+
+#[test]
+fn test_agg_stark_proof_with_keccak256_public_values() {
+    let app_log_blowup = 1;
+    let app_config = small_test_app_config(app_log_blowup);
+    let app_pk = Arc::new(Sdk.app_keygen(app_config).unwrap());
+    let agg_stark_pk = Sdk
+        .agg_stark_keygen(AggStarkConfig {
+            public_values_mode: PublicValuesMode::Keccak256,
+            ..agg_stark_config_for_test()
+        })
+        .unwrap();
+    // The app still exposes all its public values.
+    assert_eq!(agg_stark_pk.num_public_values(), NUM_PUB_VALUES);
+    let app_committed_exe = app_committed_exe_for_test(app_log_blowup);
+    let expected_commit = AppExecutionCommit::compute(
+        &app_pk.app_vm_pk.vm_config,
+        &app_committed_exe,
+        &app_pk.leaf_committed_exe,
+    );
+
+    let proof = Sdk
+        .generate_agg_stark_proof(
+            app_pk.clone(),
+            app_committed_exe.clone(),
+            agg_stark_pk.clone(),
+            StdIn::default(),
+        )
+        .unwrap();
+    let pvs = Sdk
+        .verify_agg_stark_proof(&agg_stark_pk, &expected_commit, &proof)
+        .unwrap();
+    let expected_digest = keccak256(&[0u8; NUM_PUB_VALUES]);
+    assert_eq!(
+        pvs.public_values,
+        expected_digest
+            .map(|byte| F::from_canonical_u8(byte))
+            .to_vec()
+    );
+
+    // Compressed proofs are verified to the same public values.
+    let compressed_app_vk = agg_stark_pk.get_compressed_app_vk();
+    let compressed_proof = Sdk
+        .generate_compressed_proof(app_pk, app_committed_exe, agg_stark_pk, StdIn::default())
+        .unwrap();
+    let compressed_pvs = Sdk
+        .verify_compressed_proof(&compressed_app_vk, &expected_commit, &compressed_proof)
+        .unwrap();
+    assert_eq!(compressed_pvs.public_values, pvs.public_values);
+}
+
+#[test]
+fn test_agg_stark_proof_with_leaf_verifier_commits() {
+    // Different app FRI parameters give different leaf verifiers.
+    let app_pks: Vec<_> = [1, 2]
+        .map(|app_log_blowup| {
+            Arc::new(
+                Sdk.app_keygen(small_test_app_config(app_log_blowup))
+                    .unwrap(),
+            )
+        })
+        .into();
+    let leaf_verifier_commits =
+        LeafVerifierCommitSet::new(app_pks.iter().map(|app_pk| app_pk.commit_in_babybear()));
+    let other_app_pk = Sdk.app_keygen(small_test_app_config(3)).unwrap();
+    assert!(!leaf_verifier_commits.contains(&other_app_pk.commit_in_babybear()));
+
+    let agg_stark_pk = Sdk
+        .agg_stark_keygen_with_leaf_verifier_commits(
+            agg_stark_config_for_test(),
+            leaf_verifier_commits,
+            &app_pks[0],
+        )
+        .unwrap();
+    // Prove with the App VM which was not used for the dummy proofs.
+    let app_log_blowup = 2;
+    let app_pk = app_pks[1].clone();
+    let app_committed_exe = app_committed_exe_for_test(app_log_blowup);
+    let expected_commit = AppExecutionCommit::compute(
+        &app_pk.app_vm_pk.vm_config,
+        &app_committed_exe,
+        &app_pk.leaf_committed_exe,
+    );
+    let proof = Sdk
+        .generate_agg_stark_proof(
+            app_pk,
+            app_committed_exe,
+            agg_stark_pk.clone(),
+            StdIn::default(),
+        )
+        .unwrap();
+    Sdk.verify_agg_stark_proof(&agg_stark_pk, &expected_commit, &proof)
+        .unwrap();
+}
+
 #[test]
 fn test_e2e_proof_generation_and_verification() {
     let app_log_blowup = 1;
@@ -386,6 +675,76 @@ fn test_e2e_proof_generation_and_verification() {
     assert!(Sdk.verify_evm_proof(&evm_verifier, &evm_proof).is_ok());
 }
 
+#[test]
+fn test_e2e_proof_generation_and_verification_with_keccak256_public_values() {
+    let app_log_blowup = 1;
+    let app_config = small_test_app_config(app_log_blowup);
+    let app_pk = Sdk.app_keygen(app_config).unwrap();
+    let params_reader = CacheHalo2ParamsReader::new_with_default_params_dir();
+    let mut agg_config = agg_config_for_test();
+    agg_config.agg_stark_config.public_values_mode = PublicValuesMode::Keccak256;
+    let agg_pk = Sdk
+        .agg_keygen(agg_config, &params_reader, None::<&RootVerifierProvingKey>)
+        .unwrap();
+    let evm_verifier = Sdk
+        .generate_snark_verifier_contract(&params_reader, &agg_pk)
+        .unwrap();
+
+    let evm_proof = Sdk
+        .generate_evm_proof(
+            &params_reader,
+            Arc::new(app_pk),
+            app_committed_exe_for_test(app_log_blowup),
+            agg_pk,
+            StdIn::default(),
+        )
+        .unwrap();
+    assert!(Sdk.verify_evm_proof(&evm_verifier, &evm_proof).is_ok());
+
+    // The last two instances are the big-endian high and low 128 bits of the digest.
+    let expected_digest = keccak256(&[0u8; NUM_PUB_VALUES]);
+    let instances = &evm_proof.instances[0];
+    let [hi, lo] = [
+        &instances[instances.len() - 2],
+        &instances[instances.len() - 1],
+    ]
+    .map(|instance| format!("{instance:?}"));
+    let to_hex = |bytes: &[u8]| {
+        let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+        format!("0x{hex:0>64}")
+    };
+    assert_eq!(hi, to_hex(&expected_digest[..16]));
+    assert_eq!(lo, to_hex(&expected_digest[16..]));
+}
+
+#[test]
+fn test_e2e_groth16_proof_generation_and_verification() {
+    let app_log_blowup = 1;
+    let app_config = small_test_app_config(app_log_blowup);
+    let app_pk = Sdk.app_keygen(app_config).unwrap();
+    let params_reader = CacheHalo2ParamsReader::new_with_default_params_dir();
+    let mut agg_config = agg_config_for_test();
+    agg_config.halo2_config.wrapper_backend = WrapperBackend::Groth16;
+    let agg_pk = Sdk
+        .agg_keygen(agg_config, &params_reader, None::<&RootVerifierProvingKey>)
+        .unwrap();
+    assert!(matches!(agg_pk.snark_pk, SnarkProvingKey::Groth16(_)));
+    let evm_verifier = Sdk
+        .generate_snark_verifier_contract(&params_reader, &agg_pk)
+        .unwrap();
+
+    let evm_proof = Sdk
+        .generate_evm_proof(
+            &params_reader,
+            Arc::new(app_pk),
+            app_committed_exe_for_test(app_log_blowup),
+            agg_pk,
+            StdIn::default(),
+        )
+        .unwrap();
+    assert!(Sdk.verify_evm_proof(&evm_verifier, &evm_proof).is_ok());
+}
+
 #[test]
 fn test_sdk_guest_build_and_transpile() {
     let sdk = Sdk;
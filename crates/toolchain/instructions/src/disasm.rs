@@ -0,0 +1,369 @@
+//! Human-readable text form of [Program]s.
+//!
+//! ```text
+//! .pc_base 0x200800
+//! .step 4
+//! .max_public_values 32
+//!
+//! <main>:
+//! 0x00200800: rv32.ADD a=8 c=-5 d=reg e=imm
+//! 0x00200804: system.TERMINATE
+//! ```
+//!
+//! An instruction line is an optional `pc:`, an opcode mnemonic and `name=value` operands, where
+//! omitted operands are zero. Values are decimal, hexadecimal with a `0x` prefix, or negative, and
+//! address space operands (`d` and `e` unless the opcode says otherwise) may also be given by
+//! name. Without a pc, an instruction is placed right after the previous one. `<symbol>:` lines and
+//! anything after `#` are comments.
+//!
+//! Opcode mnemonics are `class.VARIANT`, with the names an [OpcodeTable] is built from. Opcodes
+//! missing from the table are written as `op_<opcode>`.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{Debug, Write},
+};
+
+use openvm_stark_backend::p3_field::PrimeField32;
+use strum::IntoEnumIterator;
+use thiserror::Error;
+
+use crate::{
+    exe::{FnBounds, VmExe},
+    instruction::{Instruction, NUM_OPERANDS},
+    program::{Program, DEFAULT_MAX_NUM_PUBLIC_VALUES, DEFAULT_PC_STEP},
+    riscv::{RV32_IMM_AS, RV32_MEMORY_AS, RV32_REGISTER_AS},
+    LocalOpcode, PublishOpcode, SystemOpcode, VmOpcode,
+};
+
+const OPERAND_NAMES: [&str; NUM_OPERANDS] = ["a", "b", "c", "d", "e", "f", "g"];
+/// Indices of `d` and `e` in [Instruction::operands], the address spaces of most opcodes.
+const DEFAULT_ADDRESS_SPACE_OPERANDS: [usize; 2] = [3, 4];
+const UNKNOWN_OPCODE_PREFIX: &str = "op_";
+
+#[derive(Clone, Debug)]
+struct OpcodeInfo {
+    name: String,
+    /// Indices of the operands which are address spaces.
+    address_space_operands: Vec<usize>,
+}
+
+/// Names of opcodes and address spaces, used to convert [Program]s to and from text.
+#[derive(Clone, Debug)]
+pub struct OpcodeTable {
+    opcodes: BTreeMap<usize, OpcodeInfo>,
+    by_name: HashMap<String, VmOpcode>,
+    address_spaces: BTreeMap<u32, String>,
+}
+
+#[derive(Debug, Error)]
+pub enum AsmError {
+    #[error("line {line}: unknown directive `{name}`")]
+    UnknownDirective { line: usize, name: String },
+    #[error("line {line}: directive `{name}` must come before the first instruction")]
+    LateDirective { line: usize, name: String },
+    #[error("line {line}: unknown opcode `{name}`")]
+    UnknownOpcode { line: usize, name: String },
+    #[error("line {line}: unknown operand `{name}`")]
+    UnknownOperand { line: usize, name: String },
+    #[error("line {line}: operand `{name}` is given twice")]
+    DuplicateOperand { line: usize, name: String },
+    #[error("line {line}: invalid value `{value}`")]
+    InvalidValue { line: usize, value: String },
+    #[error(
+        "line {line}: pc {pc:#x} is not aligned to the step or is before the previous instruction"
+    )]
+    InvalidPc { line: usize, pc: u32 },
+}
+
+impl Default for OpcodeTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OpcodeTable {
+    /// A table with the system opcodes and the RISC-V address spaces.
+    pub fn new() -> Self {
+        let mut table = Self {
+            opcodes: BTreeMap::new(),
+            by_name: HashMap::new(),
+            address_spaces: BTreeMap::new(),
+        };
+        for opcode in SystemOpcode::iter() {
+            table.insert(opcode.global_opcode(), format!("system.{opcode:?}"));
+        }
+        table
+            .with_opcodes::<PublishOpcode>("system")
+            .with_address_space_operands(PublishOpcode::PUBLISH.global_opcode(), &[3, 4, 5])
+            .with_address_space_operands(SystemOpcode::TERMINATE.global_opcode(), &[])
+            .with_address_space_operands(SystemOpcode::PHANTOM.global_opcode(), &[])
+            .with_address_space(RV32_IMM_AS, "imm")
+            .with_address_space(RV32_REGISTER_AS, "reg")
+            .with_address_space(RV32_MEMORY_AS, "mem")
+            .with_address_space(3, "pv")
+    }
+
+    /// Names `opcode` as `name`. Its operands `d` and `e` are shown as address spaces.
+    pub fn with_opcode(mut self, opcode: VmOpcode, name: impl Into<String>) -> Self {
+        self.insert(opcode, name.into());
+        self
+    }
+
+    /// Names every variant of `E` as `class.VARIANT`, at the class offset of `E`.
+    pub fn with_opcodes<E>(self, class: &str) -> Self
+    where
+        E: LocalOpcode + IntoEnumIterator + Debug,
+    {
+        self.with_opcodes_at::<E>(class, E::CLASS_OFFSET)
+    }
+
+    /// Names every variant of `E` as `class.VARIANT`, at `offset` instead of the class offset of
+    /// `E`. This is for opcode classes repeated per modulus or curve, and for wrapper opcodes.
+    pub fn with_opcodes_at<E>(mut self, class: &str, offset: usize) -> Self
+    where
+        E: LocalOpcode + IntoEnumIterator + Debug,
+    {
+        for opcode in E::iter() {
+            self.insert(
+                VmOpcode::from_usize(offset + opcode.local_usize()),
+                format!("{class}.{opcode:?}"),
+            );
+        }
+        self
+    }
+
+    /// Shows the operands of the named `opcode` at `indices` into [Instruction::operands] as
+    /// address spaces, instead of `d` and `e`.
+    pub fn with_address_space_operands(mut self, opcode: VmOpcode, indices: &[usize]) -> Self {
+        let info = self
+            .opcodes
+            .get_mut(&opcode.as_usize())
+            .unwrap_or_else(|| panic!("opcode {opcode} is not named"));
+        info.address_space_operands = indices.to_vec();
+        self
+    }
+
+    pub fn with_address_space(mut self, address_space: u32, name: impl Into<String>) -> Self {
+        self.address_spaces.insert(address_space, name.into());
+        self
+    }
+
+    fn insert(&mut self, opcode: VmOpcode, name: String) {
+        assert!(
+            !self.by_name.contains_key(&name),
+            "opcode name {name} is already used"
+        );
+        self.by_name.insert(name.clone(), opcode);
+        let previous = self.opcodes.insert(
+            opcode.as_usize(),
+            OpcodeInfo {
+                name,
+                address_space_operands: DEFAULT_ADDRESS_SPACE_OPERANDS.to_vec(),
+            },
+        );
+        assert!(previous.is_none(), "opcode {opcode} is already named");
+    }
+
+    pub fn opcode_name(&self, opcode: VmOpcode) -> Option<&str> {
+        self.opcodes
+            .get(&opcode.as_usize())
+            .map(|info| info.name.as_str())
+    }
+
+    pub fn opcode(&self, name: &str) -> Option<VmOpcode> {
+        self.by_name.get(name).copied()
+    }
+
+    /// Formats one instruction, without its pc.
+    pub fn format_instruction<F: PrimeField32>(&self, instruction: &Instruction<F>) -> String {
+        let info = self.opcodes.get(&instruction.opcode.as_usize());
+        let mut out = match info {
+            Some(info) => info.name.clone(),
+            None => format!(
+                "{UNKNOWN_OPCODE_PREFIX}{:#x}",
+                instruction.opcode.as_usize()
+            ),
+        };
+        let address_space_operands = self.address_space_operands(instruction.opcode);
+        for (i, value) in instruction.operands().into_iter().enumerate() {
+            let value = value.as_canonical_u32();
+            let address_space = address_space_operands
+                .contains(&i)
+                .then(|| self.address_spaces.get(&value))
+                .flatten();
+            if let Some(address_space) = address_space {
+                write!(out, " {}={address_space}", OPERAND_NAMES[i]).unwrap();
+            } else if value != 0 {
+                write!(out, " {}={}", OPERAND_NAMES[i], format_value::<F>(value)).unwrap();
+            }
+        }
+        out
+    }
+
+    /// Formats `program` in the text form of this module, with a label at the start of each
+    /// function in `fn_bounds` and the DSL instruction of each debug info as a comment.
+    pub fn disassemble<F: PrimeField32>(
+        &self,
+        program: &Program<F>,
+        fn_bounds: &FnBounds,
+    ) -> String {
+        let mut out = String::new();
+        writeln!(out, ".pc_base {:#x}", program.pc_base).unwrap();
+        writeln!(out, ".step {}", program.step).unwrap();
+        writeln!(out, ".max_public_values {}", program.max_num_public_values).unwrap();
+        for (pc, instruction, debug_info) in program.enumerate_by_pc() {
+            if let Some(bound) = fn_bounds.get(&pc) {
+                writeln!(out, "\n<{}>:", bound.name).unwrap();
+            }
+            write!(out, "{pc:#010x}: {}", self.format_instruction(&instruction)).unwrap();
+            if let Some(debug_info) = debug_info {
+                let dsl_instruction = debug_info.dsl_instruction.replace('\n', " ");
+                write!(out, "  # {dsl_instruction}").unwrap();
+            }
+            writeln!(out).unwrap();
+        }
+        out
+    }
+
+    pub fn disassemble_exe<F: PrimeField32>(&self, exe: &VmExe<F>) -> String {
+        self.disassemble(&exe.program, &exe.fn_bounds)
+    }
+
+    /// Parses the text form of this module back into a program, without debug infos.
+    pub fn assemble<F: PrimeField32>(&self, source: &str) -> Result<Program<F>, AsmError> {
+        let mut program = Program::new_empty(DEFAULT_PC_STEP, 0, DEFAULT_MAX_NUM_PUBLIC_VALUES);
+        for (line, text) in source.lines().enumerate() {
+            let line = line + 1;
+            let text = text.split('#').next().unwrap().trim();
+            if text.is_empty() || (text.starts_with('<') && text.ends_with(">:")) {
+                continue;
+            }
+            let mut tokens = text.split_whitespace();
+            let mut token = tokens.next().unwrap();
+
+            if let Some(name) = token.strip_prefix('.') {
+                if !program.is_empty() {
+                    let name = name.to_string();
+                    return Err(AsmError::LateDirective { line, name });
+                }
+                let value = tokens.next().unwrap_or_default();
+                let value = parse_u32(value)
+                    .filter(|&parsed| name != "step" || parsed != 0)
+                    .ok_or_else(|| AsmError::InvalidValue {
+                        line,
+                        value: value.to_string(),
+                    })?;
+                match name {
+                    "pc_base" => program.pc_base = value,
+                    "step" => program.step = value,
+                    "max_public_values" => program.max_num_public_values = value as usize,
+                    _ => {
+                        let name = name.to_string();
+                        return Err(AsmError::UnknownDirective { line, name });
+                    }
+                }
+                continue;
+            }
+
+            if let Some(pc) = token.strip_suffix(':') {
+                let pc = parse_u32(pc).ok_or_else(|| AsmError::InvalidValue {
+                    line,
+                    value: pc.to_string(),
+                })?;
+                let next_pc = program.pc_base + program.step * program.len() as u32;
+                if pc < next_pc || (pc - next_pc) % program.step != 0 {
+                    return Err(AsmError::InvalidPc { line, pc });
+                }
+                let gap = ((pc - next_pc) / program.step) as usize;
+                program
+                    .instructions_and_debug_infos
+                    .extend(std::iter::repeat_n(None, gap));
+                token = tokens.next().unwrap_or_default();
+            }
+
+            let opcode = self
+                .opcode(token)
+                .or_else(|| {
+                    let opcode = token.strip_prefix(UNKNOWN_OPCODE_PREFIX)?;
+                    parse_u32(opcode).map(|opcode| VmOpcode::from_usize(opcode as usize))
+                })
+                .ok_or_else(|| AsmError::UnknownOpcode {
+                    line,
+                    name: token.to_string(),
+                })?;
+            let address_space_operands = self.address_space_operands(opcode);
+            let mut operands = [None; NUM_OPERANDS];
+            for token in tokens {
+                let (name, value) = token.split_once('=').unwrap_or((token, ""));
+                let i = OPERAND_NAMES
+                    .iter()
+                    .position(|operand| *operand == name)
+                    .ok_or_else(|| AsmError::UnknownOperand {
+                        line,
+                        name: name.to_string(),
+                    })?;
+                if operands[i].is_some() {
+                    let name = name.to_string();
+                    return Err(AsmError::DuplicateOperand { line, name });
+                }
+                let address_space = address_space_operands
+                    .contains(&i)
+                    .then(|| self.address_space(value))
+                    .flatten();
+                let value = address_space
+                    .map(F::from_canonical_u32)
+                    .or_else(|| parse_value(value))
+                    .ok_or_else(|| AsmError::InvalidValue {
+                        line,
+                        value: value.to_string(),
+                    })?;
+                operands[i] = Some(value);
+            }
+            let [a, b, c, d, e, f, g] = operands.map(Option::unwrap_or_default);
+            program.push_instruction(Instruction::new(opcode, a, b, c, d, e, f, g));
+        }
+        Ok(program)
+    }
+
+    fn address_space_operands(&self, opcode: VmOpcode) -> &[usize] {
+        match self.opcodes.get(&opcode.as_usize()) {
+            Some(info) => &info.address_space_operands,
+            None => &DEFAULT_ADDRESS_SPACE_OPERANDS,
+        }
+    }
+
+    fn address_space(&self, name: &str) -> Option<u32> {
+        self.address_spaces
+            .iter()
+            .find(|(_, address_space)| *address_space == name)
+            .map(|(&address_space, _)| address_space)
+    }
+}
+
+/// Field elements above half the modulus are written as negative numbers.
+fn format_value<F: PrimeField32>(value: u32) -> String {
+    if value > F::ORDER_U32 / 2 {
+        format!("-{}", F::ORDER_U32 - value)
+    } else {
+        value.to_string()
+    }
+}
+
+fn parse_value<F: PrimeField32>(value: &str) -> Option<F> {
+    match value.strip_prefix('-') {
+        Some(value) => parse_u32(value)
+            .filter(|&value| value < F::ORDER_U32)
+            .map(|value| -F::from_canonical_u32(value)),
+        None => parse_u32(value)
+            .filter(|&value| value < F::ORDER_U32)
+            .map(F::from_canonical_u32),
+    }
+}
+
+fn parse_u32(value: &str) -> Option<u32> {
+    match value.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
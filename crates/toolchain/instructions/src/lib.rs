@@ -7,6 +7,7 @@ use openvm_stark_backend::p3_field::Field;
 use serde::{Deserialize, Serialize};
 use strum_macros::{EnumCount, EnumIter, FromRepr};
 
+pub mod disasm;
 pub mod exe;
 pub mod instruction;
 mod phantom;
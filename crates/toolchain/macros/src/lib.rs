@@ -4,6 +4,8 @@ use syn::{
     Expr, Ident, Token,
 };
 
+pub mod sqrt;
+
 pub struct MacroArgs {
     pub items: Vec<Item>,
 }
@@ -0,0 +1,64 @@
+//! Modular square roots over [BigUint].
+//!
+//! These live here so that the `moduli_declare!` macro, which must choose the quadratic
+//! non-residue of each modulus at compile time, shares one implementation with the host-side
+//! square root of the guest library and the square root hints of the circuit extensions.
+
+use num_bigint::BigUint;
+
+/// Returns the smallest `n >= 2` which is a quadratic non-residue modulo `modulus` by Euler's
+/// criterion, or `None` if `modulus` is even or no small non-residue exists (in which case
+/// `modulus` is not an odd prime).
+pub fn find_non_qr(modulus: &BigUint) -> Option<BigUint> {
+    let one = BigUint::from(1u32);
+    if modulus.bits() < 2 || !modulus.bit(0) {
+        return None;
+    }
+    let p_minus_one = modulus - &one;
+    let exponent = &p_minus_one >> 1;
+    (2u32..1 << 16)
+        .map(BigUint::from)
+        .take_while(|n| n < modulus)
+        .find(|n| n.modpow(&exponent, modulus) == p_minus_one)
+}
+
+/// Square root of `x` modulo the odd prime `modulus` using the Tonelli-Shanks algorithm,
+/// where `non_qr` is any quadratic non-residue, e.g. from [find_non_qr]. Returns `None` if `x`
+/// is not a square.
+///
+/// The choice between the two square roots `r` and `-r` is not specified.
+pub fn sqrt_biguint(x: &BigUint, modulus: &BigUint, non_qr: &BigUint) -> Option<BigUint> {
+    let zero = BigUint::from(0u32);
+    let one = BigUint::from(1u32);
+    let x = x % modulus;
+    if x == zero {
+        return Some(zero);
+    }
+    // p - 1 = q * 2^s with q odd
+    let p_minus_one = modulus - &one;
+    let s = p_minus_one.trailing_zeros().expect("modulus must be odd");
+    let q = &p_minus_one >> s;
+
+    let mut m = s;
+    let mut c = non_qr.modpow(&q, modulus);
+    let mut t = x.modpow(&q, modulus);
+    let mut r = x.modpow(&((&q + &one) >> 1), modulus);
+    while t != one {
+        // Find the least 0 < i < m such that t^(2^i) = 1
+        let mut i = 0;
+        let mut t_pow = t.clone();
+        while t_pow != one {
+            t_pow = &t_pow * &t_pow % modulus;
+            i += 1;
+            if i == m {
+                return None;
+            }
+        }
+        let b = c.modpow(&(&one << (m - i - 1)), modulus);
+        m = i;
+        c = &b * &b % modulus;
+        t = t * &c % modulus;
+        r = r * &b % modulus;
+    }
+    Some(r)
+}
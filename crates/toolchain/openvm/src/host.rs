@@ -1,7 +1,9 @@
 //! Hints emulation for the non-zkVM environment.
 
 use alloc::vec::Vec;
-use core::cell::RefCell;
+use core::cell::{Cell, RefCell};
+
+use openvm_platform::data_segments::{layout_data_segments, DataSegmentsError};
 
 /// Simulated input stream on host
 pub enum HostInputStream {
@@ -50,6 +52,19 @@ pub fn set_hints(hints: Vec<Vec<u8>>) {
     HINT_STREAM.replace(Vec::new());
 }
 
+/// Data segments region in the non-zkVM environment.
+#[thread_local]
+pub static DATA_SEGMENTS: Cell<&'static [u8]> = Cell::new(&[]);
+
+/// Set the `(name, data)` segments read by [`crate::io::data_segment`], laid out as in
+/// [`openvm_platform::memory::DATA_SEGMENTS`]. Fails on the same segments as the VM. The region
+/// is leaked so that the segments can be borrowed for `'static`, as in the zkVM.
+pub fn set_data_segments(segments: &[(&str, &[u8])]) -> Result<(), DataSegmentsError> {
+    let region = layout_data_segments(segments)?;
+    DATA_SEGMENTS.set(region.leak());
+    Ok(())
+}
+
 /// Read the next hint stream from the hints.
 pub fn hint_input() {
     let mut hints = HINTS.borrow_mut();
@@ -87,7 +102,7 @@ mod tests {
     use alloc::vec;
 
     use super::*;
-    use crate::io::read_vec;
+    use crate::io::{data_segment, read_aligned, read_into, read_vec};
 
     #[test]
     fn test_read_hints() {
@@ -98,4 +113,31 @@ mod tests {
         assert_eq!(read_n_bytes(8), vec![4, 0, 0, 0, 1, 2, 3, 4]);
         assert_eq!(read_vec(), vec![1, 2, 3, 4]);
     }
+
+    #[test]
+    fn test_read_into_and_aligned() {
+        let value: [u32; 3] = [7, 8, 9];
+        set_hints(vec![
+            vec![1, 2, 3, 4, 5, 6],
+            bytemuck::bytes_of(&value).to_vec(),
+        ]);
+        let mut buf = [0u32; 4];
+        let bytes: &mut [u8] = bytemuck::cast_slice_mut(&mut buf);
+        assert_eq!(read_into(bytes), 6);
+        assert_eq!(&bytes[..6], &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(read_aligned::<[u32; 3]>(), value);
+    }
+
+    #[test]
+    fn test_data_segments() {
+        assert_eq!(data_segment("table"), None);
+        set_data_segments(&[("table", &[1, 2, 3, 4, 5]), ("genesis_state", &[6, 7])]).unwrap();
+        assert_eq!(data_segment("table"), Some(&[1, 2, 3, 4, 5][..]));
+        assert_eq!(data_segment("genesis_state"), Some(&[6, 7][..]));
+        assert_eq!(data_segment("genesis"), None);
+        assert_eq!(
+            set_data_segments(&[("table", &[1]), ("table", &[2])]),
+            Err(DataSegmentsError::DuplicateName("table".into()))
+        );
+    }
 }
@@ -5,6 +5,8 @@ use alloc::vec::Vec;
 use core::alloc::Layout;
 use core::fmt::Write;
 
+use bytemuck::Pod;
+use openvm_platform::WORD_SIZE;
 #[cfg(target_os = "zkvm")]
 use openvm_rv32im_guest::{hint_buffer_u32, hint_input, hint_store_u32};
 use serde::de::DeserializeOwned;
@@ -28,6 +30,67 @@ pub fn read<T: DeserializeOwned>() -> T {
     T::deserialize(&mut deserializer).unwrap()
 }
 
+/// Read the next input directly into `buf` and return its length in bytes.
+///
+/// Unlike [read_vec], no intermediate allocation is made: full words are hinted straight into
+/// `buf` and only a trailing partial word goes through a temporary.
+///
+/// ## Panics
+/// If `buf` is not 4-byte aligned or is shorter than the input.
+pub fn read_into(buf: &mut [u8]) -> usize {
+    assert_eq!(
+        buf.as_ptr() as usize % WORD_SIZE,
+        0,
+        "buffer must be {WORD_SIZE}-byte aligned"
+    );
+    hint_input();
+    let len = read_u32() as usize;
+    assert!(
+        len <= buf.len(),
+        "input of {len} bytes does not fit in buffer of {} bytes",
+        buf.len()
+    );
+    read_into_by_len(&mut buf[..len]);
+    len
+}
+
+/// Read the next input as a plain-old-data value of type `T`, hinted directly into place.
+///
+/// The input is expected to be the raw bytes of `T`, e.g. as written by `StdIn::write_pod` in the
+/// SDK.
+///
+/// ## Panics
+/// If the input length is not `size_of::<T>()`.
+pub fn read_aligned<T: Pod>() -> T {
+    #[repr(C, align(4))]
+    struct WordAligned<T>(T);
+
+    let mut value = WordAligned(T::zeroed());
+    let len = read_into(bytemuck::bytes_of_mut(&mut value.0));
+    assert_eq!(len, core::mem::size_of::<T>(), "input length mismatch");
+    value.0
+}
+
+/// Read the next input, an [rkyv] archive, directly into `buf` and return a reference to its
+/// archived root without copying or deserializing.
+///
+/// `buf` must be at least as large as the archive and aligned to the alignment of `T`, e.g. a
+/// slice of an [rkyv::util::AlignedVec].
+///
+/// # Safety
+/// The next input must be a valid archive of `T`, e.g. as written by `StdIn::write_archived` in
+/// the SDK with the same rkyv format features.
+#[cfg(feature = "rkyv")]
+pub unsafe fn read_archived<T: rkyv::Portable>(buf: &mut [u8]) -> &T {
+    assert_eq!(
+        buf.as_ptr() as usize % core::mem::align_of::<T>(),
+        0,
+        "buffer is not aligned for the archived type"
+    );
+    let len = read_into(buf);
+    rkyv::access_unchecked::<T>(&buf[..len])
+}
+
 /// Read the next 4 bytes from the hint stream into a register.
 /// Because [hint_store_u32] stores a word to memory, this function first reads to memory and then
 /// loads from memory to register.
@@ -79,6 +142,55 @@ pub(crate) fn read_vec_by_len(len: usize) -> Vec<u8> {
     }
 }
 
+/// Read the next `buf.len()` bytes from the hint stream into `buf`, which must be 4-byte aligned.
+fn read_into_by_len(buf: &mut [u8]) {
+    let num_words = buf.len() / WORD_SIZE;
+    let (words, remainder) = buf.split_at_mut(num_words * WORD_SIZE);
+
+    #[cfg(target_os = "zkvm")]
+    {
+        if num_words > 0 {
+            hint_buffer_u32!(words.as_mut_ptr(), num_words);
+        }
+        if !remainder.is_empty() {
+            // The hint stream is padded to a whole word, so the last word is read in full.
+            let mut word = 0u32;
+            hint_store_word(&mut word);
+            remainder.copy_from_slice(&word.to_le_bytes()[..remainder.len()]);
+        }
+    }
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        words.copy_from_slice(&read_n_bytes(words.len()));
+        remainder.copy_from_slice(&read_n_bytes(remainder.len()));
+    }
+}
+
+/// Returns the data segment named `name`, or `None` if the host didn't provide it.
+///
+/// Data segments are byte blobs that the host maps into [DATA_SEGMENTS] before execution, e.g.
+/// with `StdIn::add_data_segment` in the SDK. Unlike the input stream, they are accessed in place
+/// without being read or hashed by the guest: their merkle root is committed in the initial memory
+/// and exposed by the verifiers separately from the exe commitment. Writing to the region is not
+/// prevented, so the guest must only access it through this function. Outside the zkVM, the data
+/// segments are set with [`crate::host::set_data_segments`].
+///
+/// [DATA_SEGMENTS]: openvm_platform::memory::DATA_SEGMENTS
+pub fn data_segment(name: &str) -> Option<&'static [u8]> {
+    // SAFETY: The region is in guest memory and is only written by the host before execution.
+    #[cfg(target_os = "zkvm")]
+    let region = unsafe {
+        use openvm_platform::memory::DATA_SEGMENTS;
+        core::slice::from_raw_parts(
+            DATA_SEGMENTS.start() as *const u8,
+            DATA_SEGMENTS.len_bytes(),
+        )
+    };
+    #[cfg(not(target_os = "zkvm"))]
+    let region = crate::host::DATA_SEGMENTS.get();
+    openvm_platform::data_segments::find_data_segment(region, name)
+}
+
 /// Publish `x` as the `index`-th u32 output.
 #[allow(unused_variables)]
 pub fn reveal(x: u32, index: usize) {
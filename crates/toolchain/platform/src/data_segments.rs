@@ -0,0 +1,98 @@
+//! Layout of the [DATA_SEGMENTS] region, shared by the host, which lays the data segments out
+//! before execution, and by the guest, which looks them up.
+
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+use crate::{memory::DATA_SEGMENTS, WORD_SIZE};
+
+/// Data segments which can't be laid out in the [DATA_SEGMENTS] region.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DataSegmentsError {
+    /// Two data segments have the same name.
+    DuplicateName(String),
+    /// The layout takes more bytes than the region has.
+    TooLarge { len_bytes: usize },
+}
+
+impl fmt::Display for DataSegmentsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateName(name) => write!(f, "duplicate data segment {name}"),
+            Self::TooLarge { len_bytes } => write!(
+                f,
+                "data segments take {len_bytes} bytes, but the region only has {} bytes",
+                DATA_SEGMENTS.len_bytes()
+            ),
+        }
+    }
+}
+
+impl core::error::Error for DataSegmentsError {}
+
+/// Lays out the `(name, data)` segments as the bytes of the [DATA_SEGMENTS] region, starting with
+/// the header. The region is zero after the returned bytes.
+pub fn layout_data_segments(segments: &[(&str, &[u8])]) -> Result<Vec<u8>, DataSegmentsError> {
+    let header_len = WORD_SIZE
+        + segments
+            .iter()
+            .map(|(name, _)| 3 * WORD_SIZE + name.len().next_multiple_of(WORD_SIZE))
+            .sum::<usize>();
+    let data_len = segments
+        .iter()
+        .map(|(_, data)| data.len().next_multiple_of(WORD_SIZE))
+        .sum::<usize>();
+    if header_len + data_len > DATA_SEGMENTS.len_bytes() {
+        return Err(DataSegmentsError::TooLarge {
+            len_bytes: header_len + data_len,
+        });
+    }
+
+    // Fits in a word, since the region does.
+    let push_word = |bytes: &mut Vec<u8>, word: usize| {
+        bytes.extend_from_slice(&(word as u32).to_le_bytes());
+    };
+    let mut region = Vec::with_capacity(header_len + data_len);
+    let mut data = Vec::with_capacity(data_len);
+    push_word(&mut region, segments.len());
+    for (i, (name, segment)) in segments.iter().enumerate() {
+        if segments[..i].iter().any(|(other, _)| other == name) {
+            return Err(DataSegmentsError::DuplicateName((*name).into()));
+        }
+        push_word(&mut region, name.len());
+        region.extend_from_slice(name.as_bytes());
+        region.resize(region.len().next_multiple_of(WORD_SIZE), 0);
+        push_word(&mut region, header_len + data.len());
+        push_word(&mut region, segment.len());
+        data.extend_from_slice(segment);
+        data.resize(data.len().next_multiple_of(WORD_SIZE), 0);
+    }
+    debug_assert_eq!(region.len(), header_len);
+    region.extend(data);
+    Ok(region)
+}
+
+/// Looks up the data of the segment named `name` in the bytes of the [DATA_SEGMENTS] region.
+pub fn find_data_segment<'a>(region: &'a [u8], name: &str) -> Option<&'a [u8]> {
+    let word = |offset: usize| {
+        let bytes = region.get(offset..offset + WORD_SIZE)?;
+        Some(u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+    };
+    let num_segments = word(0)?;
+    let mut offset = WORD_SIZE;
+    for _ in 0..num_segments {
+        let name_len = word(offset)?;
+        offset += WORD_SIZE;
+        let segment_name = region.get(offset..offset + name_len)?;
+        offset += name_len.next_multiple_of(WORD_SIZE);
+        let start = word(offset)?;
+        let len = word(offset + WORD_SIZE)?;
+        offset += 2 * WORD_SIZE;
+        if segment_name == name.as_bytes() {
+            return region.get(start..start + len);
+        }
+    }
+    None
+}
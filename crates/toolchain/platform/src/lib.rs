@@ -6,6 +6,7 @@
 
 #[cfg(all(feature = "rust-runtime", target_os = "zkvm"))]
 pub use openvm_custom_insn::{custom_insn_i, custom_insn_r};
+pub mod data_segments;
 #[cfg(all(feature = "export-getrandom", target_os = "zkvm"))]
 mod getrandom;
 #[cfg(all(feature = "rust-runtime", target_os = "zkvm"))]
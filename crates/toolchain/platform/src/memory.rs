@@ -13,6 +13,12 @@ pub const TEXT_START: u32 = 0x0020_0800;
 pub const SYSTEM: Region = Region::new(0x0C00_0000, mb(16));
 pub const PAGE_TABLE: Region = Region::new(0x0D00_0000, mb(16));
 pub const PRE_LOAD: Region = Region::new(0x0D70_0000, mb(9));
+/// Data segments provided by the host before execution. The region starts with a
+/// header of little-endian `u32` words: the number of segments, then for each segment the length
+/// of its name, the name bytes padded to a multiple of 4, and the byte offset and length of its
+/// data relative to the start of the region. The data of each segment follows the header and is
+/// aligned to 4 bytes. The region is zero if no data segments are provided.
+pub const DATA_SEGMENTS: Region = Region::new(0x0E00_0000, mb(32));
 
 pub struct Region {
     start: usize,
@@ -0,0 +1,15 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use openvm::io::read;
+use openvm_verify_stark::{verify_stark, GuestConfig, Proof, StarkVerifyingKey};
+
+openvm::entry!(main);
+
+fn main() {
+    let vk: StarkVerifyingKey = read();
+    let proof: Proof<GuestConfig> = read();
+    if let Err(e) = verify_stark(&vk, &proof) {
+        panic!("STARK verification failed: {e:?}");
+    }
+}
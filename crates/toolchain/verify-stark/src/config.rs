@@ -0,0 +1,56 @@
+use openvm_stark_backend::{
+    config::StarkConfig,
+    interaction::fri_log_up::FriLogUpPhase,
+    p3_challenger::DuplexChallenger,
+    p3_commit::ExtensionMmcs,
+    p3_field::{extension::BinomialExtensionField, Field},
+};
+use p3_baby_bear::BabyBear;
+use p3_dft::Radix2DitParallel;
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use serde::{Deserialize, Serialize};
+
+use crate::poseidon2::{GuestPoseidon2, POSEIDON2_WIDTH};
+
+const RATE: usize = 8;
+const DIGEST_WIDTH: usize = 8;
+
+pub type Val = BabyBear;
+pub type Challenge = BinomialExtensionField<Val, 4>;
+pub type Hash = PaddingFreeSponge<GuestPoseidon2, POSEIDON2_WIDTH, RATE, DIGEST_WIDTH>;
+pub type Compress = TruncatedPermutation<GuestPoseidon2, 2, DIGEST_WIDTH, POSEIDON2_WIDTH>;
+pub type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, Hash, Compress, DIGEST_WIDTH>;
+pub type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+pub type Challenger = DuplexChallenger<Val, GuestPoseidon2, POSEIDON2_WIDTH, RATE>;
+pub type Pcs = TwoAdicFriPcs<Val, Radix2DitParallel<Val>, ValMmcs, ChallengeMmcs>;
+pub type RapPhase = FriLogUpPhase<Val, Challenge, Challenger>;
+
+/// The BabyBear Poseidon2 STARK configuration as seen by the guest verifier. Proofs and verifying
+/// keys serialize the same way as those of the host `BabyBearPoseidon2Config`.
+pub type GuestConfig = StarkConfig<Pcs, RapPhase, Challenge, Challenger>;
+
+/// FRI parameters a STARK proof was proven with. Serializes the same way as the host
+/// `FriParameters`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FriParameters {
+    pub log_blowup: usize,
+    pub log_final_poly_len: usize,
+    pub num_queries: usize,
+    pub proof_of_work_bits: usize,
+}
+
+pub fn guest_config(perm: &GuestPoseidon2, fri_params: FriParameters) -> GuestConfig {
+    let val_mmcs = ValMmcs::new(Hash::new(perm.clone()), Compress::new(perm.clone()));
+    let fri_config = FriConfig {
+        log_blowup: fri_params.log_blowup,
+        log_final_poly_len: fri_params.log_final_poly_len,
+        num_queries: fri_params.num_queries,
+        proof_of_work_bits: fri_params.proof_of_work_bits,
+        mmcs: ChallengeMmcs::new(val_mmcs.clone()),
+    };
+    let pcs = Pcs::new(Radix2DitParallel::default(), val_mmcs, fri_config);
+    GuestConfig::new(pcs, RapPhase::new())
+}
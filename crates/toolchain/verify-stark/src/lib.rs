@@ -0,0 +1,40 @@
+//! Verification of STARK proofs inside OpenVM guest programs.
+//!
+//! Proofs over the host `BabyBearPoseidon2Config` are checked against their verifying key with
+//! the multi-trace verifier of the STARK backend, instantiated with [GuestConfig]. The guest does
+//! not depend on the host engine or SDK: [GuestConfig] is built here, and hashes, Merkle
+//! compressions and Fiat-Shamir challenges all go through [GuestPoseidon2]. Guests using this
+//! crate must be built with `std`.
+//!
+//! The toolchain does not yet expose RV32 intrinsics for Poseidon2 or for the BabyBear extension
+//! field, so [GuestPoseidon2] and the [Challenge] arithmetic execute as plain RV32IM instructions.
+
+use openvm_stark_backend::verifier::MultiTraceStarkVerifier;
+pub use openvm_stark_backend::{
+    self, keygen::types::MultiStarkVerifyingKey, proof::Proof, verifier::VerificationError,
+};
+use serde::{Deserialize, Serialize};
+
+mod config;
+mod poseidon2;
+
+pub use config::*;
+pub use poseidon2::*;
+
+/// Verifying key of a STARK proof together with the FRI parameters it was proven with.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StarkVerifyingKey {
+    pub fri_params: FriParameters,
+    pub vk: MultiStarkVerifyingKey<GuestConfig>,
+}
+
+/// Verifies `proof` against `vk`.
+pub fn verify_stark(
+    vk: &StarkVerifyingKey,
+    proof: &Proof<GuestConfig>,
+) -> Result<(), VerificationError> {
+    let perm = GuestPoseidon2::default();
+    let config = guest_config(&perm, vk.fri_params);
+    let mut challenger = Challenger::new(perm);
+    MultiTraceStarkVerifier::new(&config).verify(&mut challenger, &vk.vk, proof)
+}
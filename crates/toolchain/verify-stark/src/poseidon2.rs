@@ -0,0 +1,62 @@
+use openvm_stark_backend::p3_field::FieldAlgebra;
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_poseidon2::ExternalLayerConstants;
+use p3_symmetric::{CryptographicPermutation, Permutation};
+use zkhash::{
+    ark_ff::PrimeField as _, fields::babybear::FpBabyBear as HorizenBabyBear,
+    poseidon2::poseidon2_instance_babybear::RC16,
+};
+
+pub const POSEIDON2_WIDTH: usize = 16;
+const HALF_FULL_ROUNDS: usize = 4;
+const PARTIAL_ROUNDS: usize = 13;
+
+/// The Poseidon2 permutation over BabyBear used by every hash, compression and challenger of the
+/// guest verifier, with the same round constants as the host prover.
+///
+/// All Poseidon2 calls of the verifier go through this type, so it is the only place to change
+/// once the toolchain has a Poseidon2 intrinsic.
+#[derive(Clone)]
+pub struct GuestPoseidon2(Poseidon2BabyBear<POSEIDON2_WIDTH>);
+
+impl Default for GuestPoseidon2 {
+    fn default() -> Self {
+        let to_babybear =
+            |horizen: &HorizenBabyBear| BabyBear::from_canonical_u64(horizen.into_bigint().0[0]);
+        let full_round = |round: &Vec<HorizenBabyBear>| -> [BabyBear; POSEIDON2_WIDTH] {
+            round
+                .iter()
+                .map(to_babybear)
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap()
+        };
+        let partial_end = HALF_FULL_ROUNDS + PARTIAL_ROUNDS;
+        let external_constants = ExternalLayerConstants::new(
+            RC16[..HALF_FULL_ROUNDS].iter().map(full_round).collect(),
+            RC16[partial_end..].iter().map(full_round).collect(),
+        );
+        let internal_constants = RC16[HALF_FULL_ROUNDS..partial_end]
+            .iter()
+            .map(|round| to_babybear(&round[0]))
+            .collect();
+        Self(Poseidon2BabyBear::new(
+            external_constants,
+            internal_constants,
+        ))
+    }
+}
+
+impl<T: Clone> Permutation<T> for GuestPoseidon2
+where
+    Poseidon2BabyBear<POSEIDON2_WIDTH>: Permutation<T>,
+{
+    fn permute_mut(&self, input: &mut T) {
+        self.0.permute_mut(input);
+    }
+}
+
+impl<T: Clone> CryptographicPermutation<T> for GuestPoseidon2 where
+    Poseidon2BabyBear<POSEIDON2_WIDTH>: CryptographicPermutation<T>
+{
+}
@@ -0,0 +1,112 @@
+use eyre::Result;
+use openvm_circuit::arch::{ExecutionError, VmExecutor};
+use openvm_instructions::exe::VmExe;
+use openvm_rv32im_circuit::Rv32ImConfig;
+use openvm_rv32im_transpiler::{
+    Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
+};
+use openvm_stark_sdk::{
+    collect_airs_and_inputs,
+    config::{
+        baby_bear_poseidon2::{default_perm, BabyBearPoseidon2Engine},
+        FriParameters,
+    },
+    dummy_airs::fib_air::chip::FibonacciChip,
+    engine::StarkFriEngine,
+    openvm_stark_backend::{p3_field::FieldAlgebra, Chip},
+    p3_baby_bear::BabyBear,
+};
+use openvm_toolchain_tests::{build_example_program_at_path_with_features, get_programs_dir};
+use openvm_transpiler::{transpiler::Transpiler, FromElf};
+use openvm_verify_stark::{
+    verify_stark, GuestConfig, GuestPoseidon2, Proof, StarkVerifyingKey, POSEIDON2_WIDTH,
+};
+use p3_symmetric::Permutation;
+use serde::{de::DeserializeOwned, Serialize};
+
+type F = BabyBear;
+
+/// Converts a host proof or verifying key to its guest counterpart, which serializes the same way.
+fn to_guest<T: Serialize, U: DeserializeOwned>(host: &T) -> U {
+    openvm::serde::from_slice(&openvm::serde::to_vec(host).unwrap()).unwrap()
+}
+
+fn fibonacci_proof(n: usize) -> (StarkVerifyingKey, Proof<GuestConfig>) {
+    let fib_chip = FibonacciChip::new(0, 1, n);
+    let (airs, per_air) = collect_airs_and_inputs!(fib_chip);
+    let vdata = BabyBearPoseidon2Engine::new(FriParameters::standard_fast())
+        .run_test(airs, per_air)
+        .unwrap();
+    let fri_params = vdata.fri_params;
+    let vk = StarkVerifyingKey {
+        fri_params: openvm_verify_stark::FriParameters {
+            log_blowup: fri_params.log_blowup,
+            log_final_poly_len: fri_params.log_final_poly_len,
+            num_queries: fri_params.num_queries,
+            proof_of_work_bits: fri_params.proof_of_work_bits,
+        },
+        vk: to_guest(&vdata.data.vk),
+    };
+    (vk, to_guest(&vdata.data.proof))
+}
+
+fn verify_fib_input(vk: &StarkVerifyingKey, proof: &Proof<GuestConfig>) -> Vec<Vec<F>> {
+    let to_input = |words: Vec<u32>| {
+        words
+            .into_iter()
+            .flat_map(|w| w.to_le_bytes())
+            .map(F::from_canonical_u8)
+            .collect()
+    };
+    vec![
+        to_input(openvm::serde::to_vec(vk).unwrap()),
+        to_input(openvm::serde::to_vec(proof).unwrap()),
+    ]
+}
+
+fn verify_fib_exe() -> Result<VmExe<F>> {
+    let elf =
+        build_example_program_at_path_with_features(get_programs_dir!(), "verify_fib", ["std"])?;
+    Ok(VmExe::from_elf(
+        elf,
+        Transpiler::<F>::default()
+            .with_extension(Rv32ITranspilerExtension)
+            .with_extension(Rv32MTranspilerExtension)
+            .with_extension(Rv32IoTranspilerExtension),
+    )?)
+}
+
+#[test]
+fn test_guest_poseidon2_matches_host() {
+    let input: [F; POSEIDON2_WIDTH] = std::array::from_fn(|i| F::from_canonical_usize(i));
+    assert_eq!(
+        GuestPoseidon2::default().permute(input),
+        default_perm().permute(input)
+    );
+}
+
+#[test]
+fn test_verify_fib_proof() -> Result<()> {
+    let (vk, proof) = fibonacci_proof(16);
+    // Sanity check the proof outside the VM.
+    verify_stark(&vk, &proof).unwrap();
+
+    let exe = verify_fib_exe()?;
+    let executor = VmExecutor::<F, _>::new(Rv32ImConfig::default());
+    executor.execute(exe, verify_fib_input(&vk, &proof))?;
+    Ok(())
+}
+
+#[test]
+fn test_verify_fib_proof_wrong_public_values() -> Result<()> {
+    let (vk, mut proof) = fibonacci_proof(16);
+    // Claim a different n-th Fibonacci number.
+    proof.per_air[0].public_values[2] += F::ONE;
+    assert!(verify_stark(&vk, &proof).is_err());
+
+    let exe = verify_fib_exe()?;
+    let executor = VmExecutor::<F, _>::new(Rv32ImConfig::default());
+    let result = executor.execute(exe, verify_fib_input(&vk, &proof));
+    assert!(matches!(result, Err(ExecutionError::FailedWithExitCode(_))));
+    Ok(())
+}
@@ -250,6 +250,7 @@ pub fn vm_generic_config_derive(input: proc_macro::TokenStream) -> proc_macro::T
             let mut executor_enum_fields = Vec::new();
             let mut periphery_enum_fields = Vec::new();
             let mut create_chip_complex = Vec::new();
+            let mut resolve_address_spaces = Vec::new();
             for &e in extensions.iter() {
                 let (field_name, field_name_upper) =
                     gen_name_with_uppercase_idents(&e.ident.clone().unwrap());
@@ -319,6 +320,12 @@ pub fn vm_generic_config_derive(input: proc_macro::TokenStream) -> proc_macro::T
                 create_chip_complex.push(quote! {
                     let complex: VmChipComplex<F, Self::Executor, Self::Periphery> = complex.extend(&self.#field_name)?;
                 });
+                resolve_address_spaces.push(quote! {
+                    config
+                        .memory_config
+                        .add_extension_address_spaces::<F>(&self.#field_name)
+                        .expect("invalid extension address space");
+                });
             }
 
             let (source_executor_type, source_periphery_type) = match &source {
@@ -376,6 +383,13 @@ pub fn vm_generic_config_derive(input: proc_macro::TokenStream) -> proc_macro::T
                         #(#create_chip_complex)*
                         Ok(complex)
                     }
+
+                    fn resolved_system_config(&self) -> SystemConfig {
+                        #[allow(unused_mut)]
+                        let mut config = VmConfig::<F>::resolved_system_config(&self.#source_name);
+                        #(#resolve_address_spaces)*
+                        config
+                    }
                 }
             })
         }
@@ -4,13 +4,13 @@ use derive_new::new;
 use openvm_circuit::system::memory::MemoryTraceHeights;
 use openvm_instructions::program::DEFAULT_MAX_NUM_PUBLIC_VALUES;
 use openvm_poseidon2_air::Poseidon2Config;
-use openvm_stark_backend::{p3_field::PrimeField32, ChipUsageGetter};
+use openvm_stark_backend::{p3_field::PrimeField32, p3_util::log2_strict_usize, ChipUsageGetter};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use super::{
     segment::{DefaultSegmentationStrategy, SegmentationStrategy},
     AnyEnum, InstructionExecutor, SystemComplex, SystemExecutor, SystemPeriphery, VmChipComplex,
-    VmInventoryError, PUBLIC_VALUES_AIR_ID,
+    VmExtension, VmInventoryError, PUBLIC_VALUES_AIR_ID,
 };
 use crate::system::memory::BOUNDARY_AIR_OFFSET;
 
@@ -35,11 +35,21 @@ pub trait VmConfig<F: PrimeField32>: Clone + Serialize + DeserializeOwned {
     fn create_chip_complex(
         &self,
     ) -> Result<VmChipComplex<F, Self::Executor, Self::Periphery>, VmInventoryError>;
+
+    /// The system config with the address spaces of all extensions appended, as reported by
+    /// [VmExtension::address_spaces](super::VmExtension::address_spaces). Its `memory_config`
+    /// describes the memory layout used during execution and must be used wherever the layout
+    /// matters, e.g. to build memory images or compute memory dimensions.
+    ///
+    /// Does not build any chips. Implementations should register the address spaces of each
+    /// extension with [MemoryConfig::add_extension_address_spaces], in the same order as
+    /// [create_chip_complex](Self::create_chip_complex) extends the chip complex.
+    fn resolved_system_config(&self) -> SystemConfig;
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, new, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone, new)]
 pub struct MemoryConfig {
-    /// The maximum height of the address space. This means the trie has `as_height` layers for searching the address space. The allowed address spaces are those in the range `[as_offset, as_offset + 2^as_height)` where `as_offset` is currently fixed to `1` to not allow address space `0` in memory.
+    /// The height of the built-in address spaces. The built-in address spaces are those in the range `[as_offset, as_offset + 2^as_height)` where `as_offset` is currently fixed to `1` to not allow address space `0` in memory. Each of them has `2^pointer_max_bits` cells.
     pub as_height: usize,
     /// The offset of the address space.
     pub as_offset: u32,
@@ -51,6 +61,60 @@ pub struct MemoryConfig {
     pub max_access_adapter_n: usize,
     /// An expected upper bound on the number of memory accesses.
     pub access_capacity: usize,
+    /// Address spaces registered by extensions, numbered consecutively after the built-in ones.
+    /// See [VmExtension::address_spaces](super::VmExtension::address_spaces).
+    #[new(default)]
+    #[serde(default)]
+    pub address_spaces: Vec<AddressSpaceConfig>,
+}
+
+/// Layout of an address space registered by an extension.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressSpaceConfig {
+    /// Unique name, so that other extensions can look up the address space.
+    pub name: String,
+    /// Number of cells. Must be a power of two.
+    ///
+    /// The boundary chips constrain every accessed pointer to be below this bound. The memory
+    /// merkle tree still reserves as many leaves for every address space as for the largest one;
+    /// leaves past `num_cells` always stay zero.
+    pub num_cells: usize,
+    pub cell_type: AddressSpaceCellType,
+    pub init: AddressSpaceInit,
+}
+
+/// The values a cell of an address space may hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AddressSpaceCellType {
+    /// Any field element.
+    Field,
+    /// A byte. The boundary chips range check the initial and final value of every accessed cell.
+    Byte,
+}
+
+/// How the initial contents of an address space may be set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AddressSpaceInit {
+    /// The address space starts zeroed. The initial memory of a program may not touch it.
+    Zeroed,
+    /// The address space may be initialized by the initial memory of a program.
+    FromExe,
+}
+
+impl AddressSpaceConfig {
+    pub fn new(
+        name: impl Into<String>,
+        num_cells: usize,
+        cell_type: AddressSpaceCellType,
+        init: AddressSpaceInit,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            num_cells,
+            cell_type,
+            init,
+        }
+    }
 }
 
 impl Default for MemoryConfig {
@@ -59,6 +123,88 @@ impl Default for MemoryConfig {
     }
 }
 
+impl MemoryConfig {
+    /// The first address space after the built-in ones, where registered address spaces start.
+    pub fn first_registered_address_space(&self) -> u32 {
+        self.as_offset + (1 << self.as_height)
+    }
+
+    /// Total number of address spaces, built-in and registered.
+    pub fn num_address_spaces(&self) -> usize {
+        (1 << self.as_height) + self.address_spaces.len()
+    }
+
+    /// The config of a registered address space, or `None` for built-in ones.
+    pub fn address_space_config(&self, addr_space: u32) -> Option<&AddressSpaceConfig> {
+        let idx = addr_space.checked_sub(self.first_registered_address_space())?;
+        self.address_spaces.get(idx as usize)
+    }
+
+    /// Registers a new address space after the existing ones and returns its id.
+    pub fn add_address_space(
+        &mut self,
+        config: AddressSpaceConfig,
+    ) -> Result<u32, VmInventoryError> {
+        if self.address_space_by_name(&config.name).is_some() {
+            return Err(VmInventoryError::AddressSpaceExists { name: config.name });
+        }
+        if !config.num_cells.is_power_of_two() {
+            return Err(VmInventoryError::InvalidAddressSpaceSize {
+                name: config.name,
+                num_cells: config.num_cells,
+            });
+        }
+        let addr_space = self.first_registered_address_space() + self.address_spaces.len() as u32;
+        self.address_spaces.push(config);
+        Ok(addr_space)
+    }
+
+    /// Registers the address spaces of `extension`, as done when the chip complex is extended
+    /// with it.
+    pub fn add_extension_address_spaces<F: PrimeField32>(
+        &mut self,
+        extension: &impl VmExtension<F>,
+    ) -> Result<(), VmInventoryError> {
+        for config in extension.address_spaces() {
+            self.add_address_space(config)?;
+        }
+        Ok(())
+    }
+
+    /// Looks up a registered address space by name.
+    pub fn address_space_by_name(&self, name: &str) -> Option<u32> {
+        self.address_spaces
+            .iter()
+            .position(|config| config.name == name)
+            .map(|idx| self.first_registered_address_space() + idx as u32)
+    }
+
+    /// Number of cells of `addr_space`.
+    pub fn address_space_size(&self, addr_space: u32) -> usize {
+        self.address_space_config(addr_space)
+            .map_or(1 << self.pointer_max_bits, |config| config.num_cells)
+    }
+
+    /// Number of bits of a pointer into `addr_space`.
+    pub fn address_space_pointer_bits(&self, addr_space: u32) -> usize {
+        log2_strict_usize(self.address_space_size(addr_space))
+    }
+
+    /// The cell type of `addr_space`. Built-in address spaces hold field elements.
+    pub fn address_space_cell_type(&self, addr_space: u32) -> AddressSpaceCellType {
+        self.address_space_config(addr_space)
+            .map_or(AddressSpaceCellType::Field, |config| config.cell_type)
+    }
+
+    /// Number of bits of a pointer into the largest address space.
+    pub fn max_pointer_bits(&self) -> usize {
+        self.address_spaces
+            .iter()
+            .map(|config| log2_strict_usize(config.num_cells))
+            .fold(self.pointer_max_bits, usize::max)
+    }
+}
+
 /// System-level configuration for the virtual machine. Contains all configuration parameters that
 /// are managed by the architecture, including configuration for continuations support.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -89,6 +235,29 @@ pub struct SystemConfig {
     /// not needed after any serialize/deserialize.
     #[serde(skip, default = "get_default_segmentation_strategy")]
     pub segmentation_strategy: Arc<dyn SegmentationStrategy>,
+    /// Limits on the resources used by an execution. No limits by default.
+    #[serde(default)]
+    pub limits: ExecutionLimits,
+    /// Whether to commit to the words that hint instructions write to memory, which include the
+    /// words read from the input stream. The commitment before and after each segment is exposed
+    /// in the public values of the connector AIR. Execution fails if a phantom instruction hints
+    /// words that are not inputs. See [input_commitment](crate::system::input_commitment).
+    #[serde(default)]
+    pub commit_input_stream: bool,
+}
+
+/// Limits on the resources an execution may use, e.g. when running untrusted programs.
+/// Exceeding a limit stops execution with an [ExecutionError](super::ExecutionError) reporting
+/// where execution stopped.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExecutionLimits {
+    /// Maximum number of instructions executed, across all segments.
+    pub max_cycles: Option<u64>,
+    /// Maximum number of segments in continuation mode.
+    pub max_segments: Option<usize>,
+    /// Maximum number of allocated memory pages of
+    /// [PAGE_SIZE](crate::system::memory::paged_vec::PAGE_SIZE) cells, across all address spaces.
+    pub max_memory_pages: Option<usize>,
 }
 
 pub fn get_default_segmentation_strategy() -> Arc<dyn SegmentationStrategy> {
@@ -115,6 +284,8 @@ impl SystemConfig {
             num_public_values,
             segmentation_strategy,
             profiling: false,
+            limits: ExecutionLimits::default(),
+            commit_input_stream: false,
         }
     }
 
@@ -149,6 +320,16 @@ impl SystemConfig {
         self.segmentation_strategy = Arc::new(strategy);
     }
 
+    pub fn with_limits(mut self, limits: ExecutionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_input_commitment(mut self) -> Self {
+        self.commit_input_stream = true;
+        self
+    }
+
     pub fn with_profiling(mut self) -> Self {
         self.profiling = true;
         self
@@ -163,6 +344,11 @@ impl SystemConfig {
         !self.continuation_enabled && self.num_public_values > 0
     }
 
+    /// The Poseidon2 periphery chip hashes persistent memory and the input commitment.
+    pub fn has_poseidon2_chip(&self) -> bool {
+        self.continuation_enabled || self.commit_input_stream
+    }
+
     /// Returns the AIR ID of the memory boundary AIR. Panic if the boundary AIR is not enabled.
     pub fn memory_boundary_air_id(&self) -> usize {
         let mut ret = PUBLIC_VALUES_AIR_ID;
@@ -213,4 +399,8 @@ impl<F: PrimeField32> VmConfig<F> for SystemConfig {
         let complex = SystemComplex::new(self.clone());
         Ok(complex)
     }
+
+    fn resolved_system_config(&self) -> SystemConfig {
+        self.clone()
+    }
 }
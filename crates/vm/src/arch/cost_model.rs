@@ -0,0 +1,283 @@
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    keygen::types::MultiStarkProvingKey,
+    p3_field::FieldExtensionAlgebra,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{SegmentationStrategy, VmInventoryError};
+
+/// The part of an AIR's shape that determines how expensive each of its trace rows is to prove.
+/// Widths are in base field elements.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AirCostShape {
+    pub air_name: String,
+    pub preprocessed_width: usize,
+    pub main_width: usize,
+    pub after_challenge_width: usize,
+    pub quotient_width: usize,
+    pub num_interactions: usize,
+}
+
+impl AirCostShape {
+    /// Extracts the shapes of all AIRs from a proving key, in AIR ID order.
+    pub fn from_pk<SC: StarkGenericConfig>(pk: &MultiStarkProvingKey<SC>) -> Vec<Self> {
+        let ext_degree = <SC::Challenge as FieldExtensionAlgebra<Val<SC>>>::D;
+        pk.per_air
+            .iter()
+            .map(|air_pk| {
+                let width = &air_pk.vk.params.width;
+                let after_challenge_width: usize = width.after_challenge.iter().sum();
+                Self {
+                    air_name: air_pk.air_name.clone(),
+                    preprocessed_width: width.preprocessed.unwrap_or(0),
+                    main_width: width.main_widths().iter().sum(),
+                    after_challenge_width: after_challenge_width * ext_degree,
+                    quotient_width: air_pk.vk.quotient_degree as usize * ext_degree,
+                    num_interactions: air_pk.vk.symbolic_constraints.interactions.len(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Linear model of the proving cost of a segment: the cost of each AIR is its padded trace
+/// height times a weighted sum of its [AirCostShape] widths.
+///
+/// The unit of the cost is that of the metric the model was calibrated against, e.g.
+/// milliseconds for `stark_prove_excluding_trace_time_ms`. The default weights count cells, so
+/// the default cost is the number of committed cells.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProvingCostModel {
+    pub per_row: f64,
+    pub per_preprocessed_cell: f64,
+    pub per_main_cell: f64,
+    pub per_after_challenge_cell: f64,
+    pub per_quotient_cell: f64,
+    pub per_interaction: f64,
+}
+
+impl Default for ProvingCostModel {
+    fn default() -> Self {
+        Self {
+            per_row: 0.0,
+            per_preprocessed_cell: 1.0,
+            per_main_cell: 1.0,
+            per_after_challenge_cell: 1.0,
+            per_quotient_cell: 1.0,
+            per_interaction: 0.0,
+        }
+    }
+}
+
+impl ProvingCostModel {
+    /// Number of weights in the model, and of features returned by [Self::features].
+    pub const NUM_FEATURES: usize = 6;
+
+    /// The quantities each weight is multiplied by, in field order.
+    pub fn features(shape: &AirCostShape, height: usize) -> [f64; Self::NUM_FEATURES] {
+        let rows = if height == 0 {
+            0.0
+        } else {
+            height.next_power_of_two() as f64
+        };
+        [
+            rows,
+            rows * shape.preprocessed_width as f64,
+            rows * shape.main_width as f64,
+            rows * shape.after_challenge_width as f64,
+            rows * shape.quotient_width as f64,
+            rows * shape.num_interactions as f64,
+        ]
+    }
+
+    pub fn from_weights(weights: [f64; Self::NUM_FEATURES]) -> Self {
+        Self {
+            per_row: weights[0],
+            per_preprocessed_cell: weights[1],
+            per_main_cell: weights[2],
+            per_after_challenge_cell: weights[3],
+            per_quotient_cell: weights[4],
+            per_interaction: weights[5],
+        }
+    }
+
+    pub fn weights(&self) -> [f64; Self::NUM_FEATURES] {
+        [
+            self.per_row,
+            self.per_preprocessed_cell,
+            self.per_main_cell,
+            self.per_after_challenge_cell,
+            self.per_quotient_cell,
+            self.per_interaction,
+        ]
+    }
+
+    pub fn air_cost(&self, shape: &AirCostShape, height: usize) -> f64 {
+        Self::features(shape, height)
+            .iter()
+            .zip(self.weights())
+            .map(|(feature, weight)| feature * weight)
+            .sum()
+    }
+
+    /// Cost of proving a segment with the given trace heights, in AIR ID order.
+    pub fn segment_cost(&self, shapes: &[AirCostShape], trace_heights: &[usize]) -> f64 {
+        debug_assert_eq!(shapes.len(), trace_heights.len());
+        shapes
+            .iter()
+            .zip(trace_heights)
+            .map(|(shape, &height)| self.air_cost(shape, height))
+            .sum()
+    }
+}
+
+/// Segmentation strategy that cuts a segment when its estimated proving cost exceeds a budget,
+/// so that segments are even in proving cost rather than in the height of their tallest chip.
+///
+/// The AIR shapes must come from the proving key of the VM being segmented, see
+/// [AirCostShape::from_pk]. This is checked when a segment is created.
+#[derive(Clone, Debug)]
+pub struct CostModelSegmentationStrategy {
+    model: ProvingCostModel,
+    shapes: Vec<AirCostShape>,
+    max_segment_cost: f64,
+    /// Any chip's height must still stay below this, since the trace domain is bounded.
+    max_segment_len: usize,
+}
+
+impl CostModelSegmentationStrategy {
+    pub fn new(model: ProvingCostModel, shapes: Vec<AirCostShape>, max_segment_cost: f64) -> Self {
+        Self {
+            model,
+            shapes,
+            max_segment_cost,
+            max_segment_len: super::segment::DEFAULT_MAX_SEGMENT_LEN,
+        }
+    }
+
+    pub fn with_max_segment_len(mut self, max_segment_len: usize) -> Self {
+        self.max_segment_len = max_segment_len;
+        self
+    }
+
+    pub fn model(&self) -> &ProvingCostModel {
+        &self.model
+    }
+
+    pub fn max_segment_cost(&self) -> f64 {
+        self.max_segment_cost
+    }
+}
+
+impl SegmentationStrategy for CostModelSegmentationStrategy {
+    fn should_segment(
+        &self,
+        air_names: &[String],
+        trace_heights: &[usize],
+        _trace_cells: &[usize],
+    ) -> bool {
+        for (i, &height) in trace_heights.iter().enumerate() {
+            if height > self.max_segment_len {
+                tracing::info!(
+                    "Should segment because chip {} (name: {}) has height {}",
+                    i,
+                    air_names[i],
+                    height
+                );
+                return true;
+            }
+        }
+        let cost = self.model.segment_cost(&self.shapes, trace_heights);
+        if cost > self.max_segment_cost {
+            tracing::info!(
+                "Should segment because estimated cost {} exceeds {}",
+                cost,
+                self.max_segment_cost
+            );
+            return true;
+        }
+        false
+    }
+
+    fn validate(&self, air_names: &[String]) -> Result<(), VmInventoryError> {
+        if air_names.len() != self.shapes.len() {
+            return Err(VmInventoryError::InvalidSegmentationStrategy {
+                reason: format!(
+                    "{} AIR shapes for {} AIRs",
+                    self.shapes.len(),
+                    air_names.len()
+                ),
+            });
+        }
+        for (air_id, (air_name, shape)) in air_names.iter().zip(&self.shapes).enumerate() {
+            if air_name != &shape.air_name {
+                return Err(VmInventoryError::InvalidSegmentationStrategy {
+                    reason: format!(
+                        "AIR {air_id} is {air_name}, but its shape is that of {}",
+                        shape.air_name
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(main_width: usize, num_interactions: usize) -> AirCostShape {
+        AirCostShape {
+            air_name: format!("air_{main_width}"),
+            preprocessed_width: 0,
+            main_width,
+            after_challenge_width: 8,
+            quotient_width: 4,
+            num_interactions,
+        }
+    }
+
+    #[test]
+    fn test_segment_cost() {
+        let model = ProvingCostModel {
+            per_row: 1.0,
+            per_interaction: 2.0,
+            ..Default::default()
+        };
+        let shapes = vec![shape(10, 1), shape(20, 0)];
+        // Heights are padded to the next power of two.
+        assert_eq!(
+            model.air_cost(&shapes[0], 3),
+            4.0 * (1.0 + 10.0 + 8.0 + 4.0 + 2.0)
+        );
+        assert_eq!(model.air_cost(&shapes[1], 0), 0.0);
+        assert_eq!(
+            model.segment_cost(&shapes, &[3, 8]),
+            100.0 + 8.0 * (1.0 + 20.0 + 8.0 + 4.0)
+        );
+        assert_eq!(ProvingCostModel::from_weights(model.weights()), model);
+
+        let names = shapes
+            .iter()
+            .map(|s| s.air_name.clone())
+            .collect::<Vec<_>>();
+        let strategy = CostModelSegmentationStrategy::new(model, shapes, 300.0);
+        assert!(strategy.validate(&names).is_ok());
+        assert!(!strategy.should_segment(&names, &[3, 4], &[]));
+        assert!(strategy.should_segment(&names, &[3, 8], &[]));
+    }
+
+    #[test]
+    fn test_shapes_from_other_pk() {
+        let shapes = vec![shape(10, 1), shape(20, 0)];
+        let names = vec!["air_20".to_string(), "air_10".to_string()];
+        let strategy = CostModelSegmentationStrategy::new(Default::default(), shapes, 300.0);
+        assert!(matches!(
+            strategy.validate(&names),
+            Err(VmInventoryError::InvalidSegmentationStrategy { .. })
+        ));
+        assert!(strategy.validate(&names[..1]).is_err());
+    }
+}
@@ -4,11 +4,12 @@ use openvm_circuit_primitives_derive::AlignedBorrow;
 use openvm_instructions::{
     instruction::Instruction, program::DEFAULT_PC_STEP, PhantomDiscriminant, VmOpcode,
 };
+use openvm_platform::data_segments::DataSegmentsError;
 use openvm_stark_backend::{interaction::InteractionBuilder, p3_field::FieldAlgebra};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use super::Streams;
+use super::{Streams, VmInventoryError};
 use crate::system::{memory::MemoryController, program::ProgramBus};
 
 pub type Result<T> = std::result::Result<T, ExecutionError>;
@@ -59,6 +60,35 @@ pub enum ExecutionError {
         discriminant: PhantomDiscriminant,
         inner: eyre::Error,
     },
+    #[error(
+        "at pc {pc}, discriminant {}, phantom instruction hinted words that are not inputs, \
+         which the input commitment would absorb",
+        .discriminant.0
+    )]
+    NonInputHint {
+        pc: u32,
+        discriminant: PhantomDiscriminant,
+    },
+    #[error("cycle limit exceeded at pc {pc} after {cycles} cycles")]
+    CycleLimitExceeded { pc: u32, cycles: u64 },
+    #[error("segment limit exceeded at pc {pc} after {segments} segments and {cycles} cycles")]
+    SegmentLimitExceeded {
+        pc: u32,
+        segments: usize,
+        cycles: u64,
+    },
+    #[error("memory limit exceeded at pc {pc} after {cycles} cycles: {pages} pages allocated")]
+    MemoryLimitExceeded { pc: u32, pages: usize, cycles: u64 },
+    #[error("at pc {pc}, execution diverged from the journal: {reason}")]
+    JournalMismatch { pc: u32, reason: String },
+    #[error("data segments require continuations")]
+    DataSegmentsWithoutContinuations,
+    #[error("memory is too small for data segments")]
+    DataSegmentsUnsupported,
+    #[error("invalid data segments: {0}")]
+    InvalidDataSegments(#[from] DataSegmentsError),
+    #[error(transparent)]
+    Inventory(#[from] VmInventoryError),
     #[error("program must terminate")]
     DidNotTerminate,
     #[error("program exit code {0}")]
@@ -11,6 +11,7 @@ use getset::Getters;
 use metrics::counter;
 use openvm_circuit_derive::{AnyEnum, InstructionExecutor};
 use openvm_circuit_primitives::{
+    assert_less_than::AssertLtSubAir,
     utils::next_power_of_two_or_zero,
     var_range::{SharedVariableRangeCheckerChip, VariableRangeCheckerBus},
 };
@@ -30,18 +31,20 @@ use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    vm_poseidon2_config, ExecutionBus, InstructionExecutor, PhantomSubExecutor, Streams,
-    SystemConfig, SystemTraceHeights,
+    hasher::HasherChip, vm_poseidon2_config, AddressSpaceConfig, ExecutionBus, InstructionExecutor,
+    PhantomSubExecutor, SharedJournalRecorder, Streams, SystemConfig, SystemTraceHeights,
 };
 #[cfg(feature = "bench-metrics")]
 use crate::metrics::VmMetrics;
 use crate::system::{
     connector::VmConnectorChip,
+    input_commitment::{InputCommitBus, InputCommitSubAir},
     memory::{
         merkle::{DirectCompressionBus, MemoryMerkleBus},
         offline_checker::{MemoryBridge, MemoryBus},
         online::MemoryLogEntry,
-        MemoryController, MemoryImage, OfflineMemory, BOUNDARY_AIR_OFFSET, MERKLE_AIR_OFFSET,
+        MemoryController, MemoryImage, OfflineMemory, BOUNDARY_AIR_OFFSET, CHUNK,
+        MERKLE_AIR_OFFSET,
     },
     native_adapter::NativeAdapterChip,
     phantom::PhantomChip,
@@ -88,6 +91,14 @@ pub trait VmExtension<F: PrimeField32> {
         &self,
         builder: &mut VmInventoryBuilder<F>,
     ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError>;
+
+    /// Address spaces this extension registers. They are numbered consecutively after the
+    /// built-in ones and those of previous extensions, in order, and are registered before
+    /// [build](Self::build) is called so that they can be looked up with
+    /// [VmInventoryBuilder::address_space]. The memory merkle tree grows to fit them.
+    fn address_spaces(&self) -> Vec<AddressSpaceConfig> {
+        Vec::new()
+    }
 }
 
 impl<F: PrimeField32, E: VmExtension<F>> VmExtension<F> for Option<E> {
@@ -104,6 +115,14 @@ impl<F: PrimeField32, E: VmExtension<F>> VmExtension<F> for Option<E> {
             Ok(VmInventory::new())
         }
     }
+
+    fn address_spaces(&self) -> Vec<AddressSpaceConfig> {
+        if let Some(extension) = self {
+            extension.address_spaces()
+        } else {
+            Vec::new()
+        }
+    }
 }
 
 /// SystemPort combines system resources needed by most extensions
@@ -192,6 +211,12 @@ impl<'a, F: PrimeField32> VmInventoryBuilder<'a, F> {
         Ok(())
     }
 
+    /// Looks up a registered address space by name, including those registered by the extension
+    /// being built.
+    pub fn address_space(&self, name: &str) -> Option<u32> {
+        self.system_config.memory_config.address_space_by_name(name)
+    }
+
     /// Shareable streams. Clone to get a shared mutable reference.
     pub fn streams(&self) -> &Arc<Mutex<Streams<F>>> {
         self.streams
@@ -248,6 +273,12 @@ pub enum VmInventoryError {
     PhantomSubExecutorExists { discriminant: PhantomDiscriminant },
     #[error("Chip {name} not found")]
     ChipNotFound { name: String },
+    #[error("Address space {name} already registered")]
+    AddressSpaceExists { name: String },
+    #[error("Address space {name} has {num_cells} cells, which is not a power of two")]
+    InvalidAddressSpaceSize { name: String, num_cells: usize },
+    #[error("Segmentation strategy does not match the VM: {reason}")]
+    InvalidSegmentationStrategy { reason: String },
 }
 
 impl<E, P> Default for VmInventory<E, P> {
@@ -343,6 +374,31 @@ impl<E, P> VmInventory<E, P> {
         self.executors.get_mut(*id)
     }
 
+    /// Returns the index of the executor for `opcode`, for use with [Self::executor_mut].
+    pub fn executor_idx(&self, opcode: VmOpcode) -> Option<usize> {
+        self.instruction_lookup.get(&opcode).copied()
+    }
+
+    /// Returns the executor at index `idx`, as returned by [Self::executor_idx].
+    #[inline(always)]
+    pub fn executor_mut(&mut self, idx: usize) -> &mut E {
+        &mut self.executors[idx]
+    }
+
+    /// Returns the [PhantomChip], which always handles `PHANTOM`.
+    pub(crate) fn phantom_chip<F: PrimeField32>(&self) -> &RefCell<PhantomChip<F>>
+    where
+        E: AnyEnum,
+    {
+        let idx = self
+            .executor_idx(SystemOpcode::PHANTOM.global_opcode())
+            .expect("PhantomChip always exists");
+        self.executors[idx]
+            .as_any_kind()
+            .downcast_ref()
+            .expect("PHANTOM is handled by PhantomChip")
+    }
+
     pub fn executors(&self) -> &[E] {
         &self.executors
     }
@@ -473,6 +529,8 @@ pub struct SystemBase<F> {
     pub memory_controller: MemoryController<F>,
     pub connector_chip: VmConnectorChip<F>,
     pub program_chip: ProgramChip<F>,
+    /// SubAir for the chip writing hints to memory, if the system commits to the input stream.
+    pub input_commit_air: Option<InputCommitSubAir>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -509,6 +567,10 @@ impl<F: PrimeField32> SystemBase<F> {
         EXECUTION_BUS
     }
 
+    pub fn input_commit_air(&self) -> Option<InputCommitSubAir> {
+        self.input_commit_air
+    }
+
     /// Return trace heights of SystemBase. Usually this is for aggregation and not useful for
     /// regular users.
     pub fn get_system_trace_heights(&self) -> SystemTraceHeights {
@@ -549,7 +611,7 @@ impl<F: PrimeField32> SystemComplex<F> {
             bus_idx_max += 2;
             MemoryController::with_persistent_memory(
                 MEMORY_BUS,
-                config.memory_config,
+                config.memory_config.clone(),
                 range_checker.clone(),
                 MemoryMerkleBus(bus_idx_max - 2),
                 DirectCompressionBus(bus_idx_max - 1),
@@ -557,14 +619,36 @@ impl<F: PrimeField32> SystemComplex<F> {
         } else {
             MemoryController::with_volatile_memory(
                 MEMORY_BUS,
-                config.memory_config,
+                config.memory_config.clone(),
                 range_checker.clone(),
             )
         };
         let memory_bridge = memory_controller.memory_bridge();
         let offline_memory = memory_controller.offline_memory();
+        // The Poseidon2 chip uses the direct compression bus of persistent memory, if any.
+        let compression_bus = config.has_poseidon2_chip().then(|| {
+            memory_controller
+                .interface_chip
+                .compression_bus()
+                .unwrap_or_else(|| {
+                    bus_idx_max += 1;
+                    DirectCompressionBus(bus_idx_max - 1)
+                })
+        });
+        let input_commit_air = config.commit_input_stream.then(|| {
+            bus_idx_max += 1;
+            InputCommitSubAir {
+                bus: InputCommitBus(bus_idx_max - 1),
+                compression_bus: compression_bus.unwrap(),
+                timestamp_lt: AssertLtSubAir::new(range_bus, config.memory_config.clk_max_bits),
+            }
+        });
         let program_chip = ProgramChip::new(PROGRAM_BUS);
-        let connector_chip = VmConnectorChip::new(EXECUTION_BUS, PROGRAM_BUS);
+        let connector_chip = VmConnectorChip::new(
+            EXECUTION_BUS,
+            PROGRAM_BUS,
+            input_commit_air.map(|air| air.bus),
+        );
 
         let mut inventory = VmInventory::new();
         // PublicValuesChip is required when num_public_values > 0 in single segment mode.
@@ -582,20 +666,15 @@ impl<F: PrimeField32> SystemComplex<F> {
                 .add_executor(chip, [PublishOpcode::PUBLISH.global_opcode()])
                 .unwrap();
         }
-        if config.continuation_enabled {
+        if let Some(compression_bus) = compression_bus {
             assert_eq!(inventory.periphery().len(), Self::POSEIDON2_PERIPHERY_IDX);
-            // Add direct poseidon2 chip for persistent memory.
+            // Add direct poseidon2 chip for persistent memory and the input commitment.
             // This is **not** an instruction executor.
             // Currently we never use poseidon2 opcodes when continuations is enabled: we will need
             // special handling when that happens
-            let direct_bus_idx = memory_controller
-                .interface_chip
-                .compression_bus()
-                .unwrap()
-                .0;
             let chip = Poseidon2PeripheryChip::new(
                 vm_poseidon2_config(),
-                direct_bus_idx,
+                compression_bus.0,
                 config.max_constraint_degree,
             );
             inventory.add_periphery_chip(chip);
@@ -605,6 +684,7 @@ impl<F: PrimeField32> SystemComplex<F> {
         let mut phantom_chip =
             PhantomChip::new(EXECUTION_BUS, PROGRAM_BUS, SystemOpcode::CLASS_OFFSET);
         phantom_chip.set_streams(streams.clone());
+        phantom_chip.set_commit_input_stream(config.commit_input_stream);
         inventory
             .add_executor(RefCell::new(phantom_chip), [phantom_opcode])
             .unwrap();
@@ -614,6 +694,7 @@ impl<F: PrimeField32> SystemComplex<F> {
             connector_chip,
             memory_controller,
             range_checker_chip: range_checker,
+            input_commit_air,
         };
 
         Self {
@@ -666,6 +747,15 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
         Ext::Executor: Into<E3>,
         Ext::Periphery: Into<P3>,
     {
+        let num_address_spaces = self.config.memory_config.num_address_spaces();
+        self.config
+            .memory_config
+            .add_extension_address_spaces(config)?;
+        if self.config.memory_config.num_address_spaces() != num_address_spaces {
+            self.base
+                .memory_controller
+                .set_memory_config(self.config.memory_config.clone());
+        }
         let mut builder = self.inventory_builder();
         let inventory_ext = config.build(&mut builder)?;
         self.bus_idx_max = builder.bus_idx_max;
@@ -771,14 +861,58 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
         };
     }
 
-    pub(crate) fn set_program(&mut self, program: Program<F>) {
-        self.base.program_chip.set_program(program);
+    /// Records the compressions of the input commitment since the last call in the Poseidon2
+    /// chip, and returns the current input commitment.
+    pub(crate) fn flush_input_commitment(&mut self) -> [F; CHUNK]
+    where
+        P: AnyEnum,
+    {
+        let (digest, pending) = {
+            let mut streams = self.streams.lock().unwrap();
+            let pending = streams.input_commitment.take_pending();
+            (streams.input_commitment.digest, pending)
+        };
+        if !pending.is_empty() {
+            let hasher = self
+                .poseidon2_chip_mut()
+                .expect("Poseidon2 chip required for the input commitment");
+            for (lhs, rhs) in pending {
+                hasher.compress_and_record(&lhs, &rhs);
+            }
+        }
+        digest
+    }
+
+    /// Sets the program and links each of its instructions to the executor that handles it.
+    pub(crate) fn set_program(&mut self, program: Program<F>)
+    where
+        E: AnyEnum,
+    {
+        let phantom_chip = self.inventory.phantom_chip::<F>().borrow();
+        let program_chip = &mut self.base.program_chip;
+        program_chip.set_program(program);
+        program_chip.link(
+            |opcode| self.inventory.executor_idx(opcode),
+            |discriminant| phantom_chip.sub_executor_idx(discriminant),
+        );
     }
 
     pub(crate) fn set_initial_memory(&mut self, memory: MemoryImage<F>) {
         self.base.memory_controller.set_initial_memory(memory);
     }
 
+    /// Sets the journal which records or replays the phantom sub-instructions executed by the
+    /// [PhantomChip].
+    pub(crate) fn set_journal(&mut self, journal: Option<SharedJournalRecorder<F>>)
+    where
+        E: AnyEnum,
+    {
+        self.inventory
+            .phantom_chip()
+            .borrow_mut()
+            .set_journal(journal);
+    }
+
     /// Warning: this sets the stream in all chips which have a shared mutable reference to the streams.
     pub(crate) fn set_streams(&mut self, streams: Streams<F>) {
         *self.streams.lock().unwrap() = streams;
@@ -823,6 +957,32 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
             })
     }
 
+    /// AIR ID of each executor, indexed by executor index.
+    pub fn executor_air_ids(&self) -> Vec<usize> {
+        let public_values_chip_idx = self.public_values_chip_idx();
+        let first_inventory_air_id =
+            2 + usize::from(public_values_chip_idx.is_some()) + self.memory_controller().num_airs();
+        let mut air_ids = vec![0; self.inventory.executors.len()];
+        if let Some(idx) = public_values_chip_idx {
+            air_ids[idx] = PUBLIC_VALUES_AIR_ID;
+        }
+        let chip_ids =
+            self.inventory
+                .insertion_order
+                .iter()
+                .rev()
+                .filter(|&&chip_id| match chip_id {
+                    ChipId::Executor(id) => Some(id) != public_values_chip_idx,
+                    ChipId::Periphery(_) => true,
+                });
+        for (air_id, chip_id) in (first_inventory_air_id..).zip(chip_ids) {
+            if let ChipId::Executor(id) = *chip_id {
+                air_ids[id] = air_id;
+            }
+        }
+        air_ids
+    }
+
     /// Return air names of all chips in order.
     pub(crate) fn air_names(&self) -> Vec<String>
     where
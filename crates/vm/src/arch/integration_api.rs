@@ -244,7 +244,9 @@ where
         let (to_state, write_record) =
             self.adapter
                 .postprocess(memory, instruction, from_state, output, &read_record)?;
-        self.records.push((read_record, write_record, core_record));
+        if !memory.is_execute_only() {
+            self.records.push((read_record, write_record, core_record));
+        }
         Ok(to_state)
     }
 
@@ -0,0 +1,273 @@
+//! Recording and replay of the non-deterministic inputs of an execution.
+//!
+//! Everything an execution consumes besides the program and its initial memory enters the VM
+//! through phantom instructions, which fill the hint stream and hint space from the input stream
+//! or from other sources such as `OsRng`. An [ExecutionJournal] records the effect of every
+//! phantom instruction on the [Streams], together with the segment boundaries and the data
+//! segments mapped into the initial memory. An effect holds only the words the instruction hinted
+//! and the number of inputs it consumed, so the journal grows with the consumed input rather than
+//! with the hint stream left over at each instruction. Replaying the journal applies the recorded effects
+//! instead of executing the phantom instructions, which reproduces the execution exactly without
+//! the original input sources.
+
+use std::sync::{Arc, Mutex};
+
+use openvm_instructions::PhantomDiscriminant;
+use openvm_stark_backend::p3_field::Field;
+use serde::{Deserialize, Serialize};
+
+use super::{ExecutionError, Streams};
+use crate::system::memory::tree::data_segments::DataSegment;
+
+/// A [JournalRecorder] shared between the segments of an execution.
+pub type SharedJournalRecorder<F> = Arc<Mutex<JournalRecorder<F>>>;
+
+/// Journal of the non-deterministic inputs of an execution, see the [module](self) docs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionJournal<F> {
+    /// Executed phantom instructions, in execution order.
+    pub phantoms: Vec<PhantomRecord<F>>,
+    /// Boundaries between continuation segments, in execution order.
+    pub segments: Vec<SegmentBoundary>,
+    /// Data segments mapped into the initial memory.
+    #[serde(default)]
+    pub data_segments: Vec<DataSegment>,
+}
+
+impl<F> Default for ExecutionJournal<F> {
+    fn default() -> Self {
+        Self {
+            phantoms: vec![],
+            segments: vec![],
+            data_segments: vec![],
+        }
+    }
+}
+
+/// An executed phantom instruction that is not a system phantom instruction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhantomRecord<F> {
+    pub pc: u32,
+    pub discriminant: u16,
+    /// The effect on the streams, or `None` if the streams were left untouched. Phantom
+    /// instructions without effect, such as printing, are executed again on replay.
+    pub effect: Option<StreamsEffect<F>>,
+}
+
+/// The changes a phantom instruction made to the streams, as far as later instructions can
+/// observe them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamsEffect<F> {
+    /// Number of entries consumed from the input stream.
+    pub num_inputs: usize,
+    /// Words which replaced the contents of the hint stream, or `None` if the hint stream was not
+    /// written, see [Streams::set_hint_stream].
+    pub hint_stream: Option<Vec<F>>,
+    /// Hints appended to the hint space.
+    pub hint_space: Vec<Vec<F>>,
+}
+
+/// End of a continuation segment that did not terminate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentBoundary {
+    /// Pc at which the next segment starts.
+    pub pc: u32,
+    /// Number of instructions executed in this and all previous segments.
+    pub num_instructions: u64,
+    /// Number of phantom instructions executed in this and all previous segments.
+    pub num_phantoms: usize,
+}
+
+/// Records an [ExecutionJournal] during execution, or replays one.
+#[derive(Debug)]
+pub struct JournalRecorder<F> {
+    journal: ExecutionJournal<F>,
+    replay: bool,
+    next_phantom: usize,
+    next_segment: usize,
+}
+
+impl<F: Field> JournalRecorder<F> {
+    /// Creates a recorder which records into an empty journal.
+    pub fn record() -> Self {
+        Self {
+            journal: ExecutionJournal::default(),
+            replay: false,
+            next_phantom: 0,
+            next_segment: 0,
+        }
+    }
+
+    /// Creates a recorder which replays `journal`. Execution fails with
+    /// [ExecutionError::JournalMismatch] as soon as it diverges from the journal.
+    pub fn replay(journal: ExecutionJournal<F>) -> Self {
+        Self {
+            journal,
+            replay: true,
+            next_phantom: 0,
+            next_segment: 0,
+        }
+    }
+
+    pub fn shared(self) -> SharedJournalRecorder<F> {
+        Arc::new(Mutex::new(self))
+    }
+
+    pub fn is_replay(&self) -> bool {
+        self.replay
+    }
+
+    pub fn journal(&self) -> &ExecutionJournal<F> {
+        &self.journal
+    }
+
+    pub fn into_journal(self) -> ExecutionJournal<F> {
+        self.journal
+    }
+
+    /// Records the data segments mapped into the initial memory of an execution starting at `pc`,
+    /// or checks them against the journal.
+    pub(crate) fn data_segments(
+        &mut self,
+        pc: u32,
+        data_segments: &[DataSegment],
+    ) -> Result<(), ExecutionError> {
+        if !self.replay {
+            self.journal.data_segments = data_segments.to_vec();
+            return Ok(());
+        }
+        if self.journal.data_segments != data_segments {
+            return Err(ExecutionError::JournalMismatch {
+                pc,
+                reason: "the data segments differ from the recorded ones".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Records the effect of `execute`, which executes the phantom instruction at `pc`, on
+    /// `streams`. On replay, applies the recorded effect instead, or calls `execute` if the
+    /// recorded instruction had none.
+    pub(crate) fn execute_phantom(
+        &mut self,
+        pc: u32,
+        discriminant: PhantomDiscriminant,
+        streams: &mut Streams<F>,
+        execute: impl FnOnce(&mut Streams<F>) -> Result<(), ExecutionError>,
+    ) -> Result<(), ExecutionError> {
+        if !self.replay {
+            let input_len = streams.input_stream.len();
+            let hint_space_len = streams.hint_space.len();
+            let hint_generation = streams.hint_generation();
+            execute(streams)?;
+            let effect = StreamsEffect {
+                num_inputs: input_len.saturating_sub(streams.input_stream.len()),
+                hint_stream: (streams.hint_generation() != hint_generation)
+                    .then(|| streams.hint_stream.iter().copied().collect()),
+                hint_space: streams
+                    .hint_space
+                    .get(hint_space_len..)
+                    .unwrap_or_default()
+                    .to_vec(),
+            };
+            let changed = effect.num_inputs != 0
+                || effect.hint_stream.is_some()
+                || !effect.hint_space.is_empty();
+            self.journal.phantoms.push(PhantomRecord {
+                pc,
+                discriminant: discriminant.0,
+                effect: changed.then_some(effect),
+            });
+            return Ok(());
+        }
+
+        let record = self
+            .journal
+            .phantoms
+            .get(self.next_phantom)
+            .ok_or_else(|| ExecutionError::JournalMismatch {
+                pc,
+                reason: format!("phantom instruction {} was not recorded", self.next_phantom),
+            })?;
+        if record.pc != pc || record.discriminant != discriminant.0 {
+            return Err(ExecutionError::JournalMismatch {
+                pc,
+                reason: format!(
+                    "found discriminant {}, but phantom instruction {} was recorded at pc {} with \
+                     discriminant {}",
+                    discriminant.0, self.next_phantom, record.pc, record.discriminant
+                ),
+            });
+        }
+        self.next_phantom += 1;
+        match &record.effect {
+            Some(effect) => {
+                // The input stream is usually empty on replay.
+                let num_inputs = effect.num_inputs.min(streams.input_stream.len());
+                streams.input_stream.drain(..num_inputs);
+                if let Some(hint_stream) = &effect.hint_stream {
+                    streams.set_hint_stream(hint_stream.iter().copied());
+                }
+                streams.hint_space.extend(effect.hint_space.iter().cloned());
+                Ok(())
+            }
+            None => execute(streams),
+        }
+    }
+
+    /// Records the end of a segment that did not terminate, or checks it against the journal.
+    pub(crate) fn end_segment(
+        &mut self,
+        pc: u32,
+        num_instructions: u64,
+    ) -> Result<(), ExecutionError> {
+        let boundary = SegmentBoundary {
+            pc,
+            num_instructions,
+            num_phantoms: if self.replay {
+                self.next_phantom
+            } else {
+                self.journal.phantoms.len()
+            },
+        };
+        if !self.replay {
+            self.journal.segments.push(boundary);
+            return Ok(());
+        }
+
+        let expected = self.journal.segments.get(self.next_segment);
+        if expected != Some(&boundary) {
+            return Err(ExecutionError::JournalMismatch {
+                pc,
+                reason: format!(
+                    "segment {} ended with {boundary:?}, but {expected:?} was recorded",
+                    self.next_segment
+                ),
+            });
+        }
+        self.next_segment += 1;
+        Ok(())
+    }
+
+    /// Checks on replay that the whole journal was consumed when execution terminated at `pc`.
+    /// Segment boundaries are only checked if `segmented`, so that a journal recorded with
+    /// continuations can be replayed in a single execute-only segment.
+    pub(crate) fn finish(&self, pc: u32, segmented: bool) -> Result<(), ExecutionError> {
+        if !self.replay {
+            return Ok(());
+        }
+        let num_phantoms = self.journal.phantoms.len();
+        let num_segments = self.journal.segments.len();
+        if self.next_phantom != num_phantoms || (segmented && self.next_segment != num_segments) {
+            return Err(ExecutionError::JournalMismatch {
+                pc,
+                reason: format!(
+                    "terminated after {} of {num_phantoms} phantom instructions and {} of \
+                     {num_segments} segment boundaries",
+                    self.next_phantom, self.next_segment
+                ),
+            });
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
 mod config;
+/// Proving cost estimation and cost-based segmentation
+mod cost_model;
 /// Instruction execution traits and types.
 /// Execution bus and interface.
 mod execution;
@@ -6,6 +8,10 @@ mod execution;
 mod extensions;
 /// Traits and wrappers to facilitate VM chip integration
 mod integration_api;
+/// Recording and replay of non-deterministic execution inputs
+mod journal;
+/// Concurrent proving of independent segments
+mod scheduler;
 /// Runtime execution and segmentation
 pub mod segment;
 /// Top level [VirtualMachine] constructor and API.
@@ -19,8 +25,11 @@ pub mod hasher;
 pub mod testing;
 
 pub use config::*;
+pub use cost_model::*;
 pub use execution::*;
 pub use extensions::*;
 pub use integration_api::*;
+pub use journal::*;
+pub use scheduler::*;
 pub use segment::*;
 pub use vm::*;
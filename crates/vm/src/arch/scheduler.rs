@@ -0,0 +1,169 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::mpsc,
+    thread,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// How proving threads are split between segments proven at the same time.
+///
+/// A single segment's proof does not saturate a machine with many cores for mid-size traces, so
+/// it can be faster to prove `num_concurrent_segments` segments at once, each with
+/// `threads_per_segment` threads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParallelProvingConfig {
+    /// Number of segments proven concurrently.
+    pub num_concurrent_segments: usize,
+    /// Number of threads used to prove each segment. Only takes effect with the `parallel`
+    /// feature.
+    pub threads_per_segment: usize,
+}
+
+impl ParallelProvingConfig {
+    pub fn new(num_concurrent_segments: usize, threads_per_segment: usize) -> Self {
+        assert!(num_concurrent_segments > 0 && threads_per_segment > 0);
+        Self {
+            num_concurrent_segments,
+            threads_per_segment,
+        }
+    }
+}
+
+/// Proves independent segments concurrently, according to a [ParallelProvingConfig].
+pub struct SegmentScheduler {
+    config: ParallelProvingConfig,
+    /// One thread pool per concurrently proven segment.
+    #[cfg(feature = "parallel")]
+    pools: Vec<rayon::ThreadPool>,
+}
+
+impl SegmentScheduler {
+    pub fn new(config: ParallelProvingConfig) -> Self {
+        Self {
+            config,
+            #[cfg(feature = "parallel")]
+            pools: (0..config.num_concurrent_segments)
+                .map(|_| {
+                    rayon::ThreadPoolBuilder::new()
+                        .num_threads(config.threads_per_segment)
+                        .build()
+                        .expect("failed to build segment proving thread pool")
+                })
+                .collect(),
+        }
+    }
+
+    pub fn config(&self) -> &ParallelProvingConfig {
+        &self.config
+    }
+
+    /// Runs `prove` on every job, with up to `num_concurrent_segments` jobs at once. `on_done` is
+    /// called on the current thread with each result as soon as it is available, in completion
+    /// order, e.g. to start aggregating finished segments.
+    ///
+    /// Jobs are pulled lazily from `jobs` on the current thread only when a worker is free, so a
+    /// job iterator that executes segments on demand keeps at most one job per worker in memory.
+    /// If `prove` panics on any job, no more jobs are started and the panic is propagated once the
+    /// running jobs finish.
+    pub fn prove_all<T, R>(
+        &self,
+        jobs: impl IntoIterator<Item = T>,
+        prove: impl Fn(usize, T) -> R + Sync,
+        mut on_done: impl FnMut(usize, R),
+    ) where
+        T: Send,
+        R: Send,
+    {
+        let mut jobs = jobs.into_iter().enumerate();
+        thread::scope(|s| {
+            // Every job sends its outcome, including a panic, so receiving never blocks forever.
+            let (result_sender, result_receiver) =
+                mpsc::channel::<(usize, usize, thread::Result<R>)>();
+            let mut handle_result =
+                |(worker_idx, idx, result): (usize, usize, thread::Result<R>)| {
+                    match result {
+                        Ok(result) => on_done(idx, result),
+                        Err(panic) => panic::resume_unwind(panic),
+                    }
+                    worker_idx
+                };
+            let mut free_workers: Vec<_> = (0..self.config.num_concurrent_segments).rev().collect();
+            let mut num_running = 0;
+            loop {
+                let worker_idx = match free_workers.pop() {
+                    Some(worker_idx) => worker_idx,
+                    None => {
+                        num_running -= 1;
+                        handle_result(result_receiver.recv().unwrap())
+                    }
+                };
+                let Some((idx, job)) = jobs.next() else {
+                    break;
+                };
+                let result_sender = result_sender.clone();
+                let prove = &prove;
+                s.spawn(move || {
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                        self.install(worker_idx, || prove(idx, job))
+                    }));
+                    let _ = result_sender.send((worker_idx, idx, result));
+                });
+                num_running += 1;
+            }
+            for _ in 0..num_running {
+                handle_result(result_receiver.recv().unwrap());
+            }
+        });
+    }
+
+    fn install<R: Send>(&self, worker_idx: usize, f: impl FnOnce() -> R + Send) -> R {
+        #[cfg(feature = "parallel")]
+        {
+            self.pools[worker_idx].install(f)
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            let _ = worker_idx;
+            f()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn test_prove_all() {
+        let scheduler = SegmentScheduler::new(ParallelProvingConfig::new(3, 1));
+        let max_active = AtomicUsize::new(0);
+        let active = AtomicUsize::new(0);
+        let mut done = vec![];
+        let mut results = vec![];
+        scheduler.prove_all(
+            0..10u64,
+            |idx, job| {
+                let now_active = active.fetch_add(1, Ordering::SeqCst) + 1;
+                max_active.fetch_max(now_active, Ordering::SeqCst);
+                thread::sleep(std::time::Duration::from_millis(10 - job));
+                active.fetch_sub(1, Ordering::SeqCst);
+                (idx, job * job)
+            },
+            |idx, result| {
+                done.push(idx);
+                results.push(result);
+            },
+        );
+        results.sort();
+        assert_eq!(
+            results,
+            (0..10u64).map(|i| (i as usize, i * i)).collect::<Vec<_>>()
+        );
+        done.sort();
+        assert_eq!(done, (0..10).collect::<Vec<_>>());
+        assert!(max_active.load(Ordering::SeqCst) <= 3);
+    }
+}
@@ -1,9 +1,6 @@
-use backtrace::Backtrace;
-use openvm_instructions::{
-    exe::FnBounds,
-    instruction::{DebugInfo, Instruction},
-    program::Program,
-};
+use std::cell::RefCell;
+
+use openvm_instructions::{exe::FnBounds, instruction::Instruction, program::Program};
 use openvm_stark_backend::{
     config::{Domain, StarkGenericConfig},
     p3_commit::PolynomialSpace,
@@ -14,20 +11,21 @@ use openvm_stark_backend::{
 };
 
 use super::{
-    ExecutionError, Streams, SystemBase, SystemConfig, VmChipComplex, VmComplexTraceHeights,
-    VmConfig,
+    ExecutionError, SharedJournalRecorder, Streams, SystemBase, SystemConfig, VmChipComplex,
+    VmComplexTraceHeights, VmConfig, VmInventoryError,
 };
 #[cfg(feature = "bench-metrics")]
 use crate::metrics::VmMetrics;
 use crate::{
-    arch::{instructions::*, ExecutionState, InstructionExecutor},
-    system::memory::MemoryImage,
+    arch::{instructions::*, AnyEnum, ExecutionState, InstructionExecutor},
+    system::{memory::MemoryImage, phantom::PhantomChip, program::InstructionDispatch},
 };
 
-/// Check segment every 100 instructions.
-const SEGMENT_CHECK_INTERVAL: usize = 100;
+/// Check segment every 100 instructions. The segmentation strategy is consulted after every
+/// `SEGMENT_CHECK_INTERVAL + 1`-th instruction of a segment.
+pub const SEGMENT_CHECK_INTERVAL: usize = 100;
 
-const DEFAULT_MAX_SEGMENT_LEN: usize = (1 << 22) - 100;
+pub(crate) const DEFAULT_MAX_SEGMENT_LEN: usize = (1 << 22) - 100;
 // a heuristic number for the maximum number of cells per chip in a segment
 // a few reasons for this number:
 //  1. `VmAirWrapper<Rv32BaseAluAdapterAir, BaseAluCoreAir<4, 8>` is
@@ -45,6 +43,12 @@ pub trait SegmentationStrategy:
         trace_heights: &[usize],
         trace_cells: &[usize],
     ) -> bool;
+
+    /// Checks that the strategy applies to a VM whose AIRs are `air_names`, in AIR ID order.
+    /// Called when a segment is created, before any call to [Self::should_segment].
+    fn validate(&self, _air_names: &[String]) -> Result<(), VmInventoryError> {
+        Ok(())
+    }
 }
 
 /// Default segmentation strategy: segment if any chip's height or cells exceed the limits.
@@ -121,6 +125,10 @@ where
     pub final_memory: Option<MemoryImage<F>>,
 
     pub since_last_segment_check: usize,
+    /// Number of instructions executed in previous segments, counted towards the cycle limit.
+    pub cycles_before: u64,
+    /// Records or replays the non-deterministic inputs of the execution, see [Self::set_journal].
+    pub journal: Option<SharedJournalRecorder<F>>,
 
     /// Air names for debug purposes only.
     pub(crate) air_names: Vec<String>,
@@ -132,6 +140,8 @@ where
 pub struct ExecutionSegmentState {
     pub pc: u32,
     pub is_terminated: bool,
+    /// Number of instructions executed in this call, excluding the final `TERMINATE`.
+    pub num_instructions: u64,
 }
 
 impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
@@ -142,8 +152,8 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
         init_streams: Streams<F>,
         initial_memory: Option<MemoryImage<F>>,
         #[allow(unused_variables)] fn_bounds: FnBounds,
-    ) -> Self {
-        let mut chip_complex = config.create_chip_complex().unwrap();
+    ) -> Result<Self, ExecutionError> {
+        let mut chip_complex = config.create_chip_complex()?;
         chip_complex.set_streams(init_streams);
         let program = if !config.system().profiling {
             program.strip_debug_infos()
@@ -156,8 +166,9 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
             chip_complex.set_initial_memory(initial_memory);
         }
         let air_names = chip_complex.air_names();
+        config.system().segmentation_strategy.validate(&air_names)?;
 
-        Self {
+        Ok(Self {
             chip_complex,
             final_memory: None,
             air_names,
@@ -167,7 +178,9 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                 ..Default::default()
             },
             since_last_segment_check: 0,
-        }
+            cycles_before: 0,
+            journal: None,
+        })
     }
 
     pub fn system_config(&self) -> &SystemConfig {
@@ -181,19 +194,64 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
             .set_override_inventory_trace_heights(overridden_heights.inventory);
     }
 
+    /// Sets the journal into which the phantom instructions executed by this segment are
+    /// recorded, or from which they are replayed. Must be called before execution.
+    pub fn set_journal(&mut self, journal: SharedJournalRecorder<F>) {
+        self.chip_complex.set_journal(Some(journal.clone()));
+        self.journal = Some(journal);
+    }
+
+    /// Switches to execute-only mode: executors keep no records and memory accesses are not
+    /// logged, so the segment can not be proven. Segmentation checks are skipped and execution
+    /// only stops at termination. Must be called before execution.
+    pub fn set_execute_only(&mut self) {
+        self.chip_complex
+            .base
+            .memory_controller
+            .set_execute_only(true);
+    }
+
+    pub fn is_execute_only(&self) -> bool {
+        self.chip_complex.memory_controller().is_execute_only()
+    }
+
     /// Stopping is triggered by should_segment()
-    pub fn execute_from_pc(
+    pub fn execute_from_pc(&mut self, pc: u32) -> Result<ExecutionSegmentState, ExecutionError> {
+        self.execute_from_pc_impl(pc, None)
+    }
+
+    /// Executes at most `max_instructions` instructions, or until termination or segmentation.
+    /// [Self::final_memory] is only set if execution stopped because of termination or
+    /// segmentation, so execution can be resumed from the returned state. Used to interleave
+    /// interpretation with other execution backends.
+    pub fn execute_instructions_from_pc(
+        &mut self,
+        pc: u32,
+        max_instructions: u64,
+    ) -> Result<ExecutionSegmentState, ExecutionError> {
+        self.execute_from_pc_impl(pc, Some(max_instructions))
+    }
+
+    fn execute_from_pc_impl(
         &mut self,
         mut pc: u32,
+        max_instructions: Option<u64>,
     ) -> Result<ExecutionSegmentState, ExecutionError> {
         let mut timestamp = self.chip_complex.memory_controller().timestamp();
-        let mut prev_backtrace: Option<Backtrace> = None;
+        let mut prev_pc: Option<u32> = None;
 
+        let input_commit = self.chip_complex.flush_input_commitment();
         self.chip_complex
             .connector_chip_mut()
-            .begin(ExecutionState::new(pc, timestamp));
+            .begin(ExecutionState::new(pc, timestamp), input_commit);
 
         let mut did_terminate = false;
+        let mut num_instructions = 0;
+        let execute_only = self.is_execute_only();
+        let limits = self.system_config().limits;
+        let cycles_before = self.cycles_before;
+        #[cfg(feature = "bench-metrics")]
+        let profiling = self.system_config().profiling;
 
         loop {
             #[allow(unused_variables)]
@@ -210,86 +268,151 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                     ..
                 } = &mut chip_complex.base;
 
-                let (instruction, debug_info) = program_chip.get_instruction(pc)?;
+                let (instruction, debug_info, dispatch) =
+                    program_chip.get_linked_instruction(pc)?;
                 tracing::trace!("pc: {pc:#x} | time: {timestamp} | {:?}", instruction);
 
                 #[allow(unused_variables)]
-                let (dsl_instr, trace) = debug_info.as_ref().map_or(
-                    (None, None),
-                    |DebugInfo {
-                         dsl_instruction,
-                         trace,
-                     }| (Some(dsl_instruction), trace.as_ref()),
-                );
+                let dsl_instr = debug_info
+                    .as_ref()
+                    .map(|debug_info| &debug_info.dsl_instruction);
 
                 let &Instruction { opcode, c, .. } = instruction;
-                if opcode == SystemOpcode::TERMINATE.global_opcode() {
-                    did_terminate = true;
-                    self.chip_complex.connector_chip_mut().end(
-                        ExecutionState::new(pc, timestamp),
-                        Some(c.as_canonical_u32()),
-                    );
-                    break;
-                }
-
-                // Some phantom instruction handling is more convenient to do here than in PhantomChip.
-                if opcode == SystemOpcode::PHANTOM.global_opcode() {
-                    // Note: the discriminant is the lower 16 bits of the c operand.
-                    let discriminant = c.as_canonical_u32() as u16;
-                    let phantom = SysPhantom::from_repr(discriminant);
-                    tracing::trace!("pc: {pc:#x} | system phantom: {phantom:?}");
-                    match phantom {
-                        Some(SysPhantom::DebugPanic) => {
-                            if let Some(mut backtrace) = prev_backtrace {
-                                backtrace.resolve();
-                                eprintln!("openvm program failure; backtrace:\n{:?}", backtrace);
-                            } else {
-                                eprintln!("openvm program failure; no backtrace");
+                let (executor_idx, sub_executor_idx) = match dispatch {
+                    InstructionDispatch::Terminate => {
+                        did_terminate = true;
+                        let input_commit = self.chip_complex.flush_input_commitment();
+                        self.chip_complex.connector_chip_mut().end(
+                            ExecutionState::new(pc, timestamp),
+                            Some(c.as_canonical_u32()),
+                            input_commit,
+                        );
+                        break;
+                    }
+                    InstructionDispatch::Disabled => {
+                        return Err(ExecutionError::DisabledOperation { pc, opcode });
+                    }
+                    InstructionDispatch::Executor(executor_idx) => (executor_idx, None),
+                    InstructionDispatch::Phantom {
+                        executor_idx,
+                        sub_executor_idx,
+                    } => (executor_idx, Some(sub_executor_idx)),
+                    // Some phantom instruction handling is more convenient to do here than in PhantomChip.
+                    InstructionDispatch::SysPhantom {
+                        phantom,
+                        executor_idx,
+                    } => {
+                        tracing::trace!("pc: {pc:#x} | system phantom: {phantom:?}");
+                        match phantom {
+                            SysPhantom::DebugPanic => {
+                                // The backtrace is only looked up on failure to avoid cloning it
+                                // on every cycle.
+                                let prev_backtrace = prev_pc
+                                    .and_then(|prev_pc| program_chip.get_debug_info(prev_pc))
+                                    .and_then(|debug_info| debug_info.trace.clone());
+                                if let Some(mut backtrace) = prev_backtrace {
+                                    backtrace.resolve();
+                                    eprintln!(
+                                        "openvm program failure; backtrace:\n{:?}",
+                                        backtrace
+                                    );
+                                } else {
+                                    eprintln!("openvm program failure; no backtrace");
+                                }
+                                return Err(ExecutionError::Fail { pc });
                             }
-                            return Err(ExecutionError::Fail { pc });
-                        }
-                        Some(SysPhantom::CtStart) =>
-                        {
-                            #[cfg(feature = "bench-metrics")]
-                            metrics
-                                .cycle_tracker
-                                .start(dsl_instr.cloned().unwrap_or("Default".to_string()))
-                        }
-                        Some(SysPhantom::CtEnd) =>
-                        {
-                            #[cfg(feature = "bench-metrics")]
-                            metrics
-                                .cycle_tracker
-                                .end(dsl_instr.cloned().unwrap_or("Default".to_string()))
+                            SysPhantom::CtStart =>
+                            {
+                                #[cfg(feature = "bench-metrics")]
+                                metrics
+                                    .cycle_tracker
+                                    .start(dsl_instr.cloned().unwrap_or("Default".to_string()))
+                            }
+                            SysPhantom::CtEnd =>
+                            {
+                                #[cfg(feature = "bench-metrics")]
+                                metrics
+                                    .cycle_tracker
+                                    .end(dsl_instr.cloned().unwrap_or("Default".to_string()))
+                            }
+                            SysPhantom::Nop => {}
                         }
-                        _ => {}
+                        (executor_idx, None)
+                    }
+                };
+                prev_pc = Some(pc);
+
+                if let Some(max_cycles) = limits.max_cycles {
+                    let cycles = cycles_before + num_instructions;
+                    if cycles >= max_cycles {
+                        return Err(ExecutionError::CycleLimitExceeded { pc, cycles });
                     }
                 }
-                prev_backtrace = trace.cloned();
 
-                if let Some(executor) = chip_complex.inventory.get_mut_executor(&opcode) {
-                    let next_state = InstructionExecutor::execute(
-                        executor,
+                let from_state = ExecutionState::new(pc, timestamp);
+                let next_state = match sub_executor_idx {
+                    Some(sub_executor_idx) => {
+                        let phantom_chip: &mut RefCell<PhantomChip<F>> = chip_complex
+                            .inventory
+                            .executor_mut(executor_idx)
+                            .as_any_kind_mut()
+                            .downcast_mut()
+                            .expect("PHANTOM is handled by PhantomChip");
+                        phantom_chip.get_mut().execute_linked(
+                            memory_controller,
+                            instruction,
+                            from_state,
+                            Some(sub_executor_idx),
+                        )?
+                    }
+                    None => InstructionExecutor::execute(
+                        chip_complex.inventory.executor_mut(executor_idx),
                         memory_controller,
                         instruction,
-                        ExecutionState::new(pc, timestamp),
-                    )?;
-                    assert!(next_state.timestamp > timestamp);
-                    pc = next_state.pc;
-                    timestamp = next_state.timestamp;
-                } else {
-                    return Err(ExecutionError::DisabledOperation { pc, opcode });
+                        from_state,
+                    )?,
                 };
-                (opcode, dsl_instr.cloned())
+                assert!(next_state.timestamp > timestamp);
+                pc = next_state.pc;
+                timestamp = next_state.timestamp;
+                // The DSL instruction is only needed for profiling metrics.
+                #[cfg(feature = "bench-metrics")]
+                let dsl_instr = dsl_instr.filter(|_| profiling).cloned();
+                #[cfg(not(feature = "bench-metrics"))]
+                let dsl_instr = ();
+                (opcode, dsl_instr)
             };
+            num_instructions += 1;
+
+            if let Some(max_memory_pages) = limits.max_memory_pages {
+                let pages = self.chip_complex.memory_controller().num_allocated_pages();
+                if pages > max_memory_pages {
+                    return Err(ExecutionError::MemoryLimitExceeded {
+                        pc,
+                        pages,
+                        cycles: cycles_before + num_instructions,
+                    });
+                }
+            }
 
             #[cfg(feature = "bench-metrics")]
             self.update_instruction_metrics(pc, opcode, dsl_instr);
 
-            if self.should_segment() {
-                self.chip_complex
-                    .connector_chip_mut()
-                    .end(ExecutionState::new(pc, timestamp), None);
+            if max_instructions.is_some_and(|max| num_instructions >= max) {
+                return Ok(ExecutionSegmentState {
+                    pc,
+                    is_terminated: false,
+                    num_instructions,
+                });
+            }
+
+            if !execute_only && self.should_segment() {
+                let input_commit = self.chip_complex.flush_input_commitment();
+                self.chip_complex.connector_chip_mut().end(
+                    ExecutionState::new(pc, timestamp),
+                    None,
+                    input_commit,
+                );
                 break;
             }
         }
@@ -304,6 +427,7 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
         Ok(ExecutionSegmentState {
             pc,
             is_terminated: did_terminate,
+            num_instructions,
         })
     }
 
@@ -336,14 +460,31 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
             return false;
         }
         self.since_last_segment_check = 0;
-        let segmentation_strategy = self.system_config().segmentation_strategy.clone();
-        segmentation_strategy.should_segment(
+        self.should_segment_with_extra_rows(&[])
+    }
+
+    /// Asks the segmentation strategy whether to end the segment now, as if every executor had
+    /// `extra_rows[executor_idx]` rows on top of those it recorded. Used by execution backends
+    /// that run some instructions without their executors, see
+    /// [Self::execute_instructions_from_pc].
+    pub fn should_segment_with_extra_rows(&self, extra_rows: &[usize]) -> bool {
+        let mut trace_heights = self
+            .chip_complex
+            .dynamic_trace_heights()
+            .collect::<Vec<_>>();
+        let mut trace_cells = self.chip_complex.current_trace_cells();
+        if !extra_rows.is_empty() {
+            let executors = self.chip_complex.inventory.executors();
+            let air_ids = self.chip_complex.executor_air_ids();
+            for ((&rows, &air_id), executor) in extra_rows.iter().zip(&air_ids).zip(executors) {
+                trace_heights[air_id] += rows;
+                trace_cells[air_id] += rows * executor.trace_width();
+            }
+        }
+        self.system_config().segmentation_strategy.should_segment(
             &self.air_names,
-            &self
-                .chip_complex
-                .dynamic_trace_heights()
-                .collect::<Vec<_>>(),
-            &self.chip_complex.current_trace_cells(),
+            &trace_heights,
+            &trace_cells,
         )
     }
 
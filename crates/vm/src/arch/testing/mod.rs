@@ -46,11 +46,13 @@ use crate::{
 
 pub mod execution;
 pub mod memory;
+pub mod mutation;
 pub mod program;
 pub mod test_adapter;
 
 pub use execution::ExecutionTester;
 pub use memory::MemoryTester;
+pub use mutation::{Mutation, MutationConfig, MutationReport};
 pub use test_adapter::TestAdapterChip;
 
 pub const EXECUTION_BUS: usize = 0;
@@ -297,6 +299,9 @@ impl<F: PrimeField32> Default for VmChipTestBuilder<F> {
 pub struct VmChipTester<SC: StarkGenericConfig> {
     pub memory: Option<MemoryTester<Val<SC>>>,
     pub air_proof_inputs: Vec<(AirRef<SC>, AirProofInput<SC>)>,
+    /// Index in `air_proof_inputs` and column names of the chip mutated by
+    /// [Self::mutation_test].
+    pub mutation_target: Option<(usize, Vec<String>)>,
 }
 
 impl<SC: StarkGenericConfig> Default for VmChipTester<SC> {
@@ -304,6 +309,7 @@ impl<SC: StarkGenericConfig> Default for VmChipTester<SC> {
         Self {
             memory: None,
             air_proof_inputs: vec![],
+            mutation_target: None,
         }
     }
 }
@@ -0,0 +1,353 @@
+//! Mutation testing of chip constraints: cells of the trace of a target chip are changed one
+//! mutation at a time, and every mutated trace must be rejected. A mutation that still verifies
+//! points at an under-constrained column.
+
+use std::{
+    fmt,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    p3_field::{Field, PrimeField32},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    utils::{disable_debug_builder, USE_DEBUG_BUILDER},
+    Chip,
+};
+use openvm_stark_sdk::{
+    config::{
+        baby_bear_blake3::{self, BabyBearBlake3Config},
+        baby_bear_poseidon2::{self, BabyBearPoseidon2Config},
+    },
+    engine::StarkEngine,
+    p3_baby_bear::BabyBear,
+};
+
+use super::{VmChipTestBuilder, VmChipTester};
+
+/// A change to one row of a trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mutation<F> {
+    /// Adds one to the cell.
+    Increment { row: usize, column: usize },
+    /// Sets the cell to a boundary value.
+    Set { row: usize, column: usize, value: F },
+    /// Swaps the cells of two columns.
+    Swap {
+        row: usize,
+        column: usize,
+        other: usize,
+    },
+}
+
+impl<F: Field> Mutation<F> {
+    /// Applies the mutation to `trace`. Returns `false`, leaving `trace` unchanged, if the
+    /// mutation would not change any cell.
+    fn apply(&self, trace: &mut RowMajorMatrix<F>) -> bool {
+        let width = trace.width();
+        let values = &mut trace.values;
+        match *self {
+            Mutation::Increment { row, column } => {
+                values[row * width + column] += F::ONE;
+                true
+            }
+            Mutation::Set { row, column, value } => {
+                let cell = &mut values[row * width + column];
+                let changed = *cell != value;
+                *cell = value;
+                changed
+            }
+            Mutation::Swap { row, column, other } => {
+                let (i, j) = (row * width + column, row * width + other);
+                let changed = values[i] != values[j];
+                values.swap(i, j);
+                changed
+            }
+        }
+    }
+
+    fn row(&self) -> usize {
+        match *self {
+            Mutation::Increment { row, .. }
+            | Mutation::Set { row, .. }
+            | Mutation::Swap { row, .. } => row,
+        }
+    }
+}
+
+/// Which mutations [VmChipTester::mutation_test] tries on each of the given rows.
+#[derive(Clone, Debug)]
+pub struct MutationConfig<F> {
+    /// Rows to mutate. They should hold executions rather than padding, since padding rows may
+    /// be legitimately unconstrained.
+    pub rows: Vec<usize>,
+    /// Values every cell is set to.
+    pub boundary_values: Vec<F>,
+    /// Whether to swap every pair of adjacent columns.
+    pub column_swaps: bool,
+    /// Cells that are not mutated, as `(row, column name)`, because they do not affect any
+    /// output of that row, e.g. the aux columns of a memory read the row does not perform.
+    pub dont_care: Vec<(usize, String)>,
+}
+
+impl<F: PrimeField32> Default for MutationConfig<F> {
+    fn default() -> Self {
+        Self {
+            rows: vec![0],
+            boundary_values: vec![
+                F::ZERO,
+                F::ONE,
+                F::NEG_ONE,
+                F::from_canonical_u32(1 << 8),
+                F::from_canonical_u32(1 << 16),
+            ],
+            column_swaps: true,
+            dont_care: vec![],
+        }
+    }
+}
+
+impl<F: Copy> MutationConfig<F> {
+    /// Marks the cells of `columns` in `rows` as don't-care, see [Self::dont_care].
+    pub fn with_dont_care<S: ToString>(
+        mut self,
+        rows: impl IntoIterator<Item = usize>,
+        columns: impl IntoIterator<Item = S> + Clone,
+    ) -> Self {
+        for row in rows {
+            self.dont_care.extend(
+                columns
+                    .clone()
+                    .into_iter()
+                    .map(|column| (row, column.to_string())),
+            );
+        }
+        self
+    }
+
+    fn mutations(&self, column_names: &[String]) -> Vec<Mutation<F>> {
+        for (_, name) in &self.dont_care {
+            assert!(column_names.contains(name), "Unknown column `{name}`");
+        }
+        let is_dont_care = |row: usize, column: usize| {
+            self.dont_care
+                .iter()
+                .any(|(r, name)| *r == row && *name == column_names[column])
+        };
+        let width = column_names.len();
+        let mut mutations = vec![];
+        for &row in &self.rows {
+            for column in 0..width {
+                if is_dont_care(row, column) {
+                    continue;
+                }
+                mutations.push(Mutation::Increment { row, column });
+                mutations.extend(self.boundary_values.iter().map(|&value| Mutation::Set {
+                    row,
+                    column,
+                    value,
+                }));
+                if self.column_swaps && column + 1 < width && !is_dont_care(row, column + 1) {
+                    mutations.push(Mutation::Swap {
+                        row,
+                        column,
+                        other: column + 1,
+                    });
+                }
+            }
+        }
+        mutations
+    }
+}
+
+/// Result of [VmChipTester::mutation_test].
+#[derive(Clone, Debug)]
+pub struct MutationReport<F> {
+    pub air_name: String,
+    /// Name of each column of the mutated trace.
+    pub column_names: Vec<String>,
+    /// Number of mutations that changed the trace.
+    pub num_mutations: usize,
+    /// Mutations whose trace was accepted.
+    pub survivors: Vec<Mutation<F>>,
+}
+
+impl<F: fmt::Display> MutationReport<F> {
+    pub fn assert_no_survivors(&self) {
+        assert!(self.survivors.is_empty(), "{self}");
+    }
+}
+
+impl<F: fmt::Display> fmt::Display for MutationReport<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: {} of {} mutations were accepted",
+            self.air_name,
+            self.survivors.len(),
+            self.num_mutations
+        )?;
+        for mutation in &self.survivors {
+            let name = |column: usize| &self.column_names[column];
+            write!(f, "  row {}: ", mutation.row())?;
+            match *mutation {
+                Mutation::Increment { column, .. } => writeln!(f, "incremented `{}`", name(column)),
+                Mutation::Set { column, value, .. } => {
+                    writeln!(f, "set `{}` to {value}", name(column))
+                }
+                Mutation::Swap { column, other, .. } => {
+                    writeln!(f, "swapped `{}` and `{}`", name(column), name(other))
+                }
+            }?;
+        }
+        Ok(())
+    }
+}
+
+impl<SC: StarkGenericConfig> VmChipTester<SC>
+where
+    Val<SC>: PrimeField32,
+{
+    /// Loads `chip` as the chip whose trace is mutated by [Self::mutation_test]. `column_names`
+    /// names the columns of its trace, e.g. by concatenating the `column_names()` of the
+    /// adapter and core column structs; missing names are replaced by column indices.
+    pub fn load_mutation_target<C: Chip<SC>>(mut self, chip: C, column_names: Vec<String>) -> Self {
+        assert!(
+            chip.current_trace_height() > 0,
+            "Mutation target must have executions"
+        );
+        self.mutation_target = Some((self.air_proof_inputs.len(), column_names));
+        self.load(chip)
+    }
+
+    /// Applies each mutation of `config` in turn to the trace of the chip loaded with
+    /// [Self::load_mutation_target], and reports the mutations that are not rejected by the
+    /// engine. Panics if the unmutated traces do not verify.
+    ///
+    /// The debug builder is disabled while the mutated traces are proved, so that constraint
+    /// failures are reported as errors, and restored afterwards.
+    pub fn mutation_test<E: StarkEngine<SC>, P: Fn() -> E>(
+        &self,
+        engine_provider: P,
+        config: &MutationConfig<Val<SC>>,
+    ) -> MutationReport<Val<SC>> {
+        let (target, column_names) = self
+            .mutation_target
+            .as_ref()
+            .expect("Mutation target must be loaded");
+        self.test(&engine_provider)
+            .expect("Unmutated traces should verify");
+        let _debug_builder = DebugBuilderGuard::disable();
+
+        let (air, air_proof_input) = &self.air_proof_inputs[*target];
+        let trace = air_proof_input
+            .raw
+            .common_main
+            .as_ref()
+            .expect("Mutation target must have a common main trace");
+        for &row in &config.rows {
+            assert!(row < trace.height(), "Row {row} is out of bounds");
+        }
+        let column_names: Vec<_> = (0..trace.width())
+            .map(|column| {
+                column_names
+                    .get(column)
+                    .cloned()
+                    .unwrap_or_else(|| format!("column {column}"))
+            })
+            .collect();
+
+        let mut num_mutations = 0;
+        let mut survivors = vec![];
+        for mutation in config.mutations(&column_names) {
+            let mut air_proof_inputs = self.air_proof_inputs.clone();
+            let trace = air_proof_inputs[*target]
+                .1
+                .raw
+                .common_main
+                .as_mut()
+                .unwrap();
+            if !mutation.apply(trace) {
+                continue;
+            }
+            num_mutations += 1;
+            let (airs, air_proof_inputs) = air_proof_inputs.into_iter().unzip();
+            // A prover that panics on the mutated trace rejects it as well.
+            let accepted = catch_unwind(AssertUnwindSafe(|| {
+                engine_provider()
+                    .run_test_impl(airs, air_proof_inputs)
+                    .is_ok()
+            }))
+            .unwrap_or(false);
+            if accepted {
+                survivors.push(mutation);
+            }
+        }
+
+        MutationReport {
+            air_name: air.name(),
+            column_names,
+            num_mutations,
+            survivors,
+        }
+    }
+}
+
+/// Disables the debug builder until dropped, then restores the previous setting.
+struct DebugBuilderGuard(bool);
+
+impl DebugBuilderGuard {
+    fn disable() -> Self {
+        let prev = USE_DEBUG_BUILDER.with(|debug| *debug.lock().unwrap());
+        disable_debug_builder();
+        Self(prev)
+    }
+}
+
+impl Drop for DebugBuilderGuard {
+    fn drop(&mut self) {
+        USE_DEBUG_BUILDER.with(|debug| *debug.lock().unwrap() = self.0);
+    }
+}
+
+impl VmChipTestBuilder<BabyBear> {
+    /// Builds the tester with `chip`, which has executed the instructions under test, as the
+    /// mutation target and the chips loaded by `load_periphery` after it. Then asserts that
+    /// [VmChipTester::mutation_test] rejects every mutation of `config`.
+    ///
+    /// `column_names` are the names of the columns of `chip`, e.g. the adapter column names
+    /// followed by the core column names.
+    pub fn assert_mutations_rejected<C: Chip<BabyBearBlake3Config>>(
+        self,
+        chip: C,
+        column_names: Vec<String>,
+        load_periphery: impl FnOnce(
+            VmChipTester<BabyBearBlake3Config>,
+        ) -> VmChipTester<BabyBearBlake3Config>,
+        config: &MutationConfig<BabyBear>,
+    ) {
+        let tester = self.build().load_mutation_target(chip, column_names);
+        load_periphery(tester)
+            .finalize()
+            .simple_mutation_test(config)
+            .assert_no_survivors();
+    }
+}
+
+impl VmChipTester<BabyBearPoseidon2Config> {
+    pub fn simple_mutation_test(
+        &self,
+        config: &MutationConfig<BabyBear>,
+    ) -> MutationReport<BabyBear> {
+        self.mutation_test(baby_bear_poseidon2::default_engine, config)
+    }
+}
+
+impl VmChipTester<BabyBearBlake3Config> {
+    pub fn simple_mutation_test(
+        &self,
+        config: &MutationConfig<BabyBear>,
+    ) -> MutationReport<BabyBear> {
+        self.mutation_test(baby_bear_blake3::default_engine, config)
+    }
+}
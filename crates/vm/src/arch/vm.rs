@@ -1,4 +1,11 @@
-use std::{borrow::Borrow, collections::VecDeque, marker::PhantomData, mem, sync::Arc};
+use std::{
+    borrow::Borrow,
+    collections::VecDeque,
+    marker::PhantomData,
+    mem,
+    sync::{mpsc, Arc},
+    thread,
+};
 
 use openvm_instructions::exe::VmExe;
 use openvm_stark_backend::{
@@ -6,7 +13,7 @@ use openvm_stark_backend::{
     engine::StarkEngine,
     keygen::types::{MultiStarkProvingKey, MultiStarkVerifyingKey},
     p3_commit::PolynomialSpace,
-    p3_field::PrimeField32,
+    p3_field::{FieldAlgebra, PrimeField32},
     proof::Proof,
     prover::types::{CommittedTraceData, ProofInput},
     utils::metrics_span,
@@ -16,18 +23,33 @@ use openvm_stark_backend::{
 use thiserror::Error;
 use tracing::info_span;
 
-use super::{ExecutionError, VmComplexTraceHeights, VmConfig, CONNECTOR_AIR_ID, MERKLE_AIR_ID};
+use super::{
+    ExecutionError, ExecutionJournal, JournalRecorder, SharedJournalRecorder,
+    VmComplexTraceHeights, VmConfig, CONNECTOR_AIR_ID, MERKLE_AIR_ID,
+};
 #[cfg(feature = "bench-metrics")]
 use crate::metrics::VmMetrics;
 use crate::{
     arch::segment::ExecutionSegment,
     system::{
         connector::{VmConnectorPvs, DEFAULT_SUSPEND_EXIT_CODE},
-        memory::{merkle::MemoryMerklePvs, paged_vec::AddressMap, MemoryImage, CHUNK},
+        input_commitment::InputCommitment,
+        memory::{
+            initial_memory_image,
+            merkle::MemoryMerklePvs,
+            tree::{
+                data_segments::{supports_data_segments, write_data_segments, DataSegment},
+                public_values::extract_public_values,
+            },
+            MemoryImage, CHUNK,
+        },
         program::trace::VmCommittedExe,
     },
 };
 
+/// Default number of segment proof inputs alive at once when proving is pipelined with execution.
+pub const DEFAULT_MAX_IN_FLIGHT_SEGMENTS: usize = 2;
+
 /// VM memory state for continuations.
 pub type VmMemoryState<F> = MemoryImage<F>;
 
@@ -36,25 +58,52 @@ pub struct Streams<F> {
     pub input_stream: VecDeque<Vec<F>>,
     pub hint_stream: VecDeque<F>,
     pub hint_space: Vec<Vec<F>>,
+    /// Commitment to the hinted words written to memory so far, see
+    /// [input_commitment](crate::system::input_commitment).
+    pub input_commitment: InputCommitment<F>,
+    /// Data segments mapped into memory before execution, see
+    /// [data_segments](crate::system::memory::tree::data_segments). Only used to build the initial
+    /// memory, so they are empty during execution.
+    pub data_segments: Vec<DataSegment>,
+    /// Number of times the hint stream was written with [Self::set_hint_stream].
+    hint_generation: u64,
 }
 
-impl<F> Streams<F> {
+impl<F: Default> Streams<F> {
     pub fn new(input_stream: impl Into<VecDeque<Vec<F>>>) -> Self {
         Self {
             input_stream: input_stream.into(),
             hint_stream: VecDeque::default(),
             hint_space: Vec::default(),
+            input_commitment: InputCommitment::default(),
+            data_segments: Vec::new(),
+            hint_generation: 0,
         }
     }
 }
 
-impl<F> From<VecDeque<Vec<F>>> for Streams<F> {
+impl<F> Streams<F> {
+    /// Replaces the contents of the hint stream with `hint`. Phantom sub-executors must write the
+    /// hint stream through this method, so that the VM can tell which instructions hinted words.
+    pub fn set_hint_stream(&mut self, hint: impl IntoIterator<Item = F>) {
+        self.hint_stream.clear();
+        self.hint_stream.extend(hint);
+        self.hint_generation += 1;
+    }
+
+    /// Number of times the hint stream was written with [Self::set_hint_stream].
+    pub fn hint_generation(&self) -> u64 {
+        self.hint_generation
+    }
+}
+
+impl<F: Default> From<VecDeque<Vec<F>>> for Streams<F> {
     fn from(value: VecDeque<Vec<F>>) -> Self {
         Streams::new(value)
     }
 }
 
-impl<F> From<Vec<Vec<F>>> for Streams<F> {
+impl<F: Default> From<Vec<Vec<F>>> for Streams<F> {
     fn from(value: Vec<Vec<F>>) -> Self {
         Streams::new(value)
     }
@@ -63,6 +112,9 @@ impl<F> From<Vec<Vec<F>>> for Streams<F> {
 pub struct VmExecutor<F, VC> {
     pub config: VC,
     pub overridden_heights: Option<VmComplexTraceHeights>,
+    /// Records or replays the non-deterministic inputs of every execution, see
+    /// [Self::set_journal].
+    pub journal: Option<SharedJournalRecorder<F>>,
     _marker: PhantomData<F>,
 }
 
@@ -79,10 +131,27 @@ pub struct VmExecutorResult<SC: StarkGenericConfig> {
     pub final_memory: Option<VmMemoryState<Val<SC>>>,
 }
 
+/// Result of an execute-only run, see [VmExecutor::execute_only].
+pub struct VmExecuteOnlyResult<F> {
+    /// Memory at termination.
+    pub final_memory: VmMemoryState<F>,
+    /// User public values. Unset public values are zero.
+    pub public_values: Vec<F>,
+    pub exit_code: u32,
+    /// Number of instructions executed, excluding the final `TERMINATE`.
+    pub num_instructions: u64,
+    /// Input commitment at termination, if the system commits to the input stream.
+    pub input_commitment: Option<InputCommitment<F>>,
+}
+
 pub struct VmExecutorNextSegmentState<F: PrimeField32> {
     pub memory: MemoryImage<F>,
     pub input: Streams<F>,
     pub pc: u32,
+    /// Number of instructions executed in previous segments.
+    pub num_instructions: u64,
+    /// Number of previous segments.
+    pub num_segments: usize,
     #[cfg(feature = "bench-metrics")]
     pub metrics: VmMetrics,
 }
@@ -93,6 +162,8 @@ impl<F: PrimeField32> VmExecutorNextSegmentState<F> {
             memory,
             input: input.into(),
             pc,
+            num_instructions: 0,
+            num_segments: 0,
             #[cfg(feature = "bench-metrics")]
             metrics: VmMetrics::default(),
         }
@@ -127,30 +198,62 @@ where
         Self {
             config,
             overridden_heights,
+            journal: None,
             _marker: Default::default(),
         }
     }
 
+    /// Records the non-deterministic inputs of all following executions into `journal`, or
+    /// replays them from it if it was created with [JournalRecorder::replay]. Replay also checks
+    /// that continuation segments end where they did when recording.
+    pub fn set_journal(&mut self, journal: SharedJournalRecorder<F>) {
+        self.journal = Some(journal);
+    }
+
     pub fn continuation_enabled(&self) -> bool {
         self.config.system().continuation_enabled
     }
 
+    fn initial_state(
+        &self,
+        exe: &VmExe<F>,
+        input: impl Into<Streams<F>>,
+    ) -> Result<VmExecutorNextSegmentState<F>, ExecutionError> {
+        let mut input = input.into();
+        let data_segments = mem::take(&mut input.data_segments);
+        record_data_segments(self.journal.as_ref(), exe.pc_start, &data_segments)?;
+        let memory = self.initial_memory(exe, &data_segments)?;
+        Ok(VmExecutorNextSegmentState::new(memory, input, exe.pc_start))
+    }
+
+    /// The initial memory of `exe` with `data_segments` mapped into it.
+    pub fn initial_memory(
+        &self,
+        exe: &VmExe<F>,
+        data_segments: &[DataSegment],
+    ) -> Result<MemoryImage<F>, ExecutionError> {
+        let mem_config = self.config.resolved_system_config().memory_config;
+        let mut memory = initial_memory_image(&mem_config, &exe.init_memory);
+        if !data_segments.is_empty() {
+            if !self.continuation_enabled() {
+                return Err(ExecutionError::DataSegmentsWithoutContinuations);
+            }
+            if !supports_data_segments(&mem_config) {
+                return Err(ExecutionError::DataSegmentsUnsupported);
+            }
+            write_data_segments(&mut memory, &exe.init_memory, data_segments)?;
+        }
+        Ok(memory)
+    }
+
     pub fn execute_segments(
         &self,
         exe: impl Into<VmExe<F>>,
         input: impl Into<Streams<F>>,
     ) -> Result<Vec<ExecutionSegment<F, VC>>, ExecutionError> {
-        let mem_config = self.config.system().memory_config;
         let exe = exe.into();
         let mut segments = vec![];
-        let memory = AddressMap::from_iter(
-            mem_config.as_offset,
-            1 << mem_config.as_height,
-            1 << mem_config.pointer_max_bits,
-            exe.init_memory.clone(),
-        );
-        let pc = exe.pc_start;
-        let mut state = VmExecutorNextSegmentState::new(memory, input, pc);
+        let mut state = self.initial_state(&exe, input)?;
         let mut segment_idx = 0;
 
         loop {
@@ -177,13 +280,26 @@ where
         from_state: VmExecutorNextSegmentState<F>,
     ) -> Result<VmExecutorOneSegmentResult<F, VC>, ExecutionError> {
         let exe = exe.into();
+        if let Some(max_segments) = self.config.system().limits.max_segments {
+            if from_state.num_segments >= max_segments {
+                return Err(ExecutionError::SegmentLimitExceeded {
+                    pc: from_state.pc,
+                    segments: from_state.num_segments,
+                    cycles: from_state.num_instructions,
+                });
+            }
+        }
         let mut segment = ExecutionSegment::new(
             &self.config,
             exe.program.clone(),
             from_state.input,
             Some(from_state.memory),
             exe.fn_bounds.clone(),
-        );
+        )?;
+        segment.cycles_before = from_state.num_instructions;
+        if let Some(journal) = &self.journal {
+            segment.set_journal(journal.clone());
+        }
         #[cfg(feature = "bench-metrics")]
         {
             segment.metrics = from_state.metrics;
@@ -193,6 +309,17 @@ where
             segment.set_override_trace_heights(overridden_heights.clone());
         }
         let state = metrics_span("execute_time_ms", || segment.execute_from_pc(from_state.pc))?;
+        if let Some(journal) = &self.journal {
+            let mut journal = journal.lock().unwrap();
+            if state.is_terminated {
+                journal.finish(state.pc, true)?;
+            } else {
+                journal.end_segment(
+                    state.pc,
+                    from_state.num_instructions + state.num_instructions,
+                )?;
+            }
+        }
 
         if state.is_terminated {
             return Ok(VmExecutorOneSegmentResult {
@@ -222,6 +349,8 @@ where
                 memory: final_memory,
                 input: streams,
                 pc: state.pc,
+                num_instructions: from_state.num_instructions + state.num_instructions,
+                num_segments: from_state.num_segments + 1,
                 #[cfg(feature = "bench-metrics")]
                 metrics,
             }),
@@ -247,6 +376,168 @@ where
         Ok(final_memory)
     }
 
+    /// Executes a program without keeping any records for trace generation, which is much faster
+    /// than [Self::execute]. The whole program runs in a single segment, so the result can not be
+    /// proven; only the final state is returned. A non-zero exit code is not an error here.
+    pub fn execute_only(
+        &self,
+        exe: impl Into<VmExe<F>>,
+        input: impl Into<Streams<F>>,
+    ) -> Result<VmExecuteOnlyResult<F>, ExecutionError> {
+        self.execute_only_impl(exe.into(), input.into(), self.journal.clone())
+    }
+
+    /// Runs [Self::execute_only] while recording the non-deterministic inputs of the execution.
+    /// The journal is returned even if execution fails, so that the failure can be reproduced
+    /// with [Self::replay].
+    pub fn execute_only_and_record(
+        &self,
+        exe: impl Into<VmExe<F>>,
+        input: impl Into<Streams<F>>,
+    ) -> (
+        Result<VmExecuteOnlyResult<F>, ExecutionError>,
+        ExecutionJournal<F>,
+    ) {
+        let journal = JournalRecorder::record().shared();
+        let result = self.execute_only_impl(exe.into(), input.into(), Some(journal.clone()));
+        let journal = Arc::into_inner(journal)
+            .expect("journal is no longer shared after execution")
+            .into_inner()
+            .unwrap()
+            .into_journal();
+        (result, journal)
+    }
+
+    /// Runs [Self::execute_only] with the non-deterministic inputs and data segments recorded in
+    /// `journal`, without any input stream. Fails with [ExecutionError::JournalMismatch] if
+    /// execution diverges from the recorded one, e.g. because `exe` is not the recorded program.
+    pub fn replay(
+        &self,
+        exe: impl Into<VmExe<F>>,
+        journal: ExecutionJournal<F>,
+    ) -> Result<VmExecuteOnlyResult<F>, ExecutionError> {
+        let input = Streams {
+            data_segments: journal.data_segments.clone(),
+            ..Default::default()
+        };
+        let journal = JournalRecorder::replay(journal).shared();
+        self.execute_only_impl(exe.into(), input, Some(journal))
+    }
+
+    fn execute_only_impl(
+        &self,
+        exe: VmExe<F>,
+        input: Streams<F>,
+        journal: Option<SharedJournalRecorder<F>>,
+    ) -> Result<VmExecuteOnlyResult<F>, ExecutionError> {
+        let pc_start = exe.pc_start;
+        record_data_segments(journal.as_ref(), pc_start, &input.data_segments)?;
+        let mut segment = self.create_execute_only_segment(exe, input)?;
+        if let Some(journal) = &journal {
+            segment.set_journal(journal.clone());
+        }
+        let state = metrics_span("execute_time_ms", || segment.execute_from_pc(pc_start))?;
+        assert!(
+            state.is_terminated,
+            "execute-only mode runs until termination"
+        );
+        if let Some(journal) = &journal {
+            journal.lock().unwrap().finish(state.pc, false)?;
+        }
+        Ok(self.execute_only_result(segment, state.num_instructions))
+    }
+
+    /// Creates the segment used by [Self::execute_only], with the initial memory of `exe`, for
+    /// other execution backends that interpret some instructions.
+    pub fn create_execute_only_segment(
+        &self,
+        exe: impl Into<VmExe<F>>,
+        input: impl Into<Streams<F>>,
+    ) -> Result<ExecutionSegment<F, VC>, ExecutionError> {
+        let mut segment = self.create_first_segment(exe, input)?;
+        segment.set_execute_only();
+        Ok(segment)
+    }
+
+    /// Creates the first segment of an execution of `exe`, with its initial memory. Unlike
+    /// [Self::create_execute_only_segment], executors and memory keep their records, so that
+    /// other execution backends can consult the segmentation strategy.
+    pub fn create_first_segment(
+        &self,
+        exe: impl Into<VmExe<F>>,
+        input: impl Into<Streams<F>>,
+    ) -> Result<ExecutionSegment<F, VC>, ExecutionError> {
+        let exe = exe.into();
+        let mut input = input.into();
+        let data_segments = mem::take(&mut input.data_segments);
+        record_data_segments(self.journal.as_ref(), exe.pc_start, &data_segments)?;
+        let initial_memory = if self.continuation_enabled() {
+            Some(self.initial_memory(&exe, &data_segments)?)
+        } else if !data_segments.is_empty() {
+            return Err(ExecutionError::DataSegmentsWithoutContinuations);
+        } else {
+            None
+        };
+        let mut segment = ExecutionSegment::new(
+            &self.config,
+            exe.program,
+            input,
+            initial_memory,
+            exe.fn_bounds,
+        )?;
+        if let Some(journal) = &self.journal {
+            segment.set_journal(journal.clone());
+        }
+        Ok(segment)
+    }
+
+    /// Extracts the result of a terminated segment created by
+    /// [Self::create_execute_only_segment].
+    pub fn execute_only_result(
+        &self,
+        mut segment: ExecutionSegment<F, VC>,
+        num_instructions: u64,
+    ) -> VmExecuteOnlyResult<F> {
+        let system_config = self.config.system();
+        let memory_dimensions = segment
+            .chip_complex
+            .config()
+            .memory_config
+            .memory_dimensions();
+        let end_state = segment.chip_complex.connector_chip().boundary_states[1]
+            .expect("end state must be set");
+        let final_memory = segment
+            .final_memory
+            .take()
+            .expect("final memory should be set after execution");
+        let public_values = if system_config.continuation_enabled {
+            extract_public_values(
+                &memory_dimensions,
+                system_config.num_public_values,
+                &final_memory,
+            )
+        } else if let Some(pv_chip) = segment.chip_complex.public_values_chip() {
+            pv_chip
+                .core
+                .get_custom_public_values()
+                .into_iter()
+                .map(|pv| pv.unwrap_or(F::ZERO))
+                .collect()
+        } else {
+            vec![]
+        };
+        let input_commitment = system_config
+            .commit_input_stream
+            .then(|| segment.chip_complex.take_streams().input_commitment);
+        VmExecuteOnlyResult {
+            final_memory,
+            public_values,
+            exit_code: end_state.exit_code,
+            num_instructions,
+            input_commitment,
+        }
+    }
+
     pub fn execute_and_generate<SC: StarkGenericConfig>(
         &self,
         exe: impl Into<VmExe<F>>,
@@ -287,23 +578,181 @@ where
         VC::Executor: Chip<SC>,
         VC::Periphery: Chip<SC>,
     {
-        let mut segments = self.execute_segments(exe, input)?;
-        let final_memory = mem::take(&mut segments.last_mut().unwrap().final_memory);
-
+        let mut segments = self.execute_and_generate_iter(exe, committed_program, input);
+        let per_segment = (&mut segments).collect::<Result<Vec<_>, _>>()?;
+        tracing::debug!("Number of continuation segments: {}", per_segment.len());
         Ok(VmExecutorResult {
-            per_segment: segments
+            per_segment,
+            final_memory: segments.final_memory,
+        })
+    }
+
+    /// Returns an iterator which executes the program one segment at a time, generating the proof
+    /// input of each segment before executing the next one. Only a single segment's records are
+    /// alive at any time.
+    pub fn execute_and_generate_iter<SC: StarkGenericConfig>(
+        &self,
+        exe: impl Into<VmExe<F>>,
+        committed_program: Option<CommittedTraceData<SC>>,
+        input: impl Into<Streams<F>>,
+    ) -> VmSegmentProofInputs<'_, SC, VC>
+    where
+        Domain<SC>: PolynomialSpace<Val = F>,
+    {
+        let exe = exe.into();
+        let next_state = Some(self.initial_state(&exe, input));
+        VmSegmentProofInputs {
+            executor: self,
+            exe,
+            committed_program,
+            next_state,
+            segment_idx: 0,
+            final_memory: None,
+        }
+    }
+
+    /// Executes the program and generates the proof input of each segment on a separate thread,
+    /// while `consume` is called on each proof input in order on the current thread, e.g. to prove
+    /// it. At most `max_in_flight` proof inputs are alive at once: the one being consumed, the ones
+    /// waiting for it and the one being generated, which bounds memory usage independently of the
+    /// number of segments. With `max_in_flight == 1`, nothing is pipelined: each segment is
+    /// executed on the current thread after the previous one is consumed.
+    ///
+    /// Returns the outputs of `consume` and the final memory.
+    pub fn execute_and_generate_pipelined<SC, R>(
+        &self,
+        exe: impl Into<VmExe<F>>,
+        committed_program: Option<CommittedTraceData<SC>>,
+        input: impl Into<Streams<F>>,
+        max_in_flight: usize,
+        mut consume: impl FnMut(usize, ProofInput<SC>) -> R,
+    ) -> Result<VmPipelinedResult<R, F>, ExecutionError>
+    where
+        SC: StarkGenericConfig,
+        Domain<SC>: PolynomialSpace<Val = F>,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+        Self: Sync,
+        ProofInput<SC>: Send,
+        CommittedTraceData<SC>: Send,
+    {
+        assert!(max_in_flight > 0, "at least one segment must be in flight");
+        let exe = exe.into();
+        let input = input.into();
+        if max_in_flight == 1 {
+            let mut segments = self.execute_and_generate_iter(exe, committed_program, input);
+            let per_segment = (&mut segments)
+                .enumerate()
+                .map(|(seg_idx, proof_input)| Ok(consume(seg_idx, proof_input?)))
+                .collect::<Result<_, ExecutionError>>()?;
+            return Ok(VmPipelinedResult {
+                per_segment,
+                final_memory: segments.final_memory,
+            });
+        }
+        // Neither the proof input being consumed nor the one the producer is blocked on sending
+        // is in the channel.
+        let (sender, receiver) = mpsc::sync_channel(max_in_flight - 2);
+        thread::scope(|s| {
+            let producer = s.spawn(move || {
+                let mut segments = self.execute_and_generate_iter(exe, committed_program, input);
+                for proof_input in &mut segments {
+                    if sender.send(proof_input?).is_err() {
+                        // The consumer has stopped.
+                        break;
+                    }
+                }
+                Ok(segments.final_memory)
+            });
+            let per_segment = receiver
                 .into_iter()
                 .enumerate()
-                .map(|(seg_idx, seg)| {
-                    tracing::info_span!("trace_gen", segment = seg_idx)
-                        .in_scope(|| seg.generate_proof_input(committed_program.clone()))
-                })
-                .collect(),
-            final_memory,
+                .map(|(seg_idx, proof_input)| consume(seg_idx, proof_input))
+                .collect();
+            let final_memory = producer
+                .join()
+                .unwrap_or_else(|e| std::panic::resume_unwind(e))?;
+            Ok(VmPipelinedResult {
+                per_segment,
+                final_memory,
+            })
         })
     }
 }
 
+/// Records `data_segments` in `journal`, or checks them against it on replay.
+fn record_data_segments<F: PrimeField32>(
+    journal: Option<&SharedJournalRecorder<F>>,
+    pc: u32,
+    data_segments: &[DataSegment],
+) -> Result<(), ExecutionError> {
+    match journal {
+        Some(journal) => journal.lock().unwrap().data_segments(pc, data_segments),
+        None => Ok(()),
+    }
+}
+
+/// Result of [VmExecutor::execute_and_generate_pipelined].
+pub struct VmPipelinedResult<R, F> {
+    /// The output of the consumer for each segment.
+    pub per_segment: Vec<R>,
+    /// When VM is running on persistent mode, public values are stored in a special memory space.
+    pub final_memory: Option<VmMemoryState<F>>,
+}
+
+/// Iterator over the proof inputs of the segments of an execution, created by
+/// [VmExecutor::execute_and_generate_iter]. Each call to `next` executes one segment and generates
+/// its proof input. Iteration stops after the first error.
+pub struct VmSegmentProofInputs<'a, SC: StarkGenericConfig, VC> {
+    executor: &'a VmExecutor<Val<SC>, VC>,
+    exe: VmExe<Val<SC>>,
+    committed_program: Option<CommittedTraceData<SC>>,
+    /// The state to execute the next segment from, or the error building the initial state.
+    next_state: Option<Result<VmExecutorNextSegmentState<Val<SC>>, ExecutionError>>,
+    segment_idx: usize,
+    /// Set once the last segment has been executed.
+    pub final_memory: Option<VmMemoryState<Val<SC>>>,
+}
+
+impl<F, SC, VC> Iterator for VmSegmentProofInputs<'_, SC, VC>
+where
+    F: PrimeField32,
+    SC: StarkGenericConfig,
+    Domain<SC>: PolynomialSpace<Val = F>,
+    VC: VmConfig<F>,
+    VC::Executor: Chip<SC>,
+    VC::Periphery: Chip<SC>,
+{
+    type Item = Result<ProofInput<SC>, ExecutionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let state = match self.next_state.take()? {
+            Ok(state) => state,
+            Err(e) => return Some(Err(e)),
+        };
+        let segment_idx = self.segment_idx;
+        self.segment_idx += 1;
+
+        let result = info_span!("execute_segment", segment = segment_idx)
+            .in_scope(|| self.executor.execute_until_segment(self.exe.clone(), state));
+        let VmExecutorOneSegmentResult {
+            mut segment,
+            next_state,
+        } = match result {
+            Ok(result) => result,
+            Err(e) => return Some(Err(e)),
+        };
+        if next_state.is_none() {
+            self.final_memory = mem::take(&mut segment.final_memory);
+        }
+        self.next_state = next_state.map(Ok);
+
+        let proof_input = info_span!("trace_gen", segment = segment_idx)
+            .in_scope(|| segment.generate_proof_input(self.committed_program.clone()));
+        Some(Ok(proof_input))
+    }
+}
+
 /// A single segment VM.
 pub struct SingleSegmentVmExecutor<F, VC> {
     pub config: VC,
@@ -404,7 +853,7 @@ where
             input.into(),
             None,
             exe.fn_bounds,
-        );
+        )?;
         if let Some(overridden_heights) = self.overridden_heights.as_ref() {
             segment.set_override_trace_heights(overridden_heights.clone());
         }
@@ -421,6 +870,9 @@ pub enum VmVerificationError {
     #[error("initial memory root mismatch")]
     InitialMemoryRootMismatch,
 
+    #[error("initial input commitment mismatch")]
+    InitialInputCommitMismatch,
+
     #[error("is terminate mismatch (expected: {expected}, actual: {actual})")]
     IsTerminateMismatch { expected: bool, actual: bool },
 
@@ -531,22 +983,123 @@ where
         self.engine.prove(pk, proof_input)
     }
 
+    /// Proves segments in order as their proof inputs are produced, e.g. by
+    /// [VmExecutor::execute_and_generate_iter] or from the receiving end of a channel.
     pub fn prove(
         &self,
         pk: &MultiStarkProvingKey<SC>,
-        results: VmExecutorResult<SC>,
+        proof_inputs: impl IntoIterator<Item = ProofInput<SC>>,
     ) -> Vec<Proof<SC>> {
-        #[cfg(feature = "bench-metrics")]
-        metrics::counter!("num_segments").absolute(results.per_segment.len() as u64);
-        results
-            .per_segment
+        let proofs: Vec<_> = proof_inputs
             .into_iter()
             .enumerate()
-            .map(|(seg_idx, proof_input)| {
-                tracing::info_span!("prove_segment", segment = seg_idx)
-                    .in_scope(|| self.engine.prove(pk, proof_input))
-            })
-            .collect()
+            .map(|(seg_idx, proof_input)| self.prove_segment(pk, seg_idx, proof_input))
+            .collect();
+        #[cfg(feature = "bench-metrics")]
+        metrics::counter!("num_segments").absolute(proofs.len() as u64);
+        proofs
+    }
+
+    /// Executes and proves the program with execution and trace generation of the next segments
+    /// pipelined with proving of the current one. See [VmExecutor::execute_and_generate_pipelined]
+    /// for the meaning of `max_in_flight`.
+    pub fn execute_and_prove_pipelined(
+        &self,
+        pk: &MultiStarkProvingKey<SC>,
+        committed_exe: Arc<VmCommittedExe<SC>>,
+        input: impl Into<Streams<F>>,
+        max_in_flight: usize,
+    ) -> Result<VmPipelinedResult<Proof<SC>, F>, ExecutionError>
+    where
+        VmExecutor<F, VC>: Sync,
+        ProofInput<SC>: Send,
+        CommittedTraceData<SC>: Send,
+    {
+        let result = self.executor.execute_and_generate_pipelined(
+            committed_exe.exe.clone(),
+            Some(committed_exe.committed_program.clone()),
+            input,
+            max_in_flight,
+            |seg_idx, proof_input| self.prove_segment(pk, seg_idx, proof_input),
+        )?;
+        #[cfg(feature = "bench-metrics")]
+        metrics::counter!("num_segments").absolute(result.per_segment.len() as u64);
+        Ok(result)
+    }
+
+    /// Proves segments on up to [ParallelProvingConfig::num_concurrent_segments] worker threads
+    /// at once. `on_proof` is called with each segment proof as soon as it is generated, in
+    /// completion order. See [SegmentScheduler::prove_all].
+    pub fn prove_parallel(
+        &self,
+        pk: &MultiStarkProvingKey<SC>,
+        proof_inputs: impl IntoIterator<Item = ProofInput<SC>>,
+        scheduler: &SegmentScheduler,
+        mut on_proof: impl FnMut(usize, Proof<SC>),
+    ) where
+        Self: Sync,
+        MultiStarkProvingKey<SC>: Sync,
+        ProofInput<SC>: Send,
+        Proof<SC>: Send,
+    {
+        #[cfg(feature = "bench-metrics")]
+        let mut num_segments = 0;
+        scheduler.prove_all(
+            proof_inputs,
+            |seg_idx, proof_input| self.prove_segment(pk, seg_idx, proof_input),
+            |seg_idx, proof| {
+                #[cfg(feature = "bench-metrics")]
+                {
+                    num_segments += 1;
+                }
+                on_proof(seg_idx, proof)
+            },
+        );
+        #[cfg(feature = "bench-metrics")]
+        metrics::counter!("num_segments").absolute(num_segments);
+    }
+
+    /// Executes and proves the program, proving segments concurrently as they are generated.
+    /// See [Self::prove_parallel]. Returns the final memory.
+    pub fn execute_and_prove_parallel(
+        &self,
+        pk: &MultiStarkProvingKey<SC>,
+        committed_exe: Arc<VmCommittedExe<SC>>,
+        input: impl Into<Streams<F>>,
+        scheduler: &SegmentScheduler,
+        on_proof: impl FnMut(usize, Proof<SC>),
+    ) -> Result<Option<VmMemoryState<F>>, ExecutionError>
+    where
+        Self: Sync,
+        MultiStarkProvingKey<SC>: Sync,
+        ProofInput<SC>: Send,
+        Proof<SC>: Send,
+    {
+        let mut segments = self.executor.execute_and_generate_iter(
+            committed_exe.exe.clone(),
+            Some(committed_exe.committed_program.clone()),
+            input,
+        );
+        let mut error = None;
+        let proof_inputs = segments
+            .by_ref()
+            .map_while(|proof_input| proof_input.map_err(|e| error = Some(e)).ok());
+        self.prove_parallel(pk, proof_inputs, scheduler, on_proof);
+        if let Some(e) = error {
+            return Err(e);
+        }
+        Ok(segments.final_memory)
+    }
+
+    /// Proves a single segment.
+    pub fn prove_segment(
+        &self,
+        pk: &MultiStarkProvingKey<SC>,
+        seg_idx: usize,
+        proof_input: ProofInput<SC>,
+    ) -> Proof<SC> {
+        tracing::info_span!("prove_segment", segment = seg_idx)
+            .in_scope(|| self.engine.prove(pk, proof_input))
     }
 
     pub fn verify_single(
@@ -586,6 +1139,8 @@ where
     {
         let mut prev_final_memory_root = None;
         let mut prev_final_pc = None;
+        // The input commitment starts from zero.
+        let mut prev_final_input_commit = [Val::<SC>::ZERO; CHUNK];
 
         for (i, proof) in proofs.iter().enumerate() {
             let res = self.engine.verify(vk, proof);
@@ -615,6 +1170,11 @@ where
                     }
                     prev_final_pc = Some(pvs.final_pc);
 
+                    if pvs.initial_input_commit != prev_final_input_commit {
+                        return Err(VmVerificationError::InitialInputCommitMismatch);
+                    }
+                    prev_final_input_commit = pvs.final_input_commit;
+
                     let expected_is_terminate = i == proofs.len() - 1;
                     if pvs.is_terminate != Val::<SC>::from_bool(expected_is_terminate) {
                         return Err(VmVerificationError::IsTerminateMismatch {
@@ -20,7 +20,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     arch::{instructions::SystemOpcode::TERMINATE, ExecutionBus, ExecutionState},
-    system::program::ProgramBus,
+    system::{input_commitment::InputCommitBus, memory::CHUNK, program::ProgramBus},
 };
 
 #[cfg(test)]
@@ -34,6 +34,8 @@ pub const DEFAULT_SUSPEND_EXIT_CODE: u32 = 42;
 pub struct VmConnectorAir {
     pub execution_bus: ExecutionBus,
     pub program_bus: ProgramBus,
+    /// Bus of the input commitment, if the system commits to the input stream.
+    pub input_commit_bus: Option<InputCommitBus>,
 }
 
 #[derive(Debug, Clone, Copy, AlignedBorrow)]
@@ -49,6 +51,12 @@ pub struct VmConnectorPvs<F> {
     /// Whether the whole program is terminated. 0 means not terminated. 1 means terminated.
     /// Only the last segment of an execution can have `is_terminate` = 1.
     pub is_terminate: F,
+    /// The input commitment at the start of this segment. Always zero if the system does not
+    /// commit to the input stream.
+    pub initial_input_commit: [F; CHUNK],
+    /// The input commitment at the end of this segment. Always zero if the system does not
+    /// commit to the input stream.
+    pub final_input_commit: [F; CHUNK],
 }
 
 impl<F: Field> BaseAirWithPublicValues<F> for VmConnectorAir {
@@ -59,7 +67,7 @@ impl<F: Field> BaseAirWithPublicValues<F> for VmConnectorAir {
 impl<F: Field> PartitionedBaseAir<F> for VmConnectorAir {}
 impl<F: Field> BaseAir<F> for VmConnectorAir {
     fn width(&self) -> usize {
-        4
+        ConnectorCols::<F>::width()
     }
 
     fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
@@ -74,6 +82,7 @@ pub struct ConnectorCols<T> {
     pub timestamp: T,
     pub is_terminate: T,
     pub exit_code: T,
+    pub input_commit: [T; CHUNK],
 }
 
 impl<T: Copy> ConnectorCols<T> {
@@ -83,11 +92,15 @@ impl<T: Copy> ConnectorCols<T> {
             timestamp: f(self.timestamp),
             is_terminate: f(self.is_terminate),
             exit_code: f(self.exit_code),
+            input_commit: self.input_commit.map(f),
         }
     }
 
-    fn flatten(&self) -> [T; 4] {
+    fn flatten(&self) -> Vec<T> {
         [self.pc, self.timestamp, self.is_terminate, self.exit_code]
+            .into_iter()
+            .chain(self.input_commit)
+            .collect()
     }
 }
 
@@ -106,6 +119,8 @@ impl<AB: InteractionBuilder + PairBuilder + AirBuilderWithPublicValues> Air<AB>
             final_pc,
             exit_code,
             is_terminate,
+            initial_input_commit,
+            final_input_commit,
         } = builder.public_values().borrow();
 
         builder.when_transition().assert_eq(begin.pc, initial_pc);
@@ -117,6 +132,14 @@ impl<AB: InteractionBuilder + PairBuilder + AirBuilderWithPublicValues> Air<AB>
         builder
             .when_transition()
             .assert_eq(end.is_terminate, is_terminate);
+        for i in 0..CHUNK {
+            builder
+                .when_transition()
+                .assert_eq(begin.input_commit[i], initial_input_commit[i]);
+            builder
+                .when_transition()
+                .assert_eq(end.input_commit[i], final_input_commit[i]);
+        }
 
         self.execution_bus.execute(
             builder,
@@ -131,6 +154,20 @@ impl<AB: InteractionBuilder + PairBuilder + AirBuilderWithPublicValues> Air<AB>
             [AB::Expr::ZERO, AB::Expr::ZERO, end.exit_code.into()],
             (AB::Expr::ONE - prep_local[0]) * end.is_terminate,
         );
+        match self.input_commit_bus {
+            // The chip hashing the input stream receives the initial and sends the final
+            // commitment, unless it has no rows, in which case they must be equal.
+            Some(bus) => {
+                bus.send(builder, begin.input_commit, AB::Expr::ONE - prep_local[0]);
+                bus.receive(builder, end.input_commit, AB::Expr::ONE - prep_local[0]);
+            }
+            None => {
+                for i in 0..CHUNK {
+                    builder.assert_zero(initial_input_commit[i]);
+                    builder.assert_zero(final_input_commit[i]);
+                }
+            }
+        }
     }
 }
 
@@ -142,32 +179,44 @@ pub struct VmConnectorChip<F> {
 }
 
 impl<F: PrimeField32> VmConnectorChip<F> {
-    pub fn new(execution_bus: ExecutionBus, program_bus: ProgramBus) -> Self {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        input_commit_bus: Option<InputCommitBus>,
+    ) -> Self {
         Self {
             air: VmConnectorAir {
                 execution_bus,
                 program_bus,
+                input_commit_bus,
             },
             boundary_states: [None, None],
             _marker: PhantomData,
         }
     }
 
-    pub fn begin(&mut self, state: ExecutionState<u32>) {
+    pub fn begin(&mut self, state: ExecutionState<u32>, input_commit: [F; CHUNK]) {
         self.boundary_states[0] = Some(ConnectorCols {
             pc: state.pc,
             timestamp: state.timestamp,
             is_terminate: 0,
             exit_code: 0,
+            input_commit: input_commit.map(|x| x.as_canonical_u32()),
         });
     }
 
-    pub fn end(&mut self, state: ExecutionState<u32>, exit_code: Option<u32>) {
+    pub fn end(
+        &mut self,
+        state: ExecutionState<u32>,
+        exit_code: Option<u32>,
+        input_commit: [F; CHUNK],
+    ) {
         self.boundary_states[1] = Some(ConnectorCols {
             pc: state.pc,
             timestamp: state.timestamp,
             is_terminate: exit_code.is_some() as u32,
             exit_code: exit_code.unwrap_or(DEFAULT_SUSPEND_EXIT_CODE),
+            input_commit: input_commit.map(|x| x.as_canonical_u32()),
         });
     }
 }
@@ -197,6 +246,8 @@ where
             final_pc: final_state.pc,
             exit_code: final_state.exit_code,
             is_terminate: final_state.is_terminate,
+            initial_input_commit: initial_state.input_commit,
+            final_input_commit: final_state.input_commit,
         };
         AirProofInput::simple(trace, public_values)
     }
@@ -216,6 +267,6 @@ impl<F: PrimeField32> ChipUsageGetter for VmConnectorChip<F> {
     }
 
     fn trace_width(&self) -> usize {
-        4
+        ConnectorCols::<F>::width()
     }
 }
@@ -0,0 +1,309 @@
+//! Commitment to the words that hint instructions write to memory, which commits to the input
+//! stream of guests that only hint their inputs.
+//!
+//! When [SystemConfig::commit_input_stream](crate::arch::SystemConfig::commit_input_stream) is
+//! set, every word that a hint instruction writes to memory is absorbed into a running digest:
+//!
+//! ```text
+//! digest_0     = [0; CHUNK]
+//! digest_{i+1} = compress(digest_i, word_i ++ [0; CHUNK - len(word_i)])
+//! ```
+//!
+//! where `compress` is the Poseidon2 compression of the system periphery chip. The chip executing
+//! the hint instructions chains the digest through its trace with [InputCommitSubAir], which sends
+//! every compression to the periphery chip. The digests before and after a segment are exposed in
+//! the public values of the connector AIR and balanced against the hint trace on the
+//! [InputCommitBus]. The rows of instructions precede the padding rows and their timestamps
+//! strictly increase, so the trace order of the words is their execution order.
+//!
+//! Each word is absorbed by its own compression, even though two words would fit. Pairing words
+//! of different rows would make the digest depend on state carried between instructions and
+//! across segment boundaries, including a final compression of an unpaired word at termination,
+//! while the compression per row keeps the commitment a function of the hinted words alone.
+//!
+//! Hints are unconstrained, so the AIR can't tell whether a word came from the input stream or
+//! from another hint, e.g. one computed by a phantom sub-executor. The digest is therefore a
+//! commitment to the hinted words: it proves that the guest wrote exactly the committed words to
+//! memory through hint instructions, in order. It is the commitment to the inputs if the guest
+//! hints nothing but its inputs and reads each of them completely, which is a property of the
+//! program, and so of the executable commitment. Execution enforces the former: with the
+//! commitment enabled, a phantom instruction that hints words without reading the input stream
+//! fails with [ExecutionError::NonInputHint](crate::arch::ExecutionError::NonInputHint).
+
+use std::{
+    borrow::{Borrow, BorrowMut},
+    iter, mem,
+};
+
+use openvm_circuit_primitives::{
+    assert_less_than::{AssertLessThanIo, AssertLtSubAir, LessThanAuxCols},
+    var_range::VariableRangeCheckerChip,
+    SubAir, TraceSubRowGenerator,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::AirBuilder,
+    p3_field::{Field, FieldAlgebra, PrimeField32},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    arch::hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+    system::memory::{merkle::DirectCompressionBus, offline_checker::AUX_LEN, CHUNK},
+};
+
+/// Bus carrying the input commitment across the boundaries of a segment.
+#[derive(Debug, Clone, Copy)]
+pub struct InputCommitBus(pub usize);
+
+impl InputCommitBus {
+    pub fn send<AB: InteractionBuilder, E: Into<AB::Expr>>(
+        &self,
+        builder: &mut AB,
+        digest: impl IntoIterator<Item = E>,
+        count: impl Into<AB::Expr>,
+    ) {
+        builder.push_send(self.0, digest, count);
+    }
+
+    pub fn receive<AB: InteractionBuilder, E: Into<AB::Expr>>(
+        &self,
+        builder: &mut AB,
+        digest: impl IntoIterator<Item = E>,
+        count: impl Into<AB::Expr>,
+    ) {
+        builder.push_receive(self.0, digest, count);
+    }
+}
+
+/// Columns owned by [InputCommitSubAir], one row per word written by a hint instruction.
+#[repr(C)]
+#[derive(AlignedBorrow, Clone, Copy, Debug)]
+pub struct InputCommitCols<T> {
+    /// 1 on the first row of the trace.
+    pub is_first: T,
+    /// 1 on the last row of the trace.
+    pub is_last: T,
+    pub prev_digest: [T; CHUNK],
+    pub digest: [T; CHUNK],
+    /// Decomposition of the difference to the timestamp of the next row, if that row belongs to an
+    /// instruction.
+    pub timestamp_lt_aux: LessThanAuxCols<T, AUX_LEN>,
+}
+
+/// The IO is typically provided with `T = AB::Expr` as external context.
+pub struct InputCommitIo<T> {
+    /// The word written to memory by the row, of at most `CHUNK` elements.
+    pub data: Vec<T>,
+    /// Whether the row belongs to an instruction, in which case its word is absorbed. Must be
+    /// boolean.
+    pub is_valid: T,
+    /// Whether the next row belongs to an instruction. Must be boolean.
+    pub next_is_valid: T,
+    /// Timestamp of the row, which must be distinct for the rows of all instructions.
+    pub timestamp: T,
+    /// Timestamp of the next row.
+    pub next_timestamp: T,
+}
+
+/// This is intended for use as a **SubAir**, not as a standalone Air.
+///
+/// Chains the input commitment through all rows of the trace: the digest of a row is the
+/// compression of the previous digest with the row's word if the row belongs to an instruction,
+/// and is unchanged otherwise. The digest before the first row is received and the digest after
+/// the last row is sent on the [InputCommitBus].
+///
+/// The rows of instructions must precede all other rows, with strictly increasing timestamps, so
+/// that the words are absorbed in execution order.
+#[derive(Debug, Clone, Copy)]
+pub struct InputCommitSubAir {
+    pub bus: InputCommitBus,
+    pub compression_bus: DirectCompressionBus,
+    /// Checks that the timestamps of consecutive rows of instructions increase.
+    pub timestamp_lt: AssertLtSubAir,
+}
+
+impl<AB: InteractionBuilder> SubAir<AB> for InputCommitSubAir {
+    /// `(io, local, next)`
+    type AirContext<'a>
+        = (
+        InputCommitIo<AB::Expr>,
+        &'a InputCommitCols<AB::Var>,
+        &'a InputCommitCols<AB::Var>,
+    )
+    where
+        AB::Expr: 'a,
+        AB::Var: 'a,
+        AB: 'a;
+
+    fn eval<'a>(&'a self, builder: &'a mut AB, (io, local, next): Self::AirContext<'a>)
+    where
+        AB::Var: 'a,
+        AB::Expr: 'a,
+    {
+        assert!(io.data.len() <= CHUNK);
+
+        builder.when_first_row().assert_one(local.is_first);
+        builder.when_transition().assert_zero(next.is_first);
+        builder.when_last_row().assert_one(local.is_last);
+        builder.when_transition().assert_zero(local.is_last);
+
+        for i in 0..CHUNK {
+            builder
+                .when(AB::Expr::ONE - io.is_valid.clone())
+                .assert_eq(local.digest[i], local.prev_digest[i]);
+            builder
+                .when_transition()
+                .assert_eq(next.prev_digest[i], local.digest[i]);
+        }
+
+        let padding = CHUNK - io.data.len();
+        builder.push_send(
+            self.compression_bus.0,
+            iter::empty()
+                .chain(local.prev_digest.map(Into::into))
+                .chain(io.data)
+                .chain(iter::repeat(AB::Expr::ZERO).take(padding))
+                .chain(local.digest.map(Into::into)),
+            io.is_valid.clone(),
+        );
+
+        self.bus.receive(builder, local.prev_digest, local.is_first);
+        self.bus.send(builder, local.digest, local.is_last);
+
+        // Rows of instructions come first, so on all rows but the last, a next row belonging to an
+        // instruction implies that this row does too.
+        builder
+            .when_transition()
+            .when(AB::Expr::ONE - io.is_valid)
+            .assert_zero(io.next_is_valid.clone());
+        let has_next = io.next_is_valid * (AB::Expr::ONE - local.is_last);
+        self.timestamp_lt.eval(
+            builder,
+            (
+                AssertLessThanIo::new(io.timestamp, io.next_timestamp, has_next),
+                &local.timestamp_lt_aux.lower_decomp,
+            ),
+        );
+    }
+}
+
+impl InputCommitSubAir {
+    /// Finalizes `trace`, whose rows of `width` hold [InputCommitCols] at `offset` and the
+    /// timestamp of the row at `timestamp_col`, and whose first `num_rows` rows belong to
+    /// instructions: sets the boundary flags and the timestamp decompositions, and fills the
+    /// padding rows with the final digest.
+    pub fn finalize_trace<F: PrimeField32>(
+        &self,
+        range_checker: &VariableRangeCheckerChip,
+        trace: &mut [F],
+        width: usize,
+        offset: usize,
+        timestamp_col: usize,
+        num_rows: usize,
+    ) {
+        if trace.is_empty() {
+            return;
+        }
+        assert_ne!(num_rows, 0, "padding rows without rows to pad");
+        let cols_width = InputCommitCols::<F>::width();
+        let height = trace.len() / width;
+        let final_digest = {
+            let row = &trace[(num_rows - 1) * width + offset..][..cols_width];
+            let cols: &InputCommitCols<F> = row.borrow();
+            cols.digest
+        };
+        let timestamps: Vec<u32> = (0..num_rows)
+            .map(|i| trace[i * width + timestamp_col].as_canonical_u32())
+            .collect();
+        for (i, row) in trace.chunks_exact_mut(width).enumerate() {
+            let cols: &mut InputCommitCols<F> = row[offset..offset + cols_width].borrow_mut();
+            if i >= num_rows {
+                cols.prev_digest = final_digest;
+                cols.digest = final_digest;
+            } else if i + 1 < num_rows {
+                self.timestamp_lt.generate_subrow(
+                    (range_checker, timestamps[i], timestamps[i + 1]),
+                    &mut cols.timestamp_lt_aux.lower_decomp,
+                );
+            }
+            cols.is_first = F::from_bool(i == 0);
+            cols.is_last = F::from_bool(i == height - 1);
+        }
+    }
+}
+
+/// The running input commitment of an execution, kept in [Streams](crate::arch::Streams) so that
+/// it carries over to the next segment.
+#[derive(Clone, Debug, Default)]
+pub struct InputCommitment<F> {
+    pub digest: [F; CHUNK],
+    /// Compressions absorbed since the last [Self::take_pending], to be recorded in the Poseidon2
+    /// periphery chip.
+    pending: Vec<([F; CHUNK], [F; CHUNK])>,
+}
+
+impl<F: Field> InputCommitment<F> {
+    /// Absorbs `word` into the digest and returns the new digest. The compression is kept for
+    /// the periphery chip if `record` is set.
+    pub fn absorb(
+        &mut self,
+        hasher: &impl Hasher<CHUNK, F>,
+        word: &[F],
+        record: bool,
+    ) -> [F; CHUNK] {
+        let mut rhs = [F::ZERO; CHUNK];
+        rhs[..word.len()].copy_from_slice(word);
+        if record {
+            self.pending.push((self.digest, rhs));
+        }
+        self.digest = hasher.compress(&self.digest, &rhs);
+        self.digest
+    }
+
+    pub fn take_pending(&mut self) -> Vec<([F; CHUNK], [F; CHUNK])> {
+        mem::take(&mut self.pending)
+    }
+}
+
+/// Computes the input commitment after absorbing `words` in order.
+pub fn compute_input_commitment<F: PrimeField32, W: AsRef<[F]>>(
+    words: impl IntoIterator<Item = W>,
+) -> [F; CHUNK] {
+    let hasher = vm_poseidon2_hasher();
+    let mut commitment = InputCommitment::default();
+    for word in words {
+        commitment.absorb(&hasher, word.as_ref(), false);
+    }
+    commitment.digest
+}
+
+/// The input commitment across a hint instruction.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "F: Field")]
+pub struct InputCommitRecord<F> {
+    /// Digest before the instruction.
+    pub prev_digest: [F; CHUNK],
+    /// Digest after each word of the instruction.
+    pub digests: Vec<[F; CHUNK]>,
+}
+
+impl<F: Field> InputCommitRecord<F> {
+    pub fn new(prev_digest: [F; CHUNK]) -> Self {
+        Self {
+            prev_digest,
+            digests: Vec::new(),
+        }
+    }
+
+    /// Fills the columns of the row of the `index`-th word of the instruction. The boundary
+    /// flags and timestamp decompositions are set by [InputCommitSubAir::finalize_trace].
+    pub fn fill_row(&self, index: usize, cols: &mut InputCommitCols<F>) {
+        cols.prev_digest = match index {
+            0 => self.prev_digest,
+            _ => self.digests[index - 1],
+        };
+        cols.digest = self.digests[index];
+    }
+}
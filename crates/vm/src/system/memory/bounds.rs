@@ -0,0 +1,231 @@
+use openvm_circuit_primitives::{
+    is_zero::{IsZeroIo, IsZeroSubAir},
+    var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip},
+    SubAir, TraceSubRowGenerator,
+};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_field::{Field, FieldAlgebra, PrimeField32},
+    p3_util::log2_strict_usize,
+};
+
+use crate::arch::{AddressSpaceCellType, MemoryConfig};
+
+/// Number of bits of a byte cell.
+const BYTE_BITS: usize = 8;
+
+/// Bounds of a registered address space, in units of blocks of the boundary chip.
+#[derive(Clone, Debug)]
+struct RegisteredAddressSpace {
+    addr_space: u32,
+    label_bits: usize,
+    is_byte: bool,
+}
+
+/// Constrains the cells of registered address spaces that a boundary chip touches: the block
+/// label must be below the size of its address space, and the values of byte address spaces
+/// must be bytes. Labels of built-in address spaces are bounded by `pointer_max_bits`.
+///
+/// Adds no columns when there are no registered address spaces. Otherwise the columns are, in
+/// order: one indicator per registered address space, the inverses for the [IsZeroSubAir]s
+/// computing them, the low limb of the label, and the multiplicity of the byte range checks.
+/// The label is split into a low limb of at most `range_max_bits` bits and a high limb, which
+/// are range checked with the bits of the indicated address space.
+#[derive(Clone, Debug)]
+pub struct AddressSpaceBoundsSubAir {
+    pub range_bus: VariableRangeCheckerBus,
+    registered: Vec<RegisteredAddressSpace>,
+    builtin_label_bits: usize,
+}
+
+pub struct AddressSpaceBoundsIo<T> {
+    pub addr_space: T,
+    /// Index of the block in its address space.
+    pub label: T,
+    pub values: Vec<T>,
+    /// Multiplicity of the range checks. Must be boolean.
+    pub count: T,
+}
+
+impl AddressSpaceBoundsSubAir {
+    /// Bounds for a boundary chip whose blocks have `block_size` cells.
+    pub fn new(
+        range_bus: VariableRangeCheckerBus,
+        mem_config: &MemoryConfig,
+        block_size: usize,
+    ) -> Self {
+        let block_bits = log2_strict_usize(block_size);
+        // Only checked with registered address spaces, since the labels are not range checked
+        // otherwise.
+        let label_bits = |pointer_bits: usize| {
+            let label_bits = pointer_bits.saturating_sub(block_bits);
+            assert!(
+                mem_config.address_spaces.is_empty() || label_bits <= 2 * range_bus.range_max_bits,
+                "address space is too large to range check its pointers"
+            );
+            label_bits
+        };
+        let first = mem_config.first_registered_address_space();
+        let registered = (first..)
+            .zip(&mem_config.address_spaces)
+            .map(|(addr_space, config)| RegisteredAddressSpace {
+                addr_space,
+                label_bits: label_bits(log2_strict_usize(config.num_cells)),
+                is_byte: config.cell_type == AddressSpaceCellType::Byte,
+            })
+            .collect();
+        Self {
+            range_bus,
+            registered,
+            builtin_label_bits: label_bits(mem_config.pointer_max_bits),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        if self.registered.is_empty() {
+            0
+        } else {
+            2 * self.registered.len() + 2
+        }
+    }
+
+    fn split_bits(&self, label_bits: usize) -> (usize, usize) {
+        let lo_bits = label_bits.min(self.range_bus.range_max_bits);
+        (lo_bits, label_bits - lo_bits)
+    }
+
+    fn label_bits(&self, addr_space: u32) -> usize {
+        self.registered
+            .iter()
+            .find(|registered| registered.addr_space == addr_space)
+            .map_or(self.builtin_label_bits, |registered| registered.label_bits)
+    }
+
+    fn is_byte(&self, addr_space: u32) -> bool {
+        self.registered
+            .iter()
+            .any(|registered| registered.addr_space == addr_space && registered.is_byte)
+    }
+}
+
+impl<AB: InteractionBuilder> SubAir<AB> for AddressSpaceBoundsSubAir {
+    type AirContext<'a>
+        = (AddressSpaceBoundsIo<AB::Expr>, &'a [AB::Var])
+    where
+        AB::Expr: 'a,
+        AB::Var: 'a,
+        AB: 'a;
+
+    fn eval<'a>(
+        &'a self,
+        builder: &'a mut AB,
+        (io, cols): (AddressSpaceBoundsIo<AB::Expr>, &'a [AB::Var]),
+    ) where
+        AB::Var: 'a,
+        AB::Expr: 'a,
+    {
+        if self.registered.is_empty() {
+            return;
+        }
+        let n = self.registered.len();
+        let (indicators, rest) = cols.split_at(n);
+        let (invs, rest) = rest.split_at(n);
+        let [label_lo, byte_count] = [rest[0], rest[1]];
+
+        for ((registered, &indicator), &inv) in self.registered.iter().zip(indicators).zip(invs) {
+            IsZeroSubAir.eval(
+                builder,
+                (
+                    IsZeroIo::new(
+                        io.addr_space.clone() - AB::F::from_canonical_u32(registered.addr_space),
+                        indicator.into(),
+                        AB::Expr::ONE,
+                    ),
+                    inv,
+                ),
+            );
+        }
+
+        // The value of `bits(label_bits)` for the address space of the row.
+        let select_bits = |bits: &dyn Fn(usize) -> usize| {
+            let builtin = AB::F::from_canonical_usize(bits(self.builtin_label_bits));
+            self.registered.iter().zip(indicators).fold(
+                AB::Expr::from(builtin),
+                |acc, (registered, &indicator)| {
+                    acc + indicator
+                        * (AB::F::from_canonical_usize(bits(registered.label_bits)) - builtin)
+                },
+            )
+        };
+        let lo_bits = select_bits(&|label_bits| self.split_bits(label_bits).0);
+        let hi_bits = select_bits(&|label_bits| self.split_bits(label_bits).1);
+        let label_hi = (io.label - label_lo)
+            * AB::F::from_canonical_usize(1 << self.range_bus.range_max_bits).inverse();
+        self.range_bus
+            .send(label_lo, lo_bits)
+            .eval(builder, io.count.clone());
+        self.range_bus
+            .send(label_hi, hi_bits)
+            .eval(builder, io.count.clone());
+
+        let is_byte = self
+            .registered
+            .iter()
+            .zip(indicators)
+            .filter(|(registered, _)| registered.is_byte)
+            .fold(AB::Expr::ZERO, |acc, (_, &indicator)| acc + indicator);
+        builder.assert_eq(byte_count, io.count * is_byte);
+        for value in io.values {
+            self.range_bus
+                .range_check(value, BYTE_BITS)
+                .eval(builder, byte_count);
+        }
+    }
+}
+
+impl<F: PrimeField32> TraceSubRowGenerator<F> for AddressSpaceBoundsSubAir {
+    /// `(range_checker, addr_space, label, values, is_valid)`
+    type TraceContext<'a> = (&'a VariableRangeCheckerChip, u32, u32, &'a [F], bool);
+    type ColsMut<'a> = &'a mut [F];
+
+    /// Must be called for padding rows as well, with `is_valid = false`.
+    fn generate_subrow<'a>(
+        &'a self,
+        (range_checker, addr_space, label, values, is_valid): (
+            &'a VariableRangeCheckerChip,
+            u32,
+            u32,
+            &'a [F],
+            bool,
+        ),
+        cols: &'a mut [F],
+    ) {
+        if self.registered.is_empty() {
+            return;
+        }
+        let n = self.registered.len();
+        let (indicators, rest) = cols.split_at_mut(n);
+        let (invs, rest) = rest.split_at_mut(n);
+        for ((registered, indicator), inv) in self.registered.iter().zip(indicators).zip(invs) {
+            IsZeroSubAir.generate_subrow(
+                F::from_canonical_u32(addr_space) - F::from_canonical_u32(registered.addr_space),
+                (inv, indicator),
+            );
+        }
+
+        let (lo_bits, hi_bits) = self.split_bits(self.label_bits(addr_space));
+        let label_lo = label & ((1 << self.range_bus.range_max_bits) - 1);
+        rest[0] = F::from_canonical_u32(label_lo);
+        let is_byte = is_valid && self.is_byte(addr_space);
+        rest[1] = F::from_bool(is_byte);
+        if is_valid {
+            range_checker.add_count(label_lo, lo_bits);
+            range_checker.add_count(label >> self.range_bus.range_max_bits, hi_bits);
+        }
+        if is_byte {
+            for value in values {
+                range_checker.add_count(value.as_canonical_u32(), BYTE_BITS);
+            }
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use derive_new::new;
-use openvm_stark_backend::p3_util::log2_strict_usize;
+use openvm_stark_backend::p3_util::{log2_ceil_usize, log2_strict_usize};
 
 use crate::{arch::MemoryConfig, system::memory::CHUNK};
 
@@ -27,10 +27,13 @@ impl MemoryDimensions {
 }
 
 impl MemoryConfig {
+    /// The dimensions of the memory merkle tree. It has a subtree for every address space, each
+    /// high enough for the largest address space. The boundary chips constrain pointers to the
+    /// size of their own address space, so the leaves past it stay zero.
     pub fn memory_dimensions(&self) -> MemoryDimensions {
         MemoryDimensions {
-            as_height: self.as_height,
-            address_height: self.pointer_max_bits - log2_strict_usize(CHUNK),
+            as_height: log2_ceil_usize(self.num_address_spaces()),
+            address_height: self.max_pointer_bits() - log2_strict_usize(CHUNK),
             as_offset: self.as_offset,
         }
     }
@@ -20,7 +20,6 @@ use openvm_stark_backend::{
     p3_commit::PolynomialSpace,
     p3_field::PrimeField32,
     p3_maybe_rayon::prelude::{IntoParallelIterator, ParallelIterator},
-    p3_util::log2_strict_usize,
     prover::types::AirProofInput,
     AirRef, Chip, ChipUsageGetter,
 };
@@ -33,10 +32,10 @@ use super::{
     volatile::VolatileBoundaryChip,
 };
 use crate::{
-    arch::{hasher::HasherChip, MemoryConfig},
+    arch::{hasher::HasherChip, AddressSpaceCellType, AddressSpaceInit, MemoryConfig},
     system::memory::{
         adapter::AccessAdapterInventory,
-        dimensions::MemoryDimensions,
+        bounds::AddressSpaceBoundsSubAir,
         merkle::{MemoryMerkleBus, MemoryMerkleChip},
         offline::{MemoryRecord, OfflineMemory, INITIAL_TIMESTAMP},
         offline_checker::{
@@ -63,6 +62,44 @@ pub struct RecordId(pub usize);
 
 pub type MemoryImage<F> = AddressMap<F, PAGE_SIZE>;
 
+/// Creates the initial memory image of a program from its initial memory, laid out according to
+/// `mem_config`.
+///
+/// Panics if the initial memory is out of bounds, touches a registered address space that must
+/// start zeroed, or holds a non-byte value in a byte address space.
+pub fn initial_memory_image<F: PrimeField32>(
+    mem_config: &MemoryConfig,
+    init_memory: &BTreeMap<(u32, u32), F>,
+) -> MemoryImage<F> {
+    let mut image = AddressMap::from_mem_config(mem_config);
+    for (&(addr_space, pointer), &value) in init_memory {
+        assert!(
+            addr_space >= mem_config.as_offset
+                && ((addr_space - mem_config.as_offset) as usize) < mem_config.num_address_spaces(),
+            "initial memory in unknown address space {addr_space}",
+        );
+        assert!(
+            (pointer as usize) < mem_config.address_space_size(addr_space),
+            "initial memory out of bounds: ({addr_space}, {pointer})",
+        );
+        if let Some(config) = mem_config.address_space_config(addr_space) {
+            assert_eq!(
+                config.init,
+                AddressSpaceInit::FromExe,
+                "address space {} cannot be initialized by the program",
+                config.name,
+            );
+            assert!(
+                config.cell_type != AddressSpaceCellType::Byte || value.as_canonical_u32() < 256,
+                "non-byte initial value in byte address space {}",
+                config.name,
+            );
+        }
+        image.insert(&(addr_space, pointer), value);
+    }
+    image
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct TimestampedValues<T, const N: usize> {
     pub timestamp: u32,
@@ -232,12 +269,10 @@ impl<F: PrimeField32> MemoryController<F> {
         let initial_memory = AddressMap::from_mem_config(&mem_config);
         Self {
             memory_bus,
-            mem_config,
             interface_chip: MemoryInterface::Volatile {
-                boundary_chip: VolatileBoundaryChip::new(
+                boundary_chip: Self::volatile_boundary_chip(
                     memory_bus,
-                    mem_config.as_height,
-                    mem_config.pointer_max_bits,
+                    &mem_config,
                     range_checker.clone(),
                 ),
             },
@@ -247,7 +282,7 @@ impl<F: PrimeField32> MemoryController<F> {
                 1,
                 memory_bus,
                 range_checker.clone(),
-                mem_config,
+                &mem_config,
             ))),
             access_adapters: AccessAdapterInventory::new(
                 range_checker.clone(),
@@ -255,6 +290,7 @@ impl<F: PrimeField32> MemoryController<F> {
                 mem_config.clk_max_bits,
                 mem_config.max_access_adapter_n,
             ),
+            mem_config,
             range_checker,
             range_checker_bus,
             final_state: None,
@@ -271,16 +307,13 @@ impl<F: PrimeField32> MemoryController<F> {
         merkle_bus: MemoryMerkleBus,
         compression_bus: DirectCompressionBus,
     ) -> Self {
-        let memory_dims = MemoryDimensions {
-            as_height: mem_config.as_height,
-            address_height: mem_config.pointer_max_bits - log2_strict_usize(CHUNK),
-            as_offset: 1,
-        };
+        let memory_dims = mem_config.memory_dimensions();
         let range_checker_bus = range_checker.bus();
         let interface_chip = MemoryInterface::Persistent {
-            boundary_chip: PersistentBoundaryChip::new(
-                memory_dims,
+            boundary_chip: Self::persistent_boundary_chip(
                 memory_bus,
+                &mem_config,
+                range_checker.clone(),
                 merkle_bus,
                 compression_bus,
             ),
@@ -289,7 +322,6 @@ impl<F: PrimeField32> MemoryController<F> {
         };
         Self {
             memory_bus,
-            mem_config,
             interface_chip,
             memory: Memory::new(&mem_config), // it is expected that the memory will be set later
             offline_memory: Arc::new(Mutex::new(OfflineMemory::new(
@@ -297,7 +329,7 @@ impl<F: PrimeField32> MemoryController<F> {
                 CHUNK,
                 memory_bus,
                 range_checker.clone(),
-                mem_config,
+                &mem_config,
             ))),
             access_adapters: AccessAdapterInventory::new(
                 range_checker.clone(),
@@ -305,12 +337,95 @@ impl<F: PrimeField32> MemoryController<F> {
                 mem_config.clk_max_bits,
                 mem_config.max_access_adapter_n,
             ),
+            mem_config,
             range_checker,
             range_checker_bus,
             final_state: None,
         }
     }
 
+    fn volatile_boundary_chip(
+        memory_bus: MemoryBus,
+        mem_config: &MemoryConfig,
+        range_checker: SharedVariableRangeCheckerChip,
+    ) -> VolatileBoundaryChip<F> {
+        VolatileBoundaryChip::new(
+            memory_bus,
+            mem_config.memory_dimensions().as_height,
+            mem_config.max_pointer_bits(),
+            AddressSpaceBoundsSubAir::new(range_checker.bus(), mem_config, 1),
+            range_checker,
+        )
+    }
+
+    fn persistent_boundary_chip(
+        memory_bus: MemoryBus,
+        mem_config: &MemoryConfig,
+        range_checker: SharedVariableRangeCheckerChip,
+        merkle_bus: MemoryMerkleBus,
+        compression_bus: DirectCompressionBus,
+    ) -> PersistentBoundaryChip<F, CHUNK> {
+        PersistentBoundaryChip::new(
+            mem_config.memory_dimensions(),
+            memory_bus,
+            merkle_bus,
+            compression_bus,
+            AddressSpaceBoundsSubAir::new(range_checker.bus(), mem_config, CHUNK),
+            range_checker,
+        )
+    }
+
+    /// Replaces the memory layout, e.g. after extensions registered new address spaces.
+    ///
+    /// Must be called before any memory access or call to `set_initial_memory`.
+    pub(crate) fn set_memory_config(&mut self, mem_config: MemoryConfig) {
+        assert_eq!(
+            self.timestamp(),
+            INITIAL_TIMESTAMP + 1,
+            "Cannot change the memory layout after the first timestamp"
+        );
+        let initial_block_size = match &mut self.interface_chip {
+            MemoryInterface::Volatile { boundary_chip } => {
+                *boundary_chip = Self::volatile_boundary_chip(
+                    self.memory_bus,
+                    &mem_config,
+                    self.range_checker.clone(),
+                );
+                1
+            }
+            MemoryInterface::Persistent {
+                boundary_chip,
+                merkle_chip,
+                initial_memory,
+            } => {
+                let memory_dims = mem_config.memory_dimensions();
+                let merkle_bus = merkle_chip.air.merkle_bus;
+                let compression_bus = merkle_chip.air.compression_bus;
+                *boundary_chip = Self::persistent_boundary_chip(
+                    self.memory_bus,
+                    &mem_config,
+                    self.range_checker.clone(),
+                    merkle_bus,
+                    compression_bus,
+                );
+                *merkle_chip = MemoryMerkleChip::new(memory_dims, merkle_bus, compression_bus);
+                *initial_memory = AddressMap::from_mem_config(&mem_config);
+                CHUNK
+            }
+        };
+        let execute_only = self.memory.is_execute_only();
+        self.memory = Memory::new(&mem_config);
+        self.memory.set_execute_only(execute_only);
+        *self.offline_memory.lock().unwrap() = OfflineMemory::new(
+            AddressMap::from_mem_config(&mem_config),
+            initial_block_size,
+            self.memory_bus,
+            self.range_checker.clone(),
+            &mem_config,
+        );
+        self.mem_config = mem_config;
+    }
+
     pub fn memory_image(&self) -> &MemoryImage<F> {
         &self.memory.data
     }
@@ -346,9 +461,11 @@ impl<F: PrimeField32> MemoryController<F> {
             panic!("Cannot set initial memory after first timestamp");
         }
         let mut offline_memory = self.offline_memory.lock().unwrap();
-        offline_memory.set_initial_memory(memory.clone(), self.mem_config);
+        offline_memory.set_initial_memory(memory.clone(), &self.mem_config);
 
+        let execute_only = self.memory.is_execute_only();
         self.memory = Memory::from_image(memory.clone(), self.mem_config.access_capacity);
+        self.memory.set_execute_only(execute_only);
 
         match &mut self.interface_chip {
             MemoryInterface::Volatile { .. } => {
@@ -363,6 +480,17 @@ impl<F: PrimeField32> MemoryController<F> {
         }
     }
 
+    /// Switches to execute-only mode, where memory accesses are not logged and executors should
+    /// not keep records for trace generation. Must be set before any access.
+    pub fn set_execute_only(&mut self, execute_only: bool) {
+        self.memory.set_execute_only(execute_only);
+    }
+
+    /// Whether executors should skip keeping records for trace generation.
+    pub fn is_execute_only(&self) -> bool {
+        self.memory.is_execute_only()
+    }
+
     pub fn memory_bridge(&self) -> MemoryBridge {
         MemoryBridge::new(
             self.memory_bus,
@@ -379,10 +507,9 @@ impl<F: PrimeField32> MemoryController<F> {
     pub fn read<const N: usize>(&mut self, address_space: F, pointer: F) -> (RecordId, [F; N]) {
         let address_space_u32 = address_space.as_canonical_u32();
         let ptr_u32 = pointer.as_canonical_u32();
-        assert!(
-            address_space == F::ZERO || ptr_u32 < (1 << self.mem_config.pointer_max_bits),
-            "memory out of bounds: {ptr_u32:?}",
-        );
+        if address_space != F::ZERO {
+            self.assert_in_bounds(address_space_u32, ptr_u32);
+        }
 
         let (record_id, values) = self.memory.read::<N>(address_space_u32, ptr_u32);
 
@@ -422,14 +549,27 @@ impl<F: PrimeField32> MemoryController<F> {
         assert_ne!(address_space, F::ZERO);
         let address_space_u32 = address_space.as_canonical_u32();
         let ptr_u32 = pointer.as_canonical_u32();
-        assert!(
-            ptr_u32 < (1 << self.mem_config.pointer_max_bits),
-            "memory out of bounds: {ptr_u32:?}",
-        );
+        self.assert_in_bounds(address_space_u32, ptr_u32);
+        if let Some(config) = self.mem_config.address_space_config(address_space_u32) {
+            if config.cell_type == AddressSpaceCellType::Byte {
+                assert!(
+                    data.iter().all(|x| x.as_canonical_u32() < 256),
+                    "non-byte value written to byte address space {}",
+                    config.name,
+                );
+            }
+        }
 
         self.memory.write(address_space_u32, ptr_u32, data)
     }
 
+    fn assert_in_bounds(&self, address_space: u32, pointer: u32) {
+        assert!(
+            (pointer as usize) < self.mem_config.address_space_size(address_space),
+            "memory out of bounds: {pointer:?}",
+        );
+    }
+
     pub fn aux_cols_factory(&self) -> MemoryAuxColsFactory<F> {
         let range_bus = self.range_checker.bus();
         MemoryAuxColsFactory {
@@ -451,6 +591,11 @@ impl<F: PrimeField32> MemoryController<F> {
         self.memory.timestamp()
     }
 
+    /// Number of allocated memory pages, across all address spaces.
+    pub fn num_allocated_pages(&self) -> usize {
+        self.memory.num_allocated_pages()
+    }
+
     fn replay_access_log(&mut self) {
         let log = mem::take(&mut self.memory.log);
 
@@ -508,6 +653,10 @@ impl<F: PrimeField32> MemoryController<F> {
         if self.final_state.is_some() {
             return;
         }
+        assert!(
+            !self.is_execute_only(),
+            "Cannot finalize memory in execute-only mode"
+        );
 
         self.replay_access_log();
         let mut offline_memory = self.offline_memory.lock().unwrap();
@@ -829,7 +978,7 @@ mod tests {
 
         let mut memory_controller = MemoryController::with_volatile_memory(
             memory_bus,
-            memory_config,
+            memory_config.clone(),
             range_checker.clone(),
         );
 
@@ -1,6 +1,7 @@
 use openvm_circuit_primitives_derive::AlignedBorrow;
 
 mod adapter;
+mod bounds;
 mod controller;
 pub mod merkle;
 mod offline;
@@ -160,10 +160,10 @@ impl<F: PrimeField32> OfflineMemory<F> {
         initial_block_size: usize,
         memory_bus: MemoryBus,
         range_checker: SharedVariableRangeCheckerChip,
-        config: MemoryConfig,
+        config: &MemoryConfig,
     ) -> Self {
         Self {
-            block_data: BlockMap::from_mem_config(&config, initial_block_size),
+            block_data: BlockMap::from_mem_config(config, initial_block_size),
             data: Self::memory_image_to_paged_vec(initial_memory, config),
             as_offset: config.as_offset,
             timestamp: INITIAL_TIMESTAMP + 1,
@@ -174,7 +174,7 @@ impl<F: PrimeField32> OfflineMemory<F> {
         }
     }
 
-    pub fn set_initial_memory(&mut self, initial_memory: MemoryImage<F>, config: MemoryConfig) {
+    pub fn set_initial_memory(&mut self, initial_memory: MemoryImage<F>, config: &MemoryConfig) {
         assert_eq!(self.timestamp, INITIAL_TIMESTAMP + 1);
         self.as_offset = config.as_offset;
         self.data = Self::memory_image_to_paged_vec(initial_memory, config);
@@ -182,13 +182,9 @@ impl<F: PrimeField32> OfflineMemory<F> {
 
     fn memory_image_to_paged_vec(
         memory_image: MemoryImage<F>,
-        config: MemoryConfig,
+        config: &MemoryConfig,
     ) -> Vec<PagedVec<F, PAGE_SIZE>> {
-        let mut paged_vec =
-            vec![
-                PagedVec::new((1usize << config.pointer_max_bits).div_ceil(PAGE_SIZE));
-                1 << config.as_height
-            ];
+        let mut paged_vec = AddressMap::<F, PAGE_SIZE>::from_mem_config(config).paged_vecs;
         for ((addr_space, pointer), value) in memory_image.items() {
             paged_vec[(addr_space - config.as_offset) as usize].set(pointer as usize, value);
         }
@@ -590,7 +586,7 @@ mod tests {
             initial_block_size,
             memory_bus,
             range_checker.clone(),
-            mem_config,
+            &mem_config,
         );
         let access_adapter_inventory = AccessAdapterInventory::new(
             range_checker,
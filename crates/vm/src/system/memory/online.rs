@@ -24,14 +24,21 @@ pub enum MemoryLogEntry<T> {
     IncrementTimestampBy(u32),
 }
 
+/// The `RecordId` returned for accesses that are not logged in execute-only mode.
+pub const UNLOGGED_RECORD_ID: RecordId = RecordId(usize::MAX);
+
 /// A simple data structure to read to/write from memory.
 ///
-/// Stores a log of memory accesses to reconstruct aspects of memory state for trace generation.
+/// Stores a log of memory accesses to reconstruct aspects of memory state for trace generation,
+/// unless in execute-only mode, where it is just a plain [AddressMap].
 #[derive(Debug)]
 pub struct Memory<F> {
     pub(super) data: AddressMap<F, PAGE_SIZE>,
     pub(super) log: Vec<MemoryLogEntry<F>>,
     timestamp: u32,
+    execute_only: bool,
+    /// Number of allocated pages of `data`, kept up to date on writes.
+    num_allocated_pages: usize,
 }
 
 impl<F: PrimeField32> Memory<F> {
@@ -40,19 +47,44 @@ impl<F: PrimeField32> Memory<F> {
             data: AddressMap::from_mem_config(mem_config),
             timestamp: INITIAL_TIMESTAMP + 1,
             log: Vec::with_capacity(mem_config.access_capacity),
+            execute_only: false,
+            num_allocated_pages: 0,
         }
     }
 
     /// Instantiates a new `Memory` data structure from an image.
     pub fn from_image(image: MemoryImage<F>, access_capacity: usize) -> Self {
         Self {
+            num_allocated_pages: image.num_allocated_pages(),
             data: image,
             timestamp: INITIAL_TIMESTAMP + 1,
             log: Vec::with_capacity(access_capacity),
+            execute_only: false,
+        }
+    }
+
+    /// In execute-only mode, accesses are not logged and [UNLOGGED_RECORD_ID] is returned in place
+    /// of their `RecordId`s.
+    pub fn set_execute_only(&mut self, execute_only: bool) {
+        assert!(
+            self.log.is_empty(),
+            "Cannot change execute-only mode after accesses were logged"
+        );
+        self.execute_only = execute_only;
+        if execute_only {
+            self.log = Vec::new();
         }
     }
 
-    fn last_record_id(&self) -> RecordId {
+    pub fn is_execute_only(&self) -> bool {
+        self.execute_only
+    }
+
+    fn push_log(&mut self, entry: impl FnOnce() -> MemoryLogEntry<F>) -> RecordId {
+        if self.execute_only {
+            return UNLOGGED_RECORD_ID;
+        }
+        self.log.push(entry());
         RecordId(self.log.len() - 1)
     }
 
@@ -67,23 +99,26 @@ impl<F: PrimeField32> Memory<F> {
     ) -> (RecordId, [F; N]) {
         assert!(N.is_power_of_two());
 
+        self.num_allocated_pages += self
+            .data
+            .num_unallocated_pages_in_range(&(address_space, pointer), N);
         let prev_data = self.data.set_range(&(address_space, pointer), &values);
 
-        self.log.push(MemoryLogEntry::Write {
+        let record_id = self.push_log(|| MemoryLogEntry::Write {
             address_space,
             pointer,
             data: values.to_vec(),
         });
         self.timestamp += 1;
 
-        (self.last_record_id(), prev_data)
+        (record_id, prev_data)
     }
 
     /// Reads an array of values from the memory at the specified address space and start index.
     pub fn read<const N: usize>(&mut self, address_space: u32, pointer: u32) -> (RecordId, [F; N]) {
         assert!(N.is_power_of_two());
 
-        self.log.push(MemoryLogEntry::Read {
+        let record_id = self.push_log(|| MemoryLogEntry::Read {
             address_space,
             pointer,
             len: N,
@@ -96,18 +131,23 @@ impl<F: PrimeField32> Memory<F> {
             self.range_array::<N>(address_space, pointer)
         };
         self.timestamp += 1;
-        (self.last_record_id(), values)
+        (record_id, values)
     }
 
     pub fn increment_timestamp_by(&mut self, amount: u32) {
         self.timestamp += amount;
-        self.log.push(MemoryLogEntry::IncrementTimestampBy(amount))
+        self.push_log(|| MemoryLogEntry::IncrementTimestampBy(amount));
     }
 
     pub fn timestamp(&self) -> u32 {
         self.timestamp
     }
 
+    /// Number of allocated memory pages, across all address spaces.
+    pub fn num_allocated_pages(&self) -> usize {
+        self.num_allocated_pages
+    }
+
     #[inline(always)]
     pub fn get(&self, address_space: u32, pointer: u32) -> F {
         *self.data.get(&(address_space, pointer)).unwrap_or(&F::ZERO)
@@ -124,7 +164,7 @@ mod tests {
     use openvm_stark_backend::p3_field::FieldAlgebra;
     use openvm_stark_sdk::p3_baby_bear::BabyBear;
 
-    use super::Memory;
+    use super::{Memory, PAGE_SIZE, UNLOGGED_RECORD_ID};
     use crate::arch::MemoryConfig;
 
     macro_rules! bba {
@@ -148,4 +188,40 @@ mod tests {
         let (_, data) = memory.read::<4>(address_space, 0);
         assert_eq!(data, bba![1, 2, 100, 4]);
     }
+
+    #[test]
+    fn test_execute_only_does_not_log() {
+        let mut memory = Memory::new(&MemoryConfig::default());
+        memory.set_execute_only(true);
+        let address_space = 1;
+
+        let (record_id, _) = memory.write(address_space, 0, bba![1, 2, 3, 4]);
+        assert_eq!(record_id.0, UNLOGGED_RECORD_ID.0);
+
+        let (_, data) = memory.read::<4>(address_space, 0);
+        assert_eq!(data, bba![1, 2, 3, 4]);
+        memory.increment_timestamp_by(2);
+        assert!(memory.log.is_empty());
+    }
+
+    #[test]
+    fn test_num_allocated_pages() {
+        let mut memory = Memory::new(&MemoryConfig::default());
+        assert_eq!(memory.num_allocated_pages(), 0);
+
+        memory.write(1, 0, bba![1, 2, 3, 4]);
+        memory.write(1, 4, bba![5]);
+        assert_eq!(memory.num_allocated_pages(), 1);
+
+        // Crosses a page boundary.
+        memory.write(2, PAGE_SIZE as u32 - 2, bba![1, 2, 3, 4]);
+        assert_eq!(memory.num_allocated_pages(), 3);
+
+        let _ = memory.read::<4>(1, 10 * PAGE_SIZE as u32);
+        assert_eq!(memory.num_allocated_pages(), 3);
+        assert_eq!(
+            memory.num_allocated_pages(),
+            memory.data.num_allocated_pages()
+        );
+    }
 }
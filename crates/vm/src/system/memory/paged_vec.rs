@@ -157,6 +157,20 @@ impl<T: Default + Clone, const PAGE_SIZE: usize> PagedVec<T, PAGE_SIZE> {
     pub fn is_empty(&self) -> bool {
         self.pages.iter().all(|page| page.is_none())
     }
+
+    pub fn num_allocated_pages(&self) -> usize {
+        self.pages.iter().filter(|page| page.is_some()).count()
+    }
+
+    /// Returns the number of pages that writing `len` elements from `start` would allocate.
+    #[inline(always)]
+    pub fn num_unallocated_pages_in_range(&self, start: usize, len: usize) -> usize {
+        let start_page = start / PAGE_SIZE;
+        let end_page = (start + len - 1) / PAGE_SIZE;
+        (start_page..=end_page)
+            .filter(|&page_idx| self.pages[page_idx].is_none())
+            .count()
+    }
 }
 
 // ------------------------------------------------------------------
@@ -246,12 +260,17 @@ impl<T: Clone + Default, const PAGE_SIZE: usize> AddressMap<T, PAGE_SIZE> {
             as_offset,
         }
     }
+    /// Creates an empty map with every address space sized as in `mem_config`.
     pub fn from_mem_config(mem_config: &MemoryConfig) -> Self {
-        Self::new(
-            mem_config.as_offset,
-            1 << mem_config.as_height,
-            1 << mem_config.pointer_max_bits,
-        )
+        Self {
+            paged_vecs: (0..mem_config.num_address_spaces() as u32)
+                .map(|idx| {
+                    let num_cells = mem_config.address_space_size(mem_config.as_offset + idx);
+                    PagedVec::new(num_cells.div_ceil(PAGE_SIZE))
+                })
+                .collect(),
+            as_offset: mem_config.as_offset,
+        }
     }
     pub fn items(&self) -> impl Iterator<Item = (Address, T)> + '_ {
         self.paged_vecs
@@ -274,6 +293,19 @@ impl<T: Clone + Default, const PAGE_SIZE: usize> AddressMap<T, PAGE_SIZE> {
     pub fn is_empty(&self) -> bool {
         self.paged_vecs.iter().all(|page| page.is_empty())
     }
+    /// Number of allocated pages across all address spaces.
+    pub fn num_allocated_pages(&self) -> usize {
+        self.paged_vecs
+            .iter()
+            .map(|page| page.num_allocated_pages())
+            .sum()
+    }
+    /// Returns the number of pages that writing `len` elements at `address` would allocate.
+    #[inline(always)]
+    pub fn num_unallocated_pages_in_range(&self, address: &Address, len: usize) -> usize {
+        self.paged_vecs[(address.0 - self.as_offset) as usize]
+            .num_unallocated_pages_in_range(address.1 as usize, len)
+    }
 
     pub fn from_iter(
         as_offset: u32,
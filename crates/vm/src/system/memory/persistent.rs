@@ -5,6 +5,9 @@ use std::{
     sync::Arc,
 };
 
+use openvm_circuit_primitives::{
+    var_range::SharedVariableRangeCheckerChip, SubAir, TraceSubRowGenerator,
+};
 use openvm_circuit_primitives_derive::AlignedBorrow;
 use openvm_stark_backend::{
     config::{StarkGenericConfig, Val},
@@ -23,7 +26,10 @@ use super::merkle::{DirectCompressionBus, SerialReceiver};
 use crate::{
     arch::hasher::Hasher,
     system::memory::{
-        dimensions::MemoryDimensions, merkle::MemoryMerkleBus, offline_checker::MemoryBus,
+        bounds::{AddressSpaceBoundsIo, AddressSpaceBoundsSubAir},
+        dimensions::MemoryDimensions,
+        merkle::MemoryMerkleBus,
+        offline_checker::MemoryBus,
         MemoryAddress, MemoryImage, TimestampedEquipartition, INITIAL_TIMESTAMP,
     },
 };
@@ -46,6 +52,7 @@ pub struct PersistentBoundaryCols<T, const CHUNK: usize> {
 
 /// Imposes the following constraints:
 /// - `expand_direction` should be -1, 0, 1
+/// - the constraints of [AddressSpaceBoundsSubAir] on non-padding rows
 ///
 /// Sends the following interactions:
 /// - if `expand_direction` is 1, sends `[0, 0, address_space_label, leaf_label]` to `merkle_bus`.
@@ -56,11 +63,13 @@ pub struct PersistentBoundaryAir<const CHUNK: usize> {
     pub memory_bus: MemoryBus,
     pub merkle_bus: MemoryMerkleBus,
     pub compression_bus: DirectCompressionBus,
+    /// Its columns follow [PersistentBoundaryCols].
+    pub bounds: AddressSpaceBoundsSubAir,
 }
 
 impl<const CHUNK: usize, F> BaseAir<F> for PersistentBoundaryAir<CHUNK> {
     fn width(&self) -> usize {
-        PersistentBoundaryCols::<F, CHUNK>::width()
+        PersistentBoundaryCols::<F, CHUNK>::width() + self.bounds.width()
     }
 }
 
@@ -71,7 +80,9 @@ impl<const CHUNK: usize, AB: InteractionBuilder> Air<AB> for PersistentBoundaryA
     fn eval(&self, builder: &mut AB) {
         let main = builder.main();
         let local = main.row_slice(0);
-        let local: &PersistentBoundaryCols<AB::Var, CHUNK> = (*local).borrow();
+        let (local, local_bounds) =
+            local.split_at(PersistentBoundaryCols::<AB::Var, CHUNK>::width());
+        let local: &PersistentBoundaryCols<AB::Var, CHUNK> = local.borrow();
 
         // `direction` should be -1, 0, 1
         builder.assert_eq(
@@ -113,11 +124,25 @@ impl<const CHUNK: usize, AB: InteractionBuilder> Air<AB> for PersistentBoundaryA
                 local.timestamp,
             )
             .eval(builder, local.expand_direction);
+
+        self.bounds.eval(
+            builder,
+            (
+                AddressSpaceBoundsIo {
+                    addr_space: local.address_space.into(),
+                    label: local.leaf_label.into(),
+                    values: local.values.map(Into::into).to_vec(),
+                    count: local.expand_direction * local.expand_direction,
+                },
+                local_bounds,
+            ),
+        );
     }
 }
 
 pub struct PersistentBoundaryChip<F, const CHUNK: usize> {
     pub air: PersistentBoundaryAir<CHUNK>,
+    range_checker: SharedVariableRangeCheckerChip,
     touched_labels: TouchedLabels<F, CHUNK>,
     overridden_height: Option<usize>,
 }
@@ -168,6 +193,8 @@ impl<const CHUNK: usize, F: PrimeField32> PersistentBoundaryChip<F, CHUNK> {
         memory_bus: MemoryBus,
         merkle_bus: MemoryMerkleBus,
         compression_bus: DirectCompressionBus,
+        bounds: AddressSpaceBoundsSubAir,
+        range_checker: SharedVariableRangeCheckerChip,
     ) -> Self {
         Self {
             air: PersistentBoundaryAir {
@@ -175,7 +202,9 @@ impl<const CHUNK: usize, F: PrimeField32> PersistentBoundaryChip<F, CHUNK> {
                 memory_bus,
                 merkle_bus,
                 compression_bus,
+                bounds,
             },
+            range_checker,
             touched_labels: Default::default(),
             overridden_height: None,
         }
@@ -247,7 +276,8 @@ where
 
     fn generate_air_proof_input(self) -> AirProofInput<SC> {
         let trace = {
-            let width = PersistentBoundaryCols::<Val<SC>, CHUNK>::width();
+            let cols_width = PersistentBoundaryCols::<Val<SC>, CHUNK>::width();
+            let width = cols_width + self.air.bounds.width();
             // Boundary AIR should always present in order to fix the AIR ID of merkle AIR.
             let mut height = (2 * self.touched_labels.len()).next_power_of_two();
             if let Some(mut oh) = self.overridden_height {
@@ -264,11 +294,17 @@ where
                 TouchedLabels::Final(touched_labels) => touched_labels,
                 _ => panic!("Cannot generate trace before finalization"),
             };
+            let bounds = &self.air.bounds;
+            let range_checker = self.range_checker.as_ref();
 
-            rows.par_chunks_mut(2 * width)
+            let (used_rows, padding_rows) = rows.split_at_mut(2 * touched_labels.len() * width);
+            used_rows
+                .par_chunks_mut(2 * width)
                 .zip(touched_labels.into_par_iter())
                 .for_each(|(row, touched_label)| {
                     let (initial_row, final_row) = row.split_at_mut(width);
+                    let (initial_row, initial_bounds) = initial_row.split_at_mut(cols_width);
+                    let (final_row, final_bounds) = final_row.split_at_mut(cols_width);
                     *initial_row.borrow_mut() = PersistentBoundaryCols {
                         expand_direction: Val::<SC>::ONE,
                         address_space: Val::<SC>::from_canonical_u32(touched_label.address_space),
@@ -277,6 +313,16 @@ where
                         hash: touched_label.init_hash,
                         timestamp: Val::<SC>::from_canonical_u32(INITIAL_TIMESTAMP),
                     };
+                    bounds.generate_subrow(
+                        (
+                            range_checker,
+                            touched_label.address_space,
+                            touched_label.label,
+                            &touched_label.init_values,
+                            true,
+                        ),
+                        initial_bounds,
+                    );
 
                     *final_row.borrow_mut() = PersistentBoundaryCols {
                         expand_direction: Val::<SC>::NEG_ONE,
@@ -286,7 +332,20 @@ where
                         hash: touched_label.final_hash,
                         timestamp: Val::<SC>::from_canonical_u32(touched_label.final_timestamp),
                     };
+                    bounds.generate_subrow(
+                        (
+                            range_checker,
+                            touched_label.address_space,
+                            touched_label.label,
+                            &touched_label.final_values,
+                            true,
+                        ),
+                        final_bounds,
+                    );
                 });
+            padding_rows.par_chunks_mut(width).for_each(|row| {
+                bounds.generate_subrow((range_checker, 0, 0, &[], false), &mut row[cols_width..]);
+            });
             RowMajorMatrix::new(rows, width)
         };
         AirProofInput::simple_no_pis(trace)
@@ -303,6 +362,6 @@ impl<const CHUNK: usize, F: PrimeField32> ChipUsageGetter for PersistentBoundary
     }
 
     fn trace_width(&self) -> usize {
-        PersistentBoundaryCols::<F, CHUNK>::width()
+        PersistentBoundaryCols::<F, CHUNK>::width() + self.air.bounds.width()
     }
 }
@@ -32,7 +32,7 @@ use super::{merkle::DirectCompressionBus, MemoryController};
 use crate::{
     arch::{
         testing::{memory::gen_pointer, MEMORY_BUS, MEMORY_MERKLE_BUS, POSEIDON2_DIRECT_BUS},
-        MemoryConfig,
+        AddressSpaceCellType, AddressSpaceConfig, AddressSpaceInit, MemoryConfig,
     },
     system::{
         memory::{
@@ -213,7 +213,7 @@ fn test_memory_controller() {
         MemoryController::with_volatile_memory(memory_bus, memory_config, range_checker.clone());
 
     let mut rng = create_seeded_rng();
-    let records = make_random_accesses(&mut memory_controller, &mut rng);
+    let records = make_random_accesses(&mut memory_controller, &mut rng, &[1, 2], 1 << 30);
     let memory_requester_air = Arc::new(MemoryRequesterAir {
         memory_bridge: memory_controller.memory_bridge(),
     });
@@ -254,7 +254,7 @@ fn test_memory_controller_persistent() {
     );
 
     let mut rng = create_seeded_rng();
-    let records = make_random_accesses(&mut memory_controller, &mut rng);
+    let records = make_random_accesses(&mut memory_controller, &mut rng, &[1, 2], 1 << 30);
 
     let memory_requester_air = MemoryRequesterAir {
         memory_bridge: memory_controller.memory_bridge(),
@@ -287,18 +287,186 @@ fn test_memory_controller_persistent() {
     BabyBearPoseidon2Engine::run_test_fast(airs, air_proof_inputs).expect("Verification failed");
 }
 
+#[test]
+fn test_memory_controller_registered_address_space() {
+    let memory_bus = MemoryBus(MEMORY_BUS);
+    let merkle_bus = MemoryMerkleBus(MEMORY_MERKLE_BUS);
+    let compression_bus = DirectCompressionBus(POSEIDON2_DIRECT_BUS);
+    let mut memory_config = MemoryConfig::default();
+    memory_config.address_spaces.push(AddressSpaceConfig::new(
+        "scratch",
+        1 << 30,
+        AddressSpaceCellType::Field,
+        AddressSpaceInit::Zeroed,
+    ));
+    let scratch = memory_config.address_space_by_name("scratch").unwrap();
+    assert_eq!(scratch, 9);
+    let memory_dimensions = memory_config.memory_dimensions();
+    assert_eq!(memory_dimensions.as_height, 4);
+    assert_eq!(memory_dimensions.address_height, 27);
+
+    let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+    let range_checker = SharedVariableRangeCheckerChip::new(range_bus);
+    let mut memory_controller = MemoryController::with_persistent_memory(
+        memory_bus,
+        memory_config,
+        range_checker.clone(),
+        merkle_bus,
+        compression_bus,
+    );
+
+    let mut rng = create_seeded_rng();
+    let records = make_random_accesses(&mut memory_controller, &mut rng, &[1, 2, scratch], 1 << 30);
+
+    let memory_requester_air = MemoryRequesterAir {
+        memory_bridge: memory_controller.memory_bridge(),
+    };
+
+    let mut poseidon_chip =
+        Poseidon2PeripheryChip::new(Poseidon2Config::default(), POSEIDON2_DIRECT_BUS, 3);
+
+    memory_controller.finalize(Some(&mut poseidon_chip));
+
+    let memory_requester_trace = {
+        let offline_memory = memory_controller.offline_memory();
+        let trace = generate_trace(records, &offline_memory.lock().unwrap());
+        trace
+    };
+
+    let mut airs = memory_controller.airs();
+    let mut air_proof_inputs = memory_controller.generate_air_proof_inputs();
+    airs.extend([
+        Arc::new(memory_requester_air),
+        poseidon_chip.air(),
+        range_checker.air(),
+    ]);
+    air_proof_inputs.extend([
+        AirProofInput::simple_no_pis(memory_requester_trace),
+        poseidon_chip.generate_air_proof_input(),
+        range_checker.generate_air_proof_input(),
+    ]);
+
+    BabyBearPoseidon2Engine::run_test_fast(airs, air_proof_inputs).expect("Verification failed");
+}
+
+#[test]
+fn test_memory_controller_byte_address_space_circuit() {
+    let memory_bus = MemoryBus(MEMORY_BUS);
+    let merkle_bus = MemoryMerkleBus(MEMORY_MERKLE_BUS);
+    let compression_bus = DirectCompressionBus(POSEIDON2_DIRECT_BUS);
+    let mut memory_config = MemoryConfig::default();
+    memory_config.address_spaces.push(AddressSpaceConfig::new(
+        "bytes",
+        1 << 29,
+        AddressSpaceCellType::Byte,
+        AddressSpaceInit::Zeroed,
+    ));
+    let bytes = memory_config.address_space_by_name("bytes").unwrap();
+
+    let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+    let range_checker = SharedVariableRangeCheckerChip::new(range_bus);
+    let mut memory_controller = MemoryController::with_persistent_memory(
+        memory_bus,
+        memory_config,
+        range_checker.clone(),
+        merkle_bus,
+        compression_bus,
+    );
+
+    let mut rng = create_seeded_rng();
+    let records = make_random_accesses(&mut memory_controller, &mut rng, &[1, bytes], 1 << 8);
+
+    let memory_requester_air = MemoryRequesterAir {
+        memory_bridge: memory_controller.memory_bridge(),
+    };
+
+    let mut poseidon_chip =
+        Poseidon2PeripheryChip::new(Poseidon2Config::default(), POSEIDON2_DIRECT_BUS, 3);
+
+    memory_controller.finalize(Some(&mut poseidon_chip));
+
+    let memory_requester_trace = {
+        let offline_memory = memory_controller.offline_memory();
+        let trace = generate_trace(records, &offline_memory.lock().unwrap());
+        trace
+    };
+
+    let mut airs = memory_controller.airs();
+    let mut air_proof_inputs = memory_controller.generate_air_proof_inputs();
+    airs.extend([
+        Arc::new(memory_requester_air),
+        poseidon_chip.air(),
+        range_checker.air(),
+    ]);
+    air_proof_inputs.extend([
+        AirProofInput::simple_no_pis(memory_requester_trace),
+        poseidon_chip.generate_air_proof_input(),
+        range_checker.generate_air_proof_input(),
+    ]);
+
+    BabyBearPoseidon2Engine::run_test_fast(airs, air_proof_inputs).expect("Verification failed");
+}
+
+#[test]
+#[should_panic(expected = "memory out of bounds")]
+fn test_memory_controller_registered_address_space_out_of_bounds() {
+    let mut memory_config = MemoryConfig::default();
+    memory_config.address_spaces.push(AddressSpaceConfig::new(
+        "small",
+        1 << 4,
+        AddressSpaceCellType::Field,
+        AddressSpaceInit::Zeroed,
+    ));
+    let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+    let mut memory_controller = MemoryController::<BabyBear>::with_volatile_memory(
+        MemoryBus(MEMORY_BUS),
+        memory_config,
+        SharedVariableRangeCheckerChip::new(range_bus),
+    );
+    memory_controller.write_cell(
+        BabyBear::from_canonical_u32(9),
+        BabyBear::from_canonical_u32(1 << 4),
+        BabyBear::ONE,
+    );
+}
+
+#[test]
+#[should_panic(expected = "non-byte value")]
+fn test_memory_controller_byte_address_space() {
+    let mut memory_config = MemoryConfig::default();
+    memory_config.address_spaces.push(AddressSpaceConfig::new(
+        "bytes",
+        1 << 4,
+        AddressSpaceCellType::Byte,
+        AddressSpaceInit::Zeroed,
+    ));
+    let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+    let mut memory_controller = MemoryController::<BabyBear>::with_volatile_memory(
+        MemoryBus(MEMORY_BUS),
+        memory_config,
+        SharedVariableRangeCheckerChip::new(range_bus),
+    );
+    memory_controller.write_cell(
+        BabyBear::from_canonical_u32(9),
+        BabyBear::ZERO,
+        BabyBear::from_canonical_u32(256),
+    );
+}
+
 fn make_random_accesses<F: PrimeField32>(
     memory_controller: &mut MemoryController<F>,
     mut rng: &mut StdRng,
+    address_spaces: &[u32],
+    max_value: u32,
 ) -> Vec<RecordId> {
     (0..1024)
         .map(|_| {
-            let address_space = F::from_canonical_u32(*[1, 2].choose(&mut rng).unwrap());
+            let address_space = F::from_canonical_u32(*address_spaces.choose(&mut rng).unwrap());
 
             match rng.gen_range(0..5) {
                 0 => {
                     let pointer = F::from_canonical_usize(gen_pointer(rng, 1));
-                    let data = F::from_canonical_u32(rng.gen_range(0..1 << 30));
+                    let data = F::from_canonical_u32(rng.gen_range(0..max_value));
                     let (record_id, _) = memory_controller.write(address_space, pointer, [data]);
                     record_id
                 }
@@ -314,7 +482,8 @@ fn make_random_accesses<F: PrimeField32>(
                 }
                 3 => {
                     let pointer = F::from_canonical_usize(gen_pointer(rng, 4));
-                    let data = array::from_fn(|_| F::from_canonical_u32(rng.gen_range(0..1 << 30)));
+                    let data =
+                        array::from_fn(|_| F::from_canonical_u32(rng.gen_range(0..max_value)));
                     let (record_id, _) = memory_controller.write::<4>(address_space, pointer, data);
                     record_id
                 }
@@ -0,0 +1,248 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use openvm_platform::{
+    data_segments::{layout_data_segments, DataSegmentsError},
+    memory::DATA_SEGMENTS,
+};
+use openvm_stark_backend::{p3_field::PrimeField32, p3_util::log2_strict_usize};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    arch::{hasher::Hasher, MemoryConfig},
+    system::memory::{
+        dimensions::MemoryDimensions, paged_vec::Address, tree::MemoryNode, MemoryImage,
+    },
+};
+
+/// Data segments live in the RV32 memory address space.
+pub const DATA_SEGMENTS_ADDRESS_SPACE_OFFSET: u32 = 1;
+/// Start of the data segments region.
+pub const DATA_SEGMENTS_START: u32 = DATA_SEGMENTS.start() as u32;
+/// Log2 of the size of the data segments region in bytes.
+pub const DATA_SEGMENTS_LOG_SIZE: usize = DATA_SEGMENTS.len_bytes().ilog2() as usize;
+
+/// A named byte blob provided by the host, which is mapped into the data segments region before
+/// execution.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataSegment {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+impl DataSegment {
+    pub fn new(name: impl Into<String>, data: Vec<u8>) -> Self {
+        Self {
+            name: name.into(),
+            data,
+        }
+    }
+}
+
+/// Returns whether the data segments region fits in the RV32 memory address space of `mem_config`.
+pub fn supports_data_segments(mem_config: &MemoryConfig) -> bool {
+    let address_space = DATA_SEGMENTS_ADDRESS_SPACE_OFFSET + mem_config.as_offset;
+    (DATA_SEGMENTS_ADDRESS_SPACE_OFFSET as usize) < mem_config.num_address_spaces()
+        && mem_config.address_space_size(address_space)
+            >= DATA_SEGMENTS_START as usize + (1 << DATA_SEGMENTS_LOG_SIZE)
+}
+
+/// Lays out `segments` as the bytes of the data segments region with
+/// [layout_data_segments]. The region is zero after the returned bytes.
+pub fn data_segments_bytes(segments: &[DataSegment]) -> Result<Vec<u8>, DataSegmentsError> {
+    let segments: Vec<_> = segments
+        .iter()
+        .map(|segment| (segment.name.as_str(), segment.data.as_slice()))
+        .collect();
+    layout_data_segments(&segments)
+}
+
+/// Writes `segments` into the data segments region of `memory`, which is the initial memory of a
+/// program with initial memory `init_memory`.
+///
+/// Panics if the program initializes any memory in the region.
+pub fn write_data_segments<F: PrimeField32>(
+    memory: &mut MemoryImage<F>,
+    init_memory: &BTreeMap<Address, F>,
+    segments: &[DataSegment],
+) -> Result<(), DataSegmentsError> {
+    let address_space = DATA_SEGMENTS_ADDRESS_SPACE_OFFSET + memory.as_offset;
+    let region_end = DATA_SEGMENTS_START + (1 << DATA_SEGMENTS_LOG_SIZE);
+    assert!(
+        init_memory
+            .range((address_space, DATA_SEGMENTS_START)..(address_space, region_end))
+            .all(|(_, value)| value.is_zero()),
+        "program initial memory overlaps the data segments region"
+    );
+    for (i, byte) in data_segments_bytes(segments)?.into_iter().enumerate() {
+        memory.insert(
+            &(address_space, DATA_SEGMENTS_START + i as u32),
+            F::from_canonical_u8(byte),
+        );
+    }
+    Ok(())
+}
+
+/// Merkle root of the data segments region with `segments` mapped into it. The computation
+/// follows the same logic as `MemoryNode`, so this is the root of the subtree of the region in the
+/// initial memory state. It is zero if there are no data segments.
+pub fn data_segments_commit<const CHUNK: usize, F: PrimeField32>(
+    segments: &[DataSegment],
+    hasher: &(impl Hasher<CHUNK, F> + Sync),
+) -> Result<[F; CHUNK], DataSegmentsError> {
+    if segments.is_empty() {
+        return Ok([F::ZERO; CHUNK]);
+    }
+    let dimensions = MemoryDimensions::new(0, region_height::<CHUNK>(), 0);
+    let region = MemoryImage::from_iter(
+        0,
+        1,
+        1 << DATA_SEGMENTS_LOG_SIZE,
+        data_segments_bytes(segments)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, byte)| ((0, i as u32), F::from_canonical_u8(byte))),
+    );
+    Ok(MemoryNode::tree_from_memory(dimensions, &region, hasher).hash())
+}
+
+/// Merkle root of the data segments region when it is zero.
+pub fn zero_data_segments_root<const CHUNK: usize, F: PrimeField32>(
+    hasher: &impl Hasher<CHUNK, F>,
+) -> [F; CHUNK] {
+    let zero_leaf = hasher.hash(&[F::ZERO; CHUNK]);
+    MemoryNode::construct_uniform(region_height::<CHUNK>(), zero_leaf, hasher).hash()
+}
+
+/// Height of the subtree of the data segments region in the memory merkle tree.
+pub fn region_height<const CHUNK: usize>() -> usize {
+    DATA_SEGMENTS_LOG_SIZE - log2_strict_usize(CHUNK)
+}
+
+/// Merkle proof for the data segments region in the initial memory state.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "F: Serialize, [F; CHUNK]: Serialize",
+    deserialize = "F: Deserialize<'de>, [F; CHUNK]: Deserialize<'de>"
+))]
+pub struct DataSegmentsProof<const CHUNK: usize, F> {
+    /// Proof of the path from the root of the data segments region to the memory root in the
+    /// format of (`bit`, `hash`), as in
+    /// [UserPublicValuesProof](super::public_values::UserPublicValuesProof).
+    pub proof: Vec<(bool, [F; CHUNK])>,
+    /// Merkle root of the data segments region, see [data_segments_commit].
+    pub data_segments_commit: [F; CHUNK],
+}
+
+impl<const CHUNK: usize, F: PrimeField32> DataSegmentsProof<CHUNK, F> {
+    /// Computes the proof of the data segments region from the initial memory state.
+    /// Assumption:
+    /// - The data segments region fits in memory, see [supports_data_segments].
+    pub fn compute(
+        memory_dimensions: MemoryDimensions,
+        hasher: &(impl Hasher<CHUNK, F> + Sync),
+        initial_memory: &MemoryImage<F>,
+    ) -> Self {
+        let root = MemoryNode::tree_from_memory(memory_dimensions, initial_memory, hasher);
+        let region_height = region_height::<CHUNK>();
+        let address_prefix_height = memory_dimensions.address_height - region_height;
+        let address_prefix = DATA_SEGMENTS_START >> DATA_SEGMENTS_LOG_SIZE;
+
+        let mut curr_node = Arc::new(root);
+        let mut proof = Vec::with_capacity(memory_dimensions.as_height + address_prefix_height);
+        let as_bits = (0..memory_dimensions.as_height).map(|i| {
+            DATA_SEGMENTS_ADDRESS_SPACE_OFFSET & (1 << (memory_dimensions.as_height - i - 1)) != 0
+        });
+        let address_bits = (0..address_prefix_height)
+            .map(|i| address_prefix & (1 << (address_prefix_height - i - 1)) != 0);
+        for is_right in as_bits.chain(address_bits) {
+            if let MemoryNode::NonLeaf { left, right, .. } = curr_node.as_ref().clone() {
+                if is_right {
+                    curr_node = right;
+                    proof.push((true, left.hash()));
+                } else {
+                    curr_node = left;
+                    proof.push((false, right.hash()));
+                }
+            } else {
+                unreachable!()
+            }
+        }
+        proof.reverse();
+        DataSegmentsProof {
+            proof,
+            data_segments_commit: curr_node.hash(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use openvm_stark_backend::p3_field::FieldAlgebra;
+    use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+    use super::{
+        data_segments_commit, write_data_segments, zero_data_segments_root, DataSegment,
+        DataSegmentsProof,
+    };
+    use crate::{
+        arch::{
+            hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+            SystemConfig,
+        },
+        system::memory::{paged_vec::AddressMap, tree::MemoryNode, CHUNK},
+    };
+
+    type F = BabyBear;
+
+    fn fold(
+        hasher: &impl Hasher<CHUNK, F>,
+        proof: &[(bool, [F; CHUNK])],
+        leaf: [F; CHUNK],
+    ) -> [F; CHUNK] {
+        proof.iter().fold(leaf, |curr, (is_right, sibling)| {
+            if *is_right {
+                hasher.compress(sibling, &curr)
+            } else {
+                hasher.compress(&curr, sibling)
+            }
+        })
+    }
+
+    #[test]
+    fn test_data_segments_proof() {
+        let mem_config = SystemConfig::default().memory_config;
+        let memory_dimensions = mem_config.memory_dimensions();
+        let hasher = vm_poseidon2_hasher();
+        let segments = vec![
+            DataSegment::new("table", (0..=255).collect()),
+            DataSegment::new("genesis", vec![7; 13]),
+        ];
+        let init_memory = BTreeMap::from([((2, 0x1000), F::from_canonical_u8(1))]);
+
+        let mut exe_memory = AddressMap::from_mem_config(&mem_config);
+        for (address, value) in &init_memory {
+            exe_memory.insert(address, *value);
+        }
+        let mut memory = exe_memory.clone();
+        write_data_segments(&mut memory, &init_memory, &segments).unwrap();
+
+        let proof = DataSegmentsProof::<CHUNK, F>::compute(memory_dimensions, &hasher, &memory);
+        assert_eq!(
+            proof.data_segments_commit,
+            data_segments_commit(&segments, &hasher).unwrap()
+        );
+        let initial_root = MemoryNode::tree_from_memory(memory_dimensions, &memory, &hasher);
+        assert_eq!(
+            fold(&hasher, &proof.proof, proof.data_segments_commit),
+            initial_root.hash()
+        );
+        // Replacing the region by zeros gives the memory root of the program.
+        let exe_root = MemoryNode::tree_from_memory(memory_dimensions, &exe_memory, &hasher);
+        assert_eq!(
+            fold(&hasher, &proof.proof, zero_data_segments_root(&hasher)),
+            exe_root.hash()
+        );
+    }
+}
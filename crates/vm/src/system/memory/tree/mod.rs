@@ -1,3 +1,4 @@
+pub mod data_segments;
 pub mod public_values;
 
 use std::{ops::Range, sync::Arc};
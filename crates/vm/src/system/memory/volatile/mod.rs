@@ -26,6 +26,7 @@ use openvm_stark_backend::{
 
 use super::TimestampedEquipartition;
 use crate::system::memory::{
+    bounds::{AddressSpaceBoundsIo, AddressSpaceBoundsSubAir},
     offline_checker::{MemoryBus, AUX_LEN},
     MemoryAddress,
 };
@@ -55,6 +56,8 @@ pub struct VolatileBoundaryCols<T> {
 pub struct VolatileBoundaryAir {
     pub memory_bus: MemoryBus,
     pub addr_lt_air: IsLtArrayWhenTransitionAir<ADDR_ELTS>,
+    /// Its columns follow [VolatileBoundaryCols].
+    pub bounds: AddressSpaceBoundsSubAir,
 }
 
 impl VolatileBoundaryAir {
@@ -63,6 +66,7 @@ impl VolatileBoundaryAir {
         addr_space_max_bits: usize,
         pointer_max_bits: usize,
         range_bus: VariableRangeCheckerBus,
+        bounds: AddressSpaceBoundsSubAir,
     ) -> Self {
         let addr_lt_air =
             IsLtArraySubAir::<ADDR_ELTS>::new(range_bus, addr_space_max_bits.max(pointer_max_bits))
@@ -70,6 +74,7 @@ impl VolatileBoundaryAir {
         Self {
             memory_bus,
             addr_lt_air,
+            bounds,
         }
     }
 }
@@ -78,7 +83,7 @@ impl<F: Field> BaseAirWithPublicValues<F> for VolatileBoundaryAir {}
 impl<F: Field> PartitionedBaseAir<F> for VolatileBoundaryAir {}
 impl<F: Field> BaseAir<F> for VolatileBoundaryAir {
     fn width(&self) -> usize {
-        VolatileBoundaryCols::<F>::width()
+        VolatileBoundaryCols::<F>::width() + self.bounds.width()
     }
 }
 
@@ -87,8 +92,10 @@ impl<AB: InteractionBuilder> Air<AB> for VolatileBoundaryAir {
         let main = builder.main();
 
         let [local, next] = [0, 1].map(|i| main.row_slice(i));
-        let local: &VolatileBoundaryCols<_> = (*local).borrow();
-        let next: &VolatileBoundaryCols<_> = (*next).borrow();
+        let (local, local_bounds) = local.split_at(VolatileBoundaryCols::<AB::Var>::width());
+        let local: &VolatileBoundaryCols<_> = local.borrow();
+        let next: &VolatileBoundaryCols<_> =
+            next[..VolatileBoundaryCols::<AB::Var>::width()].borrow();
 
         builder.assert_bool(local.is_valid);
 
@@ -109,6 +116,20 @@ impl<AB: InteractionBuilder> Air<AB> for VolatileBoundaryAir {
         self.addr_lt_air
             .eval(builder, (lt_io, (&local.addr_lt_aux).into()));
 
+        // Pointers are within their address space and byte cells hold bytes
+        self.bounds.eval(
+            builder,
+            (
+                AddressSpaceBoundsIo {
+                    addr_space: local.addr_space.into(),
+                    label: local.pointer.into(),
+                    values: vec![local.initial_data.into(), local.final_data.into()],
+                    count: local.is_valid.into(),
+                },
+                local_bounds,
+            ),
+        );
+
         // Write the initial memory values at initial timestamps
         self.memory_bus
             .send(
@@ -141,6 +162,7 @@ impl<F> VolatileBoundaryChip<F> {
         memory_bus: MemoryBus,
         addr_space_max_bits: usize,
         pointer_max_bits: usize,
+        bounds: AddressSpaceBoundsSubAir,
         range_checker: SharedVariableRangeCheckerChip,
     ) -> Self {
         let range_bus = range_checker.bus();
@@ -150,6 +172,7 @@ impl<F> VolatileBoundaryChip<F> {
                 addr_space_max_bits,
                 pointer_max_bits,
                 range_bus,
+                bounds,
             ),
             range_checker,
             overridden_height: None,
@@ -200,43 +223,58 @@ where
         let sorted_final_memory: Vec<_> = final_memory.into_par_iter().collect();
         let memory_len = sorted_final_memory.len();
 
+        let cols_width = VolatileBoundaryCols::<Val<SC>>::width();
         let mut rows = Val::<SC>::zero_vec(trace_height * width);
-        rows.par_chunks_mut(width)
-            .zip(sorted_final_memory.par_iter())
-            .enumerate()
-            .for_each(|(i, (row, ((addr_space, ptr), timestamped_values)))| {
-                // `pointer` is the same as `label` since the equipartition has block size 1
-                let [data] = timestamped_values.values;
-                let row: &mut VolatileBoundaryCols<_> = row.borrow_mut();
-                row.addr_space = Val::<SC>::from_canonical_u32(*addr_space);
-                row.pointer = Val::<SC>::from_canonical_u32(*ptr);
-                row.initial_data = Val::<SC>::ZERO;
-                row.final_data = data;
-                row.final_timestamp = Val::<SC>::from_canonical_u32(timestamped_values.timestamp);
-                row.is_valid = Val::<SC>::ONE;
+        rows.par_chunks_mut(width).enumerate().for_each(|(i, row)| {
+            let (row, bounds_row) = row.split_at_mut(cols_width);
+            let Some(((addr_space, ptr), timestamped_values)) = sorted_final_memory.get(i) else {
+                air.bounds
+                    .generate_subrow((self.range_checker.as_ref(), 0, 0, &[], false), bounds_row);
+                return;
+            };
+            // `pointer` is the same as `label` since the equipartition has block size 1
+            let [data] = timestamped_values.values;
+            let row: &mut VolatileBoundaryCols<_> = row.borrow_mut();
+            row.addr_space = Val::<SC>::from_canonical_u32(*addr_space);
+            row.pointer = Val::<SC>::from_canonical_u32(*ptr);
+            row.initial_data = Val::<SC>::ZERO;
+            row.final_data = data;
+            row.final_timestamp = Val::<SC>::from_canonical_u32(timestamped_values.timestamp);
+            row.is_valid = Val::<SC>::ONE;
+            air.bounds.generate_subrow(
+                (
+                    self.range_checker.as_ref(),
+                    *addr_space,
+                    *ptr,
+                    &[row.initial_data, row.final_data],
+                    true,
+                ),
+                bounds_row,
+            );
 
-                // If next.is_valid == 1:
-                if i != memory_len - 1 {
-                    let (next_addr_space, next_ptr) = sorted_final_memory[i + 1].0;
-                    let mut out = Val::<SC>::ZERO;
-                    air.addr_lt_air.0.generate_subrow(
-                        (
-                            self.range_checker.as_ref(),
-                            &[row.addr_space, row.pointer],
-                            &[
-                                Val::<SC>::from_canonical_u32(next_addr_space),
-                                Val::<SC>::from_canonical_u32(next_ptr),
-                            ],
-                        ),
-                        ((&mut row.addr_lt_aux).into(), &mut out),
-                    );
-                    debug_assert_eq!(out, Val::<SC>::ONE, "Addresses are not sorted");
-                }
-            });
+            // If next.is_valid == 1:
+            if i != memory_len - 1 {
+                let (next_addr_space, next_ptr) = sorted_final_memory[i + 1].0;
+                let mut out = Val::<SC>::ZERO;
+                air.addr_lt_air.0.generate_subrow(
+                    (
+                        self.range_checker.as_ref(),
+                        &[row.addr_space, row.pointer],
+                        &[
+                            Val::<SC>::from_canonical_u32(next_addr_space),
+                            Val::<SC>::from_canonical_u32(next_ptr),
+                        ],
+                    ),
+                    ((&mut row.addr_lt_aux).into(), &mut out),
+                );
+                debug_assert_eq!(out, Val::<SC>::ONE, "Addresses are not sorted");
+            }
+        });
         // Always do a dummy range check on the last row due to wraparound
         if memory_len > 0 {
             let mut out = Val::<SC>::ZERO;
-            let row: &mut VolatileBoundaryCols<_> = rows[width * (trace_height - 1)..].borrow_mut();
+            let row: &mut VolatileBoundaryCols<_> =
+                rows[width * (trace_height - 1)..][..cols_width].borrow_mut();
             air.addr_lt_air.0.generate_subrow(
                 (
                     self.range_checker.as_ref(),
@@ -266,6 +304,6 @@ impl<F: PrimeField32> ChipUsageGetter for VolatileBoundaryChip<F> {
     }
 
     fn trace_width(&self) -> usize {
-        VolatileBoundaryCols::<F>::width()
+        VolatileBoundaryCols::<F>::width() + self.air.bounds.width()
     }
 }
@@ -16,9 +16,12 @@ use openvm_stark_sdk::{
 use rand::Rng;
 use test_log::test;
 
-use crate::system::memory::{
-    offline_checker::MemoryBus, volatile::VolatileBoundaryChip, TimestampedEquipartition,
-    TimestampedValues,
+use crate::{
+    arch::MemoryConfig,
+    system::memory::{
+        bounds::AddressSpaceBoundsSubAir, offline_checker::MemoryBus,
+        volatile::VolatileBoundaryChip, TimestampedEquipartition, TimestampedValues,
+    },
 };
 
 type Val = BabyBear;
@@ -45,8 +48,13 @@ fn boundary_air_test() {
 
     let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, DECOMP);
     let range_checker = SharedVariableRangeCheckerChip::new(range_bus);
-    let mut boundary_chip =
-        VolatileBoundaryChip::new(memory_bus, 2, LIMB_BITS, range_checker.clone());
+    let mut boundary_chip = VolatileBoundaryChip::new(
+        memory_bus,
+        2,
+        LIMB_BITS,
+        AddressSpaceBoundsSubAir::new(range_bus, &MemoryConfig::default(), 1),
+        range_checker.clone(),
+    );
 
     let mut final_memory = TimestampedEquipartition::new();
 
@@ -114,8 +122,13 @@ fn boundary_air_test() {
     {
         let overridden_height = boundary_api.main_trace_height() * 2;
         let range_checker = SharedVariableRangeCheckerChip::new(range_bus);
-        let mut boundary_chip =
-            VolatileBoundaryChip::new(memory_bus, 2, LIMB_BITS, range_checker.clone());
+        let mut boundary_chip = VolatileBoundaryChip::new(
+            memory_bus,
+            2,
+            LIMB_BITS,
+            AddressSpaceBoundsSubAir::new(range_bus, &MemoryConfig::default(), 1),
+            range_checker.clone(),
+        );
         boundary_chip.set_overridden_height(overridden_height);
         boundary_chip.finalize(final_memory.clone());
         let boundary_api: AirProofInput<BabyBearPoseidon2Config> =
@@ -1,4 +1,5 @@
 pub mod connector;
+pub mod input_commitment;
 pub mod memory;
 pub mod native_adapter;
 /// Chip to handle phantom instructions.
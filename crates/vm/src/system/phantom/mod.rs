@@ -27,7 +27,7 @@ use super::memory::MemoryController;
 use crate::{
     arch::{
         ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor,
-        PcIncOrSet, PhantomSubExecutor, Streams,
+        PcIncOrSet, PhantomSubExecutor, SharedJournalRecorder, Streams,
     },
     system::program::ProgramBus,
 };
@@ -90,7 +90,14 @@ pub struct PhantomChip<F> {
     pub air: PhantomAir,
     pub rows: Vec<PhantomCols<F>>,
     streams: OnceLock<Arc<Mutex<Streams<F>>>>,
-    phantom_executors: FxHashMap<PhantomDiscriminant, Box<dyn PhantomSubExecutor<F>>>,
+    phantom_executors: Vec<Box<dyn PhantomSubExecutor<F>>>,
+    /// Index in `phantom_executors` of the sub-executor for each discriminant.
+    sub_executor_lookup: FxHashMap<PhantomDiscriminant, usize>,
+    /// Records or replays the effects of phantom sub-instructions on the streams.
+    journal: Option<SharedJournalRecorder<F>>,
+    /// Whether the VM commits to its input stream, in which case sub-instructions may only hint
+    /// inputs.
+    commit_input_stream: bool,
 }
 
 impl<F> PhantomChip<F> {
@@ -102,7 +109,10 @@ impl<F> PhantomChip<F> {
             },
             rows: vec![],
             streams: OnceLock::new(),
-            phantom_executors: FxHashMap::default(),
+            phantom_executors: Vec::new(),
+            sub_executor_lookup: FxHashMap::default(),
+            journal: None,
+            commit_input_stream: false,
         }
     }
 
@@ -112,70 +122,138 @@ impl<F> PhantomChip<F> {
         }
     }
 
+    pub fn set_journal(&mut self, journal: Option<SharedJournalRecorder<F>>) {
+        self.journal = journal;
+    }
+
+    /// Makes sub-instructions which hint words without reading the input stream fail with
+    /// [ExecutionError::NonInputHint], since the input commitment would absorb their words.
+    pub fn set_commit_input_stream(&mut self, commit_input_stream: bool) {
+        self.commit_input_stream = commit_input_stream;
+    }
+
     pub(crate) fn add_sub_executor<P: PhantomSubExecutor<F> + 'static>(
         &mut self,
         sub_executor: P,
         discriminant: PhantomDiscriminant,
     ) -> Option<Box<dyn PhantomSubExecutor<F>>> {
-        self.phantom_executors
-            .insert(discriminant, Box::new(sub_executor))
+        let sub_executor: Box<dyn PhantomSubExecutor<F>> = Box::new(sub_executor);
+        match self.sub_executor_lookup.get(&discriminant) {
+            Some(&idx) => Some(std::mem::replace(
+                &mut self.phantom_executors[idx],
+                sub_executor,
+            )),
+            None => {
+                self.sub_executor_lookup
+                    .insert(discriminant, self.phantom_executors.len());
+                self.phantom_executors.push(sub_executor);
+                None
+            }
+        }
+    }
+
+    /// Returns the index of the sub-executor for `discriminant`, for use with
+    /// [Self::execute_linked].
+    pub fn sub_executor_idx(&self, discriminant: PhantomDiscriminant) -> Option<usize> {
+        self.sub_executor_lookup.get(&discriminant).copied()
     }
 }
 
-impl<F: PrimeField32> InstructionExecutor<F> for PhantomChip<F> {
-    fn execute(
+impl<F: PrimeField32> PhantomChip<F> {
+    /// Executes a phantom instruction whose sub-executor was resolved by
+    /// [ProgramChip::link](crate::system::program::ProgramChip::link). `sub_executor_idx` is
+    /// `None` for system phantom sub-instructions, which are handled in `ExecutionSegment`.
+    pub fn execute_linked(
         &mut self,
         memory: &mut MemoryController<F>,
         instruction: &Instruction<F>,
         from_state: ExecutionState<u32>,
+        sub_executor_idx: Option<usize>,
     ) -> Result<ExecutionState<u32>, ExecutionError> {
         let &Instruction {
             opcode, a, b, c, ..
         } = instruction;
         assert_eq!(opcode, self.air.phantom_opcode);
 
-        let c_u32 = c.as_canonical_u32();
-        let discriminant = PhantomDiscriminant(c_u32 as u16);
-        // If not a system phantom sub-instruction (which is handled in
-        // ExecutionSegment), look for a phantom sub-executor to handle it.
-        if SysPhantom::from_repr(discriminant.0).is_none() {
-            let sub_executor = self
-                .phantom_executors
-                .get_mut(&discriminant)
-                .ok_or_else(|| ExecutionError::PhantomNotFound {
-                    pc: from_state.pc,
-                    discriminant,
-                })?;
+        if let Some(sub_executor_idx) = sub_executor_idx {
+            let c_u32 = c.as_canonical_u32();
+            let discriminant = PhantomDiscriminant(c_u32 as u16);
+            let sub_executor = &mut self.phantom_executors[sub_executor_idx];
             let mut streams = self.streams.get().unwrap().lock().unwrap();
-            sub_executor
-                .as_mut()
-                .phantom_execute(
-                    memory,
-                    &mut streams,
-                    discriminant,
-                    a,
-                    b,
-                    (c_u32 >> 16) as u16,
-                )
-                .map_err(|e| ExecutionError::Phantom {
-                    pc: from_state.pc,
+            let commit_input_stream = self.commit_input_stream;
+            let execute = |streams: &mut Streams<F>| {
+                let input_len = streams.input_stream.len();
+                let hint_generation = streams.hint_generation();
+                sub_executor
+                    .as_mut()
+                    .phantom_execute(memory, streams, discriminant, a, b, (c_u32 >> 16) as u16)
+                    .map_err(|e| ExecutionError::Phantom {
+                        pc: from_state.pc,
+                        discriminant,
+                        inner: e,
+                    })?;
+                // The input commitment absorbs every hinted word, so only inputs may be hinted.
+                if commit_input_stream
+                    && streams.input_stream.len() == input_len
+                    && streams.hint_generation() != hint_generation
+                {
+                    return Err(ExecutionError::NonInputHint {
+                        pc: from_state.pc,
+                        discriminant,
+                    });
+                }
+                Ok(())
+            };
+            match &self.journal {
+                Some(journal) => journal.lock().unwrap().execute_phantom(
+                    from_state.pc,
                     discriminant,
-                    inner: e,
-                })?;
+                    &mut streams,
+                    execute,
+                )?,
+                None => execute(&mut streams)?,
+            }
         }
 
-        self.rows.push(PhantomCols {
-            pc: F::from_canonical_u32(from_state.pc),
-            operands: [a, b, c],
-            timestamp: F::from_canonical_u32(from_state.timestamp),
-            is_valid: F::ONE,
-        });
+        if !memory.is_execute_only() {
+            self.rows.push(PhantomCols {
+                pc: F::from_canonical_u32(from_state.pc),
+                operands: [a, b, c],
+                timestamp: F::from_canonical_u32(from_state.timestamp),
+                is_valid: F::ONE,
+            });
+        }
         memory.increment_timestamp();
         Ok(ExecutionState::new(
             from_state.pc + DEFAULT_PC_STEP,
             from_state.timestamp + 1,
         ))
     }
+}
+
+impl<F: PrimeField32> InstructionExecutor<F> for PhantomChip<F> {
+    fn execute(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+        from_state: ExecutionState<u32>,
+    ) -> Result<ExecutionState<u32>, ExecutionError> {
+        let discriminant = PhantomDiscriminant(instruction.c.as_canonical_u32() as u16);
+        // If not a system phantom sub-instruction (which is handled in
+        // ExecutionSegment), look for a phantom sub-executor to handle it.
+        let sub_executor_idx = if SysPhantom::from_repr(discriminant.0).is_none() {
+            Some(
+                self.sub_executor_idx(discriminant)
+                    .ok_or(ExecutionError::PhantomNotFound {
+                        pc: from_state.pc,
+                        discriminant,
+                    })?,
+            )
+        } else {
+            None
+        };
+        self.execute_linked(memory, instruction, from_state, sub_executor_idx)
+    }
 
     fn get_opcode_name(&self, _: usize) -> String {
         format!("{:?}", SystemOpcode::PHANTOM)
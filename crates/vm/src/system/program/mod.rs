@@ -1,6 +1,7 @@
 use openvm_instructions::{
     instruction::{DebugInfo, Instruction},
     program::Program,
+    LocalOpcode, PhantomDiscriminant, SysPhantom, SystemOpcode, VmOpcode,
 };
 use openvm_stark_backend::{p3_field::PrimeField64, ChipUsageGetter};
 
@@ -18,18 +19,45 @@ pub use bus::*;
 
 const EXIT_CODE_FAIL: usize = 1;
 
+/// How the execution loop dispatches an instruction, resolved once per program by
+/// [ProgramChip::link] so that the loop does not look up opcodes on every cycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InstructionDispatch {
+    /// `TERMINATE`, handled by the execution loop.
+    Terminate,
+    /// A system phantom instruction, handled by the execution loop before being passed to the
+    /// phantom executor with the given index.
+    SysPhantom {
+        phantom: SysPhantom,
+        executor_idx: usize,
+    },
+    /// A phantom instruction executed by the phantom sub-executor with index `sub_executor_idx`
+    /// of the phantom executor with index `executor_idx`.
+    Phantom {
+        executor_idx: usize,
+        sub_executor_idx: usize,
+    },
+    /// Executed by the executor with the given index in the inventory.
+    Executor(usize),
+    /// No executor in the inventory handles the opcode.
+    Disabled,
+}
+
 #[derive(Debug)]
 pub struct ProgramChip<F> {
     pub air: ProgramAir,
     pub program: Program<F>,
     pub true_program_length: usize,
     pub execution_frequencies: Vec<usize>,
+    /// Dispatch of each instruction in `program`, by pc index. Populated by [Self::link].
+    pub dispatch_table: Vec<InstructionDispatch>,
 }
 
 impl<F: PrimeField64> ProgramChip<F> {
     pub fn new(bus: ProgramBus) -> Self {
         Self {
             execution_frequencies: vec![],
+            dispatch_table: vec![],
             program: Program::default(),
             true_program_length: 0,
             air: ProgramAir { bus },
@@ -51,9 +79,67 @@ impl<F: PrimeField64> ProgramChip<F> {
         }
         self.true_program_length = true_program_length;
         self.execution_frequencies = vec![0; program.len()];
+        self.dispatch_table = vec![InstructionDispatch::Disabled; program.len()];
         self.program = program;
     }
 
+    /// Resolves the dispatch of every instruction in the program, given the index of the
+    /// executor for each opcode and the index of the phantom sub-executor for each discriminant.
+    pub fn link(
+        &mut self,
+        executor_idx: impl Fn(VmOpcode) -> Option<usize>,
+        sub_executor_idx: impl Fn(PhantomDiscriminant) -> Option<usize>,
+    ) {
+        let phantom_opcode = SystemOpcode::PHANTOM.global_opcode();
+        let terminate_opcode = SystemOpcode::TERMINATE.global_opcode();
+        self.dispatch_table = self
+            .program
+            .instructions_and_debug_infos
+            .iter()
+            .map(|instruction| {
+                let Some((instruction, _)) = instruction else {
+                    return InstructionDispatch::Disabled;
+                };
+                let opcode = instruction.opcode;
+                if opcode == terminate_opcode {
+                    return InstructionDispatch::Terminate;
+                }
+                let Some(idx) = executor_idx(opcode) else {
+                    return InstructionDispatch::Disabled;
+                };
+                if opcode == phantom_opcode {
+                    // Note: the discriminant is the lower 16 bits of the c operand.
+                    let discriminant = instruction.c.as_canonical_u64() as u16;
+                    if let Some(phantom) = SysPhantom::from_repr(discriminant) {
+                        return InstructionDispatch::SysPhantom {
+                            phantom,
+                            executor_idx: idx,
+                        };
+                    }
+                    // Unknown discriminants are reported by the phantom executor.
+                    if let Some(sub_executor_idx) =
+                        sub_executor_idx(PhantomDiscriminant(discriminant))
+                    {
+                        return InstructionDispatch::Phantom {
+                            executor_idx: idx,
+                            sub_executor_idx,
+                        };
+                    }
+                }
+                InstructionDispatch::Executor(idx)
+            })
+            .collect();
+    }
+
+    /// Returns the debug info of the instruction at `pc`, without counting it as executed.
+    pub fn get_debug_info(&self, pc: u32) -> Option<&DebugInfo> {
+        let pc_index = self.get_pc_index(pc).ok()?;
+        self.program
+            .get_instruction_and_debug_info(pc_index)?
+            .1
+            .as_ref()
+    }
+
     fn get_pc_index(&self, pc: u32) -> Result<usize, ExecutionError> {
         let step = self.program.step;
         let pc_base = self.program.pc_base;
@@ -69,6 +155,25 @@ impl<F: PrimeField64> ProgramChip<F> {
         Ok(pc_index)
     }
 
+    /// Like [Self::get_instruction], but also returns the dispatch resolved by [Self::link].
+    pub fn get_linked_instruction(
+        &mut self,
+        pc: u32,
+    ) -> Result<(&Instruction<F>, &Option<DebugInfo>, InstructionDispatch), ExecutionError> {
+        let pc_index = self.get_pc_index(pc)?;
+        self.execution_frequencies[pc_index] += 1;
+        let (instruction, debug_info) = self
+            .program
+            .get_instruction_and_debug_info(pc_index)
+            .ok_or(ExecutionError::PcNotFound {
+                pc,
+                step: self.program.step,
+                pc_base: self.program.pc_base,
+                program_len: self.program.len(),
+            })?;
+        Ok((instruction, debug_info, self.dispatch_table[pc_index]))
+    }
+
     pub fn get_instruction(
         &mut self,
         pc: u32,
@@ -3,7 +3,7 @@ use std::iter;
 use openvm_instructions::{
     instruction::Instruction,
     program::{Program, DEFAULT_MAX_NUM_PUBLIC_VALUES, DEFAULT_PC_STEP},
-    LocalOpcode,
+    LocalOpcode, PhantomDiscriminant, SysPhantom,
 };
 use openvm_native_compiler::{
     FieldArithmeticOpcode::*, NativeBranchEqualOpcode, NativeJalOpcode::*, NativeLoadStoreOpcode::*,
@@ -29,7 +29,7 @@ use static_assertions::assert_impl_all;
 
 use crate::{
     arch::{instructions::SystemOpcode::*, testing::READ_INSTRUCTION_BUS},
-    system::program::{trace::VmCommittedExe, ProgramBus, ProgramChip},
+    system::program::{trace::VmCommittedExe, InstructionDispatch, ProgramBus, ProgramChip},
 };
 
 assert_impl_all!(VmCommittedExe<BabyBearPoseidon2Config>: Serialize, DeserializeOwned);
@@ -274,3 +274,62 @@ fn test_program_with_undefined_instructions() {
 
     interaction_test(program, vec![0, 2, 5]);
 }
+
+#[test]
+fn test_program_link() {
+    let instructions = vec![
+        Instruction::large_from_isize(ADD.global_opcode(), 0, 1, 0, 4, 0, 0, 0),
+        Instruction::from_isize(
+            PHANTOM.global_opcode(),
+            0,
+            0,
+            SysPhantom::CtStart as isize,
+            0,
+            0,
+        ),
+        Instruction::from_isize(PHANTOM.global_opcode(), 0, 0, 0x120, 0, 0),
+        Instruction::from_isize(PHANTOM.global_opcode(), 0, 0, 0x121, 0, 0),
+        Instruction::from_isize(STOREW.global_opcode(), 0, 0, 0, 4, 4),
+        Instruction::from_isize(TERMINATE.global_opcode(), 0, 0, 0, 0, 0),
+    ];
+    let program = Program::from_instructions(&instructions);
+    let mut chip = ProgramChip::new_with_program(program, ProgramBus(READ_INSTRUCTION_BUS));
+    chip.link(
+        |opcode| {
+            if opcode == ADD.global_opcode() {
+                Some(3)
+            } else if opcode == PHANTOM.global_opcode() {
+                Some(1)
+            } else {
+                None
+            }
+        },
+        |discriminant| (discriminant == PhantomDiscriminant(0x120)).then_some(2),
+    );
+
+    let dispatches: Vec<_> = (0..instructions.len() as u32)
+        .map(|pc_idx| {
+            chip.get_linked_instruction(pc_idx * DEFAULT_PC_STEP)
+                .unwrap()
+                .2
+        })
+        .collect();
+    assert_eq!(
+        dispatches,
+        vec![
+            InstructionDispatch::Executor(3),
+            InstructionDispatch::SysPhantom {
+                phantom: SysPhantom::CtStart,
+                executor_idx: 1,
+            },
+            InstructionDispatch::Phantom {
+                executor_idx: 1,
+                sub_executor_idx: 2,
+            },
+            // Unknown phantom sub-instructions are reported by the phantom executor.
+            InstructionDispatch::Executor(1),
+            InstructionDispatch::Disabled,
+            InstructionDispatch::Terminate,
+        ]
+    );
+}
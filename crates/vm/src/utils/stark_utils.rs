@@ -53,7 +53,7 @@ where
     let pk = vm.keygen();
     let mut result = vm.execute_and_generate(exe, input).unwrap();
     let final_memory = result.final_memory.take();
-    let proofs = vm.prove(&pk, result);
+    let proofs = vm.prove(&pk, result.per_segment);
 
     assert!(proofs.len() >= min_segments);
     vm.verify(&pk.get_vk(), proofs)
@@ -7,9 +7,10 @@ use std::{
 use openvm_circuit::{
     arch::{
         hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
-        ChipId, ExecutionSegment, MemoryConfig, SingleSegmentVmExecutor, SystemConfig,
-        SystemTraceHeights, VirtualMachine, VmComplexTraceHeights, VmConfig,
-        VmInventoryTraceHeights,
+        ChipId, DefaultSegmentationStrategy, ExecutionError, ExecutionLimits, ExecutionSegment,
+        MemoryConfig, ParallelProvingConfig, SegmentScheduler, SingleSegmentVmExecutor,
+        SystemConfig, SystemTraceHeights, VirtualMachine, VmComplexTraceHeights, VmConfig,
+        VmExecutor, VmInventoryTraceHeights,
     },
     system::{
         memory::{MemoryTraceHeights, VolatileMemoryTraceHeights, CHUNK},
@@ -232,7 +233,7 @@ fn test_vm_1_optional_air() {
             proof_input.per_air.len() < num_airs,
             "Expect less used AIRs"
         );
-        let proofs = vm.prove(&pk, result);
+        let proofs = vm.prove(&pk, result.per_segment);
         vm.verify_single(&pk.get_vk(), &proofs[0])
             .expect("Verification failed");
     }
@@ -377,11 +378,229 @@ fn test_vm_1_persistent() {
     }
 
     let result_for_proof = vm.execute_and_generate(program, vec![]).unwrap();
-    let proofs = vm.prove(&pk, result_for_proof);
+    let proofs = vm.prove(&pk, result_for_proof.per_segment);
     vm.verify(&pk.get_vk(), proofs)
         .expect("Verification failed");
 }
 
+#[test]
+fn test_vm_execute_only() {
+    let n = 1000;
+    let instructions = vec![
+        Instruction::large_from_isize(ADD.global_opcode(), 0, n, 0, 4, 0, 0, 0),
+        Instruction::large_from_isize(SUB.global_opcode(), 0, 0, 1, 4, 4, 0, 0),
+        Instruction::from_isize(
+            NativeBranchEqualOpcode(BNE).global_opcode(),
+            0,
+            0,
+            -(DEFAULT_PC_STEP as isize),
+            4,
+            0,
+        ),
+        Instruction::from_isize(TERMINATE.global_opcode(), 0, 0, 0, 0, 0),
+    ];
+    let program = Program::from_instructions(&instructions);
+
+    let mut config = NativeConfig::aggregation(0, 3).with_continuations();
+    config.system.segmentation_strategy =
+        Arc::new(DefaultSegmentationStrategy::new_with_max_segment_len(200));
+    let executor = VmExecutor::<BabyBear, _>::new(config);
+
+    assert!(
+        executor
+            .execute_segments(program.clone(), vec![])
+            .unwrap()
+            .len()
+            > 1
+    );
+    let final_memory = executor.execute(program.clone(), vec![]).unwrap().unwrap();
+
+    let result = executor.execute_only(program, vec![]).unwrap();
+    assert_eq!(result.exit_code, 0);
+    assert_eq!(result.num_instructions, 2 * n as u64 + 1);
+    assert_eq!(
+        result.final_memory.items().collect::<Vec<_>>(),
+        final_memory.items().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_vm_pipelined_proving() {
+    let n = 500;
+    let instructions = vec![
+        Instruction::large_from_isize(ADD.global_opcode(), 0, n, 0, 4, 0, 0, 0),
+        Instruction::large_from_isize(SUB.global_opcode(), 0, 0, 1, 4, 4, 0, 0),
+        Instruction::from_isize(
+            NativeBranchEqualOpcode(BNE).global_opcode(),
+            0,
+            0,
+            -(DEFAULT_PC_STEP as isize),
+            4,
+            0,
+        ),
+        Instruction::from_isize(TERMINATE.global_opcode(), 0, 0, 0, 0, 0),
+    ];
+    let program = Program::from_instructions(&instructions);
+
+    let mut config = NativeConfig::aggregation(0, 3).with_continuations();
+    config.system.segmentation_strategy =
+        Arc::new(DefaultSegmentationStrategy::new_with_max_segment_len(200));
+    let engine = BabyBearPoseidon2Engine::new(FriParameters::standard_fast());
+    let vm = VirtualMachine::new(engine, config);
+    let pk = vm.keygen();
+    let committed_exe = vm.commit_exe(program.clone());
+
+    let result = vm
+        .execute_and_prove_pipelined(&pk, committed_exe, vec![], 1)
+        .unwrap();
+    assert!(result.per_segment.len() > 1);
+    let expected_memory = vm.execute(program, vec![]).unwrap().unwrap();
+    assert_eq!(
+        result.final_memory.unwrap().items().collect::<Vec<_>>(),
+        expected_memory.items().collect::<Vec<_>>()
+    );
+    vm.verify(&pk.get_vk(), result.per_segment)
+        .expect("Verification failed");
+}
+
+#[test]
+fn test_vm_parallel_proving() {
+    let n = 500;
+    let instructions = vec![
+        Instruction::large_from_isize(ADD.global_opcode(), 0, n, 0, 4, 0, 0, 0),
+        Instruction::large_from_isize(SUB.global_opcode(), 0, 0, 1, 4, 4, 0, 0),
+        Instruction::from_isize(
+            NativeBranchEqualOpcode(BNE).global_opcode(),
+            0,
+            0,
+            -(DEFAULT_PC_STEP as isize),
+            4,
+            0,
+        ),
+        Instruction::from_isize(TERMINATE.global_opcode(), 0, 0, 0, 0, 0),
+    ];
+    let program = Program::from_instructions(&instructions);
+
+    let mut config = NativeConfig::aggregation(0, 3).with_continuations();
+    config.system.segmentation_strategy =
+        Arc::new(DefaultSegmentationStrategy::new_with_max_segment_len(200));
+    let engine = BabyBearPoseidon2Engine::new(FriParameters::standard_fast());
+    let vm = VirtualMachine::new(engine, config);
+    let pk = vm.keygen();
+    let committed_exe = vm.commit_exe(program.clone());
+
+    let scheduler = SegmentScheduler::new(ParallelProvingConfig::new(2, 1));
+    let mut finished = vec![];
+    let final_memory = vm
+        .execute_and_prove_parallel(&pk, committed_exe, vec![], &scheduler, |seg_idx, proof| {
+            finished.push((seg_idx, proof))
+        })
+        .unwrap();
+    let num_segments = finished.len();
+    assert!(num_segments > 1);
+    finished.sort_by_key(|(seg_idx, _)| *seg_idx);
+    let (seg_indices, proofs): (Vec<_>, Vec<_>) = finished.into_iter().unzip();
+    assert_eq!(seg_indices, (0..num_segments).collect::<Vec<_>>());
+    let expected_memory = vm.execute(program, vec![]).unwrap().unwrap();
+    assert_eq!(
+        final_memory.unwrap().items().collect::<Vec<_>>(),
+        expected_memory.items().collect::<Vec<_>>()
+    );
+    vm.verify(&pk.get_vk(), proofs)
+        .expect("Verification failed");
+}
+
+#[test]
+#[should_panic(expected = "segment 1 failed")]
+fn test_segment_scheduler_propagates_panic() {
+    let scheduler = SegmentScheduler::new(ParallelProvingConfig::new(1, 1));
+    scheduler.prove_all(
+        0..4,
+        |seg_idx, _| {
+            if seg_idx == 1 {
+                panic!("segment {seg_idx} failed");
+            }
+        },
+        |_, _| {},
+    );
+}
+
+#[test]
+fn test_vm_execution_limits() {
+    // Loops forever.
+    let program = Program::from_instructions(&[Instruction::<BabyBear>::from_isize(
+        JAL.global_opcode(),
+        0,
+        0,
+        0,
+        4,
+        0,
+    )]);
+    let limits = ExecutionLimits {
+        max_cycles: Some(1000),
+        ..Default::default()
+    };
+    let mut config = NativeConfig::aggregation(0, 3);
+    config.system = config.system.with_limits(limits);
+    let executor = VmExecutor::<BabyBear, _>::new(config.clone());
+    assert!(matches!(
+        executor.execute(program.clone(), vec![]),
+        Err(ExecutionError::CycleLimitExceeded {
+            pc: 0,
+            cycles: 1000
+        })
+    ));
+    assert!(matches!(
+        executor.execute_only(program.clone(), vec![]),
+        Err(ExecutionError::CycleLimitExceeded {
+            pc: 0,
+            cycles: 1000
+        })
+    ));
+
+    let limits = ExecutionLimits {
+        max_segments: Some(3),
+        ..Default::default()
+    };
+    let mut config = config.with_continuations();
+    config.system = config.system.with_max_segment_len(200).with_limits(limits);
+    let executor = VmExecutor::<BabyBear, _>::new(config);
+    assert!(matches!(
+        executor.execute(program, vec![]),
+        Err(ExecutionError::SegmentLimitExceeded { segments: 3, .. })
+    ));
+
+    // Writes to a new page on every iteration.
+    let program = Program::from_instructions(&[
+        Instruction::large_from_isize(ADD.global_opcode(), 0, 0, 4096, 4, 4, 0, 0),
+        Instruction::from_isize(STOREW.global_opcode(), 1, 0, 0, 4, 4),
+        Instruction::from_isize(
+            JAL.global_opcode(),
+            2,
+            -2 * DEFAULT_PC_STEP as isize,
+            0,
+            4,
+            0,
+        ),
+    ]);
+    let limits = ExecutionLimits {
+        max_memory_pages: Some(10),
+        ..Default::default()
+    };
+    let mut config = NativeConfig::aggregation(0, 3);
+    config.system = config.system.with_limits(limits);
+    let executor = VmExecutor::<BabyBear, _>::new(config);
+    for result in [
+        executor.execute(program.clone(), vec![]).map(|_| ()),
+        executor.execute_only(program, vec![]).map(|_| ()),
+    ] {
+        assert!(matches!(
+            result,
+            Err(ExecutionError::MemoryLimitExceeded { pages: 11, .. })
+        ));
+    }
+}
+
 #[test]
 fn test_vm_without_field_arithmetic() {
     /*
@@ -711,7 +930,8 @@ fn test_hint_load_1() {
         vec![vec![F::ONE, F::TWO]].into(),
         None,
         Default::default(),
-    );
+    )
+    .unwrap();
     segment.execute_from_pc(0).unwrap();
     let streams = segment.chip_complex.take_streams();
     assert!(streams.input_stream.is_empty());
@@ -747,7 +967,8 @@ fn test_hint_load_2() {
         vec![vec![F::ONE, F::TWO], vec![F::TWO, F::ONE]].into(),
         None,
         Default::default(),
-    );
+    )
+    .unwrap();
     segment.execute_from_pc(0).unwrap();
     assert_eq!(
         segment
@@ -1,27 +1,66 @@
 use std::{cell::RefCell, rc::Rc};
 
+use num_bigint::BigUint;
 use openvm_mod_circuit_builder::{ExprBuilder, FieldVariable, SymbolicExpr};
 
-/// Quadratic field extension of `Fp` defined by `Fp2 = Fp[u]/(1 + u^2)`. Assumes that `-1` is not a quadratic residue in `Fp`, which is equivalent to `p` being congruent to `3 (mod 4)`.
+/// Quadratic field extension of `Fp` defined by `Fp2 = Fp[u]/(u^2 - non_residue)`, where `non_residue` is a
+/// quadratic non-residue in `Fp`.
+///
+/// By default `non_residue = -1`, i.e. `Fp2 = Fp[u]/(1 + u^2)`. This assumes that `-1` is not a quadratic residue in `Fp`,
+/// which is equivalent to `p` being congruent to `3 (mod 4)`.
 #[derive(Clone)]
 pub struct Fp2 {
     pub c0: FieldVariable,
     pub c1: FieldVariable,
+    /// The quadratic non-residue `u^2`.
+    pub non_residue: isize,
 }
 
 impl Fp2 {
+    pub const DEFAULT_NON_RESIDUE: isize = -1;
+
     pub fn new(builder: Rc<RefCell<ExprBuilder>>) -> Self {
+        Self::new_with_non_residue(builder, Self::DEFAULT_NON_RESIDUE)
+    }
+
+    pub fn new_with_non_residue(builder: Rc<RefCell<ExprBuilder>>, non_residue: isize) -> Self {
         let c0 = ExprBuilder::new_input(builder.clone());
         let c1 = ExprBuilder::new_input(builder.clone());
-        Fp2 { c0, c1 }
+        Fp2 {
+            c0,
+            c1,
+            non_residue,
+        }
+    }
+
+    /// Creates a constant `c0 + c1 * u`.
+    pub fn new_const(
+        builder: Rc<RefCell<ExprBuilder>>,
+        c0: BigUint,
+        c1: BigUint,
+        non_residue: isize,
+    ) -> Self {
+        Fp2 {
+            c0: ExprBuilder::new_const(builder.clone(), c0),
+            c1: ExprBuilder::new_const(builder, c1),
+            non_residue,
+        }
     }
 
     pub fn new_var(builder: Rc<RefCell<ExprBuilder>>) -> ((usize, usize), Fp2) {
+        Self::new_var_with_non_residue(builder, Self::DEFAULT_NON_RESIDUE)
+    }
+
+    pub fn new_var_with_non_residue(
+        builder: Rc<RefCell<ExprBuilder>>,
+        non_residue: isize,
+    ) -> ((usize, usize), Fp2) {
         let (c0_idx, c0) = builder.borrow_mut().new_var();
         let (c1_idx, c1) = builder.borrow_mut().new_var();
         let fp2 = Fp2 {
             c0: FieldVariable::from_var(builder.clone(), c0),
             c1: FieldVariable::from_var(builder.clone(), c1),
+            non_residue,
         };
         ((c0_idx, c1_idx), fp2)
     }
@@ -37,33 +76,65 @@ impl Fp2 {
         self.c1.save_output();
     }
 
+    fn assert_same_field(&self, other: &Fp2) {
+        assert_eq!(
+            self.non_residue, other.non_residue,
+            "Fp2 elements over different extensions"
+        );
+    }
+
     pub fn add(&mut self, other: &mut Fp2) -> Fp2 {
+        self.assert_same_field(other);
         Fp2 {
             c0: &mut self.c0 + &mut other.c0,
             c1: &mut self.c1 + &mut other.c1,
+            non_residue: self.non_residue,
         }
     }
 
     pub fn sub(&mut self, other: &mut Fp2) -> Fp2 {
+        self.assert_same_field(other);
         Fp2 {
             c0: &mut self.c0 - &mut other.c0,
             c1: &mut self.c1 - &mut other.c1,
+            non_residue: self.non_residue,
         }
     }
 
     pub fn mul(&mut self, other: &mut Fp2) -> Fp2 {
-        let c0 = &mut self.c0 * &mut other.c0 - &mut self.c1 * &mut other.c1;
+        self.assert_same_field(other);
+        let c0 = if self.non_residue == -1 {
+            &mut self.c0 * &mut other.c0 - &mut self.c1 * &mut other.c1
+        } else {
+            &mut self.c0 * &mut other.c0 + (&mut self.c1 * &mut other.c1).int_mul(self.non_residue)
+        };
         let c1 = &mut self.c0 * &mut other.c1 + &mut self.c1 * &mut other.c0;
-        Fp2 { c0, c1 }
+        Fp2 {
+            c0,
+            c1,
+            non_residue: self.non_residue,
+        }
     }
 
     pub fn square(&mut self) -> Fp2 {
-        let c0 = self.c0.square() - self.c1.square();
+        let c0 = if self.non_residue == -1 {
+            self.c0.square() - self.c1.square()
+        } else {
+            self.c0.square() + self.c1.square().int_mul(self.non_residue)
+        };
         let c1 = (&mut self.c0 * &mut self.c1).int_mul(2);
-        Fp2 { c0, c1 }
+        Fp2 {
+            c0,
+            c1,
+            non_residue: self.non_residue,
+        }
     }
 
     pub fn div(&mut self, other: &mut Fp2) -> Fp2 {
+        self.assert_same_field(other);
+        if self.non_residue != -1 {
+            return self.div_with_non_residue(other);
+        }
         let builder = self.c0.builder.borrow();
         let prime = builder.prime.clone();
         let limb_bits = builder.limb_bits;
@@ -125,6 +196,79 @@ impl Fp2 {
         Fp2 {
             c0: z0_var,
             c1: z1_var,
+            non_residue: self.non_residue,
+        }
+    }
+
+    /// Division in `Fp[u]/(u^2 - non_residue)` for `non_residue != -1`.
+    /// Same approach as [Fp2::div], with `z = x / y` constrained by `x = y * z`:
+    /// - `x0 = y0 * z0 + non_residue * y1 * z1`
+    /// - `x1 = y1 * z0 + y0 * z1`
+    fn div_with_non_residue(&mut self, other: &mut Fp2) -> Fp2 {
+        let builder = self.c0.builder.borrow();
+        let prime = builder.prime.clone();
+        let limb_bits = builder.limb_bits;
+        let num_limbs = builder.num_limbs;
+        drop(builder);
+        let nr = self.non_residue;
+
+        let fake_z0 = SymbolicExpr::Var(0);
+        let fake_z1 = SymbolicExpr::Var(1);
+
+        // z = x * conj(y) / (y0^2 - non_residue * y1^2)
+        let compute_denom = &other.c0.expr * &other.c0.expr
+            - SymbolicExpr::IntMul(Box::new(&other.c1.expr * &other.c1.expr), nr);
+        let compute_z0_nom = &self.c0.expr * &other.c0.expr
+            - SymbolicExpr::IntMul(Box::new(&self.c1.expr * &other.c1.expr), nr);
+        let compute_z0 = &compute_z0_nom / &compute_denom;
+        let compute_z1_nom = &self.c1.expr * &other.c0.expr - &self.c0.expr * &other.c1.expr;
+        let compute_z1 = &compute_z1_nom / &compute_denom;
+
+        // Constraint 1: x0 = y0*z0 + non_residue*y1*z1
+        let constraint1 = &self.c0.expr
+            - &other.c0.expr * &fake_z0
+            - SymbolicExpr::IntMul(Box::new(&other.c1.expr * &fake_z1), nr);
+        let carry_bits = constraint1.constraint_carry_bits_with_pq(&prime, limb_bits, num_limbs);
+        if carry_bits > self.c0.range_checker_bits {
+            self.save();
+        }
+        let constraint1 = &self.c0.expr
+            - &other.c0.expr * &fake_z0
+            - SymbolicExpr::IntMul(Box::new(&other.c1.expr * &fake_z1), nr);
+        let carry_bits = constraint1.constraint_carry_bits_with_pq(&prime, limb_bits, num_limbs);
+        if carry_bits > self.c0.range_checker_bits {
+            other.save();
+        }
+
+        // Constraint 2: x1 = y1*z0 + y0*z1
+        let constraint2 = &self.c1.expr - &other.c1.expr * &fake_z0 - &other.c0.expr * &fake_z1;
+        let carry_bits = constraint2.constraint_carry_bits_with_pq(&prime, limb_bits, num_limbs);
+        if carry_bits > self.c0.range_checker_bits {
+            self.save();
+        }
+        let constraint2 = &self.c1.expr - &other.c1.expr * &fake_z0 - &other.c0.expr * &fake_z1;
+        let carry_bits = constraint2.constraint_carry_bits_with_pq(&prime, limb_bits, num_limbs);
+        if carry_bits > self.c0.range_checker_bits {
+            other.save();
+        }
+
+        let mut builder = self.c0.builder.borrow_mut();
+        let (z0_idx, z0) = builder.new_var();
+        let (z1_idx, z1) = builder.new_var();
+        let constraint1 = &self.c0.expr
+            - &other.c0.expr * &z0
+            - SymbolicExpr::IntMul(Box::new(&other.c1.expr * &z1), nr);
+        let constraint2 = &self.c1.expr - &other.c1.expr * &z0 - &other.c0.expr * &z1;
+        builder.set_compute(z0_idx, compute_z0);
+        builder.set_compute(z1_idx, compute_z1);
+        builder.set_constraint(z0_idx, constraint1);
+        builder.set_constraint(z1_idx, constraint2);
+        drop(builder);
+
+        Fp2 {
+            c0: FieldVariable::from_var(self.c0.builder.clone(), z0),
+            c1: FieldVariable::from_var(self.c0.builder.clone(), z1),
+            non_residue: nr,
         }
     }
 
@@ -132,6 +276,7 @@ impl Fp2 {
         Fp2 {
             c0: &mut self.c0 * fp,
             c1: &mut self.c1 * fp,
+            non_residue: self.non_residue,
         }
     }
 
@@ -139,14 +284,21 @@ impl Fp2 {
         Fp2 {
             c0: self.c0.int_add(c[0]),
             c1: self.c1.int_add(c[1]),
+            non_residue: self.non_residue,
         }
     }
 
     // c is like a Fp2, but with both c0 and c1 being very small numbers.
     pub fn int_mul(&mut self, c: [isize; 2]) -> Fp2 {
+        let c0 = if self.non_residue == -1 {
+            self.c0.int_mul(c[0]) - self.c1.int_mul(c[1])
+        } else {
+            self.c0.int_mul(c[0]) + self.c1.int_mul(c[1] * self.non_residue)
+        };
         Fp2 {
-            c0: self.c0.int_mul(c[0]) - self.c1.int_mul(c[1]),
+            c0,
             c1: self.c0.int_mul(c[1]) + self.c1.int_mul(c[0]),
+            non_residue: self.non_residue,
         }
     }
 
@@ -155,9 +307,11 @@ impl Fp2 {
     }
 
     pub fn select(flag_id: usize, a: &Fp2, b: &Fp2) -> Fp2 {
+        a.assert_same_field(b);
         Fp2 {
             c0: FieldVariable::select(flag_id, &a.c0, &b.c0),
             c1: FieldVariable::select(flag_id, &a.c1, &b.c1),
+            non_residue: a.non_residue,
         }
     }
 }
@@ -299,4 +453,58 @@ mod tests {
         )
         .expect("Verification failed");
     }
+
+    #[test]
+    fn test_fp2_non_residue() {
+        // -2 is a quadratic non-residue modulo the BN254 prime since p = 7 (mod 8).
+        let non_residue = -2;
+        let prime = BN254_MODULUS.clone();
+        let (range_checker, builder) = setup(&prime);
+
+        let mut x_fp2 = Fp2::new_with_non_residue(builder.clone(), non_residue);
+        let mut y_fp2 = Fp2::new_with_non_residue(builder.clone(), non_residue);
+        let mut z_fp2 = Fp2::new_with_non_residue(builder.clone(), non_residue);
+        let mut xy = x_fp2.mul(&mut y_fp2);
+        let _r = xy.div(&mut z_fp2);
+
+        let builder = builder.borrow().clone();
+        let air = FieldExpr::new(builder, range_checker.bus(), false);
+        let width = BaseAir::<BabyBear>::width(&air);
+
+        let inputs = (0..6)
+            .map(|_| generate_random_biguint(&prime))
+            .collect::<Vec<_>>();
+        let beta = &prime - BigUint::from(2u32);
+        let mul = |a: (&BigUint, &BigUint), b: (&BigUint, &BigUint)| {
+            (
+                (a.0 * b.0 + &beta * a.1 * b.1) % &prime,
+                (a.0 * b.1 + a.1 * b.0) % &prime,
+            )
+        };
+        let xy = mul((&inputs[0], &inputs[1]), (&inputs[2], &inputs[3]));
+        // z^-1 = conj(z) / (z0^2 - beta * z1^2)
+        let norm = (&inputs[4] * &inputs[4] + (&prime - &beta) * &inputs[5] * &inputs[5]) % &prime;
+        let norm_inv = norm.modpow(&(&prime - BigUint::from(2u32)), &prime);
+        let z_inv = (
+            &inputs[4] * &norm_inv % &prime,
+            (&prime - &inputs[5]) * &norm_inv % &prime,
+        );
+        let expected = mul((&xy.0, &xy.1), (&z_inv.0, &z_inv.1));
+
+        let mut row = BabyBear::zero_vec(width);
+        air.generate_subrow((&range_checker, inputs, vec![]), &mut row);
+        let FieldExprCols { vars, .. } = air.load_vars(&row);
+        let trace = RowMajorMatrix::new(row, width);
+        let range_trace = range_checker.generate_trace();
+        let r_c0 = evaluate_biguint(&vars[vars.len() - 2], LIMB_BITS);
+        let r_c1 = evaluate_biguint(&vars[vars.len() - 1], LIMB_BITS);
+        assert_eq!(r_c0, expected.0);
+        assert_eq!(r_c1, expected.1);
+
+        BabyBearBlake3Engine::run_simple_test_no_pis_fast(
+            any_rap_arc_vec![air, range_checker.air],
+            vec![trace, range_trace],
+        )
+        .expect("Verification failed");
+    }
 }
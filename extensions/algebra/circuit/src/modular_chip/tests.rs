@@ -5,7 +5,7 @@ use num_traits::Zero;
 use openvm_algebra_transpiler::Rv32ModularArithmeticOpcode;
 use openvm_circuit::arch::{
     instructions::LocalOpcode,
-    testing::{VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
+    testing::{MutationConfig, VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
 };
 use openvm_circuit_primitives::{
     bigint::utils::{big_uint_to_limbs, secp256k1_coord_prime, secp256k1_scalar_prime},
@@ -18,14 +18,18 @@ use openvm_mod_circuit_builder::{
 };
 use openvm_pairing_guest::bls12_381::BLS12_381_MODULUS;
 use openvm_rv32_adapters::{
-    rv32_write_heap_default, write_ptr_reg, Rv32IsEqualModAdapterChip, Rv32VecHeapAdapterChip,
+    rv32_write_heap_default, write_ptr_reg, Rv32IsEqualModAdapterChip, Rv32IsEqualModAdapterCols,
+    Rv32VecHeapAdapterChip,
 };
 use openvm_rv32im_circuit::adapters::RV32_REGISTER_NUM_LIMBS;
 use openvm_stark_backend::p3_field::FieldAlgebra;
 use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
 use rand::Rng;
 
-use super::{ModularAddSubChip, ModularIsEqualChip, ModularIsEqualCoreChip, ModularMulDivChip};
+use super::{
+    ModularAddSubChip, ModularIsEqualChip, ModularIsEqualCoreChip, ModularIsEqualCoreCols,
+    ModularMulDivChip,
+};
 
 const NUM_LIMBS: usize = 32;
 const LIMB_BITS: usize = 8;
@@ -293,16 +297,21 @@ fn test_muldiv(opcode_offset: usize, modulus: BigUint) {
     tester.simple_test().expect("Verification failed");
 }
 
-fn test_is_equal<const NUM_LANES: usize, const LANE_SIZE: usize, const TOTAL_LIMBS: usize>(
+/// Creates a `ModularIsEqualChip` for `modulus` and executes its setup instruction.
+fn create_is_equal_chip<
+    const NUM_LANES: usize,
+    const LANE_SIZE: usize,
+    const TOTAL_LIMBS: usize,
+>(
+    tester: &mut VmChipTestBuilder<F>,
     opcode_offset: usize,
-    modulus: BigUint,
-    num_tests: usize,
+    modulus: &BigUint,
+) -> (
+    ModularIsEqualChip<F, NUM_LANES, LANE_SIZE, TOTAL_LIMBS>,
+    SharedBitwiseOperationLookupChip<LIMB_BITS>,
 ) {
-    let mut rng = create_seeded_rng();
     let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
     let bitwise_chip = SharedBitwiseOperationLookupChip::<LIMB_BITS>::new(bitwise_bus);
-
-    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
     let mut chip = ModularIsEqualChip::<F, NUM_LANES, LANE_SIZE, TOTAL_LIMBS>::new(
         Rv32IsEqualModAdapterChip::new(
             tester.execution_bus(),
@@ -315,24 +324,53 @@ fn test_is_equal<const NUM_LANES: usize, const LANE_SIZE: usize, const TOTAL_LIM
         tester.offline_memory_mutex_arc(),
     );
 
-    {
-        let vec = big_uint_to_limbs(&modulus, LIMB_BITS);
-        let modulus_limbs: [F; TOTAL_LIMBS] = std::array::from_fn(|i| {
-            if i < vec.len() {
-                F::from_canonical_usize(vec[i])
-            } else {
-                F::ZERO
-            }
-        });
-
-        let setup_instruction = rv32_write_heap_default::<TOTAL_LIMBS>(
-            &mut tester,
-            vec![modulus_limbs],
-            vec![[F::ZERO; TOTAL_LIMBS]],
-            opcode_offset + Rv32ModularArithmeticOpcode::SETUP_ISEQ as usize,
-        );
-        tester.execute(&mut chip, &setup_instruction);
-    }
+    let vec = big_uint_to_limbs(modulus, LIMB_BITS);
+    let modulus_limbs: [F; TOTAL_LIMBS] = std::array::from_fn(|i| {
+        if i < vec.len() {
+            F::from_canonical_usize(vec[i])
+        } else {
+            F::ZERO
+        }
+    });
+    let setup_instruction = rv32_write_heap_default::<TOTAL_LIMBS>(
+        tester,
+        vec![modulus_limbs],
+        vec![[F::ZERO; TOTAL_LIMBS]],
+        opcode_offset + Rv32ModularArithmeticOpcode::SETUP_ISEQ as usize,
+    );
+    tester.execute(&mut chip, &setup_instruction);
+    (chip, bitwise_chip)
+}
+
+fn execute_is_equal<const NUM_LANES: usize, const LANE_SIZE: usize, const TOTAL_LIMBS: usize>(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut ModularIsEqualChip<F, NUM_LANES, LANE_SIZE, TOTAL_LIMBS>,
+    opcode_offset: usize,
+    b: [u32; TOTAL_LIMBS],
+    c: [u32; TOTAL_LIMBS],
+) {
+    let instruction = rv32_write_heap_default::<TOTAL_LIMBS>(
+        tester,
+        vec![b.map(F::from_canonical_u32)],
+        vec![c.map(F::from_canonical_u32)],
+        opcode_offset + Rv32ModularArithmeticOpcode::IS_EQ as usize,
+    );
+    tester.execute(chip, &instruction);
+}
+
+fn test_is_equal<const NUM_LANES: usize, const LANE_SIZE: usize, const TOTAL_LIMBS: usize>(
+    opcode_offset: usize,
+    modulus: BigUint,
+    num_tests: usize,
+) {
+    let mut rng = create_seeded_rng();
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let (mut chip, bitwise_chip) = create_is_equal_chip::<NUM_LANES, LANE_SIZE, TOTAL_LIMBS>(
+        &mut tester,
+        opcode_offset,
+        &modulus,
+    );
+
     for _ in 0..num_tests {
         let b = generate_field_element::<TOTAL_LIMBS, LIMB_BITS>(&modulus, &mut rng);
         let c = if rng.gen_bool(0.5) {
@@ -340,27 +378,14 @@ fn test_is_equal<const NUM_LANES: usize, const LANE_SIZE: usize, const TOTAL_LIM
         } else {
             generate_field_element::<TOTAL_LIMBS, LIMB_BITS>(&modulus, &mut rng)
         };
-
-        let instruction = rv32_write_heap_default::<TOTAL_LIMBS>(
-            &mut tester,
-            vec![b.map(F::from_canonical_u32)],
-            vec![c.map(F::from_canonical_u32)],
-            opcode_offset + Rv32ModularArithmeticOpcode::IS_EQ as usize,
-        );
-        tester.execute(&mut chip, &instruction);
+        execute_is_equal(&mut tester, &mut chip, opcode_offset, b, c);
     }
 
     // Special case where b == c are close to the prime
     let b_vec = big_uint_to_limbs(&modulus, LIMB_BITS);
     let mut b = from_fn(|i| if i < b_vec.len() { b_vec[i] as u32 } else { 0 });
     b[0] -= 1;
-    let instruction = rv32_write_heap_default::<TOTAL_LIMBS>(
-        &mut tester,
-        vec![b.map(F::from_canonical_u32)],
-        vec![b.map(F::from_canonical_u32)],
-        opcode_offset + Rv32ModularArithmeticOpcode::IS_EQ as usize,
-    );
-    tester.execute(&mut chip, &instruction);
+    execute_is_equal(&mut tester, &mut chip, opcode_offset, b, b);
 
     let tester = tester.build().load(chip).load(bitwise_chip).finalize();
     tester.simple_test().expect("Verification failed");
@@ -375,3 +400,42 @@ fn test_modular_is_equal_1x32() {
 fn test_modular_is_equal_3x16() {
     test_is_equal::<3, 16, 48>(17, BLS12_381_MODULUS.clone(), 100);
 }
+
+#[test]
+fn test_modular_is_equal_mutation() {
+    const TOTAL_LIMBS: usize = 32;
+    let opcode_offset = 17;
+    let modulus = secp256k1_coord_prime();
+    let mut rng = create_seeded_rng();
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let (mut chip, bitwise_chip) =
+        create_is_equal_chip::<1, 32, TOTAL_LIMBS>(&mut tester, opcode_offset, &modulus);
+
+    // One equal and one unequal comparison.
+    let b = generate_field_element::<TOTAL_LIMBS, LIMB_BITS>(&modulus, &mut rng);
+    let mut c = b;
+    c[0] ^= 1;
+    execute_is_equal(&mut tester, &mut chip, opcode_offset, b, b);
+    execute_is_equal(&mut tester, &mut chip, opcode_offset, b, c);
+
+    // The inverse markers of equal limbs do not affect the comparison: every marker of the
+    // equal row, and every marker but the first of the unequal row.
+    let eq_markers = |from: usize| (from..TOTAL_LIMBS).map(|i| format!("eq_marker[{i}]"));
+    tester.assert_mutations_rejected(
+        chip,
+        [
+            Rv32IsEqualModAdapterCols::<F, 2, 1, 32>::column_names(),
+            ModularIsEqualCoreCols::<F, TOTAL_LIMBS>::column_names(),
+        ]
+        .concat(),
+        |tester| tester.load(bitwise_chip),
+        &MutationConfig {
+            rows: vec![1, 2],
+            boundary_values: vec![F::ZERO, F::NEG_ONE],
+            column_swaps: true,
+            dont_care: vec![],
+        }
+        .with_dont_care([1], eq_markers(0))
+        .with_dont_care([2], eq_markers(1)),
+    );
+}
@@ -1,6 +1,6 @@
 use derive_more::derive::From;
 use num_bigint::BigUint;
-use openvm_algebra_transpiler::Rv32ModularArithmeticOpcode;
+use openvm_algebra_transpiler::{ModularPhantom, Rv32ModularArithmeticOpcode};
 use openvm_circuit::{
     self,
     arch::{SystemPort, VmExtension, VmInventory, VmInventoryBuilder, VmInventoryError},
@@ -11,7 +11,7 @@ use openvm_circuit_primitives::bitwise_op_lookup::{
     BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
 };
 use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
-use openvm_instructions::{LocalOpcode, VmOpcode};
+use openvm_instructions::{LocalOpcode, PhantomDiscriminant, VmOpcode};
 use openvm_mod_circuit_builder::ExprBuilderConfig;
 use openvm_rv32_adapters::{Rv32IsEqualModAdapterChip, Rv32VecHeapAdapterChip};
 use openvm_stark_backend::p3_field::PrimeField32;
@@ -217,6 +217,110 @@ impl<F: PrimeField32> VmExtension<F> for ModularExtension {
             }
         }
 
+        builder.add_phantom_sub_executor(
+            phantom::SqrtHintSubEx::new(self.supported_modulus.clone()),
+            PhantomDiscriminant(ModularPhantom::HintSqrt as u16),
+        )?;
+
         Ok(inventory)
     }
 }
+
+pub(crate) mod phantom {
+    use std::iter::repeat;
+
+    use eyre::bail;
+    use num_bigint::BigUint;
+    use openvm_algebra_guest::{find_non_qr, sqrt_biguint};
+    use openvm_circuit::{
+        arch::{PhantomSubExecutor, Streams},
+        system::memory::MemoryController,
+    };
+    use openvm_instructions::{riscv::RV32_MEMORY_AS, PhantomDiscriminant};
+    use openvm_rv32im_circuit::adapters::unsafe_read_rv32_register;
+    use openvm_stark_backend::p3_field::PrimeField32;
+
+    pub struct SqrtHintSubEx {
+        pub supported_modulus: Vec<BigUint>,
+        /// The smallest quadratic non-residue for each modulus, if the modulus is an odd prime.
+        /// This must match the `NON_QR` constant generated by `moduli_declare!`.
+        pub non_qrs: Vec<Option<BigUint>>,
+    }
+
+    impl SqrtHintSubEx {
+        pub fn new(supported_modulus: Vec<BigUint>) -> Self {
+            let non_qrs = supported_modulus.iter().map(find_non_qr).collect();
+            Self {
+                supported_modulus,
+                non_qrs,
+            }
+        }
+    }
+
+    impl<F: PrimeField32> PhantomSubExecutor<F> for SqrtHintSubEx {
+        fn phantom_execute(
+            &mut self,
+            memory: &MemoryController<F>,
+            streams: &mut Streams<F>,
+            _: PhantomDiscriminant,
+            a: F,
+            _: F,
+            c_upper: u16,
+        ) -> eyre::Result<()> {
+            let mod_idx = c_upper as usize;
+            if mod_idx >= self.supported_modulus.len() {
+                bail!(
+                    "Modulus index {mod_idx} out of range: {} supported moduli",
+                    self.supported_modulus.len()
+                );
+            }
+            let modulus = &self.supported_modulus[mod_idx];
+            let Some(non_qr) = &self.non_qrs[mod_idx] else {
+                bail!("Square root hint is only supported for odd prime moduli, got {modulus}");
+            };
+            let num_limbs: usize = if modulus.bits().div_ceil(8) <= 32 {
+                32
+            } else if modulus.bits().div_ceil(8) <= 48 {
+                48
+            } else {
+                bail!("Modulus too large")
+            };
+
+            let rs1 = unsafe_read_rv32_register(memory, a);
+            let mut x_limbs: Vec<u8> = Vec::with_capacity(num_limbs);
+            for i in 0..num_limbs {
+                let limb = memory.unsafe_read_cell(
+                    F::from_canonical_u32(RV32_MEMORY_AS),
+                    F::from_canonical_u32(rs1 + i as u32),
+                );
+                x_limbs.push(limb.as_canonical_u32() as u8);
+            }
+            let x = BigUint::from_bytes_le(&x_limbs) % modulus;
+
+            // If `x` is not a square, `x * non_qr` is, and its square root proves it.
+            let (is_square, root) = match sqrt_biguint(&x, modulus, non_qr) {
+                Some(root) => (true, root),
+                None => {
+                    let Some(root) = sqrt_biguint(&(x * non_qr), modulus, non_qr) else {
+                        bail!("Failed to compute square root modulo {modulus}");
+                    };
+                    (false, root)
+                }
+            };
+
+            let is_square_bytes = (is_square as u32).to_le_bytes();
+            streams.set_hint_stream(
+                is_square_bytes
+                    .into_iter()
+                    .chain(
+                        root.to_bytes_le()
+                            .into_iter()
+                            .chain(repeat(0u8))
+                            .take(num_limbs),
+                    )
+                    .map(F::from_canonical_u8),
+            );
+            Ok(())
+        }
+    }
+}
@@ -17,6 +17,7 @@ pub enum ModArithBaseFunct7 {
     DivMod,
     IsEqMod,
     SetupMod,
+    HintSqrt,
 }
 
 impl ModArithBaseFunct7 {
@@ -232,6 +233,39 @@ pub trait IntMod:
     }
 }
 
+/// Square root in a prime field.
+///
+/// For types declared with `moduli_declare!`, the square root is computed on the host via the
+/// `HintSqrt` phantom instruction. The host returns either a square root of `self`, or a square
+/// root of `self * non_qr` for a fixed quadratic non-residue `non_qr` as a proof that `self` is
+/// not a square. Either way, the guest verifies the hint with a single multiplication.
+pub trait Sqrt: Sized {
+    /// Returns a square root of `self` if it exists, and `None` otherwise.
+    ///
+    /// The choice between the two square roots `r` and `-r` is not specified.
+    fn sqrt(&self) -> Option<Self>;
+}
+
+/// Reads the output of a `HintSqrt` phantom instruction from the hint stream: a `u32` flag which
+/// is nonzero if and only if the input is a square, followed by `num_limbs` bytes of the root.
+///
+/// # Safety
+/// `sqrt_ptr` must be valid for writes of `num_limbs` bytes and 4-byte aligned.
+#[cfg(target_os = "zkvm")]
+#[doc(hidden)]
+#[inline(always)]
+pub unsafe fn hint_sqrt_result(sqrt_ptr: *mut u8, num_limbs: usize) -> bool {
+    let mut is_square = core::mem::MaybeUninit::<u32>::uninit();
+    openvm_rv32im_guest::hint_store_u32!(is_square.as_mut_ptr());
+    openvm_rv32im_guest::hint_buffer_u32!(sqrt_ptr, num_limbs / 4);
+    is_square.assume_init() != 0
+}
+
+/// Host implementation of [Sqrt] for types declared with `moduli_declare!`.
+#[cfg(not(target_os = "zkvm"))]
+#[doc(hidden)]
+pub use openvm_macros_common::sqrt::{find_non_qr, sqrt_biguint};
+
 // Ref: https://docs.rs/elliptic-curve/latest/elliptic_curve/ops/trait.Reduce.html
 pub trait Reduce: Sized {
     /// Interpret the given bytes as an integer and perform a modular reduction.
@@ -4,7 +4,8 @@ extern crate proc_macro;
 
 use std::sync::atomic::AtomicUsize;
 
-use openvm_macros_common::{string_to_bytes, MacroArgs};
+use num_bigint::BigUint;
+use openvm_macros_common::{sqrt::find_non_qr, string_to_bytes, MacroArgs};
 use proc_macro::TokenStream;
 use quote::format_ident;
 use syn::{
@@ -99,6 +100,7 @@ pub fn moduli_declare(input: TokenStream) -> TokenStream {
         create_extern_func!(mul_extern_func);
         create_extern_func!(div_extern_func);
         create_extern_func!(is_eq_extern_func);
+        create_extern_func!(hint_sqrt_extern_func);
 
         let block_size = proc_macro::Literal::usize_unsuffixed(block_size);
         let block_size = syn::Lit::new(block_size.to_string().parse::<_>().unwrap());
@@ -116,6 +118,7 @@ pub fn moduli_declare(input: TokenStream) -> TokenStream {
                 fn #mul_extern_func(rd: usize, rs1: usize, rs2: usize);
                 fn #div_extern_func(rd: usize, rs1: usize, rs2: usize);
                 fn #is_eq_extern_func(rs1: usize, rs2: usize) -> bool;
+                fn #hint_sqrt_extern_func(rs1: usize);
             }
 
             impl #struct_name {
@@ -676,6 +679,68 @@ pub fn moduli_declare(input: TokenStream) -> TokenStream {
         });
 
         output.push(result);
+
+        // `Sqrt` is only implemented when the modulus looks like an odd prime, as witnessed by
+        // a small quadratic non-residue. The host uses the same non-residue in `HintSqrt`.
+        if let Some(non_qr) = find_non_qr(&BigUint::from_bytes_le(&modulus_bytes)) {
+            let non_qr_bytes = non_qr
+                .to_bytes_le()
+                .into_iter()
+                .chain(vec![0u8; limbs])
+                .take(limbs)
+                .collect::<Vec<_>>();
+            let sqrt_result = TokenStream::from(quote::quote_spanned! { span.into() =>
+                impl #struct_name {
+                    /// The smallest quadratic non-residue modulo the modulus.
+                    pub const NON_QR: Self = Self([#(#non_qr_bytes),*]);
+
+                    /// Returns `(is_square, root)` where `root` is a square root of `self` if
+                    /// `is_square`, and a square root of `self * Self::NON_QR` otherwise.
+                    /// The result is **not** verified.
+                    #[cfg(target_os = "zkvm")]
+                    fn hint_sqrt_impl(&self) -> (bool, Self) {
+                        let mut root = core::mem::MaybeUninit::<#struct_name>::uninit();
+                        unsafe {
+                            #hint_sqrt_extern_func(self as *const #struct_name as usize);
+                            let is_square = openvm_algebra_guest::hint_sqrt_result(
+                                root.as_mut_ptr() as *mut u8,
+                                #limbs,
+                            );
+                            (is_square, root.assume_init())
+                        }
+                    }
+                }
+
+                impl openvm_algebra_guest::Sqrt for #struct_name {
+                    fn sqrt(&self) -> Option<Self> {
+                        #[cfg(not(target_os = "zkvm"))]
+                        {
+                            use openvm_algebra_guest::IntMod;
+
+                            openvm_algebra_guest::sqrt_biguint(
+                                &self.as_biguint(),
+                                &Self::modulus_biguint(),
+                                &Self::NON_QR.as_biguint(),
+                            )
+                            .map(Self::from_biguint)
+                        }
+                        #[cfg(target_os = "zkvm")]
+                        {
+                            let (is_square, root) = self.hint_sqrt_impl();
+                            // Equality checks also constrain both sides to be reduced.
+                            if is_square {
+                                assert_eq!(&root * &root, *self, "invalid sqrt hint");
+                                Some(root)
+                            } else {
+                                assert_eq!(&root * &root, self * &Self::NON_QR, "invalid sqrt hint");
+                                None
+                            }
+                        }
+                    }
+                }
+            });
+            output.push(sqrt_result);
+        }
     }
 
     TokenStream::from_iter(output)
@@ -808,6 +873,24 @@ pub fn moduli_init(input: TokenStream) -> TokenStream {
             }
         });
 
+        let hint_sqrt_extern_func = syn::Ident::new(
+            &format!("hint_sqrt_extern_func_{}", modulus_hex),
+            span.into(),
+        );
+        externs.push(quote::quote_spanned! { span.into() =>
+            #[no_mangle]
+            extern "C" fn #hint_sqrt_extern_func(rs1: usize) {
+                openvm::platform::custom_insn_r!(
+                    opcode = ::openvm_algebra_guest::OPCODE,
+                    funct3 = ::openvm_algebra_guest::MODULAR_ARITHMETIC_FUNCT3 as usize,
+                    funct7 = ::openvm_algebra_guest::ModArithBaseFunct7::HintSqrt as usize + #mod_idx * (::openvm_algebra_guest::ModArithBaseFunct7::MODULAR_ARITHMETIC_MAX_KINDS as usize),
+                    rd = Const "x0",
+                    rs1 = In rs1,
+                    rs2 = Const "x0"
+                );
+            }
+        });
+
         setup_all_moduli.push(quote::quote_spanned! { span.into() =>
             #setup_function();
         });
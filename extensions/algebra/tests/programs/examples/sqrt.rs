@@ -0,0 +1,37 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use openvm_algebra_guest::{IntMod, Sqrt};
+
+openvm::entry!(main);
+
+openvm_algebra_moduli_macros::moduli_declare! {
+    // p = 3 mod 4
+    Secp256k1Coord { modulus = "0xFFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFE FFFFFC2F" },
+    // p - 1 has 2-adicity 28, which exercises Tonelli-Shanks on the host
+    Bn254Scalar { modulus = "21888242871839275222246405745257275088548364400416034343698204186575808495617" },
+}
+
+openvm_algebra_moduli_macros::moduli_init!(
+    "0xFFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFE FFFFFC2F",
+    "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+);
+
+fn test_sqrt<F: IntMod + Sqrt>(non_qr: &F) {
+    for i in 0..16u32 {
+        let square = F::from_u32(i * i + 7).square();
+        let root = square.sqrt().expect("square must have a square root");
+        assert_eq!(root.square(), square);
+
+        // a square times a non-residue is a non-residue
+        let non_square = square * non_qr;
+        assert!(non_square.sqrt().is_none());
+    }
+    assert_eq!(F::ZERO.sqrt(), Some(F::ZERO));
+}
+
+pub fn main() {
+    setup_all_moduli();
+    test_sqrt(&Secp256k1Coord::NON_QR);
+    test_sqrt(&Bn254Scalar::NON_QR);
+}
@@ -55,6 +55,28 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_sqrt() -> Result<()> {
+        let elf = build_example_program_at_path(get_programs_dir!(), "sqrt")?;
+        let openvm_exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension)
+                .with_extension(ModularTranspilerExtension),
+        )?;
+        let config = Rv32ModularConfig::new(vec![
+            SECP256K1_CONFIG.modulus.clone(),
+            BigUint::from_str(
+                "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+            )
+            .unwrap(),
+        ]);
+        air_test(config, openvm_exe);
+        Ok(())
+    }
+
     #[test]
     fn test_complex_two_moduli() -> Result<()> {
         let elf = build_example_program_at_path(get_programs_dir!(), "complex-two-modulos")?;
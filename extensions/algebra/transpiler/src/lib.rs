@@ -3,7 +3,8 @@ use openvm_algebra_guest::{
     MODULAR_ARITHMETIC_FUNCT3, OPCODE,
 };
 use openvm_instructions::{
-    instruction::Instruction, riscv::RV32_REGISTER_NUM_LIMBS, LocalOpcode, VmOpcode,
+    instruction::Instruction, riscv::RV32_REGISTER_NUM_LIMBS, LocalOpcode, PhantomDiscriminant,
+    VmOpcode,
 };
 use openvm_instructions_derive::LocalOpcode;
 use openvm_stark_backend::p3_field::PrimeField32;
@@ -43,6 +44,12 @@ pub enum Fp2Opcode {
     SETUP_MULDIV,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, FromRepr)]
+#[repr(u16)]
+pub enum ModularPhantom {
+    HintSqrt = 0x50,
+}
+
 #[derive(Default)]
 pub struct ModularTranspilerExtension;
 
@@ -73,10 +80,20 @@ impl<F: PrimeField32> TranspilerExtension<F> for ModularTranspilerExtension {
                 Rv32ModularArithmeticOpcode::COUNT
                     <= ModArithBaseFunct7::MODULAR_ARITHMETIC_MAX_KINDS as usize
             );
-            let mod_idx_shift = ((dec_insn.funct7 as u8)
+            let mod_idx = ((dec_insn.funct7 as u8)
                 / ModArithBaseFunct7::MODULAR_ARITHMETIC_MAX_KINDS)
-                as usize
-                * Rv32ModularArithmeticOpcode::COUNT;
+                as usize;
+            let mod_idx_shift = mod_idx * Rv32ModularArithmeticOpcode::COUNT;
+            if let Some(ModArithBaseFunct7::HintSqrt) = ModArithBaseFunct7::from_repr(base_funct7) {
+                assert_eq!(dec_insn.rd, 0);
+                assert_eq!(dec_insn.rs2, 0);
+                return Some(TranspilerOutput::one_to_one(Instruction::phantom(
+                    PhantomDiscriminant(ModularPhantom::HintSqrt as u16),
+                    F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs1),
+                    F::ZERO,
+                    mod_idx as u16,
+                )));
+            }
             if base_funct7 == ModArithBaseFunct7::SetupMod as u8 {
                 let local_opcode = match dec_insn.rs2 {
                     0 => Rv32ModularArithmeticOpcode::SETUP_ADDSUB,
@@ -1,5 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
+use openvm_algebra_circuit::Fp2;
 use openvm_circuit_primitives::var_range::VariableRangeCheckerBus;
 use openvm_mod_circuit_builder::{ExprBuilder, ExprBuilderConfig, FieldExpr};
 
@@ -24,3 +25,26 @@ pub fn ec_add_ne_expr(
     let builder = builder.borrow().clone();
     FieldExpr::new(builder, range_bus, true)
 }
+
+pub fn ec_add_ne_fp2_expr(
+    config: ExprBuilderConfig, // The base field of the Fp2 coordinates.
+    range_bus: VariableRangeCheckerBus,
+    non_residue: isize,
+) -> FieldExpr {
+    config.check_valid();
+    let builder = ExprBuilder::new(config, range_bus.range_max_bits);
+    let builder = Rc::new(RefCell::new(builder));
+
+    let mut x1 = Fp2::new_with_non_residue(builder.clone(), non_residue);
+    let mut y1 = Fp2::new_with_non_residue(builder.clone(), non_residue);
+    let mut x2 = Fp2::new_with_non_residue(builder.clone(), non_residue);
+    let mut y2 = Fp2::new_with_non_residue(builder.clone(), non_residue);
+    let mut lambda = y2.sub(&mut y1).div(&mut x2.sub(&mut x1));
+    let mut x3 = lambda.square().sub(&mut x1).sub(&mut x2);
+    x3.save_output();
+    let mut y3 = lambda.mul(&mut x1.sub(&mut x3)).sub(&mut y1);
+    y3.save_output();
+
+    let builder = builder.borrow().clone();
+    FieldExpr::new(builder, range_bus, true)
+}
@@ -1,7 +1,8 @@
 use std::{cell::RefCell, rc::Rc};
 
 use num_bigint::BigUint;
-use num_traits::One;
+use num_traits::{One, Zero};
+use openvm_algebra_circuit::Fp2;
 use openvm_circuit_primitives::var_range::VariableRangeCheckerBus;
 use openvm_mod_circuit_builder::{ExprBuilder, ExprBuilderConfig, FieldExpr, FieldVariable};
 
@@ -34,3 +35,46 @@ pub fn ec_double_ne_expr(
     let builder = builder.borrow().clone();
     FieldExpr::new_with_setup_values(builder, range_bus, true, vec![a_biguint])
 }
+
+/// `a` is the curve coefficient in `Fp2`, given as `[c0, c1]`.
+pub fn ec_double_fp2_expr(
+    config: ExprBuilderConfig, // The base field of the Fp2 coordinates.
+    range_bus: VariableRangeCheckerBus,
+    non_residue: isize,
+    a_biguint: [BigUint; 2],
+) -> FieldExpr {
+    config.check_valid();
+    let builder = ExprBuilder::new(config, range_bus.range_max_bits);
+    let builder = Rc::new(RefCell::new(builder));
+
+    let mut x1 = Fp2::new_with_non_residue(builder.clone(), non_residue);
+    let mut y1 = Fp2::new_with_non_residue(builder.clone(), non_residue);
+    let [a_c0, a_c1] = a_biguint;
+    let mut a = Fp2::new_const(builder.clone(), a_c0.clone(), a_c1.clone(), non_residue);
+    let is_double_flag = builder.borrow_mut().new_flag();
+    // We need to prevent divide by zero when not double flag
+    // (equivalently, when it is the setup opcode)
+    let mut lambda_denom = Fp2::select(
+        is_double_flag,
+        &y1.int_mul([2, 0]),
+        &Fp2::new_const(
+            builder.clone(),
+            BigUint::one(),
+            BigUint::zero(),
+            non_residue,
+        ),
+    );
+    let mut lambda = x1
+        .square()
+        .int_mul([3, 0])
+        .add(&mut a)
+        .div(&mut lambda_denom);
+    let mut x3 = lambda.square().sub(&mut x1.int_mul([2, 0]));
+    x3.save_output();
+    let mut y3 = lambda.mul(&mut x1.sub(&mut x3)).sub(&mut y1);
+    y3.save_output();
+
+    let builder = builder.borrow().clone();
+    // The setup point is `((p, 0), a)`, so the values after the modulus are `0, a.c0, a.c1`.
+    FieldExpr::new_with_setup_values(builder, range_bus, true, vec![BigUint::zero(), a_c0, a_c1])
+}
@@ -97,3 +97,81 @@ impl<F: PrimeField32, const BLOCKS: usize, const BLOCK_SIZE: usize>
         Self(VmChipWrapper::new(adapter, core, offline_memory))
     }
 }
+
+/// Same as [EcAddNeChip], but for curves with coordinates in `Fp2 = Fp[u]/(u^2 - non_residue)`.
+/// Each coordinate is two `Fp` elements, so for bn254 G2, BLOCK_SIZE = 32 and BLOCKS = 4.
+#[derive(Chip, ChipUsageGetter, InstructionExecutor)]
+pub struct EcAddNeFp2Chip<F: PrimeField32, const BLOCKS: usize, const BLOCK_SIZE: usize>(
+    VmChipWrapper<
+        F,
+        Rv32VecHeapAdapterChip<F, 2, BLOCKS, BLOCKS, BLOCK_SIZE, BLOCK_SIZE>,
+        FieldExpressionCoreChip,
+    >,
+);
+
+impl<F: PrimeField32, const BLOCKS: usize, const BLOCK_SIZE: usize>
+    EcAddNeFp2Chip<F, BLOCKS, BLOCK_SIZE>
+{
+    pub fn new(
+        adapter: Rv32VecHeapAdapterChip<F, 2, BLOCKS, BLOCKS, BLOCK_SIZE, BLOCK_SIZE>,
+        config: ExprBuilderConfig,
+        offset: usize,
+        non_residue: isize,
+        range_checker: SharedVariableRangeCheckerChip,
+        offline_memory: Arc<Mutex<OfflineMemory<F>>>,
+    ) -> Self {
+        let expr = ec_add_ne_fp2_expr(config, range_checker.bus(), non_residue);
+        let core = FieldExpressionCoreChip::new(
+            expr,
+            offset,
+            vec![
+                Rv32WeierstrassOpcode::EC_ADD_NE as usize,
+                Rv32WeierstrassOpcode::SETUP_EC_ADD_NE as usize,
+            ],
+            vec![],
+            range_checker,
+            "EcAddNeFp2",
+            false,
+        );
+        Self(VmChipWrapper::new(adapter, core, offline_memory))
+    }
+}
+
+/// Same as [EcDoubleChip], but for curves with coordinates in `Fp2 = Fp[u]/(u^2 - non_residue)`.
+#[derive(Chip, ChipUsageGetter, InstructionExecutor)]
+pub struct EcDoubleFp2Chip<F: PrimeField32, const BLOCKS: usize, const BLOCK_SIZE: usize>(
+    VmChipWrapper<
+        F,
+        Rv32VecHeapAdapterChip<F, 1, BLOCKS, BLOCKS, BLOCK_SIZE, BLOCK_SIZE>,
+        FieldExpressionCoreChip,
+    >,
+);
+
+impl<F: PrimeField32, const BLOCKS: usize, const BLOCK_SIZE: usize>
+    EcDoubleFp2Chip<F, BLOCKS, BLOCK_SIZE>
+{
+    pub fn new(
+        adapter: Rv32VecHeapAdapterChip<F, 1, BLOCKS, BLOCKS, BLOCK_SIZE, BLOCK_SIZE>,
+        range_checker: SharedVariableRangeCheckerChip,
+        config: ExprBuilderConfig,
+        offset: usize,
+        non_residue: isize,
+        a: [BigUint; 2],
+        offline_memory: Arc<Mutex<OfflineMemory<F>>>,
+    ) -> Self {
+        let expr = ec_double_fp2_expr(config, range_checker.bus(), non_residue, a);
+        let core = FieldExpressionCoreChip::new(
+            expr,
+            offset,
+            vec![
+                Rv32WeierstrassOpcode::EC_DOUBLE as usize,
+                Rv32WeierstrassOpcode::SETUP_EC_DOUBLE as usize,
+            ],
+            vec![],
+            range_checker,
+            "EcDoubleFp2",
+            true,
+        );
+        Self(VmChipWrapper::new(adapter, core, offline_memory))
+    }
+}
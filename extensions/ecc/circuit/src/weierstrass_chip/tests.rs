@@ -2,19 +2,25 @@ use std::str::FromStr;
 
 use num_bigint::BigUint;
 use num_traits::{FromPrimitive, Num, Zero};
-use openvm_circuit::arch::testing::{VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS};
+use openvm_circuit::arch::testing::{MutationConfig, VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS};
 use openvm_circuit_primitives::{
     bigint::utils::{secp256k1_coord_prime, secp256r1_coord_prime},
     bitwise_op_lookup::{BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip},
 };
+use openvm_ecc_guest::halo2curves::{
+    bn256::{Fq, G2Affine},
+    group::{prime::PrimeCurveAffine, Curve},
+};
 use openvm_ecc_transpiler::Rv32WeierstrassOpcode;
 use openvm_instructions::{riscv::RV32_CELL_BITS, LocalOpcode};
 use openvm_mod_circuit_builder::{test_utils::biguint_to_limbs, ExprBuilderConfig, FieldExpr};
-use openvm_rv32_adapters::{rv32_write_heap_default, Rv32VecHeapAdapterChip};
+use openvm_rv32_adapters::{
+    rv32_write_heap_default, Rv32VecHeapAdapterChip, Rv32VecHeapAdapterCols,
+};
 use openvm_stark_backend::p3_field::FieldAlgebra;
 use openvm_stark_sdk::p3_baby_bear::BabyBear;
 
-use super::{EcAddNeChip, EcDoubleChip};
+use super::{EcAddNeChip, EcAddNeFp2Chip, EcDoubleChip, EcDoubleFp2Chip};
 
 const NUM_LIMBS: usize = 32;
 const LIMB_BITS: usize = 8;
@@ -77,9 +83,13 @@ fn prime_limbs(expr: &FieldExpr) -> Vec<BabyBear> {
         .collect::<Vec<_>>()
 }
 
-#[test]
-fn test_add_ne() {
-    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+/// Creates an `EcAddNeChip` for secp256k1 and executes its setup instruction.
+fn create_add_ne_chip(
+    tester: &mut VmChipTestBuilder<F>,
+) -> (
+    EcAddNeChip<F, 2, BLOCK_SIZE>,
+    SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+) {
     let config = ExprBuilderConfig {
         modulus: secp256k1_coord_prime(),
         num_limbs: NUM_LIMBS,
@@ -101,20 +111,45 @@ fn test_add_ne() {
         tester.range_checker(),
         tester.offline_memory_mutex_arc(),
     );
+
+    let prime_limbs: [BabyBear; NUM_LIMBS] = prime_limbs(chip.0.core.expr()).try_into().unwrap();
+    let one_limbs = [BabyBear::ONE; NUM_LIMBS];
+    let setup_instruction = rv32_write_heap_default(
+        tester,
+        vec![prime_limbs, one_limbs], // inputs[0] = prime, others doesn't matter
+        vec![one_limbs, one_limbs],
+        chip.0.core.air.offset + Rv32WeierstrassOpcode::SETUP_EC_ADD_NE as usize,
+    );
+    tester.execute(&mut chip, &setup_instruction);
+    (chip, bitwise_chip)
+}
+
+fn execute_add_ne(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut EcAddNeChip<F, 2, BLOCK_SIZE>,
+    (p1_x, p1_y): &(BigUint, BigUint),
+    (p2_x, p2_y): &(BigUint, BigUint),
+) {
+    let limbs = |x: &BigUint| {
+        biguint_to_limbs::<NUM_LIMBS>(x.clone(), LIMB_BITS).map(BabyBear::from_canonical_u32)
+    };
+    let instruction = rv32_write_heap_default(
+        tester,
+        vec![limbs(p1_x), limbs(p1_y)],
+        vec![limbs(p2_x), limbs(p2_y)],
+        chip.0.core.air.offset + Rv32WeierstrassOpcode::EC_ADD_NE as usize,
+    );
+    tester.execute(chip, &instruction);
+}
+
+#[test]
+fn test_add_ne() {
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let (mut chip, bitwise_chip) = create_add_ne_chip(&mut tester);
     assert_eq!(chip.0.core.expr().builder.num_variables, 3); // lambda, x3, y3
 
     let (p1_x, p1_y) = SampleEcPoints[0].clone();
     let (p2_x, p2_y) = SampleEcPoints[1].clone();
-
-    let p1_x_limbs =
-        biguint_to_limbs::<NUM_LIMBS>(p1_x.clone(), LIMB_BITS).map(BabyBear::from_canonical_u32);
-    let p1_y_limbs =
-        biguint_to_limbs::<NUM_LIMBS>(p1_y.clone(), LIMB_BITS).map(BabyBear::from_canonical_u32);
-    let p2_x_limbs =
-        biguint_to_limbs::<NUM_LIMBS>(p2_x.clone(), LIMB_BITS).map(BabyBear::from_canonical_u32);
-    let p2_y_limbs =
-        biguint_to_limbs::<NUM_LIMBS>(p2_y.clone(), LIMB_BITS).map(BabyBear::from_canonical_u32);
-
     let r = chip
         .0
         .core
@@ -124,25 +159,12 @@ fn test_add_ne() {
     assert_eq!(r[1], SampleEcPoints[2].0);
     assert_eq!(r[2], SampleEcPoints[2].1);
 
-    let prime_limbs: [BabyBear; NUM_LIMBS] = prime_limbs(chip.0.core.expr()).try_into().unwrap();
-    let mut one_limbs = [BabyBear::ONE; NUM_LIMBS];
-    one_limbs[0] = BabyBear::ONE;
-    let setup_instruction = rv32_write_heap_default(
+    execute_add_ne(
         &mut tester,
-        vec![prime_limbs, one_limbs], // inputs[0] = prime, others doesn't matter
-        vec![one_limbs, one_limbs],
-        chip.0.core.air.offset + Rv32WeierstrassOpcode::SETUP_EC_ADD_NE as usize,
+        &mut chip,
+        &SampleEcPoints[0],
+        &SampleEcPoints[1],
     );
-    tester.execute(&mut chip, &setup_instruction);
-
-    let instruction = rv32_write_heap_default(
-        &mut tester,
-        vec![p1_x_limbs, p1_y_limbs],
-        vec![p2_x_limbs, p2_y_limbs],
-        chip.0.core.air.offset + Rv32WeierstrassOpcode::EC_ADD_NE as usize,
-    );
-
-    tester.execute(&mut chip, &instruction);
 
     let tester = tester.build().load(chip).load(bitwise_chip).finalize();
 
@@ -298,3 +320,180 @@ fn test_p256_double() {
 
     tester.simple_test().expect("Verification failed");
 }
+
+fn bn254_coord_prime() -> BigUint {
+    BigUint::from_str(
+        "21888242871839275222246405745257275088696311157297823662689037894645226208583",
+    )
+    .unwrap()
+}
+
+fn fq_to_biguint(x: Fq) -> BigUint {
+    BigUint::from_bytes_le(&x.to_bytes())
+}
+
+/// The coordinates of a bn254 G2 point as `[x.c0, x.c1, y.c0, y.c1]`.
+fn g2_to_biguints(p: &G2Affine) -> Vec<BigUint> {
+    vec![
+        fq_to_biguint(p.x.c0),
+        fq_to_biguint(p.x.c1),
+        fq_to_biguint(p.y.c0),
+        fq_to_biguint(p.y.c1),
+    ]
+}
+
+fn biguints_to_limbs(x: &[BigUint]) -> Vec<[BabyBear; NUM_LIMBS]> {
+    x.iter()
+        .map(|x| {
+            biguint_to_limbs::<NUM_LIMBS>(x.clone(), LIMB_BITS).map(BabyBear::from_canonical_u32)
+        })
+        .collect()
+}
+
+#[test]
+fn test_fp2_add_ne() {
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let config = ExprBuilderConfig {
+        modulus: bn254_coord_prime(),
+        num_limbs: NUM_LIMBS,
+        limb_bits: LIMB_BITS,
+    };
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+    let adapter = Rv32VecHeapAdapterChip::<F, 2, 4, 4, BLOCK_SIZE, BLOCK_SIZE>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_bridge(),
+        tester.address_bits(),
+        bitwise_chip.clone(),
+    );
+    let mut chip = EcAddNeFp2Chip::new(
+        adapter,
+        config,
+        Rv32WeierstrassOpcode::CLASS_OFFSET,
+        -1,
+        tester.range_checker(),
+        tester.offline_memory_mutex_arc(),
+    );
+
+    let p1 = G2Affine::generator();
+    let p2 = (p1 + p1).to_affine();
+    let p3 = (p1 + p2).to_affine();
+    let inputs = [g2_to_biguints(&p1), g2_to_biguints(&p2)].concat();
+
+    let r = chip.0.core.expr().execute_with_output(inputs, vec![true]);
+    assert_eq!(r, g2_to_biguints(&p3));
+
+    let prime_limbs: [BabyBear; NUM_LIMBS] = prime_limbs(chip.0.core.expr()).try_into().unwrap();
+    let zero_limbs = [BabyBear::ZERO; NUM_LIMBS];
+    let mut one_limbs = [BabyBear::ZERO; NUM_LIMBS];
+    one_limbs[0] = BabyBear::ONE;
+    let setup_instruction = rv32_write_heap_default(
+        &mut tester,
+        // inputs[0] = prime, others don't matter
+        vec![prime_limbs, zero_limbs, one_limbs, one_limbs],
+        vec![one_limbs, zero_limbs, one_limbs, zero_limbs],
+        chip.0.core.air.offset + Rv32WeierstrassOpcode::SETUP_EC_ADD_NE as usize,
+    );
+    tester.execute(&mut chip, &setup_instruction);
+
+    let instruction = rv32_write_heap_default(
+        &mut tester,
+        biguints_to_limbs(&g2_to_biguints(&p1)),
+        biguints_to_limbs(&g2_to_biguints(&p2)),
+        chip.0.core.air.offset + Rv32WeierstrassOpcode::EC_ADD_NE as usize,
+    );
+    tester.execute(&mut chip, &instruction);
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn test_fp2_double() {
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let config = ExprBuilderConfig {
+        modulus: bn254_coord_prime(),
+        num_limbs: NUM_LIMBS,
+        limb_bits: LIMB_BITS,
+    };
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+    let adapter = Rv32VecHeapAdapterChip::<F, 1, 4, 4, BLOCK_SIZE, BLOCK_SIZE>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_bridge(),
+        tester.address_bits(),
+        bitwise_chip.clone(),
+    );
+    let mut chip = EcDoubleFp2Chip::new(
+        adapter,
+        tester.range_checker(),
+        config,
+        Rv32WeierstrassOpcode::CLASS_OFFSET,
+        -1,
+        [BigUint::zero(), BigUint::zero()],
+        tester.offline_memory_mutex_arc(),
+    );
+
+    let p1 = G2Affine::generator();
+    let p2 = (p1 + p1).to_affine();
+
+    let r = chip
+        .0
+        .core
+        .expr()
+        .execute_with_output(g2_to_biguints(&p1), vec![true]);
+    assert_eq!(r, g2_to_biguints(&p2));
+
+    let prime_limbs: [BabyBear; NUM_LIMBS] = prime_limbs(chip.0.core.expr()).try_into().unwrap();
+    let zero_limbs = [BabyBear::ZERO; NUM_LIMBS];
+    let setup_instruction = rv32_write_heap_default(
+        &mut tester,
+        // inputs = ((prime, 0), a)
+        vec![prime_limbs, zero_limbs, zero_limbs, zero_limbs],
+        vec![],
+        chip.0.core.air.offset + Rv32WeierstrassOpcode::SETUP_EC_DOUBLE as usize,
+    );
+    tester.execute(&mut chip, &setup_instruction);
+
+    let instruction = rv32_write_heap_default(
+        &mut tester,
+        biguints_to_limbs(&g2_to_biguints(&p1)),
+        vec![],
+        chip.0.core.air.offset + Rv32WeierstrassOpcode::EC_DOUBLE as usize,
+    );
+    tester.execute(&mut chip, &instruction);
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn test_add_ne_mutation() {
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let (mut chip, bitwise_chip) = create_add_ne_chip(&mut tester);
+    execute_add_ne(
+        &mut tester,
+        &mut chip,
+        &SampleEcPoints[0],
+        &SampleEcPoints[1],
+    );
+
+    let column_names = [
+        Rv32VecHeapAdapterCols::<F, 2, 2, 2, BLOCK_SIZE, BLOCK_SIZE>::column_names(),
+        chip.0.core.expr().column_names(),
+    ]
+    .concat();
+    tester.assert_mutations_rejected(
+        chip,
+        column_names,
+        |tester| tester.load(bitwise_chip),
+        &MutationConfig {
+            rows: vec![1],
+            boundary_values: vec![F::ZERO],
+            column_swaps: false,
+            dont_care: vec![],
+        },
+    );
+}
@@ -1,3 +1,5 @@
+use std::sync::{Arc, Mutex};
+
 use derive_more::derive::From;
 use num_bigint::BigUint;
 use num_traits::{FromPrimitive, Zero};
@@ -5,11 +7,12 @@ use once_cell::sync::Lazy;
 use openvm_algebra_guest::IntMod;
 use openvm_circuit::{
     arch::{SystemPort, VmExtension, VmInventory, VmInventoryBuilder, VmInventoryError},
-    system::phantom::PhantomChip,
+    system::{memory::OfflineMemory, phantom::PhantomChip},
 };
 use openvm_circuit_derive::{AnyEnum, InstructionExecutor};
-use openvm_circuit_primitives::bitwise_op_lookup::{
-    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip},
+    var_range::SharedVariableRangeCheckerChip,
 };
 use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
 use openvm_ecc_guest::{
@@ -25,7 +28,7 @@ use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use strum::EnumCount;
 
-use super::{EcAddNeChip, EcDoubleChip};
+use super::{EcAddNeChip, EcAddNeFp2Chip, EcDoubleChip, EcDoubleFp2Chip};
 
 #[serde_as]
 #[derive(Clone, Debug, derive_new::new, Serialize, Deserialize)]
@@ -39,9 +42,42 @@ pub struct CurveConfig {
     /// The coefficient a of y^2 = x^3 + ax + b.
     #[serde_as(as = "DisplayFromStr")]
     pub a: BigUint,
-    /// The coefficient b of y^2 = x^3 + ax + b.
+    /// The coefficient b of y^2 = x^3 + ax + b. It is only used to decompress points, which is
+    /// not supported for curves with `Fp2` coordinates.
     #[serde_as(as = "DisplayFromStr")]
     pub b: BigUint,
+    /// If set, the coordinates of the curve are in the quadratic extension `Fp2` of the field
+    /// defined by `modulus`, and `a` above is the `c0` coefficient of the curve equation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[new(default)]
+    pub fp2: Option<Fp2CurveConfig>,
+}
+
+/// Parameters of a curve with coordinates in `Fp2 = Fp[u]/(u^2 - non_residue)`.
+#[serde_as]
+#[derive(Clone, Debug, derive_new::new, Serialize, Deserialize)]
+pub struct Fp2CurveConfig {
+    /// The quadratic non-residue in `Fp` that `u^2` is equal to.
+    pub non_residue: isize,
+    /// The `c1` coefficient of a, where `a = a_c0 + a_c1 * u`.
+    #[serde_as(as = "DisplayFromStr")]
+    pub a_c1: BigUint,
+}
+
+impl CurveConfig {
+    /// Config for a curve with coordinates in `Fp2 = Fp[u]/(u^2 - non_residue)` where `Fp` is
+    /// defined by `modulus`. The coefficient a is given as `[c0, c1]`. The coefficient b is not
+    /// needed by the chips and is set to zero.
+    pub fn new_fp2(modulus: BigUint, scalar: BigUint, non_residue: isize, a: [BigUint; 2]) -> Self {
+        let [a_c0, a_c1] = a;
+        Self {
+            modulus,
+            scalar,
+            a: a_c0,
+            b: BigUint::zero(),
+            fp2: Some(Fp2CurveConfig::new(non_residue, a_c1)),
+        }
+    }
 }
 
 pub static SECP256K1_CONFIG: Lazy<CurveConfig> = Lazy::new(|| CurveConfig {
@@ -49,6 +85,7 @@ pub static SECP256K1_CONFIG: Lazy<CurveConfig> = Lazy::new(|| CurveConfig {
     scalar: SECP256K1_ORDER.clone(),
     a: BigUint::zero(),
     b: BigUint::from_u8(7u8).unwrap(),
+    fp2: None,
 });
 
 pub static P256_CONFIG: Lazy<CurveConfig> = Lazy::new(|| CurveConfig {
@@ -56,6 +93,7 @@ pub static P256_CONFIG: Lazy<CurveConfig> = Lazy::new(|| CurveConfig {
     scalar: P256_ORDER.clone(),
     a: BigUint::from_bytes_le(P256_A.as_le_bytes()),
     b: BigUint::from_bytes_le(P256_B.as_le_bytes()),
+    fp2: None,
 });
 
 #[derive(Clone, Debug, derive_new::new, Serialize, Deserialize)]
@@ -71,6 +109,12 @@ pub enum WeierstrassExtensionExecutor<F: PrimeField32> {
     // 48 limbs prime
     EcAddNeRv32_48(EcAddNeChip<F, 6, 16>),
     EcDoubleRv32_48(EcDoubleChip<F, 6, 16>),
+    // 32 limbs prime, Fp2 coordinates
+    EcAddNeFp2Rv32_32(EcAddNeFp2Chip<F, 4, 32>),
+    EcDoubleFp2Rv32_32(EcDoubleFp2Chip<F, 4, 32>),
+    // 48 limbs prime, Fp2 coordinates
+    EcAddNeFp2Rv32_48(EcAddNeFp2Chip<F, 12, 16>),
+    EcDoubleFp2Rv32_48(EcDoubleFp2Chip<F, 12, 16>),
 }
 
 #[derive(ChipUsageGetter, Chip, AnyEnum, From)]
@@ -88,11 +132,6 @@ impl<F: PrimeField32> VmExtension<F> for WeierstrassExtension {
         builder: &mut VmInventoryBuilder<F>,
     ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
         let mut inventory = VmInventory::new();
-        let SystemPort {
-            execution_bus,
-            program_bus,
-            memory_bridge,
-        } = builder.system_port();
         let bitwise_lu_chip = if let Some(&chip) = builder
             .find_chip::<SharedBitwiseOperationLookupChip<8>>()
             .first()
@@ -116,99 +155,57 @@ impl<F: PrimeField32> VmExtension<F> for WeierstrassExtension {
             let start_offset =
                 Rv32WeierstrassOpcode::CLASS_OFFSET + i * Rv32WeierstrassOpcode::COUNT;
             let bytes = curve.modulus.bits().div_ceil(8);
-            let config32 = ExprBuilderConfig {
-                modulus: curve.modulus.clone(),
-                num_limbs: 32,
-                limb_bits: 8,
-            };
-            let config48 = ExprBuilderConfig {
-                modulus: curve.modulus.clone(),
-                num_limbs: 48,
-                limb_bits: 8,
-            };
-            if bytes <= 32 {
-                let add_ne_chip = EcAddNeChip::new(
-                    Rv32VecHeapAdapterChip::<F, 2, 2, 2, 32, 32>::new(
-                        execution_bus,
-                        program_bus,
-                        memory_bridge,
-                        pointer_bits,
-                        bitwise_lu_chip.clone(),
-                    ),
-                    config32.clone(),
-                    start_offset,
-                    range_checker.clone(),
-                    offline_memory.clone(),
-                );
-                inventory.add_executor(
-                    WeierstrassExtensionExecutor::EcAddNeRv32_32(add_ne_chip),
-                    ec_add_ne_opcodes
-                        .clone()
-                        .map(|x| VmOpcode::from_usize(x + start_offset)),
-                )?;
-                let double_chip = EcDoubleChip::new(
-                    Rv32VecHeapAdapterChip::<F, 1, 2, 2, 32, 32>::new(
-                        execution_bus,
-                        program_bus,
-                        memory_bridge,
-                        pointer_bits,
-                        bitwise_lu_chip.clone(),
-                    ),
-                    range_checker.clone(),
-                    config32.clone(),
-                    start_offset,
-                    curve.a.clone(),
-                    offline_memory.clone(),
-                );
-                inventory.add_executor(
-                    WeierstrassExtensionExecutor::EcDoubleRv32_32(double_chip),
-                    ec_double_opcodes
-                        .clone()
-                        .map(|x| VmOpcode::from_usize(x + start_offset)),
-                )?;
+            let num_limbs = if bytes <= 32 {
+                32
             } else if bytes <= 48 {
-                let add_ne_chip = EcAddNeChip::new(
-                    Rv32VecHeapAdapterChip::<F, 2, 6, 6, 16, 16>::new(
-                        execution_bus,
-                        program_bus,
-                        memory_bridge,
-                        pointer_bits,
-                        bitwise_lu_chip.clone(),
-                    ),
-                    config48.clone(),
-                    start_offset,
-                    range_checker.clone(),
-                    offline_memory.clone(),
-                );
-                inventory.add_executor(
-                    WeierstrassExtensionExecutor::EcAddNeRv32_48(add_ne_chip),
-                    ec_add_ne_opcodes
-                        .clone()
-                        .map(|x| VmOpcode::from_usize(x + start_offset)),
-                )?;
-                let double_chip = EcDoubleChip::new(
-                    Rv32VecHeapAdapterChip::<F, 1, 6, 6, 16, 16>::new(
-                        execution_bus,
-                        program_bus,
-                        memory_bridge,
-                        pointer_bits,
-                        bitwise_lu_chip.clone(),
-                    ),
-                    range_checker.clone(),
-                    config48.clone(),
-                    start_offset,
-                    curve.a.clone(),
-                    offline_memory.clone(),
-                );
-                inventory.add_executor(
-                    WeierstrassExtensionExecutor::EcDoubleRv32_48(double_chip),
-                    ec_double_opcodes
-                        .clone()
-                        .map(|x| VmOpcode::from_usize(x + start_offset)),
-                )?;
+                48
             } else {
                 panic!("Modulus too large");
-            }
+            };
+            let chips = CurveChipBuilder {
+                system_port: builder.system_port(),
+                pointer_bits,
+                bitwise_lu_chip: bitwise_lu_chip.clone(),
+                range_checker: range_checker.clone(),
+                offline_memory: offline_memory.clone(),
+                config: ExprBuilderConfig {
+                    modulus: curve.modulus.clone(),
+                    num_limbs,
+                    limb_bits: 8,
+                },
+                offset: start_offset,
+            };
+            use WeierstrassExtensionExecutor::*;
+            let (add_ne_chip, double_chip) = match &curve.fp2 {
+                None if num_limbs == 32 => (
+                    EcAddNeRv32_32(chips.add_ne()),
+                    EcDoubleRv32_32(chips.double(curve.a.clone())),
+                ),
+                None => (
+                    EcAddNeRv32_48(chips.add_ne()),
+                    EcDoubleRv32_48(chips.double(curve.a.clone())),
+                ),
+                Some(fp2) if num_limbs == 32 => (
+                    EcAddNeFp2Rv32_32(chips.add_ne_fp2(fp2)),
+                    EcDoubleFp2Rv32_32(chips.double_fp2(&curve.a, fp2)),
+                ),
+                Some(fp2) => (
+                    EcAddNeFp2Rv32_48(chips.add_ne_fp2(fp2)),
+                    EcDoubleFp2Rv32_48(chips.double_fp2(&curve.a, fp2)),
+                ),
+            };
+            inventory.add_executor(
+                add_ne_chip,
+                ec_add_ne_opcodes
+                    .clone()
+                    .map(|x| VmOpcode::from_usize(x + start_offset)),
+            )?;
+            inventory.add_executor(
+                double_chip,
+                ec_double_opcodes
+                    .clone()
+                    .map(|x| VmOpcode::from_usize(x + start_offset)),
+            )?;
         }
         builder.add_phantom_sub_executor(
             phantom::DecompressHintSubEx::new(self.supported_curves.clone()),
@@ -219,13 +216,95 @@ impl<F: PrimeField32> VmExtension<F> for WeierstrassExtension {
     }
 }
 
+/// Builds the chips of one curve. The number of blocks of the adapters is determined by the type
+/// of the chip.
+struct CurveChipBuilder<F: PrimeField32> {
+    system_port: SystemPort,
+    pointer_bits: usize,
+    bitwise_lu_chip: SharedBitwiseOperationLookupChip<8>,
+    range_checker: SharedVariableRangeCheckerChip,
+    offline_memory: Arc<Mutex<OfflineMemory<F>>>,
+    config: ExprBuilderConfig,
+    offset: usize,
+}
+
+impl<F: PrimeField32> CurveChipBuilder<F> {
+    fn adapter<const NUM_READS: usize, const BLOCKS: usize, const BLOCK_SIZE: usize>(
+        &self,
+    ) -> Rv32VecHeapAdapterChip<F, NUM_READS, BLOCKS, BLOCKS, BLOCK_SIZE, BLOCK_SIZE> {
+        Rv32VecHeapAdapterChip::new(
+            self.system_port.execution_bus,
+            self.system_port.program_bus,
+            self.system_port.memory_bridge,
+            self.pointer_bits,
+            self.bitwise_lu_chip.clone(),
+        )
+    }
+
+    fn add_ne<const BLOCKS: usize, const BLOCK_SIZE: usize>(
+        &self,
+    ) -> EcAddNeChip<F, BLOCKS, BLOCK_SIZE> {
+        EcAddNeChip::new(
+            self.adapter(),
+            self.config.clone(),
+            self.offset,
+            self.range_checker.clone(),
+            self.offline_memory.clone(),
+        )
+    }
+
+    fn double<const BLOCKS: usize, const BLOCK_SIZE: usize>(
+        &self,
+        a: BigUint,
+    ) -> EcDoubleChip<F, BLOCKS, BLOCK_SIZE> {
+        EcDoubleChip::new(
+            self.adapter(),
+            self.range_checker.clone(),
+            self.config.clone(),
+            self.offset,
+            a,
+            self.offline_memory.clone(),
+        )
+    }
+
+    fn add_ne_fp2<const BLOCKS: usize, const BLOCK_SIZE: usize>(
+        &self,
+        fp2: &Fp2CurveConfig,
+    ) -> EcAddNeFp2Chip<F, BLOCKS, BLOCK_SIZE> {
+        EcAddNeFp2Chip::new(
+            self.adapter(),
+            self.config.clone(),
+            self.offset,
+            fp2.non_residue,
+            self.range_checker.clone(),
+            self.offline_memory.clone(),
+        )
+    }
+
+    fn double_fp2<const BLOCKS: usize, const BLOCK_SIZE: usize>(
+        &self,
+        a_c0: &BigUint,
+        fp2: &Fp2CurveConfig,
+    ) -> EcDoubleFp2Chip<F, BLOCKS, BLOCK_SIZE> {
+        EcDoubleFp2Chip::new(
+            self.adapter(),
+            self.range_checker.clone(),
+            self.config.clone(),
+            self.offset,
+            fp2.non_residue,
+            [a_c0.clone(), fp2.a_c1.clone()],
+            self.offline_memory.clone(),
+        )
+    }
+}
+
 pub(crate) mod phantom {
     use std::iter::repeat;
 
     use eyre::bail;
     use num_bigint::BigUint;
     use num_integer::Integer;
-    use num_traits::One;
+    use openvm_algebra_guest::{find_non_qr, sqrt_biguint};
     use openvm_circuit::{
         arch::{PhantomSubExecutor, Streams},
         system::memory::MemoryController,
@@ -236,9 +315,24 @@ pub(crate) mod phantom {
 
     use super::CurveConfig;
 
-    #[derive(derive_new::new)]
     pub struct DecompressHintSubEx {
         pub supported_curves: Vec<CurveConfig>,
+        /// A quadratic non-residue modulo the coordinate modulus of each curve, if the modulus is
+        /// an odd prime.
+        pub non_qrs: Vec<Option<BigUint>>,
+    }
+
+    impl DecompressHintSubEx {
+        pub fn new(supported_curves: Vec<CurveConfig>) -> Self {
+            let non_qrs = supported_curves
+                .iter()
+                .map(|curve| find_non_qr(&curve.modulus))
+                .collect();
+            Self {
+                supported_curves,
+                non_qrs,
+            }
+        }
     }
 
     impl<F: PrimeField32> PhantomSubExecutor<F> for DecompressHintSubEx {
@@ -259,11 +353,15 @@ pub(crate) mod phantom {
                 );
             }
             let curve = &self.supported_curves[c_idx];
-            let modulus_mod_4 = BigUint::from(3u8) & curve.modulus.clone();
-            if modulus_mod_4 != BigUint::from(3u8) {
-                bail!("Currently only supporting curves with modulus congruent to 3 mod 4.");
-                // TODO: Tonelli-Shanks algorithm
+            if curve.fp2.is_some() {
+                bail!("Point decompression is not supported for curves over Fp2.");
             }
+            let Some(non_qr) = &self.non_qrs[c_idx] else {
+                bail!(
+                    "Point decompression is only supported for odd prime moduli, got {}",
+                    curve.modulus
+                );
+            };
             let rs1 = unsafe_read_rv32_register(memory, a);
             let num_limbs: usize = if curve.modulus.bits().div_ceil(8) <= 32 {
                 32
@@ -286,31 +384,34 @@ pub(crate) mod phantom {
                 F::from_canonical_u32(RV32_MEMORY_AS),
                 F::from_canonical_u32(rs2),
             );
-            let y = decompress_point(x, rec_id.as_canonical_u32() & 1 == 1, curve);
-            let y_bytes = y
-                .to_bytes_le()
-                .into_iter()
-                .map(F::from_canonical_u8)
-                .chain(repeat(F::ZERO))
-                .take(num_limbs)
-                .collect();
-            streams.hint_stream = y_bytes;
+            let y = decompress_point(x, rec_id.as_canonical_u32() & 1 == 1, curve, non_qr);
+            streams.set_hint_stream(
+                y.to_bytes_le()
+                    .into_iter()
+                    .map(F::from_canonical_u8)
+                    .chain(repeat(F::ZERO))
+                    .take(num_limbs),
+            );
             Ok(())
         }
     }
 
-    fn decompress_point(x: BigUint, is_y_odd: bool, curve: &CurveConfig) -> BigUint {
+    /// Returns the `y` coordinate with the given parity of the point with coordinate `x`. If `x` is
+    /// not the coordinate of a point, returns zero and the guest fails to verify the hint.
+    fn decompress_point(
+        x: BigUint,
+        is_y_odd: bool,
+        curve: &CurveConfig,
+        non_qr: &BigUint,
+    ) -> BigUint {
         let alpha = ((&x * &x * &x) + (&x * &curve.a) + &curve.b) % &curve.modulus;
-        let beta = mod_sqrt(alpha, &curve.modulus);
+        let Some(beta) = sqrt_biguint(&alpha, &curve.modulus, non_qr) else {
+            return BigUint::ZERO;
+        };
         if is_y_odd == beta.is_odd() {
             beta
         } else {
-            &curve.modulus - &beta
+            (&curve.modulus - &beta) % &curve.modulus
         }
     }
-
-    fn mod_sqrt(x: BigUint, modulus: &BigUint) -> BigUint {
-        let exponent = (modulus + BigUint::one()) >> 2;
-        x.modpow(&exponent, modulus)
-    }
 }
@@ -14,10 +14,16 @@ use syn::{
 /// Usage:
 /// ```
 /// sw_declare! {
-///     [TODO]
+///     Secp256k1Point { mod_type = Secp256k1Coord, b = CURVE_B },
+///     Bn254G2Point { mod_type = Bn254Fp, coord_type = Bn254Fp2, b = G2_CURVE_B },
 /// }
 /// ```
 ///
+/// The optional `coord_type` parameter declares a curve whose coordinates lie in a quadratic
+/// extension of `mod_type`, such as one created by `complex_declare!`. It must be `#[repr(C)]`
+/// with fields `c0, c1` of type `mod_type`. Point decompression is only available for curves
+/// with coordinates in `mod_type`.
+///
 /// For this macro to work, you must import the `elliptic_curve` crate and the `openvm_ecc_guest` crate..
 #[proc_macro]
 pub fn sw_declare(input: TokenStream) -> TokenStream {
@@ -32,6 +38,7 @@ pub fn sw_declare(input: TokenStream) -> TokenStream {
         let struct_name = syn::Ident::new(&struct_name, span.into());
         let struct_path: syn::Path = syn::parse_quote!(#struct_name);
         let mut intmod_type: Option<syn::Path> = None;
+        let mut coord_type: Option<syn::Path> = None;
         let mut const_a: Option<syn::Expr> = None;
         let mut const_b: Option<syn::Expr> = None;
         for param in item.params {
@@ -45,6 +52,15 @@ pub fn sw_declare(input: TokenStream) -> TokenStream {
                             .into();
                     }
                 }
+                "coord_type" => {
+                    if let syn::Expr::Path(ExprPath { path, .. }) = param.value {
+                        coord_type = Some(path)
+                    } else {
+                        return syn::Error::new_spanned(param.value, "Expected a type")
+                            .to_compile_error()
+                            .into();
+                    }
+                }
                 "a" => {
                     // We currently leave it to the compiler to check if the expression is actually a constant
                     const_a = Some(param.value);
@@ -60,10 +76,23 @@ pub fn sw_declare(input: TokenStream) -> TokenStream {
         }
 
         let intmod_type = intmod_type.expect("mod_type parameter is required");
+        let const_b = const_b.expect("constant b coefficient is required");
+
+        if let Some(coord_type) = coord_type {
+            output.push(sw_declare_fp2(
+                &struct_name,
+                &struct_path,
+                &intmod_type,
+                &coord_type,
+                const_a,
+                const_b,
+            ));
+            continue;
+        }
+
         // const_a is optional, default to 0
         let const_a = const_a
             .unwrap_or(syn::parse_quote!(<#intmod_type as openvm_algebra_guest::IntMod>::ZERO));
-        let const_b = const_b.expect("constant b coefficient is required");
 
         macro_rules! create_extern_func {
             ($name:ident) => {
@@ -103,6 +132,10 @@ pub fn sw_declare(input: TokenStream) -> TokenStream {
             }
 
             impl #struct_name {
+                /// The modulus of the base field of the coordinates, used by the setup function in `sw_init!`.
+                #[doc(hidden)]
+                pub const SW_BASE_MODULUS: <#intmod_type as openvm_algebra_guest::IntMod>::Repr = <#intmod_type as openvm_algebra_guest::IntMod>::MODULUS;
+
                 const fn identity() -> Self {
                     Self {
                         x: <#intmod_type as openvm_algebra_guest::IntMod>::ZERO,
@@ -326,6 +359,246 @@ pub fn sw_declare(input: TokenStream) -> TokenStream {
     TokenStream::from_iter(output)
 }
 
+/// Generates the curve struct for `sw_declare!` when the coordinates lie in the quadratic
+/// extension `coord_type` of `intmod_type`.
+fn sw_declare_fp2(
+    struct_name: &syn::Ident,
+    struct_path: &syn::Path,
+    intmod_type: &syn::Path,
+    coord_type: &syn::Path,
+    const_a: Option<syn::Expr>,
+    const_b: syn::Expr,
+) -> TokenStream {
+    let span = proc_macro::Span::call_site();
+    // const_a is optional, default to 0
+    let const_a =
+        const_a.unwrap_or(syn::parse_quote!(<#coord_type as openvm_algebra_guest::Field>::ZERO));
+
+    let extern_name = |name: &str| {
+        syn::Ident::new(
+            &format!(
+                "{}_{}",
+                name,
+                struct_path
+                    .segments
+                    .iter()
+                    .map(|x| x.ident.to_string())
+                    .collect::<Vec<_>>()
+                    .join("_")
+            ),
+            span.into(),
+        )
+    };
+    let sw_add_ne_extern_func = extern_name("sw_add_ne_extern_func");
+    let sw_double_extern_func = extern_name("sw_double_extern_func");
+
+    let group_ops_mod_name = format_ident!("{}_ops", struct_name.to_string().to_lowercase());
+
+    TokenStream::from(quote::quote_spanned! { span.into() =>
+        extern "C" {
+            fn #sw_add_ne_extern_func(rd: usize, rs1: usize, rs2: usize);
+            fn #sw_double_extern_func(rd: usize, rs1: usize);
+        }
+
+        #[derive(Eq, PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
+        #[repr(C)]
+        pub struct #struct_name {
+            x: #coord_type,
+            y: #coord_type,
+        }
+
+        impl #struct_name {
+            /// The modulus of the base field of the coordinates, used by the setup function in `sw_init!`.
+            #[doc(hidden)]
+            pub const SW_BASE_MODULUS: <#intmod_type as openvm_algebra_guest::IntMod>::Repr = <#intmod_type as openvm_algebra_guest::IntMod>::MODULUS;
+
+            const fn identity() -> Self {
+                Self {
+                    x: <#coord_type as openvm_algebra_guest::Field>::ZERO,
+                    y: <#coord_type as openvm_algebra_guest::Field>::ZERO,
+                }
+            }
+            // Below are wrapper functions for the intrinsic instructions.
+            // Should not be called directly.
+            #[inline(always)]
+            fn add_ne(p1: &#struct_name, p2: &#struct_name) -> #struct_name {
+                #[cfg(not(target_os = "zkvm"))]
+                {
+                    use openvm_algebra_guest::DivUnsafe;
+                    let lambda = (&p2.y - &p1.y).div_unsafe(&p2.x - &p1.x);
+                    let x3 = &lambda * &lambda - &p1.x - &p2.x;
+                    let y3 = &lambda * &(&p1.x - &x3) - &p1.y;
+                    #struct_name { x: x3, y: y3 }
+                }
+                #[cfg(target_os = "zkvm")]
+                {
+                    let mut uninit: core::mem::MaybeUninit<#struct_name> = core::mem::MaybeUninit::uninit();
+                    unsafe {
+                        #sw_add_ne_extern_func(
+                            uninit.as_mut_ptr() as usize,
+                            p1 as *const #struct_name as usize,
+                            p2 as *const #struct_name as usize
+                        )
+                    };
+                    unsafe { uninit.assume_init() }
+                }
+            }
+
+            #[inline(always)]
+            fn add_ne_assign(&mut self, p2: &#struct_name) {
+                #[cfg(not(target_os = "zkvm"))]
+                {
+                    *self = Self::add_ne(self, p2);
+                }
+                #[cfg(target_os = "zkvm")]
+                {
+                    unsafe {
+                        #sw_add_ne_extern_func(
+                            self as *mut #struct_name as usize,
+                            self as *const #struct_name as usize,
+                            p2 as *const #struct_name as usize
+                        )
+                    };
+                }
+            }
+
+            /// Assumes that `p` is not identity.
+            #[inline(always)]
+            fn double_impl(p: &#struct_name) -> #struct_name {
+                #[cfg(not(target_os = "zkvm"))]
+                {
+                    use openvm_algebra_guest::{DivUnsafe, Field};
+                    let two = <#coord_type as Field>::ONE + &<#coord_type as Field>::ONE;
+                    let three = two.clone() + &<#coord_type as Field>::ONE;
+                    let curve_a: #coord_type = #const_a;
+                    let lambda = (&p.x * &p.x * &three + &curve_a).div_unsafe(&p.y * &two);
+                    let x3 = &lambda * &lambda - &p.x * &two;
+                    let y3 = &lambda * &(&p.x - &x3) - &p.y;
+                    #struct_name { x: x3, y: y3 }
+                }
+                #[cfg(target_os = "zkvm")]
+                {
+                    let mut uninit: core::mem::MaybeUninit<#struct_name> = core::mem::MaybeUninit::uninit();
+                    unsafe {
+                        #sw_double_extern_func(
+                            uninit.as_mut_ptr() as usize,
+                            p as *const #struct_name as usize,
+                        )
+                    };
+                    unsafe { uninit.assume_init() }
+                }
+            }
+
+            #[inline(always)]
+            fn double_assign_impl(&mut self) {
+                #[cfg(not(target_os = "zkvm"))]
+                {
+                    *self = Self::double_impl(self);
+                }
+                #[cfg(target_os = "zkvm")]
+                {
+                    unsafe {
+                        #sw_double_extern_func(
+                            self as *mut #struct_name as usize,
+                            self as *const #struct_name as usize
+                        )
+                    };
+                }
+            }
+        }
+
+        impl ::openvm_ecc_guest::weierstrass::WeierstrassPoint for #struct_name {
+            const CURVE_A: #coord_type = #const_a;
+            const CURVE_B: #coord_type = #const_b;
+            const IDENTITY: Self = Self::identity();
+            type Coordinate = #coord_type;
+
+            /// SAFETY: assumes that #coord_type has a memory representation
+            /// such that with repr(C), two coordinates are packed contiguously.
+            fn as_le_bytes(&self) -> &[u8] {
+                unsafe { &*core::ptr::slice_from_raw_parts(self as *const Self as *const u8, core::mem::size_of::<Self>()) }
+            }
+
+            fn from_xy_unchecked(x: Self::Coordinate, y: Self::Coordinate) -> Self {
+                Self { x, y }
+            }
+
+            fn x(&self) -> &Self::Coordinate {
+                &self.x
+            }
+
+            fn y(&self) -> &Self::Coordinate {
+                &self.y
+            }
+
+            fn x_mut(&mut self) -> &mut Self::Coordinate {
+                &mut self.x
+            }
+
+            fn y_mut(&mut self) -> &mut Self::Coordinate {
+                &mut self.y
+            }
+
+            fn into_coords(self) -> (Self::Coordinate, Self::Coordinate) {
+                (self.x, self.y)
+            }
+
+            fn add_ne_nonidentity(&self, p2: &Self) -> Self {
+                Self::add_ne(self, p2)
+            }
+
+            fn add_ne_assign_nonidentity(&mut self, p2: &Self) {
+                Self::add_ne_assign(self, p2);
+            }
+
+            fn sub_ne_nonidentity(&self, p2: &Self) -> Self {
+                Self::add_ne(self, &p2.clone().neg())
+            }
+
+            fn sub_ne_assign_nonidentity(&mut self, p2: &Self) {
+                Self::add_ne_assign(self, &p2.clone().neg());
+            }
+
+            fn double_nonidentity(&self) -> Self {
+                Self::double_impl(self)
+            }
+
+            fn double_assign_nonidentity(&mut self) {
+                Self::double_assign_impl(self);
+            }
+        }
+
+        impl core::ops::Neg for #struct_name {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                #struct_name {
+                    x: self.x,
+                    y: -self.y,
+                }
+            }
+        }
+
+        impl core::ops::Neg for &#struct_name {
+            type Output = #struct_name;
+
+            fn neg(self) -> #struct_name {
+                #struct_name {
+                    x: self.x.clone(),
+                    y: core::ops::Neg::neg(self.y.clone()),
+                }
+            }
+        }
+
+        mod #group_ops_mod_name {
+            use ::openvm_ecc_guest::{weierstrass::WeierstrassPoint, impl_sw_group_ops};
+            use super::*;
+
+            impl_sw_group_ops!(#struct_name, #coord_type);
+        }
+    })
+}
+
 struct SwDefine {
     items: Vec<Path>,
 }
@@ -421,13 +694,20 @@ pub fn sw_init(input: TokenStream) -> TokenStream {
                 #[cfg(target_os = "zkvm")]
                 {
                     // p1 is (x1, y1), and x1 must be the modulus.
-                    // y1 can be anything for SetupEcAdd, but must equal `a` for SetupEcDouble
-                    let modulus_bytes = <<#item as openvm_ecc_guest::weierstrass::WeierstrassPoint>::Coordinate as openvm_algebra_guest::IntMod>::MODULUS;
-                    let mut one = [0u8; <<#item as openvm_ecc_guest::weierstrass::WeierstrassPoint>::Coordinate as openvm_algebra_guest::IntMod>::NUM_LIMBS];
+                    // y1 can be anything for SetupEcAdd, but must equal `a` for SetupEcDouble.
+                    // For curves over a quadratic extension, x1 is `(modulus, 0)`.
+                    const COORD_BYTES: usize = core::mem::size_of::<<#item as openvm_ecc_guest::weierstrass::WeierstrassPoint>::Coordinate>();
+                    let modulus_bytes = #item::SW_BASE_MODULUS;
+                    let modulus_bytes: &[u8] = modulus_bytes.as_ref();
+                    let mut x1 = [0u8; COORD_BYTES];
+                    x1[..modulus_bytes.len()].copy_from_slice(modulus_bytes);
+                    let mut one = [0u8; COORD_BYTES];
                     one[0] = 1;
-                    let curve_a_bytes = openvm_algebra_guest::IntMod::as_le_bytes(&<#item as openvm_ecc_guest::weierstrass::WeierstrassPoint>::CURVE_A);
+                    let curve_a = <#item as openvm_ecc_guest::weierstrass::WeierstrassPoint>::CURVE_A;
+                    // SAFETY: the coordinate is exactly COORD_BYTES bytes in little endian
+                    let curve_a_bytes = unsafe { core::slice::from_raw_parts(&curve_a as *const _ as *const u8, COORD_BYTES) };
                     // p1 should be (p, a)
-                    let p1 = [modulus_bytes.as_ref(), curve_a_bytes.as_ref()].concat();
+                    let p1 = [x1.as_ref(), curve_a_bytes].concat();
                     // (EcAdd only) p2 is (x2, y2), and x1 - x2 has to be non-zero to avoid division over zero in add.
                     let p2 = [one.as_ref(), one.as_ref()].concat();
                     let mut uninit: core::mem::MaybeUninit<[#item; 2]> = core::mem::MaybeUninit::uninit();
@@ -12,7 +12,7 @@ use openvm_circuit_primitives::{
     bitwise_op_lookup::BitwiseOperationLookupBus,
     utils::{assert_array_eq, not, select},
 };
-use openvm_instructions::riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_NUM_LIMBS};
+use openvm_instructions::riscv::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
 use openvm_keccak256_transpiler::Rv32KeccakOpcode;
 use openvm_rv32im_circuit::adapters::abstract_compose;
 use openvm_stark_backend::{
@@ -27,9 +27,9 @@ use p3_keccak_air::{KeccakAir, NUM_KECCAK_COLS as NUM_KECCAK_PERM_COLS, U64_LIMB
 
 use super::{
     columns::{KeccakVmCols, NUM_KECCAK_VM_COLS},
-    KECCAK_ABSORB_READS, KECCAK_DIGEST_BYTES, KECCAK_DIGEST_WRITES, KECCAK_RATE_BYTES,
-    KECCAK_RATE_U16S, KECCAK_REGISTER_READS, KECCAK_WIDTH_U16S, KECCAK_WORD_SIZE,
-    NUM_ABSORB_ROUNDS,
+    KeccakAddressSpaces, KECCAK_ABSORB_READS, KECCAK_DIGEST_BYTES, KECCAK_DIGEST_WRITES,
+    KECCAK_RATE_BYTES, KECCAK_RATE_U16S, KECCAK_REGISTER_READS, KECCAK_WIDTH_U16S,
+    KECCAK_WORD_SIZE, NUM_ABSORB_ROUNDS,
 };
 
 #[derive(Clone, Copy, Debug, derive_new::new)]
@@ -41,6 +41,9 @@ pub struct KeccakVmAir {
     /// Maximum number of bits allowed for an address pointer
     pub ptr_max_bits: usize,
     pub(super) offset: usize,
+    /// Address spaces of the pointer operands and of the hashed bytes.
+    #[new(default)]
+    pub address_spaces: KeccakAddressSpaces,
 }
 
 impl<F> BaseAirWithPublicValues<F> for KeccakVmAir {}
@@ -421,7 +424,7 @@ impl KeccakVmAir {
             instruction.src_ptr,
             instruction.len_ptr,
         ];
-        let reg_addr_sp = AB::F::ONE;
+        let reg_addr_sp = AB::F::from_canonical_u32(self.address_spaces.register_as);
         let timestamp_change: AB::Expr = Self::timestamp_change(instruction.remaining_len);
         self.execution_bridge
             .execute_and_increment_pc(
@@ -431,7 +434,7 @@ impl KeccakVmAir {
                     src_ptr.into(),
                     len_ptr.into(),
                     reg_addr_sp.into(),
-                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                    AB::Expr::from_canonical_u32(self.address_spaces.memory_as),
                 ],
                 ExecutionState::new(instruction.pc, instruction.start_timestamp),
                 timestamp_change,
@@ -556,7 +559,10 @@ impl KeccakVmAir {
 
             self.memory_bridge
                 .read(
-                    MemoryAddress::new(AB::Expr::from_canonical_u32(RV32_MEMORY_AS), ptr),
+                    MemoryAddress::new(
+                        AB::Expr::from_canonical_u32(self.address_spaces.memory_as),
+                        ptr,
+                    ),
                     word, // degree 2
                     timestamp.clone(),
                     mem_aux,
@@ -609,7 +615,7 @@ impl KeccakVmAir {
             self.memory_bridge
                 .write(
                     MemoryAddress::new(
-                        AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                        AB::Expr::from_canonical_u32(self.address_spaces.memory_as),
                         dst.clone() + AB::F::from_canonical_usize(i * KECCAK_WORD_SIZE),
                     ),
                     digest_bytes.try_into().unwrap(),
@@ -68,40 +68,73 @@ impl<F: PrimeField32> VmExtension<F> for Keccak256 {
         &self,
         builder: &mut VmInventoryBuilder<F>,
     ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
-        let mut inventory = VmInventory::new();
-        let SystemPort {
-            execution_bus,
-            program_bus,
-            memory_bridge,
-        } = builder.system_port();
-        let bitwise_lu_chip = if let Some(&chip) = builder
-            .find_chip::<SharedBitwiseOperationLookupChip<8>>()
-            .first()
-        {
-            chip.clone()
-        } else {
-            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
-            let chip = SharedBitwiseOperationLookupChip::new(bitwise_lu_bus);
-            inventory.add_periphery_chip(chip.clone());
-            chip
-        };
-        let offline_memory = builder.system_base().offline_memory();
-        let address_bits = builder.system_config().memory_config.pointer_max_bits;
+        build_keccak256(builder, KeccakAddressSpaces::default())
+    }
+}
 
-        let keccak_chip = KeccakVmChip::new(
-            execution_bus,
-            program_bus,
-            memory_bridge,
-            address_bits,
-            bitwise_lu_chip,
-            Rv32KeccakOpcode::CLASS_OFFSET,
-            offline_memory,
-        );
-        inventory.add_executor(
-            keccak_chip,
-            Rv32KeccakOpcode::iter().map(|x| x.global_opcode()),
-        )?;
+/// KECCAK256 for programs that keep the pointer operands and the hashed bytes in a single
+/// address space instead of RV32 registers and memory, e.g. programs of the native kernel.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Keccak256InAddressSpace {
+    pub address_space: u32,
+}
 
-        Ok(inventory)
+impl<F: PrimeField32> VmExtension<F> for Keccak256InAddressSpace {
+    type Executor = Keccak256Executor<F>;
+    type Periphery = Keccak256Periphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        build_keccak256(
+            builder,
+            KeccakAddressSpaces {
+                register_as: self.address_space,
+                memory_as: self.address_space,
+            },
+        )
     }
 }
+
+fn build_keccak256<F: PrimeField32>(
+    builder: &mut VmInventoryBuilder<F>,
+    address_spaces: KeccakAddressSpaces,
+) -> Result<VmInventory<Keccak256Executor<F>, Keccak256Periphery<F>>, VmInventoryError> {
+    let mut inventory = VmInventory::new();
+    let SystemPort {
+        execution_bus,
+        program_bus,
+        memory_bridge,
+    } = builder.system_port();
+    let bitwise_lu_chip = if let Some(&chip) = builder
+        .find_chip::<SharedBitwiseOperationLookupChip<8>>()
+        .first()
+    {
+        chip.clone()
+    } else {
+        let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+        let chip = SharedBitwiseOperationLookupChip::new(bitwise_lu_bus);
+        inventory.add_periphery_chip(chip.clone());
+        chip
+    };
+    let offline_memory = builder.system_base().offline_memory();
+    let address_bits = builder.system_config().memory_config.pointer_max_bits;
+
+    let keccak_chip = KeccakVmChip::new(
+        execution_bus,
+        program_bus,
+        memory_bridge,
+        address_bits,
+        bitwise_lu_chip,
+        Rv32KeccakOpcode::CLASS_OFFSET,
+        offline_memory,
+    )
+    .with_address_spaces(address_spaces);
+    inventory.add_executor(
+        keccak_chip,
+        Rv32KeccakOpcode::iter().map(|x| x.global_opcode()),
+    )?;
+
+    Ok(inventory)
+}
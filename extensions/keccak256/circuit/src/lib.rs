@@ -33,7 +33,10 @@ use openvm_circuit::{
     },
 };
 use openvm_instructions::{
-    instruction::Instruction, program::DEFAULT_PC_STEP, riscv::RV32_REGISTER_NUM_LIMBS, LocalOpcode,
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS},
+    LocalOpcode,
 };
 use openvm_keccak256_transpiler::Rv32KeccakOpcode;
 use openvm_rv32im_circuit::adapters::read_rv32_register;
@@ -69,6 +72,25 @@ pub const KECCAK_DIGEST_BYTES: usize = 32;
 /// Number of 64-bit digest limbs.
 pub const KECCAK_DIGEST_U64S: usize = KECCAK_DIGEST_BYTES / 8;
 
+/// Address spaces accessed by the KECCAK256 instruction. The `dst`, `src` and `len` operands
+/// point to 4-byte little-endian words in `register_as`, and the input and output bytes live in
+/// `memory_as`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeccakAddressSpaces {
+    pub register_as: u32,
+    pub memory_as: u32,
+}
+
+impl Default for KeccakAddressSpaces {
+    /// RV32 registers and memory.
+    fn default() -> Self {
+        Self {
+            register_as: RV32_REGISTER_AS,
+            memory_as: RV32_MEMORY_AS,
+        }
+    }
+}
+
 pub struct KeccakVmChip<F: PrimeField32> {
     pub air: KeccakVmAir,
     /// IO and memory data necessary for each opcode call
@@ -128,6 +150,11 @@ impl<F: PrimeField32> KeccakVmChip<F> {
             offline_memory,
         }
     }
+
+    pub fn with_address_spaces(mut self, address_spaces: KeccakAddressSpaces) -> Self {
+        self.air.address_spaces = address_spaces;
+        self
+    }
 }
 
 impl<F: PrimeField32> InstructionExecutor<F> for KeccakVmChip<F> {
@@ -248,7 +275,9 @@ impl<F: PrimeField32> InstructionExecutor<F> for KeccakVmChip<F> {
         };
 
         // Add the events to chip state for later trace generation usage
-        self.records.push(record);
+        if !memory.is_execute_only() {
+            self.records.push(record);
+        }
 
         // NOTE: Check this is consistent with KeccakVmAir::timestamp_change (we don't use it to avoid
         // unnecessary conversions here)
@@ -18,7 +18,7 @@ use p3_keccak_air::NUM_ROUNDS;
 use rand::Rng;
 use tiny_keccak::Hasher;
 
-use super::{columns::KeccakVmCols, utils::num_keccak_f, KeccakVmChip};
+use super::{columns::KeccakVmCols, utils::num_keccak_f, KeccakAddressSpaces, KeccakVmChip};
 
 type F = BabyBear;
 // io is vector of (input, expected_output, prank_output) where prank_output is Some if the trace
@@ -26,6 +26,7 @@ type F = BabyBear;
 #[allow(clippy::type_complexity)]
 fn build_keccak256_test(
     io: Vec<(Vec<u8>, Option<[u8; 32]>, Option<[u8; 32]>)>,
+    address_spaces: KeccakAddressSpaces,
 ) -> VmChipTester<BabyBearBlake3Config> {
     let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
     let bitwise_chip = SharedBitwiseOperationLookupChip::<8>::new(bitwise_bus);
@@ -39,14 +40,16 @@ fn build_keccak256_test(
         bitwise_chip.clone(),
         Rv32KeccakOpcode::CLASS_OFFSET,
         tester.offline_memory_mutex_arc(),
-    );
+    )
+    .with_address_spaces(address_spaces);
 
-    let mut dst = 0;
-    let src = 0;
+    // Keep the input and output clear of the register limbs if they share an address space.
+    let mut dst = 1 << 12;
+    let src = 16;
 
     for (input, expected_output, _) in &io {
         let [a, b, c] = [0, 4, 8]; // space apart for register limbs
-        let [d, e] = [1, 2];
+        let [d, e] = [address_spaces.register_as, address_spaces.memory_as].map(|x| x as usize);
 
         tester.write(d, a, (dst as u32).to_le_bytes().map(F::from_canonical_u8));
         tester.write(d, b, (src as u32).to_le_bytes().map(F::from_canonical_u8));
@@ -122,7 +125,7 @@ fn test_keccak256_negative() {
     let mut out = [0u8; 32];
     hasher.finalize(&mut out);
     out[0] = rng.gen();
-    let tester = build_keccak256_test(vec![(input, None, Some(out))]);
+    let tester = build_keccak256_test(vec![(input, None, Some(out))], Default::default());
     disable_debug_builder();
     assert_eq!(
         tester.simple_test().err(),
@@ -153,6 +156,21 @@ fn test_keccak256_positive_kat_vectors() {
         io.push((input, Some(output.try_into().unwrap()), None));
     }
 
-    let tester = build_keccak256_test(io);
+    let tester = build_keccak256_test(io, Default::default());
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn test_keccak256_single_address_space() {
+    let mut hasher = tiny_keccak::Keccak::v256();
+    let input: Vec<_> = (0..200).map(|i| i as u8).collect();
+    hasher.update(&input);
+    let mut out = [0u8; 32];
+    hasher.finalize(&mut out);
+    let address_spaces = KeccakAddressSpaces {
+        register_as: 4,
+        memory_as: 4,
+    };
+    let tester = build_keccak256_test(vec![(input, Some(out), None)], address_spaces);
     tester.simple_test().expect("Verification failed");
 }
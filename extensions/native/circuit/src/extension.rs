@@ -274,9 +274,7 @@ pub(crate) mod phantom {
             };
             assert!(streams.hint_stream.is_empty());
             streams
-                .hint_stream
-                .push_back(F::from_canonical_usize(hint.len()));
-            streams.hint_stream.extend(hint);
+                .set_hint_stream(std::iter::once(F::from_canonical_usize(hint.len())).chain(hint));
             Ok(())
         }
     }
@@ -299,7 +297,7 @@ pub(crate) mod phantom {
             };
             assert!(streams.hint_stream.is_empty());
             assert_eq!(hint.len(), N);
-            streams.hint_stream = hint.into();
+            streams.set_hint_stream(hint);
             Ok(())
         }
     }
@@ -337,12 +335,11 @@ pub(crate) mod phantom {
 
             let len = b.as_canonical_u32();
             assert!(streams.hint_stream.is_empty());
-            for _ in 0..len {
-                streams
-                    .hint_stream
-                    .push_back(F::from_canonical_u32(val & 1));
+            streams.set_hint_stream((0..len).map(|_| {
+                let bit = F::from_canonical_u32(val & 1);
                 val >>= 1;
-            }
+                bit
+            }));
             Ok(())
         }
     }
@@ -367,7 +364,7 @@ pub(crate) mod phantom {
             streams.hint_space.push(payload);
             // Hint stream should have already been consumed.
             assert!(streams.hint_stream.is_empty());
-            streams.hint_stream.push_back(F::from_canonical_usize(id));
+            streams.set_hint_stream([F::from_canonical_usize(id)]);
             Ok(())
         }
     }
@@ -1,6 +1,6 @@
 use std::borrow::BorrowMut;
 
-use openvm_circuit::arch::testing::{memory::gen_pointer, VmChipTestBuilder};
+use openvm_circuit::arch::testing::{memory::gen_pointer, MutationConfig, VmChipTestBuilder};
 use openvm_instructions::{instruction::Instruction, LocalOpcode};
 use openvm_native_compiler::FieldArithmeticOpcode;
 use openvm_stark_backend::{
@@ -21,14 +21,8 @@ use super::{
 };
 use crate::adapters::alu_native_adapter::{AluNativeAdapterChip, AluNativeAdapterCols};
 
-#[test]
-fn new_field_arithmetic_air_test() {
-    let num_ops = 3; // non-power-of-2 to also test padding
-    let elem_range = || 1..=100;
-    let xy_address_space_range = || 0usize..=1;
-
-    let mut tester = VmChipTestBuilder::default();
-    let mut chip = FieldArithmeticChip::new(
+fn create_test_chip(tester: &VmChipTestBuilder<BabyBear>) -> FieldArithmeticChip<BabyBear> {
+    FieldArithmeticChip::new(
         AluNativeAdapterChip::new(
             tester.execution_bus(),
             tester.program_bus(),
@@ -36,7 +30,17 @@ fn new_field_arithmetic_air_test() {
         ),
         FieldArithmeticCoreChip::new(),
         tester.offline_memory_mutex_arc(),
-    );
+    )
+}
+
+#[test]
+fn new_field_arithmetic_air_test() {
+    let num_ops = 3; // non-power-of-2 to also test padding
+    let elem_range = || 1..=100;
+    let xy_address_space_range = || 0usize..=1;
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = create_test_chip(&tester);
 
     let mut rng = create_seeded_rng();
 
@@ -122,15 +126,7 @@ fn new_field_arithmetic_air_test() {
 #[test]
 fn new_field_arithmetic_air_zero_div_zero() {
     let mut tester = VmChipTestBuilder::default();
-    let mut chip = FieldArithmeticChip::new(
-        AluNativeAdapterChip::new(
-            tester.execution_bus(),
-            tester.program_bus(),
-            tester.memory_bridge(),
-        ),
-        FieldArithmeticCoreChip::new(),
-        tester.offline_memory_mutex_arc(),
-    );
+    let mut chip = create_test_chip(&tester);
     tester.write_cell(4, 6, BabyBear::from_canonical_u32(111));
     tester.write_cell(4, 7, BabyBear::from_canonical_u32(222));
 
@@ -166,15 +162,7 @@ fn new_field_arithmetic_air_zero_div_zero() {
 #[test]
 fn new_field_arithmetic_air_test_panic() {
     let mut tester = VmChipTestBuilder::default();
-    let mut chip = FieldArithmeticChip::new(
-        AluNativeAdapterChip::new(
-            tester.execution_bus(),
-            tester.program_bus(),
-            tester.memory_bridge(),
-        ),
-        FieldArithmeticCoreChip::new(),
-        tester.offline_memory_mutex_arc(),
-    );
+    let mut chip = create_test_chip(&tester);
     tester.write_cell(4, 0, BabyBear::ZERO);
     // should panic
     tester.execute(
@@ -185,3 +173,48 @@ fn new_field_arithmetic_air_test_panic() {
         ),
     );
 }
+
+#[test]
+fn new_field_arithmetic_air_mutation_test() {
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = create_test_chip(&tester);
+    let mut rng = create_seeded_rng();
+
+    for opcode in 0..FieldArithmeticOpcode::COUNT {
+        let opcode = FieldArithmeticOpcode::from_usize(opcode);
+        let address1 = gen_pointer(&mut rng, 1);
+        let address2 = address1 + 1;
+        let result_address = address1 + 2;
+        tester.write_cell(
+            4,
+            address1,
+            BabyBear::from_canonical_u32(rng.gen_range(1..=100)),
+        );
+        tester.write_cell(
+            4,
+            address2,
+            BabyBear::from_canonical_u32(rng.gen_range(1..=100)),
+        );
+        tester.execute(
+            &mut chip,
+            &Instruction::from_usize(
+                opcode.global_opcode(),
+                [result_address, address1, address2, 4, 4, 4],
+            ),
+        );
+    }
+
+    tester.assert_mutations_rejected(
+        chip,
+        [
+            AluNativeAdapterCols::<BabyBear>::column_names(),
+            FieldArithmeticCoreCols::<BabyBear>::column_names(),
+        ]
+        .concat(),
+        |tester| tester,
+        &MutationConfig {
+            rows: (0..FieldArithmeticOpcode::COUNT).collect(),
+            ..Default::default()
+        },
+    );
+}
@@ -622,8 +622,10 @@ impl<F: PrimeField32> InstructionExecutor<F> for FriReducedOpeningChip<F> {
             b_reads: b_reads.into_iter().map(|r| r.0).collect(),
             result_write,
         };
-        self.height += record.get_height();
-        self.records.push(record);
+        if !memory.is_execute_only() {
+            self.height += record.get_height();
+            self.records.push(record);
+        }
 
         Ok(ExecutionState {
             pc: from_state.pc + DEFAULT_PC_STEP,
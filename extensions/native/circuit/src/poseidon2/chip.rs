@@ -238,23 +238,25 @@ impl<F: PrimeField32, const SBOX_REGISTERS: usize> InstructionExecutor<F>
                 from_state.timestamp + NUM_SIMPLE_ACCESSES
             );
 
-            self.record_set
-                .simple_permute_records
-                .push(SimplePoseidonRecord {
-                    from_state,
-                    instruction: instruction.clone(),
-                    read_input_pointer_1,
-                    read_input_pointer_2,
-                    read_output_pointer,
-                    read_data_1,
-                    read_data_2,
-                    write_data_1,
-                    write_data_2,
-                    input_pointer_1,
-                    input_pointer_2,
-                    output_pointer,
-                    p2_input,
-                });
+            if !memory.is_execute_only() {
+                self.record_set
+                    .simple_permute_records
+                    .push(SimplePoseidonRecord {
+                        from_state,
+                        instruction: instruction.clone(),
+                        read_input_pointer_1,
+                        read_input_pointer_2,
+                        read_output_pointer,
+                        read_data_1,
+                        read_data_2,
+                        write_data_1,
+                        write_data_2,
+                        input_pointer_1,
+                        input_pointer_2,
+                        output_pointer,
+                        p2_input,
+                    });
+            }
             self.height += 1;
         } else if instruction.opcode == VERIFY_BATCH.global_opcode() {
             let &Instruction {
@@ -463,25 +465,27 @@ impl<F: PrimeField32, const SBOX_REGISTERS: usize> InstructionExecutor<F>
             }
 
             assert_eq!(commit, root);
-            self.record_set
-                .verify_batch_records
-                .push(VerifyBatchRecord {
-                    from_state,
-                    instruction: instruction.clone(),
-                    dim_base_pointer,
-                    opened_base_pointer,
-                    opened_length,
-                    index_base_pointer,
-                    commit_pointer,
-                    dim_base_pointer_read,
-                    opened_base_pointer_read,
-                    opened_length_read,
-                    index_base_pointer_read,
-                    commit_pointer_read,
-                    commit_read,
-                    initial_log_height: initial_log_height as usize,
-                    top_level,
-                });
+            if !memory.is_execute_only() {
+                self.record_set
+                    .verify_batch_records
+                    .push(VerifyBatchRecord {
+                        from_state,
+                        instruction: instruction.clone(),
+                        dim_base_pointer,
+                        opened_base_pointer,
+                        opened_length,
+                        index_base_pointer,
+                        commit_pointer,
+                        dim_base_pointer_read,
+                        opened_base_pointer_read,
+                        opened_length_read,
+                        index_base_pointer_read,
+                        commit_pointer_read,
+                        commit_read,
+                        initial_log_height: initial_log_height as usize,
+                        top_level,
+                    });
+            }
         } else {
             unreachable!()
         }
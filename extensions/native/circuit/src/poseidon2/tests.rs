@@ -503,7 +503,7 @@ fn air_test_with_compress_poseidon2(
 
     let pk = vm.keygen();
     let result = vm.execute_and_generate(program, vec![]).unwrap();
-    let proofs = vm.prove(&pk, result);
+    let proofs = vm.prove(&pk, result.per_segment);
     for proof in proofs {
         vm.verify_single(&pk.get_vk(), &proof)
             .expect("Verification failed");
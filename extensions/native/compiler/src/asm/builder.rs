@@ -4,6 +4,7 @@ use openvm_stark_backend::p3_field::{ExtensionField, PrimeField32, TwoAdicField}
 use super::{config::AsmConfig, AsmCompiler};
 use crate::{
     conversion::{convert_program, CompilerOptions},
+    passes::optimize,
     prelude::Builder,
 };
 
@@ -15,7 +16,10 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmBu
         self.compile_isa_with_options(CompilerOptions::default())
     }
 
-    pub fn compile_isa_with_options(self, options: CompilerOptions) -> Program<F> {
+    pub fn compile_isa_with_options(mut self, options: CompilerOptions) -> Program<F> {
+        if options.enable_ir_optimizations {
+            optimize(&mut self.operations);
+        }
         let mut compiler = AsmCompiler::new(options.word_size);
         compiler.build(self.operations);
         let asm_code = compiler.code();
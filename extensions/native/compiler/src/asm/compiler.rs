@@ -500,6 +500,10 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmCo
                         _ => unimplemented!(),
                     }
                 }
+                DslIr::Keccak256(dst, src, len) => self.push(
+                    AsmInstruction::Keccak256(dst.fp(), src.fp(), len.fp()),
+                    debug_info,
+                ),
                 DslIr::Error() => self.push(AsmInstruction::j(self.trap_label), debug_info),
                 DslIr::PrintF(dst) => {
                     self.push(AsmInstruction::PrintF(dst.fp()), debug_info);
@@ -116,6 +116,10 @@ pub enum AsmInstruction<F, EF> {
     /// (a, b, c) are memory pointers to (dst, lhs, rhs)
     Poseidon2Compress(i32, i32, i32),
 
+    /// Hash bytes using Keccak256.
+    /// (a, b, c) are pointers to the little-endian bytes of (dst, src, len).
+    Keccak256(i32, i32, i32),
+
     /// (a, b, res, alpha, hint_id, hint_offset, is_init)
     FriReducedOpening(i32, i32, i32, i32, i32, i32, i32),
 
@@ -337,6 +341,9 @@ impl<F: PrimeField32, EF: ExtensionField<F>> AsmInstruction<F, EF> {
                     result, src1, src2
                 )
             }
+            AsmInstruction::Keccak256(dst, src, len) => {
+                write!(f, "keccak256 ({})fp, ({})fp, ({})fp", dst, src, len)
+            }
             AsmInstruction::PrintF(dst) => {
                 write!(f, "print_f ({})fp", dst)
             }
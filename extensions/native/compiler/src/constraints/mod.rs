@@ -12,6 +12,8 @@ use crate::{
 
 #[cfg(feature = "halo2-compiler")]
 pub mod halo2;
+#[cfg(feature = "r1cs-compiler")]
+pub mod r1cs;
 
 pub mod opcodes;
 
@@ -0,0 +1,456 @@
+//! Non-native BabyBear arithmetic over BN254 R1CS.
+//!
+//! Mirrors [crate::constraints::halo2::baby_bear]: a BabyBear element is a BN254 value whose
+//! absolute value, as a signed integer, is bounded by `2^max_bits`. Reductions modulo the BabyBear
+//! prime are only inserted when the bound would otherwise exceed the BN254 capacity.
+
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField as _};
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    R1CSVar,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use itertools::Itertools;
+use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+use openvm_stark_backend::p3_field::{
+    extension::{BinomialExtensionField, BinomiallyExtendable},
+    Field, FieldAlgebra, FieldExtensionAlgebra, PrimeField32, PrimeField64,
+};
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+pub(crate) const BABYBEAR_MAX_BITS: usize = 31;
+// bits reserved so that if we do lazy range checking, we still have a valid result
+// the first reserved bit is so that we can represent negative numbers
+// the second is to accommodate lazy range checking
+const RESERVED_HIGH_BITS: usize = 2;
+const MAX_BITS: usize = Fr::MODULUS_BIT_SIZE as usize - 1 - RESERVED_HIGH_BITS;
+
+pub type BabyBearExt4 = BinomialExtensionField<BabyBear, 4>;
+
+#[derive(Clone, Debug)]
+pub struct BabyBearVar {
+    pub value: FpVar<Fr>,
+    /// The value is guaranteed to be less than 2^max_bits.
+    pub max_bits: usize,
+}
+
+impl BabyBearVar {
+    /// Returns the assigned BabyBear element, or [SynthesisError::AssignmentMissing] in setup
+    /// mode.
+    pub fn to_baby_bear(&self) -> Result<BabyBear, SynthesisError> {
+        let b_int = fr_to_bigint(&self.value.value()?).mod_floor(&BabyBear::ORDER_U32.into());
+        Ok(BabyBear::from_canonical_u32(b_int.try_into().unwrap()))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BabyBearExt4Var(pub [BabyBearVar; 4]);
+
+impl BabyBearExt4Var {
+    pub fn to_extension_field(&self) -> Result<BabyBearExt4, SynthesisError> {
+        let b_val: Vec<_> = self.0.iter().map(|x| x.to_baby_bear()).try_collect()?;
+        Ok(BabyBearExt4::from_base_slice(&b_val))
+    }
+}
+
+pub struct BabyBearChip {
+    pub cs: ConstraintSystemRef<Fr>,
+}
+
+impl BabyBearChip {
+    pub fn new(cs: ConstraintSystemRef<Fr>) -> Self {
+        BabyBearChip { cs }
+    }
+
+    pub fn load_witness(
+        &self,
+        value: impl FnOnce() -> Result<BabyBear, SynthesisError>,
+    ) -> Result<BabyBearVar, SynthesisError> {
+        let value = FpVar::new_witness(self.cs.clone(), || {
+            Ok(Fr::from(value()?.as_canonical_u64()))
+        })?;
+        range_check(&value, BABYBEAR_MAX_BITS)?;
+        Ok(BabyBearVar {
+            value,
+            max_bits: BABYBEAR_MAX_BITS,
+        })
+    }
+
+    pub fn load_constant(&self, value: BabyBear) -> BabyBearVar {
+        let value = value.as_canonical_u64();
+        BabyBearVar {
+            value: FpVar::constant(Fr::from(value)),
+            max_bits: (u64::BITS - value.leading_zeros()) as usize,
+        }
+    }
+
+    pub fn reduce(&self, a: &BabyBearVar) -> Result<BabyBearVar, SynthesisError> {
+        let (_, r) = signed_div_mod(&a.value, BabyBear::ORDER_U32, a.max_bits)?;
+        Ok(BabyBearVar {
+            value: r,
+            max_bits: BABYBEAR_MAX_BITS,
+        })
+    }
+
+    /// Reduce max_bits if possible. This function doesn't guarantee that the actual value is within BabyBear.
+    pub fn reduce_max_bits(&self, a: &BabyBearVar) -> Result<BabyBearVar, SynthesisError> {
+        if a.max_bits > BABYBEAR_MAX_BITS {
+            self.reduce(a)
+        } else {
+            Ok(a.clone())
+        }
+    }
+
+    pub fn add(&self, a: &BabyBearVar, b: &BabyBearVar) -> Result<BabyBearVar, SynthesisError> {
+        let (a, b) = self.reduce_pair(a, b, |a, b| a.max(b) + 1)?;
+        let c = BabyBearVar {
+            value: &a.value + &b.value,
+            max_bits: a.max_bits.max(b.max_bits) + 1,
+        };
+        Ok(c)
+    }
+
+    pub fn neg(&self, a: &BabyBearVar) -> BabyBearVar {
+        BabyBearVar {
+            value: FpVar::zero() - &a.value,
+            max_bits: a.max_bits,
+        }
+    }
+
+    pub fn sub(&self, a: &BabyBearVar, b: &BabyBearVar) -> Result<BabyBearVar, SynthesisError> {
+        let (a, b) = self.reduce_pair(a, b, |a, b| a.max(b) + 1)?;
+        let c = BabyBearVar {
+            value: &a.value - &b.value,
+            max_bits: a.max_bits.max(b.max_bits) + 1,
+        };
+        Ok(c)
+    }
+
+    pub fn mul(&self, a: &BabyBearVar, b: &BabyBearVar) -> Result<BabyBearVar, SynthesisError> {
+        let (a, b) = self.reduce_pair(a, b, |a, b| a + b)?;
+        let c = BabyBearVar {
+            value: &a.value * &b.value,
+            max_bits: a.max_bits + b.max_bits,
+        };
+        Ok(c)
+    }
+
+    /// Computes `a * b + c`.
+    pub fn mul_add(
+        &self,
+        a: &BabyBearVar,
+        b: &BabyBearVar,
+        c: &BabyBearVar,
+    ) -> Result<BabyBearVar, SynthesisError> {
+        let (a, b) = self.reduce_pair(a, b, |a, b| a + b + 1)?;
+        let c = if c.max_bits + 1 > MAX_BITS {
+            self.reduce(c)?
+        } else {
+            c.clone()
+        };
+        let d = BabyBearVar {
+            value: &a.value * &b.value + &c.value,
+            max_bits: c.max_bits.max(a.max_bits + b.max_bits) + 1,
+        };
+        if d.max_bits > MAX_BITS {
+            self.reduce(&d)
+        } else {
+            Ok(d)
+        }
+    }
+
+    pub fn div(&self, a: &BabyBearVar, b: &BabyBearVar) -> Result<BabyBearVar, SynthesisError> {
+        let c = self.load_witness(|| {
+            let b_inv = b
+                .to_baby_bear()?
+                .try_inverse()
+                .ok_or(SynthesisError::DivisionByZero)?;
+            Ok(a.to_baby_bear()? * b_inv)
+        })?;
+        // constraint a = b * c (mod p)
+        let prod = self.mul(b, &c)?;
+        self.assert_equal(a, &prod)?;
+        Ok(c)
+    }
+
+    pub fn select(
+        &self,
+        cond: &FpVar<Fr>,
+        a: &BabyBearVar,
+        b: &BabyBearVar,
+    ) -> Result<BabyBearVar, SynthesisError> {
+        Ok(BabyBearVar {
+            value: select(cond, &a.value, &b.value)?,
+            max_bits: a.max_bits.max(b.max_bits),
+        })
+    }
+
+    pub fn assert_zero(&self, a: &BabyBearVar) -> Result<(), SynthesisError> {
+        assert!(a.max_bits <= MAX_BITS);
+        let b: BigUint = BabyBear::ORDER_U32.into();
+        let div = FpVar::new_witness(self.cs.clone(), || {
+            let (div, rem) = fr_to_bigint(&a.value.value()?).div_mod_floor(&b.clone().into());
+            if rem.sign() != Sign::NoSign {
+                return Err(SynthesisError::Unsatisfiable);
+            }
+            Ok(bigint_to_fr(&div))
+        })?;
+        (&div * Fr::from(b.clone())).enforce_equal(&a.value)?;
+        // Constrain that `abs(div) <= 2 ** (2 ** a_num_bits / b).bits()`, see `signed_div_mod`.
+        let bound = (BigUint::from(1u32) << (a.max_bits as u32)) / &b;
+        let shifted_div = div + Fr::from(bound.clone());
+        range_check(&shifted_div, (bound * 2u32 + 1u32).bits() as usize)?;
+        Ok(())
+    }
+
+    pub fn assert_equal(&self, a: &BabyBearVar, b: &BabyBearVar) -> Result<(), SynthesisError> {
+        let diff = self.sub(a, b)?;
+        self.assert_zero(&diff)
+    }
+
+    /// Reduces the operand with more bits, and then the other one, until
+    /// `max_bits(a.max_bits, b.max_bits)` fits into the BN254 capacity.
+    fn reduce_pair(
+        &self,
+        a: &BabyBearVar,
+        b: &BabyBearVar,
+        max_bits: impl Fn(usize, usize) -> usize,
+    ) -> Result<(BabyBearVar, BabyBearVar), SynthesisError> {
+        let (mut a, mut b) = (a.clone(), b.clone());
+        let (first, second) = if a.max_bits < b.max_bits {
+            (&mut b, &mut a)
+        } else {
+            (&mut a, &mut b)
+        };
+        if max_bits(first.max_bits, second.max_bits) > MAX_BITS {
+            *first = self.reduce(first)?;
+            if max_bits(first.max_bits, second.max_bits) > MAX_BITS {
+                *second = self.reduce(second)?;
+            }
+        }
+        Ok((a, b))
+    }
+}
+
+/// Constrains and returns `(c, r)` such that `a = b * c + r` and `0 <= r < b`.
+///
+/// ## Assumptions
+/// * `b != 0` and that `abs(a) < 2^a_max_bits`
+/// * `a_max_bits <= Fr::CAPACITY - RESERVED_HIGH_BITS`
+fn signed_div_mod(
+    a: &FpVar<Fr>,
+    b: u32,
+    a_num_bits: usize,
+) -> Result<(FpVar<Fr>, FpVar<Fr>), SynthesisError> {
+    assert!(a_num_bits <= MAX_BITS);
+    let cs = a.cs();
+    let div_mod = || -> Result<_, SynthesisError> {
+        Ok(fr_to_bigint(&a.value()?).div_mod_floor(&BigInt::from(b)))
+    };
+    let div = FpVar::new_witness(cs.clone(), || Ok(bigint_to_fr(&div_mod()?.0)))?;
+    let rem = FpVar::new_witness(cs, || Ok(bigint_to_fr(&div_mod()?.1)))?;
+    (&div * Fr::from(b) + &rem).enforce_equal(a)?;
+    // Constrain that `abs(div) <= 2 ** (2 ** a_num_bits / b).bits()`.
+    // Since a_num_bits <= Fr::CAPACITY - RESERVED_HIGH_BITS, `b * shifted_div` cannot wrap around
+    // the BN254 modulus, so the quotient is unique. See the halo2 `signed_div_mod` for details.
+    let bound = (BigUint::from(1u32) << (a_num_bits as u32)) / b;
+    let shifted_div = &div + Fr::from(bound.clone());
+    range_check(&shifted_div, (bound * 2u32 + 1u32).bits() as usize)?;
+    // Constrain that remainder is less than divisor (i.e. `r < b`).
+    let b_bits = (u32::BITS - b.leading_zeros()) as usize;
+    range_check(&rem, b_bits)?;
+    range_check(&(FpVar::constant(Fr::from(b - 1)) - &rem), b_bits)?;
+    Ok((div, rem))
+}
+
+// irred poly is x^4 - 11
+pub struct BabyBearExt4Chip<'a> {
+    pub base: &'a BabyBearChip,
+}
+
+impl<'a> BabyBearExt4Chip<'a> {
+    pub fn new(base_chip: &'a BabyBearChip) -> Self {
+        BabyBearExt4Chip { base: base_chip }
+    }
+
+    pub fn load_witness(
+        &self,
+        value: impl Fn() -> Result<BabyBearExt4, SynthesisError>,
+    ) -> Result<BabyBearExt4Var, SynthesisError> {
+        let coeffs: Vec<_> = (0..4)
+            .map(|i| self.base.load_witness(|| Ok(value()?.as_base_slice()[i])))
+            .try_collect()?;
+        Ok(BabyBearExt4Var(coeffs.try_into().unwrap()))
+    }
+
+    pub fn load_constant(&self, value: BabyBearExt4) -> BabyBearExt4Var {
+        BabyBearExt4Var(
+            value
+                .as_base_slice()
+                .iter()
+                .map(|x| self.base.load_constant(*x))
+                .collect_vec()
+                .try_into()
+                .unwrap(),
+        )
+    }
+
+    pub fn add(
+        &self,
+        a: &BabyBearExt4Var,
+        b: &BabyBearExt4Var,
+    ) -> Result<BabyBearExt4Var, SynthesisError> {
+        self.zip_with(a, b, |a, b| self.base.add(a, b))
+    }
+
+    pub fn neg(&self, a: &BabyBearExt4Var) -> BabyBearExt4Var {
+        BabyBearExt4Var(a.0.clone().map(|x| self.base.neg(&x)))
+    }
+
+    pub fn sub(
+        &self,
+        a: &BabyBearExt4Var,
+        b: &BabyBearExt4Var,
+    ) -> Result<BabyBearExt4Var, SynthesisError> {
+        self.zip_with(a, b, |a, b| self.base.sub(a, b))
+    }
+
+    pub fn scalar_mul(
+        &self,
+        a: &BabyBearExt4Var,
+        b: &BabyBearVar,
+    ) -> Result<BabyBearExt4Var, SynthesisError> {
+        let coeffs: Vec<_> = a.0.iter().map(|x| self.base.mul(x, b)).try_collect()?;
+        Ok(BabyBearExt4Var(coeffs.try_into().unwrap()))
+    }
+
+    pub fn select(
+        &self,
+        cond: &FpVar<Fr>,
+        a: &BabyBearExt4Var,
+        b: &BabyBearExt4Var,
+    ) -> Result<BabyBearExt4Var, SynthesisError> {
+        self.zip_with(a, b, |a, b| self.base.select(cond, a, b))
+    }
+
+    pub fn assert_equal(
+        &self,
+        a: &BabyBearExt4Var,
+        b: &BabyBearExt4Var,
+    ) -> Result<(), SynthesisError> {
+        for (a, b) in a.0.iter().zip(b.0.iter()) {
+            self.base.assert_equal(a, b)?;
+        }
+        Ok(())
+    }
+
+    pub fn mul(
+        &self,
+        a: &BabyBearExt4Var,
+        b: &BabyBearExt4Var,
+    ) -> Result<BabyBearExt4Var, SynthesisError> {
+        // Schoolbook multiplication. Additions are free in R1CS, so unlike the halo2 chip there is
+        // nothing to gain from a dedicated inner product.
+        let mut coeffs: Vec<Option<BabyBearVar>> = vec![None; 7];
+        for (i, a) in a.0.iter().enumerate() {
+            for (j, b) in b.0.iter().enumerate() {
+                let prod = self.base.mul(a, b)?;
+                coeffs[i + j] = Some(match &coeffs[i + j] {
+                    Some(acc) => self.base.add(acc, &prod)?,
+                    None => prod,
+                });
+            }
+        }
+        let mut coeffs = coeffs.into_iter().map(Option::unwrap).collect_vec();
+        let w = self
+            .base
+            .load_constant(<BabyBear as BinomiallyExtendable<4>>::W);
+        for i in 4..7 {
+            coeffs[i - 4] = self.base.mul_add(&coeffs[i], &w, &coeffs[i - 4])?;
+        }
+        coeffs.truncate(4);
+        Ok(BabyBearExt4Var(coeffs.try_into().unwrap()))
+    }
+
+    pub fn div(
+        &self,
+        a: &BabyBearExt4Var,
+        b: &BabyBearExt4Var,
+    ) -> Result<BabyBearExt4Var, SynthesisError> {
+        let c = self.load_witness(|| {
+            let b_inv = b
+                .to_extension_field()?
+                .try_inverse()
+                .ok_or(SynthesisError::DivisionByZero)?;
+            Ok(a.to_extension_field()? * b_inv)
+        })?;
+        // constraint a = b * c
+        let prod = self.mul(b, &c)?;
+        self.assert_equal(a, &prod)?;
+        Ok(c)
+    }
+
+    pub fn reduce_max_bits(&self, a: &BabyBearExt4Var) -> Result<BabyBearExt4Var, SynthesisError> {
+        let coeffs: Vec<_> =
+            a.0.iter()
+                .map(|x| self.base.reduce_max_bits(x))
+                .try_collect()?;
+        Ok(BabyBearExt4Var(coeffs.try_into().unwrap()))
+    }
+
+    fn zip_with(
+        &self,
+        a: &BabyBearExt4Var,
+        b: &BabyBearExt4Var,
+        f: impl Fn(&BabyBearVar, &BabyBearVar) -> Result<BabyBearVar, SynthesisError>,
+    ) -> Result<BabyBearExt4Var, SynthesisError> {
+        let coeffs: Vec<_> =
+            a.0.iter()
+                .zip(b.0.iter())
+                .map(|(a, b)| f(a, b))
+                .try_collect()?;
+        Ok(BabyBearExt4Var(coeffs.try_into().unwrap()))
+    }
+}
+
+/// Constrains `a < 2^num_bits` and returns the little-endian bits of `a`. Requires
+/// `num_bits < Fr::MODULUS_BIT_SIZE`.
+pub fn range_check(a: &FpVar<Fr>, num_bits: usize) -> Result<Vec<Boolean<Fr>>, SynthesisError> {
+    assert!(num_bits < Fr::MODULUS_BIT_SIZE as usize);
+    let cs = a.cs();
+    let bits: Vec<_> = (0..num_bits)
+        .map(|i| Boolean::new_witness(cs.clone(), || Ok(a.value()?.into_bigint().get_bit(i))))
+        .try_collect()?;
+    Boolean::le_bits_to_fp_var(&bits)?.enforce_equal(a)?;
+    Ok(bits)
+}
+
+/// Returns `a` if `cond` is one and `b` if `cond` is zero. `cond` is assumed to be boolean.
+pub fn select(cond: &FpVar<Fr>, a: &FpVar<Fr>, b: &FpVar<Fr>) -> Result<FpVar<Fr>, SynthesisError> {
+    Ok(cond * (a - b) + b)
+}
+
+/// Interprets `a` as a signed integer in `(-p/2, p/2]`.
+pub fn fr_to_bigint(a: &Fr) -> BigInt {
+    let a = BigUint::from(a.into_bigint());
+    let modulus = BigUint::from(Fr::MODULUS);
+    if a > BigUint::from(Fr::MODULUS_MINUS_ONE_DIV_TWO) {
+        -BigInt::from(modulus - a)
+    } else {
+        BigInt::from(a)
+    }
+}
+
+pub fn bigint_to_fr(a: &BigInt) -> Fr {
+    let magnitude = Fr::from(a.magnitude().clone());
+    if a.sign() == Sign::Minus {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
@@ -0,0 +1,413 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    marker::PhantomData,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::LazyLock,
+};
+
+use ark_bn254::Fr;
+use ark_ff::PrimeField as _;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    R1CSVar, ToBitsGadget,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use itertools::Itertools;
+use openvm_stark_backend::p3_field::PrimeField;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, p3_bn254_fr::Bn254Fr};
+
+use crate::{
+    constraints::r1cs::{
+        baby_bear::{
+            range_check, select, BabyBearChip, BabyBearExt4, BabyBearExt4Chip, BabyBearExt4Var,
+            BabyBearVar,
+        },
+        poseidon2_perm::{Poseidon2Params, Poseidon2State},
+    },
+    ir::{Config, DslIr, TracedVec, Witness},
+};
+
+const POSEIDON2_T: usize = 3;
+static POSEIDON2_PARAMS: LazyLock<Poseidon2Params<POSEIDON2_T>> = LazyLock::new(|| {
+    use zkhash::{
+        ark_ff::{BigInteger, PrimeField as _},
+        fields::bn256::FpBN256 as ark_FpBN256,
+        poseidon2::poseidon2_instance_bn256::{MAT_DIAG3_M_1, RC3},
+    };
+
+    fn convert_fr(input: ark_FpBN256) -> Fr {
+        Fr::from_le_bytes_mod_order(&input.into_bigint().to_bytes_le())
+    }
+    const T: usize = 3;
+    let rounds_f = 8;
+    let rounds_p = 56;
+    let mut round_constants: Vec<[Fr; T]> = RC3
+        .iter()
+        .map(|vec| {
+            vec.iter()
+                .cloned()
+                .map(convert_fr)
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap()
+        })
+        .collect();
+
+    let rounds_f_beginning = rounds_f / 2;
+    let p_end = rounds_f_beginning + rounds_p;
+    let internal_round_constants = round_constants
+        .drain(rounds_f_beginning..p_end)
+        .map(|vec| vec[0])
+        .collect::<Vec<_>>();
+    let external_round_constants = round_constants;
+    Poseidon2Params {
+        rounds_f,
+        rounds_p,
+        mat_internal_diag_m_1: MAT_DIAG3_M_1
+            .iter()
+            .copied()
+            .map(convert_fr)
+            .collect_vec()
+            .try_into()
+            .unwrap(),
+        external_rc: external_round_constants,
+        internal_rc: internal_round_constants,
+    }
+});
+
+/// The backend for the R1CS constraint compiler. It supports the same DSL operations as the
+/// Halo2 constraint compiler and produces an equivalent circuit.
+#[derive(Debug, Clone)]
+pub struct R1csConstraintCompiler<C: Config> {
+    pub num_public_values: usize,
+    pub phantom: PhantomData<C>,
+}
+
+/// A circuit given by DSL operations. The witness is only read when proving, so it can be left
+/// empty for setup.
+#[derive(Debug, Clone)]
+pub struct R1csCircuit<C: Config> {
+    pub operations: TracedVec<DslIr<C>>,
+    pub witness: Witness<C>,
+    pub num_public_values: usize,
+}
+
+impl<C> ConstraintSynthesizer<Fr> for R1csCircuit<C>
+where
+    C: Config<N = Bn254Fr, F = BabyBear, EF = BabyBearExt4> + Debug,
+{
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        R1csConstraintCompiler::<C>::new(self.num_public_values).constrain_r1cs(
+            cs,
+            self.operations,
+            &self.witness,
+        )
+    }
+}
+
+impl<C: Config + Debug> R1csConstraintCompiler<C> {
+    pub fn new(num_public_values: usize) -> Self {
+        Self {
+            num_public_values,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Creates R1CS constraints from a list of operations in the DSL. The public values are
+    /// allocated as the instance variables of `cs`, in order.
+    pub fn constrain_r1cs(
+        &self,
+        cs: ConstraintSystemRef<Fr>,
+        operations: TracedVec<DslIr<C>>,
+        witness: &Witness<C>,
+    ) -> Result<(), SynthesisError>
+    where
+        C: Config<N = Bn254Fr, F = BabyBear, EF = BabyBearExt4>,
+    {
+        let f_chip = BabyBearChip::new(cs.clone());
+        let ext_chip = BabyBearExt4Chip::new(&f_chip);
+        let mut public_values = vec![FpVar::zero(); self.num_public_values];
+
+        // Local variables for referencing during the course of constraint building
+        let mut vars = HashMap::<u32, FpVar<Fr>>::new();
+        let mut felts = HashMap::<u32, BabyBearVar>::new();
+        let mut exts = HashMap::<u32, BabyBearExt4Var>::new();
+
+        for (instruction, backtrace) in operations {
+            let res = catch_unwind(AssertUnwindSafe(|| -> Result<(), SynthesisError> {
+                match instruction {
+                    DslIr::ImmV(a, b) => {
+                        vars.insert(a.0, FpVar::constant(convert_fr(&b)));
+                    }
+                    DslIr::ImmF(a, b) => {
+                        felts.insert(a.0, f_chip.load_constant(b));
+                    }
+                    DslIr::ImmE(a, b) => {
+                        exts.insert(a.0, ext_chip.load_constant(b));
+                    }
+                    DslIr::AddV(a, b, c) => {
+                        vars.insert(a.0, &vars[&b.0] + &vars[&c.0]);
+                    }
+                    DslIr::AddVI(a, b, c) => {
+                        vars.insert(a.0, &vars[&b.0] + convert_fr(&c));
+                    }
+                    DslIr::AddF(a, b, c) => {
+                        let x = f_chip.add(&felts[&b.0], &felts[&c.0])?;
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::AddFI(a, b, c) => {
+                        let x = f_chip.add(&felts[&b.0], &f_chip.load_constant(c))?;
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::AddE(a, b, c) => {
+                        let x = ext_chip.add(&exts[&b.0], &exts[&c.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::AddEF(a, b, c) => {
+                        let mut x = exts[&b.0].clone();
+                        x.0[0] = f_chip.add(&x.0[0], &felts[&c.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::AddEFI(a, b, c) => {
+                        let mut x = exts[&b.0].clone();
+                        x.0[0] = f_chip.add(&x.0[0], &f_chip.load_constant(c))?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::AddEI(a, b, c) => {
+                        let x = ext_chip.add(&exts[&b.0], &ext_chip.load_constant(c))?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::AddEFFI(a, b, c) => {
+                        let mut x = ext_chip.load_constant(c);
+                        x.0[0] = f_chip.add(&x.0[0], &felts[&b.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::SubV(a, b, c) => {
+                        vars.insert(a.0, &vars[&b.0] - &vars[&c.0]);
+                    }
+                    DslIr::SubF(a, b, c) => {
+                        let x = f_chip.sub(&felts[&b.0], &felts[&c.0])?;
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::SubE(a, b, c) => {
+                        let x = ext_chip.sub(&exts[&b.0], &exts[&c.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::SubEF(a, b, c) => {
+                        let mut x = exts[&b.0].clone();
+                        x.0[0] = f_chip.sub(&x.0[0], &felts[&c.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::SubEI(a, b, c) => {
+                        let x = ext_chip.sub(&exts[&b.0], &ext_chip.load_constant(c))?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::SubVIN(a, b, c) => {
+                        vars.insert(a.0, FpVar::constant(convert_fr(&b)) - &vars[&c.0]);
+                    }
+                    DslIr::SubEIN(a, b, c) => {
+                        let x = ext_chip.sub(&ext_chip.load_constant(b), &exts[&c.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::SubEFI(a, b, c) => {
+                        let mut x = exts[&b.0].clone();
+                        x.0[0] = f_chip.sub(&x.0[0], &f_chip.load_constant(c))?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::MulV(a, b, c) => {
+                        vars.insert(a.0, &vars[&b.0] * &vars[&c.0]);
+                    }
+                    DslIr::MulVI(a, b, c) => {
+                        vars.insert(a.0, &vars[&b.0] * convert_fr(&c));
+                    }
+                    DslIr::MulF(a, b, c) => {
+                        let x = f_chip.mul(&felts[&b.0], &felts[&c.0])?;
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::MulFI(a, b, c) => {
+                        let x = f_chip.mul(&felts[&b.0], &f_chip.load_constant(c))?;
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::MulE(a, b, c) => {
+                        let x = ext_chip.mul(&exts[&b.0], &exts[&c.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::MulEI(a, b, c) => {
+                        let x = ext_chip.mul(&exts[&b.0], &ext_chip.load_constant(c))?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::MulEF(a, b, c) => {
+                        let x = ext_chip.scalar_mul(&exts[&b.0], &felts[&c.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::MulEFI(a, b, c) => {
+                        let x = ext_chip.scalar_mul(&exts[&b.0], &f_chip.load_constant(c))?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::DivF(a, b, c) => {
+                        let x = f_chip.div(&felts[&b.0], &felts[&c.0])?;
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::DivFIN(a, b, c) => {
+                        // a = b / c
+                        let x = f_chip.div(&f_chip.load_constant(b), &felts[&c.0])?;
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::DivE(a, b, c) => {
+                        let x = ext_chip.div(&exts[&b.0], &exts[&c.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::DivEIN(a, b, c) => {
+                        let x = ext_chip.div(&ext_chip.load_constant(b), &exts[&c.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::NegE(a, b) => {
+                        exts.insert(a.0, ext_chip.neg(&exts[&b.0]));
+                    }
+                    DslIr::CastFV(a, b) => {
+                        let reduced_felt = f_chip.reduce(&felts[&b.0])?;
+                        vars.insert(a.0, reduced_felt.value);
+                    }
+                    DslIr::CircuitNum2BitsV(value, bits, output) => {
+                        let value = &vars[&value.0];
+                        let x = if bits < Fr::MODULUS_BIT_SIZE as usize {
+                            range_check(value, bits)?
+                        } else {
+                            value.to_bits_le()?
+                        };
+                        for (i, o) in output.into_iter().enumerate() {
+                            let bit = x.get(i).map_or(FpVar::zero(), |bit| bit.clone().into());
+                            vars.insert(o.0, bit);
+                        }
+                    }
+                    DslIr::CircuitNum2BitsF(value, output) => {
+                        let val = f_chip.reduce(&felts[&value.0])?;
+                        let x = range_check(&val.value, 32)?; // C::F::bits());
+                        assert!(output.len() <= x.len());
+                        for (o, x) in output.into_iter().zip(x) {
+                            vars.insert(o.0, x.into());
+                        }
+                    }
+                    DslIr::CircuitPoseidon2Permute(state_vars) => {
+                        let mut state = Poseidon2State::<POSEIDON2_T>::new(
+                            state_vars.map(|x| vars[&x.0].clone()),
+                        );
+                        state.permutation(&POSEIDON2_PARAMS);
+                        for (x, s) in state_vars.iter().zip(state.s) {
+                            vars.insert(x.0, s);
+                        }
+                    }
+                    DslIr::CircuitSelectV(cond, a, b, out) => {
+                        let x = select(&vars[&cond.0], &vars[&a.0], &vars[&b.0])?;
+                        vars.insert(out.0, x);
+                    }
+                    DslIr::CircuitSelectF(cond, a, b, out) => {
+                        let x = f_chip.select(&vars[&cond.0], &felts[&a.0], &felts[&b.0])?;
+                        felts.insert(out.0, x);
+                    }
+                    DslIr::CircuitSelectE(cond, a, b, out) => {
+                        let x = ext_chip.select(&vars[&cond.0], &exts[&a.0], &exts[&b.0])?;
+                        exts.insert(out.0, x);
+                    }
+                    DslIr::CircuitExt2Felt(a, b) => {
+                        for (i, x) in a.iter().enumerate() {
+                            felts.insert(x.0, exts[&b.0].0[i].clone());
+                        }
+                    }
+                    DslIr::AssertEqV(a, b) => {
+                        vars[&a.0].enforce_equal(&vars[&b.0])?;
+                    }
+                    DslIr::AssertEqVI(a, b) => {
+                        vars[&a.0].enforce_equal(&FpVar::constant(convert_fr(&b)))?;
+                    }
+                    DslIr::AssertEqF(a, b) => {
+                        f_chip.assert_equal(&felts[&a.0], &felts[&b.0])?;
+                    }
+                    DslIr::AssertEqFI(a, b) => {
+                        f_chip.assert_equal(&felts[&a.0], &f_chip.load_constant(b))?;
+                    }
+                    DslIr::AssertEqE(a, b) => {
+                        ext_chip.assert_equal(&exts[&a.0], &exts[&b.0])?;
+                    }
+                    DslIr::AssertEqEI(a, b) => {
+                        ext_chip.assert_equal(&exts[&a.0], &ext_chip.load_constant(b))?;
+                    }
+                    DslIr::PrintV(a) => {
+                        println!("PrintV: {:?}", vars[&a.0].value());
+                    }
+                    DslIr::PrintF(a) => {
+                        println!("PrintF: {:?}", felts[&a.0].to_baby_bear());
+                    }
+                    DslIr::PrintE(a) => {
+                        println!("PrintE:");
+                        for x in exts[&a.0].0.iter() {
+                            println!("{:?}", x.to_baby_bear());
+                        }
+                    }
+                    DslIr::WitnessVar(a, b) => {
+                        let x = FpVar::new_witness(cs.clone(), || {
+                            let x = witness.vars.get(b as usize);
+                            x.map(convert_fr).ok_or(SynthesisError::AssignmentMissing)
+                        })?;
+                        vars.insert(a.0, x);
+                    }
+                    DslIr::WitnessFelt(a, b) => {
+                        let x = f_chip.load_witness(|| {
+                            let x = witness.felts.get(b as usize);
+                            x.copied().ok_or(SynthesisError::AssignmentMissing)
+                        })?;
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::WitnessExt(a, b) => {
+                        let x = ext_chip.load_witness(|| {
+                            let x = witness.exts.get(b as usize);
+                            x.copied().ok_or(SynthesisError::AssignmentMissing)
+                        })?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::CircuitFelts2Ext(a, b) => {
+                        let x = BabyBearExt4Var(a.map(|a| felts[&a.0].clone()));
+                        exts.insert(b.0, x);
+                    }
+                    DslIr::CircuitFeltReduce(a) => {
+                        let x = f_chip.reduce_max_bits(&felts[&a.0])?;
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::CircuitExtReduce(a) => {
+                        let x = ext_chip.reduce_max_bits(&exts[&a.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::CycleTrackerStart(_) | DslIr::CycleTrackerEnd(_) => {}
+                    DslIr::CircuitPublish(val, index) => {
+                        public_values[index] = vars[&val.0].clone();
+                    }
+                    _ => panic!("unsupported {:?}", instruction),
+                }
+                Ok(())
+            }));
+            match res {
+                Ok(res) => res?,
+                Err(err) => {
+                    if let Some(mut backtrace) = backtrace {
+                        backtrace.resolve();
+                        eprintln!("openvm circuit failure; backtrace:\n{:?}", backtrace);
+                    }
+                    std::panic::resume_unwind(err);
+                }
+            }
+        }
+
+        for value in public_values {
+            FpVar::new_input(cs.clone(), || value.value())?.enforce_equal(&value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Assumes F is Bn254 Fr and converts to arkworks Fr type
+pub fn convert_fr<F: PrimeField>(a: &F) -> Fr {
+    Fr::from(a.as_canonical_biguint())
+}
@@ -0,0 +1,5 @@
+//! R1CS helper functions
+
+pub mod baby_bear;
+pub mod compiler;
+pub mod poseidon2_perm;
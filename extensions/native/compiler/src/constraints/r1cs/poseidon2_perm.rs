@@ -0,0 +1,92 @@
+//! R1CS implementation of poseidon2 perm for Bn254Fr
+//! sbox degree 5
+
+use ark_bn254::Fr;
+use ark_r1cs_std::fields::fp::FpVar;
+
+#[derive(Debug, Clone)]
+pub struct Poseidon2Params<const T: usize> {
+    /// Number of full rounds
+    pub rounds_f: usize,
+    pub rounds_p: usize,
+    pub mat_internal_diag_m_1: [Fr; T],
+    pub external_rc: Vec<[Fr; T]>,
+    pub internal_rc: Vec<Fr>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Poseidon2State<const T: usize> {
+    pub s: [FpVar<Fr>; T],
+}
+
+impl<const T: usize> Poseidon2State<T> {
+    pub fn new(state: [FpVar<Fr>; T]) -> Self {
+        Self { s: state }
+    }
+
+    /// Perform permutation on this state.
+    pub fn permutation(&mut self, params: &Poseidon2Params<T>) {
+        let rounds_f_beginning = params.rounds_f / 2;
+
+        // First half of the full round
+        self.matmul_external();
+        for r in 0..rounds_f_beginning {
+            self.add_rc(&params.external_rc[r]);
+            self.sbox();
+            self.matmul_external();
+        }
+
+        for r in 0..params.rounds_p {
+            self.s[0] += params.internal_rc[r];
+            self.s[0] = Self::x_power5(&self.s[0]);
+            self.matmul_internal(&params.mat_internal_diag_m_1);
+        }
+
+        for r in rounds_f_beginning..params.rounds_f {
+            self.add_rc(&params.external_rc[r]);
+            self.sbox();
+            self.matmul_external();
+        }
+    }
+
+    fn x_power5(x: &FpVar<Fr>) -> FpVar<Fr> {
+        let x2 = x * x;
+        let x4 = &x2 * &x2;
+        x * x4
+    }
+
+    fn sbox(&mut self) {
+        for x in self.s.iter_mut() {
+            *x = Self::x_power5(x);
+        }
+    }
+
+    fn matmul_external(&mut self) {
+        // Only doing T = 3 case
+        assert_eq!(T, 3);
+
+        // Matrix is circ(2, 1, 1)
+        let sum = self.sum();
+        for x in self.s.iter_mut() {
+            *x += &sum;
+        }
+    }
+
+    fn add_rc(&mut self, round_constants: &[Fr; T]) {
+        for (x, rc) in self.s.iter_mut().zip(round_constants.iter()) {
+            *x += *rc;
+        }
+    }
+
+    fn matmul_internal(&mut self, mat_internal_diag_m_1: &[Fr; T]) {
+        assert_eq!(T, 3);
+        let sum = self.sum();
+        for (x, diag_m_1) in self.s.iter_mut().zip(mat_internal_diag_m_1.iter()) {
+            *x = &*x * *diag_m_1 + &sum;
+        }
+    }
+
+    fn sum(&self) -> FpVar<Fr> {
+        self.s.iter().sum()
+    }
+}
@@ -4,6 +4,7 @@ use openvm_instructions::{
     program::{DEFAULT_MAX_NUM_PUBLIC_VALUES, DEFAULT_PC_STEP},
     LocalOpcode, PhantomDiscriminant, PublishOpcode, SysPhantom, SystemOpcode, VmOpcode,
 };
+use openvm_keccak256_transpiler::Rv32KeccakOpcode;
 use openvm_rv32im_transpiler::BranchEqualOpcode;
 use openvm_stark_backend::p3_field::{ExtensionField, PrimeField32, PrimeField64};
 use serde::{Deserialize, Serialize};
@@ -20,6 +21,9 @@ pub struct CompilerOptions {
     // The compiler will ensure that the heap pointer is aligned to be a multiple of `word_size`.
     pub word_size: usize,
     pub enable_cycle_tracker: bool,
+    /// Run the [passes](crate::passes) over the DSL program before lowering it to assembly.
+    #[serde(default)]
+    pub enable_ir_optimizations: bool,
 }
 
 impl Default for CompilerOptions {
@@ -27,6 +31,7 @@ impl Default for CompilerOptions {
         CompilerOptions {
             word_size: 8,
             enable_cycle_tracker: false,
+            enable_ir_optimizations: false,
         }
     }
 }
@@ -40,6 +45,10 @@ impl CompilerOptions {
         self.enable_cycle_tracker = true;
         self
     }
+    pub fn with_ir_optimizations(mut self) -> Self {
+        self.enable_ir_optimizations = true;
+        self
+    }
 }
 
 fn inst<F: PrimeField64>(opcode: VmOpcode, a: F, b: F, c: F, d: AS, e: AS) -> Instruction<F> {
@@ -447,6 +456,14 @@ fn convert_instruction<F: PrimeField32, EF: ExtensionField<F>>(
             AS::Native,
             AS::Native,
         )],
+        AsmInstruction::Keccak256(dst, src, len) => vec![inst(
+            options.opcode_with_offset(Rv32KeccakOpcode::KECCAK256),
+            i32_f(dst),
+            i32_f(src),
+            i32_f(len),
+            AS::Native,
+            AS::Native,
+        )],
         AsmInstruction::CycleTrackerStart() => {
             if options.enable_cycle_tracker {
                 vec![Instruction::debug(PhantomDiscriminant(SysPhantom::CtStart as u16))]
@@ -196,6 +196,9 @@ pub enum DslIr<C: Config> {
     /// Permutes an array of Bn254 elements using Poseidon2 (output = p2_permute(array)). Should only
     /// be used when target is a circuit.
     CircuitPoseidon2Permute([Var<C::N>; 3]),
+    /// Hashes bytes using Keccak256. The operands hold the little-endian bytes of the output
+    /// pointer, the input pointer and the input length, like the registers of RV32 KECCAK256.
+    Keccak256(Ext<C::F, C::EF>, Ext<C::F, C::EF>, Ext<C::F, C::EF>),
 
     // Miscellaneous instructions.
     /// Prints a variable.
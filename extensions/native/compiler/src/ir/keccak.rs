@@ -0,0 +1,52 @@
+use openvm_stark_backend::p3_field::FieldAlgebra;
+
+use super::{Array, Builder, Config, DslIr, Ext, Felt, RVar, Var};
+
+/// Number of bytes of a Keccak256 digest.
+pub const KECCAK_DIGEST_BYTES: usize = 32;
+
+impl<C: Config> Builder<C> {
+    /// Computes the Keccak256 digest of `input` and returns its 32 bytes.
+    ///
+    /// Every element of `input` must be a byte, and `input` must start at a multiple of 4 (arrays
+    /// allocated on the heap do, shifted views may not). The VM must support KECCAK256 in the
+    /// native address space.
+    pub fn keccak256(&mut self, input: &Array<C, Felt<C::F>>) -> Array<C, Felt<C::F>> {
+        assert!(!self.flags.static_only, "dynamic mode only");
+        if let Array::Fixed(_) = input {
+            panic!("Keccak256 is not allowed on fixed arrays");
+        }
+        let output = self.dyn_array::<Felt<C::F>>(KECCAK_DIGEST_BYTES);
+        let len: Var<_> = self.eval(RVar::from(input.len()));
+
+        let dst = self.le_bytes_register(output.ptr().address);
+        let src = self.le_bytes_register(input.ptr().address);
+        let len = self.le_bytes_register(len);
+        self.push(DslIr::Keccak256(dst, src, len));
+        output
+    }
+
+    /// Stores the little-endian bytes of `value` in the coefficients of an extension element,
+    /// which KECCAK256 reads like an RV32 register.
+    fn le_bytes_register(&mut self, value: Var<C::N>) -> Ext<C::F, C::EF> {
+        let value = self.unsafe_cast_var_to_felt(value);
+        // BabyBear elements fit in 31 bits.
+        let num_bits = 31;
+        let bits = self.num2bits_f(value, num_bits as u32);
+        let bytes: Vec<Felt<_>> = (0..num_bits)
+            .step_by(8)
+            .map(|start| {
+                let byte: Var<_> = self.eval(C::N::ZERO);
+                for i in start..(start + 8).min(num_bits) {
+                    let bit = self.get(&bits, i);
+                    self.assign(
+                        &byte,
+                        byte + bit * C::N::from_canonical_u32(1 << (i - start)),
+                    );
+                }
+                self.unsafe_cast_var_to_felt(byte)
+            })
+            .collect();
+        self.ext_from_base_slice(&bytes)
+    }
+}
@@ -1,6 +1,7 @@
 pub use builder::*;
 pub use collections::*;
 pub use instructions::*;
+pub use keccak::KECCAK_DIGEST_BYTES;
 use openvm_stark_backend::p3_field::{ExtensionField, PrimeField, TwoAdicField};
 pub use poseidon::{DIGEST_SIZE, PERMUTATION_WIDTH};
 pub use ptr::*;
@@ -15,6 +16,7 @@ mod builder;
 mod collections;
 mod fri;
 mod instructions;
+mod keccak;
 mod poseidon;
 mod ptr;
 mod select;
@@ -1,7 +1,7 @@
 #![allow(clippy::type_complexity)]
 #![allow(clippy::needless_range_loop)]
 
-use openvm_instructions::LocalOpcode;
+use openvm_instructions::{disasm::OpcodeTable, LocalOpcode};
 use openvm_instructions_derive::LocalOpcode;
 use openvm_rv32im_transpiler::BranchEqualOpcode;
 use serde::{Deserialize, Serialize};
@@ -14,6 +14,7 @@ pub mod asm;
 pub mod constraints;
 pub mod conversion;
 pub mod ir;
+pub mod passes;
 
 pub mod prelude {
     pub use openvm_native_compiler_derive::DslVariable;
@@ -201,3 +202,26 @@ pub enum VerifyBatchOpcode {
     /// per column polynomial, per opening point
     VERIFY_BATCH,
 }
+
+/// Adds the opcodes of the native extension and the native address space to `table`, to
+/// disassemble and assemble native programs.
+pub fn with_native_opcodes(table: OpcodeTable) -> OpcodeTable {
+    let mut table = table
+        .with_opcodes::<NativeLoadStoreOpcode>("native")
+        .with_opcodes_at::<NativeLoadStoreOpcode>("native4", NativeLoadStore4Opcode::CLASS_OFFSET)
+        .with_opcodes_at::<BranchEqualOpcode>("native", NativeBranchEqualOpcode::CLASS_OFFSET)
+        .with_opcodes::<NativeJalOpcode>("native")
+        .with_opcodes::<FieldArithmeticOpcode>("native")
+        .with_opcodes::<FieldExtensionOpcode>("native")
+        .with_opcodes::<Poseidon2Opcode>("native")
+        .with_opcodes::<FriOpcode>("native")
+        .with_opcodes::<VerifyBatchOpcode>("native")
+        .with_address_space_operands(FriOpcode::FRI_REDUCED_OPENING.global_opcode(), &[])
+        .with_address_space_operands(VerifyBatchOpcode::VERIFY_BATCH.global_opcode(), &[])
+        .with_address_space(conversion::AS::Native as u32, "native");
+    // The operands `b` and `c` each have their own address space.
+    for opcode in FieldArithmeticOpcode::iter() {
+        table = table.with_address_space_operands(opcode.global_opcode(), &[3, 4, 5]);
+    }
+    table
+}
@@ -0,0 +1,457 @@
+use std::collections::HashMap;
+
+use crate::ir::{Array, Config, DslIr, MemIndex, Ptr, RVar, TracedVec, Usize};
+
+/// A memory-backed DSL variable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) enum Loc {
+    Var(u32),
+    Felt(u32),
+    Ext(u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Access {
+    Use,
+    Def,
+    /// Read and then overwritten by the same instruction.
+    UseDef,
+}
+
+impl Access {
+    pub fn is_use(self) -> bool {
+        matches!(self, Access::Use | Access::UseDef)
+    }
+
+    pub fn is_def(self) -> bool {
+        matches!(self, Access::Def | Access::UseDef)
+    }
+}
+
+/// Calls `f` on every variable read or written by `op` itself. Nested blocks of control flow
+/// instructions are not visited.
+///
+/// Returns `false` if `op` has operands the passes cannot reason about (static arrays).
+pub(crate) fn visit_operands<C: Config>(
+    op: &mut DslIr<C>,
+    f: &mut impl FnMut(Loc, &mut u32, Access),
+) -> bool {
+    use Access::*;
+    macro_rules! var {
+        ($v:expr, $a:expr) => {
+            f(Loc::Var($v.0), &mut $v.0, $a)
+        };
+    }
+    macro_rules! felt {
+        ($v:expr, $a:expr) => {
+            f(Loc::Felt($v.0), &mut $v.0, $a)
+        };
+    }
+    macro_rules! ext {
+        ($v:expr, $a:expr) => {
+            f(Loc::Ext($v.0), &mut $v.0, $a)
+        };
+    }
+    match op {
+        DslIr::ImmV(dst, _) => var!(dst, Def),
+        DslIr::ImmF(dst, _) => felt!(dst, Def),
+        DslIr::ImmE(dst, _) => ext!(dst, Def),
+
+        DslIr::AddV(dst, lhs, rhs) | DslIr::SubV(dst, lhs, rhs) | DslIr::MulV(dst, lhs, rhs) => {
+            var!(lhs, Use);
+            var!(rhs, Use);
+            var!(dst, Def);
+        }
+        DslIr::AddVI(dst, src, _)
+        | DslIr::SubVI(dst, src, _)
+        | DslIr::SubVIN(dst, _, src)
+        | DslIr::MulVI(dst, src, _)
+        | DslIr::NegV(dst, src) => {
+            var!(src, Use);
+            var!(dst, Def);
+        }
+        DslIr::AddF(dst, lhs, rhs)
+        | DslIr::SubF(dst, lhs, rhs)
+        | DslIr::MulF(dst, lhs, rhs)
+        | DslIr::DivF(dst, lhs, rhs) => {
+            felt!(lhs, Use);
+            felt!(rhs, Use);
+            felt!(dst, Def);
+        }
+        DslIr::AddFI(dst, src, _)
+        | DslIr::SubFI(dst, src, _)
+        | DslIr::SubFIN(dst, _, src)
+        | DslIr::MulFI(dst, src, _)
+        | DslIr::DivFI(dst, src, _)
+        | DslIr::DivFIN(dst, _, src)
+        | DslIr::NegF(dst, src) => {
+            felt!(src, Use);
+            felt!(dst, Def);
+        }
+        DslIr::AddE(dst, lhs, rhs)
+        | DslIr::SubE(dst, lhs, rhs)
+        | DslIr::MulE(dst, lhs, rhs)
+        | DslIr::DivE(dst, lhs, rhs) => {
+            ext!(lhs, Use);
+            ext!(rhs, Use);
+            ext!(dst, Def);
+        }
+        DslIr::AddEI(dst, src, _)
+        | DslIr::SubEI(dst, src, _)
+        | DslIr::SubEIN(dst, _, src)
+        | DslIr::MulEI(dst, src, _)
+        | DslIr::DivEI(dst, src, _)
+        | DslIr::DivEIN(dst, _, src)
+        | DslIr::AddEFI(dst, src, _)
+        | DslIr::SubEFI(dst, src, _)
+        | DslIr::MulEFI(dst, src, _)
+        | DslIr::DivEFI(dst, src, _)
+        | DslIr::NegE(dst, src) => {
+            ext!(src, Use);
+            ext!(dst, Def);
+        }
+        DslIr::AddEF(dst, lhs, rhs)
+        | DslIr::SubEF(dst, lhs, rhs)
+        | DslIr::MulEF(dst, lhs, rhs)
+        | DslIr::DivEF(dst, lhs, rhs) => {
+            ext!(lhs, Use);
+            felt!(rhs, Use);
+            ext!(dst, Def);
+        }
+        DslIr::AddEFFI(dst, src, _) => {
+            felt!(src, Use);
+            ext!(dst, Def);
+        }
+        DslIr::CastFV(dst, src) => {
+            felt!(src, Use);
+            var!(dst, Def);
+        }
+        DslIr::UnsafeCastVF(dst, src) => {
+            var!(src, Use);
+            felt!(dst, Def);
+        }
+
+        DslIr::ZipFor(starts, end0, _, loop_vars, _) => {
+            for start in starts.iter_mut() {
+                rvar(start, f);
+            }
+            rvar(end0, f);
+            for v in loop_vars.iter_mut() {
+                var!(v, Def);
+            }
+        }
+        DslIr::IfEq(lhs, rhs, _, _) | DslIr::IfNe(lhs, rhs, _, _) => {
+            var!(lhs, Use);
+            var!(rhs, Use);
+        }
+        DslIr::IfEqI(lhs, _, _, _) | DslIr::IfNeI(lhs, _, _, _) => var!(lhs, Use),
+
+        DslIr::AssertEqV(lhs, rhs) => {
+            var!(lhs, Use);
+            var!(rhs, Use);
+        }
+        DslIr::AssertEqF(lhs, rhs) => {
+            felt!(lhs, Use);
+            felt!(rhs, Use);
+        }
+        DslIr::AssertEqE(lhs, rhs) => {
+            ext!(lhs, Use);
+            ext!(rhs, Use);
+        }
+        DslIr::AssertEqVI(lhs, _) => var!(lhs, Use),
+        DslIr::AssertEqFI(lhs, _) => felt!(lhs, Use),
+        DslIr::AssertEqEI(lhs, _) => ext!(lhs, Use),
+        DslIr::AssertNonZero(u) => usize_var(u, f),
+
+        DslIr::Alloc(ptr, len, _) => {
+            rvar(len, f);
+            var!(ptr.address, Def);
+        }
+        DslIr::LoadV(dst, ptr, index) => {
+            mem_operands(ptr, index, f);
+            var!(dst, Def);
+        }
+        DslIr::LoadF(dst, ptr, index) => {
+            mem_operands(ptr, index, f);
+            felt!(dst, Def);
+        }
+        DslIr::LoadE(dst, ptr, index) => {
+            mem_operands(ptr, index, f);
+            ext!(dst, Def);
+        }
+        DslIr::LoadHeapPtr(ptr) => var!(ptr.address, Def),
+        DslIr::StoreV(src, ptr, index) => {
+            var!(src, Use);
+            mem_operands(ptr, index, f);
+        }
+        DslIr::StoreF(src, ptr, index) => {
+            felt!(src, Use);
+            mem_operands(ptr, index, f);
+        }
+        DslIr::StoreE(src, ptr, index) => {
+            ext!(src, Use);
+            mem_operands(ptr, index, f);
+        }
+        DslIr::StoreHeapPtr(ptr) => var!(ptr.address, Use),
+        DslIr::StoreHintWord(ptr, index) => mem_operands(ptr, index, f),
+
+        DslIr::CircuitNum2BitsV(src, _, bits) => {
+            var!(src, Use);
+            for bit in bits.iter_mut() {
+                var!(bit, Def);
+            }
+        }
+        DslIr::CircuitNum2BitsF(src, bits) => {
+            felt!(src, Use);
+            for bit in bits.iter_mut() {
+                var!(bit, Def);
+            }
+        }
+        DslIr::Poseidon2PermuteBabyBear(dst, src) => {
+            return array(dst, f) && array(src, f);
+        }
+        DslIr::Poseidon2CompressBabyBear(result, left, right) => {
+            return array(result, f) && array(left, f) && array(right, f);
+        }
+        DslIr::Keccak256(dst, src, len) => {
+            ext!(dst, Use);
+            ext!(src, Use);
+            ext!(len, Use);
+        }
+        DslIr::CircuitPoseidon2Permute(state) => {
+            for v in state.iter_mut() {
+                var!(v, UseDef);
+            }
+        }
+
+        DslIr::PrintV(v) => var!(v, Use),
+        DslIr::PrintF(v) => felt!(v, Use),
+        DslIr::PrintE(v) => ext!(v, Use),
+        DslIr::HintBitsF(v, _) => felt!(v, Use),
+        DslIr::Error()
+        | DslIr::HintInputVec()
+        | DslIr::HintFelt()
+        | DslIr::HintLoad()
+        | DslIr::Halt
+        | DslIr::CycleTrackerStart(_)
+        | DslIr::CycleTrackerEnd(_) => {}
+
+        DslIr::WitnessVar(dst, _) => var!(dst, Def),
+        DslIr::WitnessFelt(dst, _) => felt!(dst, Def),
+        DslIr::WitnessExt(dst, _) => ext!(dst, Def),
+        DslIr::Publish(val, index) => {
+            felt!(val, Use);
+            var!(index, Use);
+        }
+        DslIr::CircuitPublish(val, _) => var!(val, Use),
+        DslIr::CircuitSelectV(cond, a, b, out) => {
+            var!(cond, Use);
+            var!(a, Use);
+            var!(b, Use);
+            var!(out, Def);
+        }
+        DslIr::CircuitSelectF(cond, a, b, out) => {
+            var!(cond, Use);
+            felt!(a, Use);
+            felt!(b, Use);
+            felt!(out, Def);
+        }
+        DslIr::CircuitSelectE(cond, a, b, out) => {
+            var!(cond, Use);
+            ext!(a, Use);
+            ext!(b, Use);
+            ext!(out, Def);
+        }
+        DslIr::CircuitExt2Felt(felts, src) => {
+            ext!(src, Use);
+            for v in felts.iter_mut() {
+                felt!(v, Def);
+            }
+        }
+        DslIr::CircuitFelts2Ext(felts, dst) => {
+            for v in felts.iter_mut() {
+                felt!(v, Use);
+            }
+            ext!(dst, Def);
+        }
+        DslIr::CircuitFeltReduce(v) => felt!(v, UseDef),
+        DslIr::CircuitExtReduce(v) => ext!(v, UseDef),
+        DslIr::FriReducedOpening(alpha, hint_id, is_init, at_x, at_z, result) => {
+            ext!(alpha, Use);
+            var!(hint_id, Use);
+            var!(is_init, Use);
+            // The result may be accumulated into, so it is treated as read as well.
+            ext!(result, UseDef);
+            return array(at_x, f) && array(at_z, f);
+        }
+        DslIr::VerifyBatchFelt(dim, opened, proof_id, index, commit) => {
+            var!(proof_id, Use);
+            return array(dim, f) && array(opened, f) && array(index, f) && array(commit, f);
+        }
+        DslIr::VerifyBatchExt(dim, opened, proof_id, index, commit) => {
+            var!(proof_id, Use);
+            return array(dim, f) && array(opened, f) && array(index, f) && array(commit, f);
+        }
+    }
+    true
+}
+
+fn rvar<N>(v: &mut RVar<N>, f: &mut impl FnMut(Loc, &mut u32, Access)) {
+    if let RVar::Val(v) = v {
+        f(Loc::Var(v.0), &mut v.0, Access::Use);
+    }
+}
+
+fn usize_var<N>(u: &mut Usize<N>, f: &mut impl FnMut(Loc, &mut u32, Access)) {
+    if let Usize::Var(v) = u {
+        f(Loc::Var(v.0), &mut v.0, Access::Use);
+    }
+}
+
+fn mem_operands<N>(
+    ptr: &mut Ptr<N>,
+    index: &mut MemIndex<N>,
+    f: &mut impl FnMut(Loc, &mut u32, Access),
+) {
+    f(Loc::Var(ptr.address.0), &mut ptr.address.0, Access::Use);
+    rvar(&mut index.index, f);
+}
+
+/// Only heap arrays are supported; static arrays have no memory representation.
+fn array<C: Config, T>(arr: &mut Array<C, T>, f: &mut impl FnMut(Loc, &mut u32, Access)) -> bool {
+    match arr {
+        Array::Fixed(_) => false,
+        Array::Dyn(ptr, len) => {
+            f(Loc::Var(ptr.address.0), &mut ptr.address.0, Access::Use);
+            usize_var(len, f);
+            true
+        }
+    }
+}
+
+/// Nested blocks of a control flow instruction.
+pub(crate) fn blocks_mut<C: Config>(op: &mut DslIr<C>) -> Vec<&mut TracedVec<DslIr<C>>> {
+    match op {
+        DslIr::ZipFor(_, _, _, _, body) => vec![body],
+        DslIr::IfEq(_, _, then_block, else_block)
+        | DslIr::IfNe(_, _, then_block, else_block)
+        | DslIr::IfEqI(_, _, then_block, else_block)
+        | DslIr::IfNeI(_, _, then_block, else_block) => vec![then_block, else_block],
+        _ => vec![],
+    }
+}
+
+/// Number of reads and writes of every variable in a program, nested blocks included.
+#[derive(Default, Debug)]
+pub(crate) struct AccessCounts {
+    pub uses: HashMap<Loc, usize>,
+    pub defs: HashMap<Loc, usize>,
+}
+
+impl AccessCounts {
+    /// Returns `None` if the program contains instructions the passes cannot reason about.
+    pub fn compute<C: Config>(ops: &mut TracedVec<DslIr<C>>) -> Option<Self> {
+        let mut counts = Self::default();
+        counts.add_block(ops).then_some(counts)
+    }
+
+    fn add_block<C: Config>(&mut self, ops: &mut TracedVec<DslIr<C>>) -> bool {
+        ops.vec.iter_mut().all(|op| self.add(op))
+    }
+
+    fn add<C: Config>(&mut self, op: &mut DslIr<C>) -> bool {
+        let supported = visit_operands(op, &mut |loc, _, access| {
+            if access.is_use() {
+                *self.uses.entry(loc).or_default() += 1;
+            }
+            if access.is_def() {
+                *self.defs.entry(loc).or_default() += 1;
+            }
+        });
+        supported
+            && blocks_mut(op)
+                .into_iter()
+                .all(|block| self.add_block(block))
+    }
+
+    pub fn num_uses(&self, loc: Loc) -> usize {
+        self.uses.get(&loc).copied().unwrap_or_default()
+    }
+
+    pub fn num_defs(&self, loc: Loc) -> usize {
+        self.defs.get(&loc).copied().unwrap_or_default()
+    }
+}
+
+/// Variables written anywhere inside the nested blocks of `op`.
+pub(crate) fn nested_defs<C: Config>(op: &mut DslIr<C>) -> Vec<Loc> {
+    let mut defs = Vec::new();
+    for block in blocks_mut(op) {
+        for op in block.vec.iter_mut() {
+            collect_defs(op, &mut defs);
+        }
+    }
+    defs
+}
+
+/// Variables written by `op`, including inside its nested blocks.
+pub(crate) fn collect_defs<C: Config>(op: &mut DslIr<C>, defs: &mut Vec<Loc>) {
+    visit_operands(op, &mut |loc, _, access| {
+        if access.is_def() {
+            defs.push(loc);
+        }
+    });
+    for block in blocks_mut(op) {
+        for op in block.vec.iter_mut() {
+            collect_defs(op, defs);
+        }
+    }
+}
+
+/// A map whose changes since a checkpoint can be undone, for state that is forked when walking
+/// into a nested block.
+pub(crate) struct ScopedMap<K, V> {
+    map: HashMap<K, V>,
+    log: Vec<(K, Option<V>)>,
+}
+
+impl<K, V> Default for ScopedMap<K, V> {
+    fn default() -> Self {
+        Self {
+            map: HashMap::new(),
+            log: Vec::new(),
+        }
+    }
+}
+
+impl<K: Copy + Eq + std::hash::Hash, V> ScopedMap<K, V> {
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.map.get(key)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        let old = self.map.insert(key, value);
+        self.log.push((key, old));
+    }
+
+    pub fn remove(&mut self, key: &K) {
+        if let Some(old) = self.map.remove(key) {
+            self.log.push((*key, Some(old)));
+        }
+    }
+
+    pub fn checkpoint(&self) -> usize {
+        self.log.len()
+    }
+
+    /// Undoes every change made since `checkpoint`.
+    pub fn rollback(&mut self, checkpoint: usize) {
+        while self.log.len() > checkpoint {
+            let (key, old) = self.log.pop().unwrap();
+            match old {
+                Some(value) => self.map.insert(key, value),
+                None => self.map.remove(&key),
+            };
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use openvm_stark_backend::p3_field::{Field, FieldExtensionAlgebra};
+
+use super::{
+    analysis::{blocks_mut, nested_defs, visit_operands, AccessCounts, Loc, ScopedMap},
+    rewrite_block,
+};
+use crate::ir::{Config, DslIr, Ext, Felt, TracedVec, Var};
+
+/// Evaluates arithmetic on known constants and switches instructions with a constant operand
+/// to their immediate form. Assertions which are known to hold are removed.
+///
+/// Extension field results are only materialized with `ImmE` when that replaces an instruction
+/// that is at least as expensive; otherwise the value is just propagated.
+pub fn fold_constants<C: Config>(operations: &mut TracedVec<DslIr<C>>) {
+    if AccessCounts::compute(operations).is_none() {
+        return;
+    }
+    fold_block(operations, &mut Constants::default());
+}
+
+enum Value<C: Config> {
+    Var(C::N),
+    Felt(C::F),
+    Ext(C::EF),
+}
+
+/// Values of the variables known to be constant at the current point of the walk.
+struct Constants<C: Config>(ScopedMap<Loc, Value<C>>);
+
+impl<C: Config> Default for Constants<C> {
+    fn default() -> Self {
+        Self(ScopedMap::default())
+    }
+}
+
+impl<C: Config> Constants<C> {
+    fn var(&self, v: Var<C::N>) -> Option<C::N> {
+        match self.0.get(&Loc::Var(v.0)) {
+            Some(Value::Var(x)) => Some(*x),
+            _ => None,
+        }
+    }
+
+    fn felt(&self, f: Felt<C::F>) -> Option<C::F> {
+        match self.0.get(&Loc::Felt(f.0)) {
+            Some(Value::Felt(x)) => Some(*x),
+            _ => None,
+        }
+    }
+
+    fn ext(&self, e: Ext<C::F, C::EF>) -> Option<C::EF> {
+        match self.0.get(&Loc::Ext(e.0)) {
+            Some(Value::Ext(x)) => Some(*x),
+            _ => None,
+        }
+    }
+}
+
+fn fold_block<C: Config>(block: &mut TracedVec<DslIr<C>>, consts: &mut Constants<C>) {
+    rewrite_block(block, |op| fold_op(op, consts));
+}
+
+fn fold_op<C: Config>(op: DslIr<C>, c: &mut Constants<C>) -> Option<DslIr<C>> {
+    let mut op = rewrite(op, c)?;
+
+    // Single instruction extension arithmetic is cheaper than an `ImmE`, so the result is only
+    // recorded for later folds.
+    let ext_value = match &op {
+        DslIr::AddE(dst, lhs, rhs) => c.ext(*lhs).zip(c.ext(*rhs)).map(|(x, y)| (*dst, x + y)),
+        DslIr::SubE(dst, lhs, rhs) => c.ext(*lhs).zip(c.ext(*rhs)).map(|(x, y)| (*dst, x - y)),
+        DslIr::MulE(dst, lhs, rhs) => c.ext(*lhs).zip(c.ext(*rhs)).map(|(x, y)| (*dst, x * y)),
+        DslIr::DivE(dst, lhs, rhs) => c
+            .ext(*lhs)
+            .zip(c.ext(*rhs).filter(|y| !y.is_zero()))
+            .map(|(x, y)| (*dst, x / y)),
+        DslIr::ImmE(dst, x) => Some((*dst, *x)),
+        _ => None,
+    };
+
+    let nested = nested_defs(&mut op);
+    if let DslIr::ZipFor(_, _, _, loop_vars, body) = &mut op {
+        // The body may run any number of times, so nothing it writes is known on entry.
+        let checkpoint = c.0.checkpoint();
+        for loc in &nested {
+            c.0.remove(loc);
+        }
+        for v in loop_vars.iter() {
+            c.0.remove(&Loc::Var(v.0));
+        }
+        fold_block(body, c);
+        c.0.rollback(checkpoint);
+    } else {
+        for block in blocks_mut(&mut op) {
+            let checkpoint = c.0.checkpoint();
+            fold_block(block, c);
+            c.0.rollback(checkpoint);
+        }
+    }
+    for loc in nested {
+        c.0.remove(&loc);
+    }
+
+    visit_operands(&mut op, &mut |loc, _, access| {
+        if access.is_def() {
+            c.0.remove(&loc);
+        }
+    });
+    match &op {
+        DslIr::ImmV(dst, x) => c.0.insert(Loc::Var(dst.0), Value::Var(*x)),
+        DslIr::ImmF(dst, x) => c.0.insert(Loc::Felt(dst.0), Value::Felt(*x)),
+        _ => {}
+    }
+    if let Some((dst, x)) = ext_value {
+        c.0.insert(Loc::Ext(dst.0), Value::Ext(x));
+    }
+    Some(op)
+}
+
+/// Rewrites `op` given the constants known before it executes. Returns `None` if it has no
+/// effect.
+fn rewrite<C: Config>(op: DslIr<C>, c: &Constants<C>) -> Option<DslIr<C>> {
+    if let Some(imm) = evaluate(&op, c) {
+        return Some(imm);
+    }
+    let base = |x: C::F| C::EF::from_base(x);
+    let op = match op {
+        DslIr::AddV(dst, lhs, rhs) => match (c.var(lhs), c.var(rhs)) {
+            (Some(x), Some(y)) => DslIr::ImmV(dst, x + y),
+            (Some(x), None) => DslIr::AddVI(dst, rhs, x),
+            (None, Some(y)) => DslIr::AddVI(dst, lhs, y),
+            (None, None) => DslIr::AddV(dst, lhs, rhs),
+        },
+        DslIr::SubV(dst, lhs, rhs) => match (c.var(lhs), c.var(rhs)) {
+            (Some(x), Some(y)) => DslIr::ImmV(dst, x - y),
+            (Some(x), None) => DslIr::SubVIN(dst, x, rhs),
+            (None, Some(y)) => DslIr::SubVI(dst, lhs, y),
+            (None, None) => DslIr::SubV(dst, lhs, rhs),
+        },
+        DslIr::MulV(dst, lhs, rhs) => match (c.var(lhs), c.var(rhs)) {
+            (Some(x), Some(y)) => DslIr::ImmV(dst, x * y),
+            (Some(x), None) => DslIr::MulVI(dst, rhs, x),
+            (None, Some(y)) => DslIr::MulVI(dst, lhs, y),
+            (None, None) => DslIr::MulV(dst, lhs, rhs),
+        },
+        DslIr::AddF(dst, lhs, rhs) => match (c.felt(lhs), c.felt(rhs)) {
+            (Some(x), Some(y)) => DslIr::ImmF(dst, x + y),
+            (Some(x), None) => DslIr::AddFI(dst, rhs, x),
+            (None, Some(y)) => DslIr::AddFI(dst, lhs, y),
+            (None, None) => DslIr::AddF(dst, lhs, rhs),
+        },
+        DslIr::SubF(dst, lhs, rhs) => match (c.felt(lhs), c.felt(rhs)) {
+            (Some(x), Some(y)) => DslIr::ImmF(dst, x - y),
+            (Some(x), None) => DslIr::SubFIN(dst, x, rhs),
+            (None, Some(y)) => DslIr::SubFI(dst, lhs, y),
+            (None, None) => DslIr::SubF(dst, lhs, rhs),
+        },
+        DslIr::MulF(dst, lhs, rhs) => match (c.felt(lhs), c.felt(rhs)) {
+            (Some(x), Some(y)) => DslIr::ImmF(dst, x * y),
+            (Some(x), None) => DslIr::MulFI(dst, rhs, x),
+            (None, Some(y)) => DslIr::MulFI(dst, lhs, y),
+            (None, None) => DslIr::MulF(dst, lhs, rhs),
+        },
+        // Division by zero must still fail at runtime, so it is never folded.
+        DslIr::DivF(dst, lhs, rhs) => match (c.felt(lhs), c.felt(rhs).filter(|y| !y.is_zero())) {
+            (Some(x), Some(y)) => DslIr::ImmF(dst, x / y),
+            (None, Some(y)) => DslIr::DivFI(dst, lhs, y),
+            (Some(x), None) => DslIr::DivFIN(dst, x, rhs),
+            (None, None) => DslIr::DivF(dst, lhs, rhs),
+        },
+        DslIr::AddEF(dst, lhs, rhs) => match (c.ext(lhs), c.felt(rhs)) {
+            (Some(x), Some(y)) => DslIr::ImmE(dst, x + base(y)),
+            (None, Some(y)) => DslIr::AddEFI(dst, lhs, y),
+            _ => DslIr::AddEF(dst, lhs, rhs),
+        },
+        DslIr::SubEF(dst, lhs, rhs) => match (c.ext(lhs), c.felt(rhs)) {
+            (Some(x), Some(y)) => DslIr::ImmE(dst, x - base(y)),
+            (None, Some(y)) => DslIr::SubEFI(dst, lhs, y),
+            _ => DslIr::SubEF(dst, lhs, rhs),
+        },
+        DslIr::MulEF(dst, lhs, rhs) => match (c.ext(lhs), c.felt(rhs)) {
+            (Some(x), Some(y)) => DslIr::ImmE(dst, x * base(y)),
+            (None, Some(y)) => DslIr::MulEFI(dst, lhs, y),
+            _ => DslIr::MulEF(dst, lhs, rhs),
+        },
+        DslIr::DivEF(dst, lhs, rhs) => match (c.ext(lhs), c.felt(rhs).filter(|y| !y.is_zero())) {
+            (Some(x), Some(y)) => DslIr::ImmE(dst, x / base(y)),
+            (None, Some(y)) => DslIr::DivEFI(dst, lhs, y),
+            _ => DslIr::DivEF(dst, lhs, rhs),
+        },
+
+        DslIr::AssertEqV(lhs, rhs) if c.var(lhs).is_some() && c.var(lhs) == c.var(rhs) => {
+            return None;
+        }
+        DslIr::AssertEqF(lhs, rhs) if c.felt(lhs).is_some() && c.felt(lhs) == c.felt(rhs) => {
+            return None;
+        }
+        DslIr::AssertEqE(lhs, rhs) if c.ext(lhs).is_some() && c.ext(lhs) == c.ext(rhs) => {
+            return None;
+        }
+        DslIr::AssertEqVI(lhs, y) if c.var(lhs) == Some(y) => return None,
+        DslIr::AssertEqFI(lhs, y) if c.felt(lhs) == Some(y) => return None,
+        DslIr::AssertEqEI(lhs, y) if c.ext(lhs) == Some(y) => return None,
+
+        op => op,
+    };
+    Some(op)
+}
+
+/// Evaluates an instruction with a single variable operand if that operand is known.
+fn evaluate<C: Config>(op: &DslIr<C>, c: &Constants<C>) -> Option<DslIr<C>> {
+    let base = |x: C::F| C::EF::from_base(x);
+    let nonzero = |x: C::F| (!x.is_zero()).then_some(x);
+    let nonzero_ext = |x: C::EF| (!x.is_zero()).then_some(x);
+    match *op {
+        DslIr::AddVI(dst, lhs, y) => c.var(lhs).map(|x| DslIr::ImmV(dst, x + y)),
+        DslIr::SubVI(dst, lhs, y) => c.var(lhs).map(|x| DslIr::ImmV(dst, x - y)),
+        DslIr::SubVIN(dst, x, rhs) => c.var(rhs).map(|y| DslIr::ImmV(dst, x - y)),
+        DslIr::MulVI(dst, lhs, y) => c.var(lhs).map(|x| DslIr::ImmV(dst, x * y)),
+        DslIr::NegV(dst, src) => c.var(src).map(|x| DslIr::ImmV(dst, -x)),
+
+        DslIr::AddFI(dst, lhs, y) => c.felt(lhs).map(|x| DslIr::ImmF(dst, x + y)),
+        DslIr::SubFI(dst, lhs, y) => c.felt(lhs).map(|x| DslIr::ImmF(dst, x - y)),
+        DslIr::SubFIN(dst, x, rhs) => c.felt(rhs).map(|y| DslIr::ImmF(dst, x - y)),
+        DslIr::MulFI(dst, lhs, y) => c.felt(lhs).map(|x| DslIr::ImmF(dst, x * y)),
+        DslIr::DivFI(dst, lhs, y) => c
+            .felt(lhs)
+            .zip(nonzero(y))
+            .map(|(x, y)| DslIr::ImmF(dst, x / y)),
+        DslIr::DivFIN(dst, x, rhs) => c
+            .felt(rhs)
+            .and_then(nonzero)
+            .map(|y| DslIr::ImmF(dst, x / y)),
+        DslIr::NegF(dst, src) => c.felt(src).map(|x| DslIr::ImmF(dst, -x)),
+
+        DslIr::AddEI(dst, lhs, y) => c.ext(lhs).map(|x| DslIr::ImmE(dst, x + y)),
+        DslIr::SubEI(dst, lhs, y) => c.ext(lhs).map(|x| DslIr::ImmE(dst, x - y)),
+        DslIr::SubEIN(dst, x, rhs) => c.ext(rhs).map(|y| DslIr::ImmE(dst, x - y)),
+        DslIr::MulEI(dst, lhs, y) => c.ext(lhs).map(|x| DslIr::ImmE(dst, x * y)),
+        DslIr::DivEI(dst, lhs, y) => c
+            .ext(lhs)
+            .zip(nonzero_ext(y))
+            .map(|(x, y)| DslIr::ImmE(dst, x / y)),
+        DslIr::DivEIN(dst, x, rhs) => c
+            .ext(rhs)
+            .and_then(nonzero_ext)
+            .map(|y| DslIr::ImmE(dst, x / y)),
+        DslIr::AddEFI(dst, lhs, y) => c.ext(lhs).map(|x| DslIr::ImmE(dst, x + base(y))),
+        DslIr::SubEFI(dst, lhs, y) => c.ext(lhs).map(|x| DslIr::ImmE(dst, x - base(y))),
+        DslIr::MulEFI(dst, lhs, y) => c.ext(lhs).map(|x| DslIr::ImmE(dst, x * base(y))),
+        DslIr::DivEFI(dst, lhs, y) => c
+            .ext(lhs)
+            .zip(nonzero(y))
+            .map(|(x, y)| DslIr::ImmE(dst, x / base(y))),
+        DslIr::AddEFFI(dst, lhs, y) => c.felt(lhs).map(|x| DslIr::ImmE(dst, y + base(x))),
+        DslIr::NegE(dst, src) => c.ext(src).map(|x| DslIr::ImmE(dst, -x)),
+        _ => None,
+    }
+}
@@ -0,0 +1,223 @@
+use std::{
+    collections::{HashMap, HashSet},
+    mem::{discriminant, Discriminant},
+};
+
+use super::{
+    analysis::{blocks_mut, nested_defs, visit_operands, AccessCounts, Loc, ScopedMap},
+    rewrite_block,
+};
+use crate::ir::{Config, DslIr, Ext, Felt, TracedVec};
+
+/// Removes `Felt` and `Ext` arithmetic (including immediates) that recomputes a value which is
+/// still available in another variable, and redirects its uses to that variable.
+///
+/// Only variables with a single definition whose uses all follow it, in the same block or in
+/// blocks nested after it, are replaced.
+pub fn eliminate_common_subexpressions<C: Config>(operations: &mut TracedVec<DslIr<C>>) {
+    let Some(counts) = AccessCounts::compute(operations) else {
+        return;
+    };
+    let mut unscoped = HashSet::new();
+    find_unscoped_uses(operations, &mut HashSet::new(), &mut unscoped);
+
+    let mut cse = Cse::<C> {
+        counts,
+        unscoped,
+        renames: HashMap::new(),
+        available: ScopedMap::default(),
+        stamps: HashMap::new(),
+        clock: 0,
+    };
+    cse.eliminate_block(operations);
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Operand<F, EF> {
+    Loc(Loc),
+    Felt(F),
+    Ext(EF),
+    None,
+}
+
+type Key<C> = (
+    Discriminant<DslIr<C>>,
+    [Operand<<C as Config>::F, <C as Config>::EF>; 2],
+);
+
+struct Cse<C: Config> {
+    counts: AccessCounts,
+    unscoped: HashSet<Loc>,
+    /// Eliminated variables and the variable holding their value.
+    renames: HashMap<Loc, Loc>,
+    /// Expressions computed so far, with the variable holding the result and the write stamps of
+    /// the operands and the result at that point.
+    available: ScopedMap<Key<C>, (Loc, [u64; 3])>,
+    /// Each write gets a fresh stamp, so an expression is stale once a stamp changes.
+    stamps: HashMap<Loc, u64>,
+    clock: u64,
+}
+
+impl<C: Config> Cse<C> {
+    fn stamp(&self, loc: Loc) -> u64 {
+        self.stamps.get(&loc).copied().unwrap_or_default()
+    }
+
+    fn write(&mut self, loc: Loc) {
+        self.clock += 1;
+        self.stamps.insert(loc, self.clock);
+    }
+
+    fn stamps_of(&self, key: &Key<C>, dst: Loc) -> [u64; 3] {
+        let [a, b] = key.1.map(|operand| match operand {
+            Operand::Loc(loc) => self.stamp(loc),
+            _ => 0,
+        });
+        [a, b, self.stamp(dst)]
+    }
+
+    fn lookup(&self, key: &Key<C>) -> Option<Loc> {
+        let &(dst, stamps) = self.available.get(key)?;
+        (self.stamps_of(key, dst) == stamps).then_some(dst)
+    }
+
+    fn eliminate_block(&mut self, block: &mut TracedVec<DslIr<C>>) {
+        rewrite_block(block, |op| self.eliminate(op));
+    }
+
+    fn eliminate(&mut self, mut op: DslIr<C>) -> Option<DslIr<C>> {
+        visit_operands(&mut op, &mut |loc, index, _| {
+            if let Some(Loc::Felt(i) | Loc::Ext(i)) = self.renames.get(&loc) {
+                *index = *i;
+            }
+        });
+
+        let expr = key_of(&op);
+        if let Some((key, dst)) = expr {
+            if let Some(kept) = self.lookup(&key) {
+                let single_def = |loc| self.counts.num_defs(loc) == 1;
+                if single_def(dst) && single_def(kept) && !self.unscoped.contains(&dst) {
+                    self.renames.insert(dst, kept);
+                    return None;
+                }
+            }
+        }
+
+        let nested = nested_defs(&mut op);
+        if let DslIr::ZipFor(_, _, _, loop_vars, _) = &op {
+            // The body may run any number of times, so anything it writes is stale on entry.
+            for &loc in &nested {
+                self.write(loc);
+            }
+            for v in loop_vars {
+                self.write(Loc::Var(v.0));
+            }
+        }
+        for block in blocks_mut(&mut op) {
+            let checkpoint = self.available.checkpoint();
+            self.eliminate_block(block);
+            self.available.rollback(checkpoint);
+        }
+        for loc in nested {
+            self.write(loc);
+        }
+
+        visit_operands(&mut op, &mut |loc, _, access| {
+            if access.is_def() {
+                self.write(loc);
+            }
+        });
+        if let Some((key, dst)) = expr {
+            let stamps = self.stamps_of(&key, dst);
+            self.available.insert(key, (dst, stamps));
+        }
+        Some(op)
+    }
+}
+
+/// The expression computed by a pure `Felt` or `Ext` instruction, and its destination.
+/// Instructions overwriting one of their operands are skipped.
+fn key_of<C: Config>(op: &DslIr<C>) -> Option<(Key<C>, Loc)> {
+    use Operand::{Ext as E, Felt as F};
+    let felt = |x: Felt<C::F>| Operand::Loc(Loc::Felt(x.0));
+    let ext = |x: Ext<C::F, C::EF>| Operand::Loc(Loc::Ext(x.0));
+    let sorted = |a: Operand<C::F, C::EF>, b: Operand<C::F, C::EF>| match (a, b) {
+        (Operand::Loc(x), Operand::Loc(y)) if y < x => [b, a],
+        _ => [a, b],
+    };
+    let (dst, operands) = match *op {
+        DslIr::ImmF(dst, x) => (Loc::Felt(dst.0), [F(x), Operand::None]),
+        DslIr::AddF(dst, lhs, rhs) | DslIr::MulF(dst, lhs, rhs) => {
+            (Loc::Felt(dst.0), sorted(felt(lhs), felt(rhs)))
+        }
+        DslIr::SubF(dst, lhs, rhs) | DslIr::DivF(dst, lhs, rhs) => {
+            (Loc::Felt(dst.0), [felt(lhs), felt(rhs)])
+        }
+        DslIr::AddFI(dst, lhs, rhs)
+        | DslIr::SubFI(dst, lhs, rhs)
+        | DslIr::MulFI(dst, lhs, rhs)
+        | DslIr::DivFI(dst, lhs, rhs) => (Loc::Felt(dst.0), [felt(lhs), F(rhs)]),
+        DslIr::SubFIN(dst, lhs, rhs) | DslIr::DivFIN(dst, lhs, rhs) => {
+            (Loc::Felt(dst.0), [F(lhs), felt(rhs)])
+        }
+        DslIr::NegF(dst, src) => (Loc::Felt(dst.0), [felt(src), Operand::None]),
+
+        DslIr::ImmE(dst, x) => (Loc::Ext(dst.0), [E(x), Operand::None]),
+        DslIr::AddE(dst, lhs, rhs) | DslIr::MulE(dst, lhs, rhs) => {
+            (Loc::Ext(dst.0), sorted(ext(lhs), ext(rhs)))
+        }
+        DslIr::SubE(dst, lhs, rhs) | DslIr::DivE(dst, lhs, rhs) => {
+            (Loc::Ext(dst.0), [ext(lhs), ext(rhs)])
+        }
+        DslIr::AddEI(dst, lhs, rhs)
+        | DslIr::SubEI(dst, lhs, rhs)
+        | DslIr::MulEI(dst, lhs, rhs)
+        | DslIr::DivEI(dst, lhs, rhs) => (Loc::Ext(dst.0), [ext(lhs), E(rhs)]),
+        DslIr::SubEIN(dst, lhs, rhs) | DslIr::DivEIN(dst, lhs, rhs) => {
+            (Loc::Ext(dst.0), [E(lhs), ext(rhs)])
+        }
+        DslIr::AddEF(dst, lhs, rhs)
+        | DslIr::SubEF(dst, lhs, rhs)
+        | DslIr::MulEF(dst, lhs, rhs)
+        | DslIr::DivEF(dst, lhs, rhs) => (Loc::Ext(dst.0), [ext(lhs), felt(rhs)]),
+        DslIr::AddEFI(dst, lhs, rhs)
+        | DslIr::SubEFI(dst, lhs, rhs)
+        | DslIr::MulEFI(dst, lhs, rhs)
+        | DslIr::DivEFI(dst, lhs, rhs) => (Loc::Ext(dst.0), [ext(lhs), F(rhs)]),
+        DslIr::AddEFFI(dst, lhs, rhs) => (Loc::Ext(dst.0), [felt(lhs), E(rhs)]),
+        DslIr::NegE(dst, src) => (Loc::Ext(dst.0), [ext(src), Operand::None]),
+        _ => return None,
+    };
+    if operands.contains(&Operand::Loc(dst)) {
+        return None;
+    }
+    Some(((discriminant(op), operands), dst))
+}
+
+/// Collects into `unscoped` the variables read somewhere their definition does not precede in
+/// structured order, e.g. before it in a loop body or after the branch defining it.
+fn find_unscoped_uses<C: Config>(
+    block: &mut TracedVec<DslIr<C>>,
+    scoped: &mut HashSet<Loc>,
+    unscoped: &mut HashSet<Loc>,
+) {
+    let mut defined = Vec::new();
+    for op in block.vec.iter_mut() {
+        visit_operands(op, &mut |loc, _, access| {
+            if access.is_use() && !scoped.contains(&loc) {
+                unscoped.insert(loc);
+            }
+        });
+        visit_operands(op, &mut |loc, _, access| {
+            if access.is_def() && scoped.insert(loc) {
+                defined.push(loc);
+            }
+        });
+        for block in blocks_mut(op) {
+            find_unscoped_uses(block, scoped, unscoped);
+        }
+    }
+    for loc in defined {
+        scoped.remove(&loc);
+    }
+}
@@ -0,0 +1,97 @@
+use super::analysis::{blocks_mut, visit_operands, AccessCounts, Loc};
+use crate::ir::{Config, DslIr, TracedVec};
+
+/// Removes side-effect free instructions whose result is never read.
+///
+/// Divisions are kept since they fail on a zero divisor, as are loads and allocations.
+pub fn eliminate_dead_code<C: Config>(operations: &mut TracedVec<DslIr<C>>) {
+    // Sweeping backwards removes a chain of dead values at once, unless a value is read before
+    // it is written in program order, as in loops.
+    while let Some(mut counts) = AccessCounts::compute(operations) {
+        if sweep_block(operations, &mut counts) == 0 {
+            break;
+        }
+    }
+}
+
+/// Returns the number of removed instructions.
+fn sweep_block<C: Config>(block: &mut TracedVec<DslIr<C>>, counts: &mut AccessCounts) -> usize {
+    let mut removed = 0;
+    let mut keep = vec![true; block.vec.len()];
+    for (op, keep) in block.vec.iter_mut().zip(keep.iter_mut()).rev() {
+        for nested in blocks_mut(op) {
+            removed += sweep_block(nested, counts);
+        }
+        if !is_pure(op) {
+            continue;
+        }
+        let mut dead = true;
+        let mut used = Vec::<Loc>::new();
+        visit_operands(op, &mut |loc, _, access| {
+            if access.is_def() && counts.num_uses(loc) > 0 {
+                dead = false;
+            }
+            if access.is_use() {
+                used.push(loc);
+            }
+        });
+        if dead {
+            for loc in used {
+                if let Some(n) = counts.uses.get_mut(&loc) {
+                    *n -= 1;
+                }
+            }
+            *keep = false;
+        }
+    }
+
+    if keep.contains(&false) {
+        removed += keep.iter().filter(|keep| !**keep).count();
+        let mut keep = keep.into_iter();
+        let ops = std::mem::take(block);
+        block.extend(ops.into_iter().filter(|_| keep.next().unwrap()));
+    }
+    removed
+}
+
+fn is_pure<C: Config>(op: &DslIr<C>) -> bool {
+    matches!(
+        op,
+        DslIr::ImmV(..)
+            | DslIr::ImmF(..)
+            | DslIr::ImmE(..)
+            | DslIr::AddV(..)
+            | DslIr::AddVI(..)
+            | DslIr::AddF(..)
+            | DslIr::AddFI(..)
+            | DslIr::AddE(..)
+            | DslIr::AddEI(..)
+            | DslIr::AddEF(..)
+            | DslIr::AddEFI(..)
+            | DslIr::AddEFFI(..)
+            | DslIr::SubV(..)
+            | DslIr::SubVI(..)
+            | DslIr::SubVIN(..)
+            | DslIr::SubF(..)
+            | DslIr::SubFI(..)
+            | DslIr::SubFIN(..)
+            | DslIr::SubE(..)
+            | DslIr::SubEI(..)
+            | DslIr::SubEIN(..)
+            | DslIr::SubEF(..)
+            | DslIr::SubEFI(..)
+            | DslIr::MulV(..)
+            | DslIr::MulVI(..)
+            | DslIr::MulF(..)
+            | DslIr::MulFI(..)
+            | DslIr::MulE(..)
+            | DslIr::MulEI(..)
+            | DslIr::MulEF(..)
+            | DslIr::MulEFI(..)
+            | DslIr::NegV(..)
+            | DslIr::NegF(..)
+            | DslIr::NegE(..)
+            | DslIr::CastFV(..)
+            | DslIr::UnsafeCastVF(..)
+    )
+}
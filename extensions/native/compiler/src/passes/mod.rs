@@ -0,0 +1,47 @@
+//! Optimization passes over [DslIr] programs, run before lowering to assembly.
+//!
+//! The passes only reason about the stack variables ([Var], [Felt] and [Ext]) named by each
+//! instruction, and treat memory, hints and every instruction they do not model as opaque. A
+//! program containing static arrays is left untouched.
+//!
+//! [Var]: crate::ir::Var
+//! [Felt]: crate::ir::Felt
+//! [Ext]: crate::ir::Ext
+
+mod analysis;
+mod const_fold;
+mod cse;
+mod dce;
+mod strength_reduce;
+
+pub use const_fold::fold_constants;
+pub use cse::eliminate_common_subexpressions;
+pub use dce::eliminate_dead_code;
+pub use strength_reduce::reduce_strength;
+
+use crate::ir::{Config, DslIr, TracedVec};
+
+/// Runs the full pass pipeline on `operations`.
+pub fn optimize<C: Config>(operations: &mut TracedVec<DslIr<C>>) {
+    if analysis::AccessCounts::compute(operations).is_none() {
+        return;
+    }
+    fold_constants(operations);
+    reduce_strength(operations);
+    eliminate_common_subexpressions(operations);
+    eliminate_dead_code(operations);
+}
+
+/// Replaces every instruction of `block` by the output of `f`, dropping it on `None`. Backtraces
+/// follow their instruction.
+fn rewrite_block<C: Config>(
+    block: &mut TracedVec<DslIr<C>>,
+    mut f: impl FnMut(DslIr<C>) -> Option<DslIr<C>>,
+) {
+    for (op, trace) in std::mem::take(block) {
+        if let Some(op) = f(op) {
+            block.vec.push(op);
+            block.traces.push(trace);
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use openvm_stark_backend::p3_field::{Field, FieldAlgebra, FieldExtensionAlgebra};
+
+use super::{
+    analysis::{blocks_mut, AccessCounts},
+    rewrite_block,
+};
+use crate::ir::{Config, DslIr, Ext, TracedVec};
+
+/// Replaces multiplications and divisions by constants with cheaper equivalents, and removes
+/// arithmetic that leaves its operand unchanged in place.
+///
+/// `MulEI` loads its immediate into a temporary extension element before multiplying, so a
+/// constant from the base field is better handled by `MulEFI`, and doubling by `AddE`.
+pub fn reduce_strength<C: Config>(operations: &mut TracedVec<DslIr<C>>) {
+    if AccessCounts::compute(operations).is_none() {
+        return;
+    }
+    reduce_block(operations);
+}
+
+fn reduce_block<C: Config>(block: &mut TracedVec<DslIr<C>>) {
+    rewrite_block(block, |mut op| {
+        for block in blocks_mut(&mut op) {
+            reduce_block(block);
+        }
+        reduce(op)
+    });
+}
+
+fn reduce<C: Config>(op: DslIr<C>) -> Option<DslIr<C>> {
+    let op = match op {
+        DslIr::MulEI(dst, src, y) => match as_base::<C>(y) {
+            Some(y) => return mul_ext_base(dst, src, y),
+            None => DslIr::MulEI(dst, src, y),
+        },
+        DslIr::MulEFI(dst, src, y) => return mul_ext_base(dst, src, y),
+        DslIr::DivEI(dst, src, y) => match as_base::<C>(y).filter(|y| !y.is_zero()) {
+            Some(y) => return mul_ext_base(dst, src, y.inverse()),
+            None => DslIr::DivEI(dst, src, y),
+        },
+        DslIr::DivEFI(dst, src, y) if !y.is_zero() => return mul_ext_base(dst, src, y.inverse()),
+
+        DslIr::MulFI(dst, _, y) if y.is_zero() => DslIr::ImmF(dst, C::F::ZERO),
+        DslIr::MulVI(dst, _, y) if y.is_zero() => DslIr::ImmV(dst, C::N::ZERO),
+        DslIr::MulFI(dst, src, y) | DslIr::DivFI(dst, src, y) if dst == src && y.is_one() => {
+            return None;
+        }
+        DslIr::MulVI(dst, src, y) if dst == src && y.is_one() => return None,
+        DslIr::AddFI(dst, src, y) | DslIr::SubFI(dst, src, y) if dst == src && y.is_zero() => {
+            return None;
+        }
+        DslIr::AddVI(dst, src, y) | DslIr::SubVI(dst, src, y) if dst == src && y.is_zero() => {
+            return None;
+        }
+        DslIr::AddEI(dst, src, y) | DslIr::SubEI(dst, src, y) if dst == src && y.is_zero() => {
+            return None;
+        }
+        DslIr::AddEFI(dst, src, y) | DslIr::SubEFI(dst, src, y) if dst == src && y.is_zero() => {
+            return None;
+        }
+        op => op,
+    };
+    Some(op)
+}
+
+/// `dst = src * y` for `y` in the base field, or `None` if that is a no-op.
+fn mul_ext_base<C: Config>(
+    dst: Ext<C::F, C::EF>,
+    src: Ext<C::F, C::EF>,
+    y: C::F,
+) -> Option<DslIr<C>> {
+    if y.is_zero() {
+        Some(DslIr::ImmE(dst, C::EF::ZERO))
+    } else if y.is_one() {
+        (dst != src).then_some(DslIr::MulEFI(dst, src, y))
+    } else if y == C::F::TWO {
+        Some(DslIr::AddE(dst, src, src))
+    } else {
+        Some(DslIr::MulEFI(dst, src, y))
+    }
+}
+
+fn as_base<C: Config>(x: C::EF) -> Option<C::F> {
+    let coeffs = x.as_base_slice();
+    coeffs[1..].iter().all(|c| c.is_zero()).then_some(coeffs[0])
+}
@@ -0,0 +1,102 @@
+use openvm_circuit::arch::{ExecutionError, SystemConfig, VmExecutor};
+use openvm_instructions::{disasm::OpcodeTable, program::Program};
+use openvm_native_circuit::{execute_program, Native, NativeConfig};
+use openvm_native_compiler::{
+    asm::AsmBuilder,
+    ir::{Ext, ExtConst, Felt, Var},
+    with_native_opcodes,
+};
+use openvm_stark_backend::p3_field::{extension::BinomialExtensionField, FieldAlgebra};
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+type F = BabyBear;
+type EF = BinomialExtensionField<BabyBear, 4>;
+
+fn assemble(source: &str) -> Program<F> {
+    with_native_opcodes(OpcodeTable::new())
+        .assemble(source)
+        .unwrap()
+}
+
+/// Computes `7 * 6` and fails unless it equals `expected`.
+fn multiplication_program(expected: u32) -> String {
+    format!(
+        "
+        native.ADD a=8 b=7 d=native e=imm f=native  # mem[8] = 7 + mem[0]
+        native.MUL a=9 b=8 c=6 d=native e=native    # mem[9] = mem[8] * 6
+        native.BEQ a=9 b={expected} c=8 d=native e=imm
+        system.PHANTOM c=1                          # DebugPanic
+        system.TERMINATE
+        "
+    )
+}
+
+#[test]
+fn test_assemble_native_program() {
+    execute_program(assemble(&multiplication_program(42)), vec![]);
+
+    let config = NativeConfig::new(SystemConfig::default(), Native);
+    let executor = VmExecutor::<F, NativeConfig>::new(config);
+    let result = executor.execute(assemble(&multiplication_program(41)), vec![]);
+    assert!(matches!(result, Err(ExecutionError::Fail { pc: 12 })));
+}
+
+#[test]
+fn test_assemble_explicit_pcs() {
+    let program = assemble(
+        "
+        .pc_base 0x100
+        0x108: native.JAL a=0 b=8 d=native
+        0x110: system.TERMINATE
+        ",
+    );
+    assert_eq!(program.pc_base, 0x100);
+    assert_eq!(program.len(), 5);
+    assert_eq!(program.num_defined_instructions(), 2);
+    assert!(program.get_instruction_and_debug_info(3).is_none());
+
+    let table = with_native_opcodes(OpcodeTable::new());
+    assert!(table
+        .assemble::<F>("0x104: system.TERMINATE\n0x100: system.TERMINATE")
+        .is_err());
+    assert!(table.assemble::<F>("native.NOPE").is_err());
+    assert!(table.assemble::<F>("native.ADD a=1 a=2").is_err());
+    assert!(table.assemble::<F>("native.ADD h=1").is_err());
+}
+
+#[test]
+fn test_disassemble_round_trip() {
+    let mut builder = AsmBuilder::<F, EF>::default();
+    let n: Var<_> = builder.eval(F::from_canonical_u32(10));
+    let i: Felt<_> = builder.eval(F::ZERO);
+    let sum: Felt<_> = builder.eval(F::ZERO);
+    let prod: Ext<_, _> = builder.eval(EF::ONE.cons());
+    builder.range(0, n).for_each(|_, builder| {
+        builder.assign(&sum, sum + i);
+        builder.assign(&prod, prod * F::TWO - i);
+        builder.assign(&i, i + F::ONE);
+    });
+    builder.assert_felt_eq(sum, F::from_canonical_u32(45));
+    builder.assert_ext_eq(prod, EF::from_canonical_u32(11).cons());
+    builder.halt();
+    let program = builder.compile_isa();
+
+    let table = with_native_opcodes(OpcodeTable::new());
+    for instruction in program.defined_instructions() {
+        assert!(table.opcode_name(instruction.opcode).is_some());
+    }
+    let text = table.disassemble(&program, &Default::default());
+    let reassembled: Program<F> = table.assemble(&text).unwrap();
+    assert_eq!(reassembled.pc_base, program.pc_base);
+    assert_eq!(reassembled.step, program.step);
+    assert_eq!(
+        reassembled.defined_instructions(),
+        program.defined_instructions()
+    );
+    // Debug infos are written as comments, which are not parsed back.
+    assert_eq!(
+        table.disassemble(&reassembled, &Default::default()),
+        table.disassemble(&program.strip_debug_infos(), &Default::default())
+    );
+    execute_program(reassembled, vec![]);
+}
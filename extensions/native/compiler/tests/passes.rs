@@ -0,0 +1,127 @@
+use openvm_circuit::arch::{SystemConfig, VmExecutor};
+use openvm_instructions::program::Program;
+use openvm_native_circuit::{Native, NativeConfig};
+use openvm_native_compiler::{
+    asm::AsmBuilder,
+    conversion::CompilerOptions,
+    ir::{Ext, ExtConst, Felt, Var},
+};
+use openvm_stark_backend::p3_field::{extension::BinomialExtensionField, FieldAlgebra};
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+type F = BabyBear;
+type EF = BinomialExtensionField<BabyBear, 4>;
+
+/// Executes `program` and returns the number of instructions executed.
+fn count_cycles(program: Program<F>, input: Vec<Vec<F>>) -> usize {
+    let config = NativeConfig::new(SystemConfig::default().with_public_values(4), Native);
+    let executor = VmExecutor::<F, NativeConfig>::new(config);
+    let segments = executor.execute_segments(program, input).unwrap();
+    segments
+        .iter()
+        .map(|segment| {
+            let frequencies = &segment.chip_complex.program_chip().execution_frequencies;
+            frequencies.iter().sum::<usize>()
+        })
+        .sum()
+}
+
+/// Compiles `build` with and without the passes, checks that both programs run to completion on
+/// `input` and returns their cycle counts.
+fn compare(input: Vec<Vec<F>>, build: impl Fn(&mut AsmBuilder<F, EF>)) -> (usize, usize) {
+    let compile = |options: CompilerOptions| {
+        let mut builder = AsmBuilder::<F, EF>::default();
+        build(&mut builder);
+        builder.halt();
+        builder.compile_isa_with_options(options)
+    };
+    let plain = count_cycles(compile(CompilerOptions::default()), input.clone());
+    let optimized = count_cycles(
+        compile(CompilerOptions::default().with_ir_optimizations()),
+        input,
+    );
+    (plain, optimized)
+}
+
+#[test]
+fn test_passes_fold_constants() {
+    let (plain, optimized) = compare(vec![], |builder| {
+        let a: Felt<_> = builder.eval(F::from_canonical_u32(3));
+        let b: Felt<_> = builder.eval(a * F::from_canonical_u32(5) + F::ONE);
+        let c: Var<_> = builder.eval(F::from_canonical_u32(7));
+        let d: Var<_> = builder.eval(c * c - F::ONE);
+        builder.assert_felt_eq(b, F::from_canonical_u32(16));
+        builder.assert_var_eq(d, F::from_canonical_u32(48));
+
+        let x: Ext<_, _> = builder.eval(EF::from_canonical_u32(2).cons());
+        let y: Ext<_, _> = builder.eval(x * x + b);
+        builder.assert_ext_eq(y, EF::from_canonical_u32(20).cons());
+    });
+    assert!(optimized < plain, "{optimized} >= {plain}");
+}
+
+#[test]
+fn test_passes_common_subexpressions() {
+    let input = vec![
+        vec![F::from_canonical_u32(11)],
+        vec![F::from_canonical_u32(13)],
+    ];
+    let (plain, optimized) = compare(input, |builder| {
+        // Hinted so that the values are not known to the compiler.
+        let a = builder.hint_felt();
+        let b = builder.hint_felt();
+        let x: Ext<_, _> = builder.eval(EF::from_canonical_u32(5).cons());
+        let y: Ext<_, _> = builder.eval(x + a);
+
+        // The same products are evaluated over and over, as in the verifier's constraint folding.
+        let n: Var<_> = builder.eval(F::from_canonical_u32(4));
+        let acc: Ext<_, _> = builder.eval(EF::ZERO.cons());
+        builder.range(0, n).for_each(|_, builder| {
+            let p1: Felt<_> = builder.eval(a * b);
+            let p2: Felt<_> = builder.eval(a * b);
+            let e1: Ext<_, _> = builder.eval(x * y);
+            let e2: Ext<_, _> = builder.eval(x * y);
+            builder.assign(&acc, acc + e1 + e2 + p1 + p2);
+        });
+        let expected = (EF::from_canonical_u32(5 * 16) * F::TWO
+            + EF::from_canonical_u32(11 * 13) * F::TWO)
+            * F::from_canonical_u32(4);
+        builder.assert_ext_eq(acc, expected.cons());
+    });
+    assert!(optimized < plain, "{optimized} >= {plain}");
+}
+
+#[test]
+fn test_passes_dead_code() {
+    let (plain, optimized) = compare(vec![], |builder| {
+        let a: Felt<_> = builder.eval(F::from_canonical_u32(3));
+        let x: Ext<_, _> = builder.eval(EF::from_canonical_u32(2).cons());
+        let n: Var<_> = builder.eval(F::from_canonical_u32(8));
+        builder.range(0, n).for_each(|i, builder| {
+            let _unused: Felt<_> = builder.eval(a * a + a);
+            let _unused: Ext<_, _> = builder.eval(x * x * x);
+            let _unused: Var<_> = builder.eval(i[0] + i[0]);
+        });
+        builder.assert_felt_eq(a, F::from_canonical_u32(3));
+    });
+    assert!(optimized < plain, "{optimized} >= {plain}");
+}
+
+#[test]
+fn test_passes_strength_reduction() {
+    let (plain, optimized) = compare(vec![vec![F::from_canonical_u32(3)]], |builder| {
+        let x = builder.hint_felt();
+        let n: Var<_> = builder.eval(F::from_canonical_u32(8));
+        let acc: Ext<_, _> = builder.eval(EF::ONE.cons());
+        builder.range(0, n).for_each(|_, builder| {
+            builder.assign(&acc, acc * EF::TWO.cons());
+            builder.assign(&acc, acc * EF::from_canonical_u32(3).cons() + x);
+        });
+        let mut expected = EF::ONE;
+        for _ in 0..8 {
+            expected = expected * F::from_canonical_u32(6) + F::from_canonical_u32(3);
+        }
+        builder.assert_ext_eq(acc, expected.cons());
+    });
+    assert!(optimized < plain, "{optimized} >= {plain}");
+}
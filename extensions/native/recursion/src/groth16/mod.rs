@@ -0,0 +1,222 @@
+//! Groth16 wrapper over the static verifier program.
+//!
+//! The DSL program is compiled to R1CS over BN254 and proven with arkworks Groth16. Compared to
+//! the Halo2 wrapper, the proof is constant size and cheap to verify on-chain, but the setup is
+//! circuit specific.
+
+pub mod solidity;
+#[cfg(test)]
+mod tests;
+
+use ark_bn254::{Bn254, Fq, Fr as ArkFr};
+use ark_ff::{BigInteger, PrimeField, UniformRand};
+use ark_groth16::{prepare_verifying_key, Groth16, Proof, ProvingKey, VerifyingKey};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, OptimizationGoal};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use itertools::Itertools;
+use openvm_native_compiler::{constraints::r1cs::compiler::R1csCircuit, ir::Witness};
+use rand::{rngs::OsRng, CryptoRng, RngCore};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use snark_verifier_sdk::{
+    evm::evm_verify,
+    snark_verifier::{
+        halo2_base::{halo2_proofs::halo2curves::bn256::Fr, utils::ScalarField},
+        loader::evm::compile_solidity,
+    },
+};
+
+use crate::{
+    config::outer::OuterConfig,
+    halo2::{wrapper::EvmVerifier, DslOperations, EvmProof},
+};
+
+/// Proving key of the Groth16 wrapper circuit. The circuit is fully determined by `dsl_ops`.
+#[derive(Debug, Clone)]
+pub struct Groth16WrapperProvingKey {
+    pub pk: ProvingKey<Bn254>,
+    pub dsl_ops: DslOperations<OuterConfig>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Groth16Proof {
+    pub proof: Proof<Bn254>,
+    /// Public inputs of the wrapper circuit, i.e. the public values of the DSL program.
+    pub public_inputs: Vec<ArkFr>,
+}
+
+impl Groth16WrapperProvingKey {
+    /// Runs the circuit specific setup with randomness from `rng`.
+    ///
+    /// Attention: whoever knows the randomness can forge proofs. A key generated locally is only
+    /// suitable for testing; production deployments should use a CRS from a trusted setup.
+    pub fn keygen(
+        dsl_ops: DslOperations<OuterConfig>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Self {
+        #[cfg(feature = "bench-metrics")]
+        let start = std::time::Instant::now();
+        let circuit = R1csCircuit {
+            operations: dsl_ops.operations.clone(),
+            witness: Witness::default(),
+            num_public_values: dsl_ops.num_public_values,
+        };
+        let pk = Groth16::<Bn254>::generate_random_parameters_with_reduction(circuit, rng)
+            .expect("Groth16 setup failed");
+        #[cfg(feature = "bench-metrics")]
+        metrics::gauge!("groth16_keygen_time_ms").set(start.elapsed().as_millis() as f64);
+        Self { pk, dsl_ops }
+    }
+
+    pub fn vk(&self) -> &VerifyingKey<Bn254> {
+        &self.pk.vk
+    }
+
+    pub fn prove(&self, witness: Witness<OuterConfig>) -> Groth16Proof {
+        #[cfg(feature = "bench-metrics")]
+        let start = std::time::Instant::now();
+        let circuit = R1csCircuit {
+            operations: self.dsl_ops.operations.clone(),
+            witness,
+            num_public_values: self.dsl_ops.num_public_values,
+        };
+        // Same synthesis as `Groth16::prove`, but we keep the constraint system around to read
+        // the public inputs.
+        let cs = ConstraintSystem::<ArkFr>::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        circuit
+            .generate_constraints(cs.clone())
+            .expect("failed to synthesize Groth16 wrapper circuit");
+        assert!(
+            cs.is_satisfied().unwrap(),
+            "Groth16 wrapper circuit is not satisfied"
+        );
+        cs.finalize();
+        let matrices = cs.to_matrices().unwrap();
+        let cs = cs.borrow().unwrap();
+        let full_assignment = [
+            cs.instance_assignment.as_slice(),
+            cs.witness_assignment.as_slice(),
+        ]
+        .concat();
+
+        let r = ArkFr::rand(&mut OsRng);
+        let s = ArkFr::rand(&mut OsRng);
+        let proof = Groth16::<Bn254>::create_proof_with_reduction_and_matrices(
+            &self.pk,
+            r,
+            s,
+            &matrices,
+            cs.num_instance_variables,
+            cs.num_constraints,
+            &full_assignment,
+        )
+        .expect("Groth16 proving failed");
+        #[cfg(feature = "bench-metrics")]
+        metrics::gauge!("total_proof_time_ms").set(start.elapsed().as_millis() as f64);
+
+        Groth16Proof {
+            proof,
+            // The first instance variable is the constant one.
+            public_inputs: cs.instance_assignment[1..].to_vec(),
+        }
+    }
+
+    pub fn verify(&self, proof: &Groth16Proof) -> bool {
+        let pvk = prepare_verifying_key(&self.pk.vk);
+        Groth16::<Bn254>::verify_proof(&pvk, &proof.proof, &proof.public_inputs).unwrap_or(false)
+    }
+
+    pub fn prove_for_evm(&self, witness: Witness<OuterConfig>) -> EvmProof {
+        self.prove(witness).to_evm_proof()
+    }
+
+    /// Return deployment code for EVM verifier which can verify proofs of this circuit.
+    pub fn generate_evm_verifier(&self) -> EvmVerifier {
+        let sol_code = solidity::generate_solidity_verifier(&self.pk.vk);
+        EvmVerifier(compile_solidity(&sol_code))
+    }
+
+    /// A helper function for testing to verify the proof of this circuit with evm verifier.
+    pub fn evm_verify(evm_verifier: &EvmVerifier, evm_proof: &EvmProof) -> Result<u64, String> {
+        evm_verify(
+            evm_verifier.0.clone(),
+            evm_proof.instances.clone(),
+            evm_proof.proof.clone(),
+        )
+    }
+}
+
+impl Groth16Proof {
+    /// Proof bytes in the layout expected by the generated Solidity verifier: `A`, `B`, `C` as
+    /// big-endian 32-byte words, with `G2` coordinates ordered imaginary part first (EIP-197).
+    pub fn proof_bytes(&self) -> Vec<u8> {
+        let Proof { a, b, c } = &self.proof;
+        [a.x, a.y]
+            .iter()
+            .chain(&[b.x.c1, b.x.c0, b.y.c1, b.y.c0])
+            .chain(&[c.x, c.y])
+            .flat_map(fq_to_be_bytes)
+            .collect()
+    }
+
+    pub fn to_evm_proof(&self) -> EvmProof {
+        EvmProof {
+            instances: vec![self
+                .public_inputs
+                .iter()
+                .map(|x| Fr::from_bytes_le(&x.into_bigint().to_bytes_le()))
+                .collect_vec()],
+            proof: self.proof_bytes(),
+        }
+    }
+
+    /// Calldata for the generated Solidity verifier: public inputs followed by the proof.
+    pub fn calldata(&self) -> Vec<u8> {
+        self.public_inputs
+            .iter()
+            .flat_map(|x| x.into_bigint().to_bytes_be())
+            .chain(self.proof_bytes())
+            .collect()
+    }
+}
+
+fn fq_to_be_bytes(x: &Fq) -> Vec<u8> {
+    x.into_bigint().to_bytes_be()
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedGroth16WrapperProvingKey {
+    pk_bytes: Vec<u8>,
+    dsl_ops: DslOperations<OuterConfig>,
+}
+
+impl Serialize for Groth16WrapperProvingKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut pk_bytes = Vec::new();
+        self.pk
+            .serialize_uncompressed(&mut pk_bytes)
+            .map_err(serde::ser::Error::custom)?;
+        SerializedGroth16WrapperProvingKey {
+            pk_bytes,
+            dsl_ops: self.dsl_ops.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Groth16WrapperProvingKey {
+    fn deserialize<D>(deserializer: D) -> Result<Groth16WrapperProvingKey, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let SerializedGroth16WrapperProvingKey { pk_bytes, dsl_ops } =
+            SerializedGroth16WrapperProvingKey::deserialize(deserializer)?;
+        // Like the Halo2 proving key, the bytes are trusted and points are not re-validated.
+        let pk = ProvingKey::<Bn254>::deserialize_uncompressed_unchecked(pk_bytes.as_slice())
+            .map_err(|e| de::Error::custom(format!("invalid bytes for proving key: {}", e)))?;
+        Ok(Self { pk, dsl_ops })
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt::Write;
+
+use ark_bn254::{Bn254, Fq, G1Affine, G2Affine};
+use ark_ff::PrimeField;
+use ark_groth16::VerifyingKey;
+
+/// Scalar field modulus of BN254.
+const R: &str = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+/// Base field modulus of BN254.
+const Q: &str = "21888242871839275222246405745257275088696311157297823662689037894645226208583";
+
+/// Generates a Solidity contract verifying Groth16 proofs for `vk`.
+///
+/// The contract has no ABI: its fallback expects the calldata produced by
+/// [Groth16Proof::calldata](super::Groth16Proof::calldata), i.e. the public inputs followed by
+/// the proof, and reverts unless the proof is valid.
+pub fn generate_solidity_verifier(vk: &VerifyingKey<Bn254>) -> String {
+    let num_inputs = vk.gamma_abc_g1.len() - 1;
+    let proof_offset = 32 * num_inputs;
+
+    let mut body = String::new();
+    let b = &mut body;
+    writeln!(
+        b,
+        "            if iszero(eq(calldatasize(), {})) {{ revert(0, 0) }}",
+        proof_offset + 256
+    )
+    .unwrap();
+    // vk_x = gamma_abc[0] + sum_i input_i * gamma_abc[i + 1], accumulated at 0x00.
+    let (x, y) = g1_coords(&vk.gamma_abc_g1[0]);
+    writeln!(b, "            mstore(0x00, {x})").unwrap();
+    writeln!(b, "            mstore(0x20, {y})").unwrap();
+    for (i, point) in vk.gamma_abc_g1[1..].iter().enumerate() {
+        let (x, y) = g1_coords(point);
+        writeln!(b, "            {{").unwrap();
+        writeln!(b, "                let s := calldataload({})", 32 * i).unwrap();
+        writeln!(b, "                if iszero(lt(s, r)) {{ revert(0, 0) }}").unwrap();
+        writeln!(b, "                mstore(0x40, {x})").unwrap();
+        writeln!(b, "                mstore(0x60, {y})").unwrap();
+        writeln!(b, "                mstore(0x80, s)").unwrap();
+        writeln!(b, "                if iszero(staticcall(gas(), 0x07, 0x40, 0x60, 0x40, 0x40)) {{ revert(0, 0) }}").unwrap();
+        writeln!(b, "                if iszero(staticcall(gas(), 0x06, 0x00, 0x80, 0x00, 0x40)) {{ revert(0, 0) }}").unwrap();
+        writeln!(b, "            }}").unwrap();
+    }
+
+    // Pairing check e(-A, B) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) == 1, with the
+    // input laid out from 0x100.
+    writeln!(
+        b,
+        "            let ay := calldataload({})",
+        proof_offset + 0x20
+    )
+    .unwrap();
+    writeln!(b, "            if iszero(lt(ay, q)) {{ revert(0, 0) }}").unwrap();
+    let proof_word = |i: usize| format!("calldataload({})", proof_offset + 32 * i);
+    let (alpha_x, alpha_y) = g1_coords(&vk.alpha_g1);
+    let words = [proof_word(0), "mod(sub(q, ay), q)".to_string()]
+        .into_iter()
+        .chain((2..6).map(proof_word))
+        .chain([alpha_x, alpha_y])
+        .chain(g2_coords(&vk.beta_g2))
+        .chain(["mload(0x00)".to_string(), "mload(0x20)".to_string()])
+        .chain(g2_coords(&vk.gamma_g2))
+        .chain((6..8).map(proof_word))
+        .chain(g2_coords(&vk.delta_g2))
+        .collect::<Vec<_>>();
+    assert_eq!(words.len(), 24);
+    for (i, word) in words.iter().enumerate() {
+        writeln!(b, "            mstore({:#x}, {word})", 0x100 + 32 * i).unwrap();
+    }
+    writeln!(b, "            if iszero(staticcall(gas(), 0x08, 0x100, 0x300, 0x00, 0x20)) {{ revert(0, 0) }}").unwrap();
+    writeln!(b, "            if iszero(mload(0x00)) {{ revert(0, 0) }}").unwrap();
+
+    format!(
+        r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.19;
+
+contract Groth16Verifier {{
+    fallback() external {{
+        assembly {{
+            let r := {R}
+            let q := {Q}
+{body}        }}
+    }}
+}}
+"#
+    )
+}
+
+fn fq_to_dec(x: &Fq) -> String {
+    x.into_bigint().to_string()
+}
+
+fn g1_coords(p: &G1Affine) -> (String, String) {
+    (fq_to_dec(&p.x), fq_to_dec(&p.y))
+}
+
+/// Coordinates of a G2 point in EIP-197 order.
+fn g2_coords(p: &G2Affine) -> Vec<String> {
+    [p.x.c1, p.x.c0, p.y.c1, p.y.c0]
+        .iter()
+        .map(fq_to_dec)
+        .collect()
+}
@@ -0,0 +1,112 @@
+use std::io::Write;
+
+use ark_bn254::Fr as ArkFr;
+use openvm_native_compiler::ir::{Builder, Felt, Var, Witness};
+use openvm_stark_backend::p3_field::{FieldAlgebra, PrimeField};
+use openvm_stark_sdk::{
+    config::baby_bear_poseidon2_root::root_perm, p3_baby_bear::BabyBear, p3_bn254_fr::Bn254Fr,
+};
+use p3_symmetric::Permutation;
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    config::outer::OuterConfig,
+    groth16::{solidity::generate_solidity_verifier, Groth16WrapperProvingKey},
+    halo2::{DslOperations, Halo2Prover},
+    outer_poseidon2::Poseidon2CircuitBuilder,
+};
+
+fn convert_ark_fr(x: Bn254Fr) -> ArkFr {
+    ArkFr::from(x.as_canonical_biguint())
+}
+
+/// Returns the program and its expected public values.
+fn build_test_program() -> (DslOperations<OuterConfig>, Vec<Bn254Fr>) {
+    let input = [0, 1, 2].map(Bn254Fr::from_canonical_u32);
+    let mut output = input;
+    root_perm().permute_mut(&mut output);
+    let a_val = BabyBear::from_canonical_u32(1345237507);
+    let b_val = BabyBear::from_canonical_u32(1000001);
+
+    let mut builder = Builder::<OuterConfig>::default();
+    builder.flags.static_only = true;
+    let state: [Var<_>; 3] = input.map(|x| builder.eval(x));
+    builder.p2_permute_mut(state);
+    for (i, v) in state.into_iter().enumerate() {
+        builder.static_commit_public_value(i, v);
+    }
+    let a: Felt<_> = builder.eval(a_val);
+    let b: Felt<_> = builder.eval(b_val);
+    let c: Felt<_> = builder.eval(a * b - a / b);
+    let c_var = builder.cast_felt_to_var(c);
+    builder.static_commit_public_value(3, c_var);
+
+    let c_val = a_val * b_val - a_val / b_val;
+    let expected = output
+        .into_iter()
+        .chain([Bn254Fr::from_canonical_u32(c_val.as_canonical_u32())])
+        .collect();
+    (
+        DslOperations {
+            operations: builder.operations,
+            num_public_values: 4,
+        },
+        expected,
+    )
+}
+
+fn test_proving_key(dsl_ops: DslOperations<OuterConfig>) -> Groth16WrapperProvingKey {
+    Groth16WrapperProvingKey::keygen(dsl_ops, &mut StdRng::seed_from_u64(42))
+}
+
+#[test]
+fn test_groth16_prove_verify() {
+    let (dsl_ops, expected) = build_test_program();
+    // The R1CS and Halo2 backends must agree on the public values.
+    let halo2_pvs = Halo2Prover::mock(10, dsl_ops.clone(), Witness::default());
+    assert_eq!(halo2_pvs[0].len(), expected.len());
+
+    let pk = test_proving_key(dsl_ops);
+    let mut proof = pk.prove(Witness::default());
+    assert_eq!(
+        proof.public_inputs,
+        expected.into_iter().map(convert_ark_fr).collect::<Vec<_>>()
+    );
+    assert!(pk.verify(&proof));
+
+    proof.public_inputs[3] += ArkFr::from(1u64);
+    assert!(!pk.verify(&proof));
+}
+
+#[test]
+fn test_groth16_proving_key_serde() {
+    let (dsl_ops, _) = build_test_program();
+    let pk = test_proving_key(dsl_ops);
+
+    let mut f = tempfile::NamedTempFile::new().unwrap();
+    f.write_all(&bitcode::serialize(&pk).unwrap()).unwrap();
+    let new_pk: Groth16WrapperProvingKey =
+        bitcode::deserialize(&std::fs::read(f.path()).unwrap()).unwrap();
+    assert_eq!(new_pk.pk, pk.pk);
+
+    let proof = new_pk.prove(Witness::default());
+    assert!(pk.verify(&proof));
+}
+
+#[test]
+fn test_groth16_evm_verify() {
+    let (dsl_ops, _) = build_test_program();
+    let pk = test_proving_key(dsl_ops);
+    let sol_code = generate_solidity_verifier(pk.vk());
+    assert!(sol_code.contains("contract Groth16Verifier"));
+
+    let evm_verifier = pk.generate_evm_verifier();
+    let proof = pk.prove(Witness::default());
+    assert_eq!(proof.calldata().len(), 32 * (4 + 8));
+    let evm_proof = proof.to_evm_proof();
+    Groth16WrapperProvingKey::evm_verify(&evm_verifier, &evm_proof).unwrap();
+
+    let mut bad_proof = evm_proof.clone();
+    bad_proof.instances[0][0] += bad_proof.instances[0][1];
+    assert!(Groth16WrapperProvingKey::evm_verify(&evm_verifier, &bad_proof).is_err());
+}
@@ -14,6 +14,8 @@ pub mod vars;
 mod view;
 pub mod witness;
 
+#[cfg(feature = "groth16")]
+pub mod groth16;
 #[cfg(feature = "static-verifier")]
 pub mod halo2;
 
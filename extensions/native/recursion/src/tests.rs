@@ -28,7 +28,9 @@ use openvm_stark_sdk::{
 };
 
 use crate::{
-    hints::Hintable, stark::VerifierProgram, testing_utils::inner::run_recursive_test,
+    hints::Hintable,
+    stark::VerifierProgram,
+    testing_utils::inner::{build_verification_program, run_recursive_test},
     types::new_from_inner_multi_vk,
 };
 
@@ -101,6 +103,33 @@ fn test_fibonacci_small() {
     )
 }
 
+#[test]
+fn test_fibonacci_small_ir_optimizations() {
+    use openvm_circuit::arch::VmExecutor;
+    use openvm_native_compiler::conversion::CompilerOptions;
+
+    let executor = VmExecutor::<_, NativeConfig>::new(NativeConfig::aggregation(4, 7));
+    let count_cycles = |compiler_options| {
+        let vparams = fibonacci_test_proof_input::<BabyBearPoseidon2Config>(1 << 5)
+            .run_test(&BabyBearPoseidon2Engine::new(
+                standard_fri_params_with_100_bits_conjectured_security(3),
+            ))
+            .unwrap();
+        let (program, input_stream) = build_verification_program(vparams, compiler_options);
+        let segments = executor.execute_segments(program, input_stream).unwrap();
+        segments
+            .iter()
+            .map(|segment| {
+                let frequencies = &segment.chip_complex.program_chip().execution_frequencies;
+                frequencies.iter().sum::<usize>()
+            })
+            .sum::<usize>()
+    };
+    let plain = count_cycles(CompilerOptions::default());
+    let optimized = count_cycles(CompilerOptions::default().with_ir_optimizations());
+    assert!(optimized < plain, "{optimized} >= {plain}");
+}
+
 #[test]
 fn test_fibonacci() {
     // test lde = 27
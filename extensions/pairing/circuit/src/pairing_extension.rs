@@ -51,6 +51,26 @@ impl PairingCurve {
         }
     }
 
+    /// Config of the curve `G2` lies on, with coordinates in `Fp2 = Fp[u]/(u^2 + 1)`.
+    pub fn g2_curve_config(&self) -> CurveConfig {
+        match self {
+            // y^2 = x^3 + 3 / (9 + u)
+            PairingCurve::Bn254 => CurveConfig::new_fp2(
+                BN254_MODULUS.clone(),
+                BN254_ORDER.clone(),
+                -1,
+                [BigUint::zero(), BigUint::zero()],
+            ),
+            // y^2 = x^3 + 4 * (1 + u)
+            PairingCurve::Bls12_381 => CurveConfig::new_fp2(
+                BLS12_381_MODULUS.clone(),
+                BLS12_381_ORDER.clone(),
+                -1,
+                [BigUint::zero(), BigUint::zero()],
+            ),
+        }
+    }
+
     pub fn xi(&self) -> [isize; 2] {
         match self {
             PairingCurve::Bn254 => BN254_XI_ISIZE,
@@ -389,8 +409,6 @@ impl<F: PrimeField32> VmExtension<F> for PairingExtension {
 }
 
 pub(crate) mod phantom {
-    use std::collections::VecDeque;
-
     use eyre::bail;
     use openvm_circuit::{
         arch::{PhantomSubExecutor, Streams},
@@ -425,13 +443,13 @@ pub(crate) mod phantom {
         ) -> eyre::Result<()> {
             let rs1 = unsafe_read_rv32_register(memory, a);
             let rs2 = unsafe_read_rv32_register(memory, b);
-            hint_pairing(memory, &mut streams.hint_stream, rs1, rs2, c_upper)
+            hint_pairing(memory, streams, rs1, rs2, c_upper)
         }
     }
 
     fn hint_pairing<F: PrimeField32>(
         memory: &MemoryController<F>,
-        hint_stream: &mut VecDeque<F>,
+        streams: &mut Streams<F>,
         rs1: u32,
         rs2: u32,
         c_upper: u16,
@@ -488,8 +506,7 @@ pub(crate) mod phantom {
 
                 let f: Fq12 = Bn254::multi_miller_loop(&p, &q);
                 let (c, u) = Bn254::final_exp_hint(&f);
-                hint_stream.clear();
-                hint_stream.extend(
+                streams.set_hint_stream(
                     c.to_coeffs()
                         .into_iter()
                         .chain(u.to_coeffs())
@@ -530,8 +547,7 @@ pub(crate) mod phantom {
 
                 let f: Fq12 = Bls12_381::multi_miller_loop(&p, &q);
                 let (c, u) = Bls12_381::final_exp_hint(&f);
-                hint_stream.clear();
-                hint_stream.extend(
+                streams.set_hint_stream(
                     c.to_coeffs()
                         .into_iter()
                         .chain(u.to_coeffs())
@@ -0,0 +1,98 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::ops::Neg;
+
+use hex_literal::hex;
+use openvm_algebra_guest::IntMod;
+use openvm_ecc_guest::{weierstrass::WeierstrassPoint, Group};
+use openvm_pairing_guest::bn254::{Fp, Fp2};
+
+openvm::entry!(main);
+
+openvm_algebra_moduli_macros::moduli_init! {
+    "0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47",
+    "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"
+}
+
+openvm_algebra_complex_macros::complex_init! {
+    Bn254Fp2 { mod_idx = 0 },
+}
+
+// 3 / (9 + u)
+const G2_CURVE_B: Fp2 = Fp2::new(
+    Fp::from_const_bytes(hex!(
+        "e538a124dce66732a3efdb59e5c5b4b5c36ae01b9918be81aeaab8ce409d142b"
+    )),
+    Fp::from_const_bytes(hex!(
+        "d215c38506bda2e452182de584a04fa7f4fdd8eeadaf2ccdd4fef03ab0139700"
+    )),
+);
+
+openvm_ecc_sw_macros::sw_declare! {
+    Bn254G2Point { mod_type = Fp, coord_type = Fp2, b = G2_CURVE_B },
+}
+
+openvm_ecc_sw_macros::sw_init! {
+    Bn254G2Point,
+}
+
+pub fn main() {
+    setup_all_moduli();
+    setup_all_complex_extensions();
+    setup_all_curves();
+
+    // The generator of G2, see https://eips.ethereum.org/EIPS/eip-197
+    let x = Fp2::new(
+        Fp::from_le_bytes(&hex!(
+            "edf692d95cbdde46ddda5ef7d422436779445c5e66006a42761e1f12efde0018"
+        )),
+        Fp::from_le_bytes(&hex!(
+            "c212f3aeb785e49712e7a9353349aaf1255dfb31b7bf60723a480d9293938e19"
+        )),
+    );
+    let y = Fp2::new(
+        Fp::from_le_bytes(&hex!(
+            "aa7dfa6601cce64c7bd3430c69e7d1e38f40cb8d8071ab4aeb6d8cdba55ec812"
+        )),
+        Fp::from_le_bytes(&hex!(
+            "5b9722d1dcdaac55f38eb37033314bbc95330c69ad999eec75f05f58d0890609"
+        )),
+    );
+    let generator = Bn254G2Point::from_xy(x, y).unwrap();
+
+    // 3 * generator
+    let x3 = Fp2::new(
+        Fp::from_le_bytes(&hex!(
+            "f5c7fb9406fade12005e9ab08c477e8d5e7192e12628e551900eb14d784e0606"
+        )),
+        Fp::from_le_bytes(&hex!(
+            "856eb6ff324f82c9a7a078686b1504bcebe4cf5dcd9151734297bb572f771410"
+        )),
+    );
+    let y3 = Fp2::new(
+        Fp::from_le_bytes(&hex!(
+            "97556c66576d036597e72145d720b969a0682c8d9c0f4b07e0b9b581561d8e05"
+        )),
+        Fp::from_le_bytes(&hex!(
+            "b2117714caea2a454154b549ac5394dd23338df3c2fc2f92b74b35f335231e02"
+        )),
+    );
+    let expected = Bn254G2Point::from_xy(x3, y3).unwrap();
+
+    let double = generator.double();
+    let triple = &double + &generator;
+    if triple != expected {
+        panic!();
+    }
+
+    let mut sum = double.clone();
+    sum += &generator;
+    sum -= &generator;
+    if sum != double {
+        panic!();
+    }
+    if &triple + &triple.clone().neg() != <Bn254G2Point as Group>::IDENTITY {
+        panic!();
+    }
+}
@@ -17,10 +17,11 @@ mod bn254 {
         },
         AffinePoint,
     };
+    use openvm_ecc_transpiler::EccTranspilerExtension;
     use openvm_instructions::exe::VmExe;
     use openvm_pairing_circuit::{PairingCurve, PairingExtension, Rv32PairingConfig};
     use openvm_pairing_guest::{
-        bn254::BN254_MODULUS,
+        bn254::{BN254_MODULUS, BN254_ORDER},
         halo2curves_shims::bn254::Bn254,
         pairing::{EvaluatedLine, LineMulDType, MillerStep, MultiMillerLoop},
     };
@@ -291,6 +292,37 @@ mod bn254 {
         air_test_with_min_segments(get_testing_config(), openvm_exe, vec![io_all], 1);
         Ok(())
     }
+
+    #[test]
+    fn test_bn254_g2_ec() -> Result<()> {
+        let elf = build_example_program_at_path_with_features(
+            get_programs_dir!(),
+            "bn254_g2_ec",
+            ["bn254"],
+        )?;
+        let openvm_exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension)
+                .with_extension(EccTranspilerExtension)
+                .with_extension(ModularTranspilerExtension)
+                .with_extension(Fp2TranspilerExtension),
+        )?;
+        let config = Rv32PairingConfig {
+            system: SystemConfig::default().with_continuations(),
+            base: Default::default(),
+            mul: Default::default(),
+            io: Default::default(),
+            modular: ModularExtension::new(vec![BN254_MODULUS.clone(), BN254_ORDER.clone()]),
+            fp2: Fp2Extension::new(vec![BN254_MODULUS.clone()]),
+            weierstrass: WeierstrassExtension::new(vec![PairingCurve::Bn254.g2_curve_config()]),
+            pairing: PairingExtension::new(vec![]),
+        };
+        air_test_with_min_segments(config, openvm_exe, vec![], 1);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -357,6 +389,7 @@ mod bls12_381 {
             scalar: BLS12_381_ORDER.clone(),
             a: BigUint::ZERO,
             b: BigUint::from_u8(4).unwrap(),
+            fp2: None,
         };
         let openvm_exe = VmExe::from_elf(
             elf,
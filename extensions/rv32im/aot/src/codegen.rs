@@ -0,0 +1,622 @@
+use cranelift_codegen::{
+    ir::{
+        condcodes::IntCC, types, AbiParam, Block, FuncRef, InstBuilder, JumpTableData, MemFlags,
+        Value,
+    },
+    settings::{self, Configurable},
+};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext, Variable};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{default_libcall_names, Linkage, Module};
+use openvm_instructions::{
+    program::{DEFAULT_PC_STEP, PC_BITS},
+    riscv::RV32_MEMORY_AS,
+};
+use openvm_rv32im_transpiler::{
+    BaseAluOpcode, BranchEqualOpcode, BranchLessThanOpcode, LessThanOpcode, MulHOpcode,
+    Rv32LoadStoreOpcode, ShiftOpcode,
+};
+
+use crate::{
+    decode::{Op, Operand, Reg},
+    runtime::*,
+    AotError,
+};
+
+const READ_WORD: &str = "openvm_aot_read_word";
+const WRITE_WORD: &str = "openvm_aot_write_word";
+const DIV_REM: &str = "openvm_aot_div_rem";
+
+/// Addresses of the runtime functions called from native code.
+pub(crate) struct HostFunctions {
+    pub read_word: *const u8,
+    pub write_word: *const u8,
+    pub div_rem: *const u8,
+}
+
+type RunFn = unsafe extern "C" fn(*mut AotContext) -> u32;
+
+/// A program translated to a single native function, which runs from `ctx.pc` until an
+/// instruction must be interpreted and returns a status.
+pub(crate) struct CompiledProgram {
+    /// Owns the code `run` points to.
+    _module: JITModule,
+    run: RunFn,
+}
+
+impl CompiledProgram {
+    /// # Safety
+    /// `ctx.memory` and `ctx.executor_heights` must be valid, see [AotContext].
+    pub(crate) unsafe fn run(&self, ctx: &mut AotContext) -> u32 {
+        (self.run)(ctx)
+    }
+}
+
+fn codegen_error(err: impl std::fmt::Display) -> AotError {
+    AotError::Codegen(err.to_string())
+}
+
+/// Compiles `program`, where instruction `i` is at `pc_base + 4 * i`. If `executor_indices` is
+/// set, native code counts the rows of each executor in `ctx.executor_heights`.
+pub(crate) fn compile(
+    program: &[Option<Op>],
+    pc_base: u32,
+    executor_indices: Option<&[Option<usize>]>,
+    pointer_max_bits: usize,
+    host: &HostFunctions,
+) -> Result<CompiledProgram, AotError> {
+    let mut flag_builder = settings::builder();
+    flag_builder
+        .set("opt_level", "speed")
+        .map_err(codegen_error)?;
+    let isa = cranelift_native::builder()
+        .map_err(codegen_error)?
+        .finish(settings::Flags::new(flag_builder))
+        .map_err(codegen_error)?;
+    let mut jit_builder = JITBuilder::with_isa(isa, default_libcall_names());
+    jit_builder
+        .symbol(READ_WORD, host.read_word)
+        .symbol(WRITE_WORD, host.write_word)
+        .symbol(DIV_REM, host.div_rem);
+    let mut module = JITModule::new(jit_builder);
+    let ptr_type = module.target_config().pointer_type();
+
+    let mut import = |name: &str, params: &[types::Type]| {
+        let mut sig = module.make_signature();
+        sig.params
+            .extend(params.iter().map(|&param| AbiParam::new(param)));
+        sig.returns.push(AbiParam::new(types::I32));
+        module
+            .declare_function(name, Linkage::Import, &sig)
+            .map_err(codegen_error)
+    };
+    let read_word = import(READ_WORD, &[ptr_type, types::I32, types::I32])?;
+    let write_word = import(WRITE_WORD, &[ptr_type, types::I32, types::I32, types::I32])?;
+    let div_rem = import(DIV_REM, &[types::I32, types::I32, types::I32])?;
+
+    let mut ctx = module.make_context();
+    ctx.func.signature.params.push(AbiParam::new(ptr_type));
+    ctx.func.signature.returns.push(AbiParam::new(types::I32));
+    let run = module
+        .declare_function("run", Linkage::Local, &ctx.func.signature)
+        .map_err(codegen_error)?;
+
+    let mut builder_ctx = FunctionBuilderContext::new();
+    let mut builder = FunctionBuilder::new(&mut ctx.func, &mut builder_ctx);
+    let functions =
+        [read_word, write_word, div_rem].map(|id| module.declare_func_in_func(id, builder.func));
+    let entry = builder.create_block();
+    builder.append_block_params_for_function_params(entry);
+    let ctx_ptr = builder.block_params(entry)[0];
+    let blocks = program.iter().map(|_| builder.create_block()).collect();
+    let dispatch = builder.create_block();
+    let translator = Translator {
+        builder,
+        ctx: ctx_ptr,
+        ptr_type,
+        count: Variable::from_u32(0),
+        next_pc: Variable::from_u32(1),
+        blocks,
+        dispatch,
+        exits: vec![],
+        pc_base,
+        pointer_max_bits,
+        read_word: functions[0],
+        write_word: functions[1],
+        div_rem: functions[2],
+    };
+    translator.translate(entry, program, executor_indices);
+
+    module
+        .define_function(run, &mut ctx)
+        .map_err(codegen_error)?;
+    module.clear_context(&mut ctx);
+    module.finalize_definitions().map_err(codegen_error)?;
+    let code = module.get_finalized_function(run);
+    // SAFETY: `run` was declared with this signature and the system calling convention.
+    let run = unsafe { std::mem::transmute::<*const u8, RunFn>(code) };
+    Ok(CompiledProgram {
+        _module: module,
+        run,
+    })
+}
+
+struct Translator<'a> {
+    builder: FunctionBuilder<'a>,
+    ctx: Value,
+    ptr_type: types::Type,
+    /// Number of instructions executed, as an `i64`.
+    count: Variable,
+    /// Target of an indirect jump, as an `i32`.
+    next_pc: Variable,
+    /// Block of each instruction.
+    blocks: Vec<Block>,
+    /// Jumps to the block of `next_pc`.
+    dispatch: Block,
+    /// Blocks that return to the interpreter at a pc outside the translated program.
+    exits: Vec<(Block, u32)>,
+    pc_base: u32,
+    pointer_max_bits: usize,
+    read_word: FuncRef,
+    write_word: FuncRef,
+    div_rem: FuncRef,
+}
+
+impl Translator<'_> {
+    fn translate(
+        mut self,
+        entry: Block,
+        program: &[Option<Op>],
+        executor_indices: Option<&[Option<usize>]>,
+    ) {
+        self.builder.declare_var(self.count, types::I64);
+        self.builder.declare_var(self.next_pc, types::I32);
+
+        self.builder.switch_to_block(entry);
+        let pc = self.load(types::I32, PC_OFFSET);
+        let count = self.load(types::I64, NUM_INSTRUCTIONS_OFFSET);
+        self.builder.def_var(self.next_pc, pc);
+        self.builder.def_var(self.count, count);
+        self.builder.ins().jump(self.dispatch, &[]);
+
+        self.translate_dispatch();
+        for (i, op) in program.iter().enumerate() {
+            let executor_idx = executor_indices.and_then(|indices| indices[i]);
+            self.translate_instruction(i, op.as_ref(), executor_idx);
+        }
+        for (block, pc) in std::mem::take(&mut self.exits) {
+            self.builder.switch_to_block(block);
+            let pc = self.iconst(pc);
+            let count = self.builder.use_var(self.count);
+            self.exit(pc, count, STATUS_INTERPRET);
+        }
+
+        self.builder.seal_all_blocks();
+        self.builder.finalize();
+    }
+
+    fn translate_dispatch(&mut self) {
+        self.builder.switch_to_block(self.dispatch);
+        let pc = self.builder.use_var(self.next_pc);
+        let offset = self.builder.ins().iadd_imm(pc, -(self.pc_base as i64));
+        let misalignment = self.builder.ins().band_imm(offset, 3);
+        let index = self.builder.ins().ushr_imm(offset, 2);
+
+        let table = self.builder.create_block();
+        let fallback = self.builder.create_block();
+        self.builder.set_cold_block(fallback);
+        self.builder
+            .ins()
+            .brif(misalignment, fallback, &[], table, &[]);
+
+        self.builder.switch_to_block(table);
+        let default = self.builder.func.dfg.block_call(fallback, &[]);
+        let targets = self
+            .blocks
+            .iter()
+            .map(|&block| self.builder.func.dfg.block_call(block, &[]))
+            .collect::<Vec<_>>();
+        let jump_table = self
+            .builder
+            .create_jump_table(JumpTableData::new(default, &targets));
+        self.builder.ins().br_table(index, jump_table);
+
+        self.builder.switch_to_block(fallback);
+        let count = self.builder.use_var(self.count);
+        self.exit(pc, count, STATUS_INTERPRET);
+    }
+
+    fn translate_instruction(&mut self, i: usize, op: Option<&Op>, executor_idx: Option<usize>) {
+        let pc = self.pc_base + i as u32 * DEFAULT_PC_STEP;
+        let next_pc = pc + DEFAULT_PC_STEP;
+        self.builder.switch_to_block(self.blocks[i]);
+        let pc_value = self.iconst(pc);
+        let count = self.builder.use_var(self.count);
+        let Some(&op) = op else {
+            self.exit(pc_value, count, STATUS_INTERPRET);
+            return;
+        };
+
+        let max_instructions = self.load(types::I64, MAX_INSTRUCTIONS_OFFSET);
+        let limit_reached =
+            self.builder
+                .ins()
+                .icmp(IntCC::UnsignedGreaterThanOrEqual, count, max_instructions);
+        self.exit_if(limit_reached, pc_value, count, STATUS_INTERPRET);
+
+        if let Some(executor_idx) = executor_idx {
+            let heights = self.load(self.ptr_type, EXECUTOR_HEIGHTS_OFFSET);
+            let offset = (executor_idx * size_of::<u64>()) as i32;
+            let height = self
+                .builder
+                .ins()
+                .load(types::I64, MemFlags::trusted(), heights, offset);
+            let height = self.builder.ins().iadd_imm(height, 1);
+            self.builder
+                .ins()
+                .store(MemFlags::trusted(), height, heights, offset);
+        }
+
+        let next_count = self.builder.ins().iadd_imm(count, 1);
+        self.builder.def_var(self.count, next_count);
+
+        match op {
+            Op::Nop => {}
+            Op::BaseAlu {
+                opcode,
+                rd,
+                rs1,
+                rs2,
+            } => {
+                let x = self.read_reg(rs1);
+                let y = self.operand(rs2);
+                let ins = self.builder.ins();
+                let value = match opcode {
+                    BaseAluOpcode::ADD => ins.iadd(x, y),
+                    BaseAluOpcode::SUB => ins.isub(x, y),
+                    BaseAluOpcode::XOR => ins.bxor(x, y),
+                    BaseAluOpcode::OR => ins.bor(x, y),
+                    BaseAluOpcode::AND => ins.band(x, y),
+                };
+                self.write_reg(rd, value);
+            }
+            Op::Shift {
+                opcode,
+                rd,
+                rs1,
+                rs2,
+            } => {
+                let x = self.read_reg(rs1);
+                let y = self.operand(rs2);
+                let shift = self.builder.ins().band_imm(y, 31);
+                let ins = self.builder.ins();
+                let value = match opcode {
+                    ShiftOpcode::SLL => ins.ishl(x, shift),
+                    ShiftOpcode::SRL => ins.ushr(x, shift),
+                    ShiftOpcode::SRA => ins.sshr(x, shift),
+                };
+                self.write_reg(rd, value);
+            }
+            Op::LessThan {
+                opcode,
+                rd,
+                rs1,
+                rs2,
+            } => {
+                let x = self.read_reg(rs1);
+                let y = self.operand(rs2);
+                let cc = match opcode {
+                    LessThanOpcode::SLT => IntCC::SignedLessThan,
+                    LessThanOpcode::SLTU => IntCC::UnsignedLessThan,
+                };
+                let cmp = self.builder.ins().icmp(cc, x, y);
+                let value = self.builder.ins().uextend(types::I32, cmp);
+                self.write_reg(rd, value);
+            }
+            Op::Load {
+                opcode,
+                rd,
+                rs1,
+                imm,
+            } => {
+                let (ptr, word_ptr) = self.memory_ptr(rs1, imm, opcode, pc_value, count);
+                let word = self.call(self.read_word, &[word_ptr]);
+                let shift = self.byte_shift(ptr);
+                let shifted = self.builder.ins().ushr(word, shift);
+                let ins = self.builder.ins();
+                let value = match opcode {
+                    Rv32LoadStoreOpcode::LOADW => word,
+                    Rv32LoadStoreOpcode::LOADBU => ins.band_imm(shifted, 0xff),
+                    Rv32LoadStoreOpcode::LOADHU => ins.band_imm(shifted, 0xffff),
+                    Rv32LoadStoreOpcode::LOADB => {
+                        let byte = ins.ireduce(types::I8, shifted);
+                        self.builder.ins().sextend(types::I32, byte)
+                    }
+                    Rv32LoadStoreOpcode::LOADH => {
+                        let half = ins.ireduce(types::I16, shifted);
+                        self.builder.ins().sextend(types::I32, half)
+                    }
+                    _ => unreachable!("not a load: {opcode:?}"),
+                };
+                self.write_reg(rd, value);
+            }
+            Op::Store {
+                opcode,
+                rs1,
+                rs2,
+                imm,
+            } => {
+                let (ptr, word_ptr) = self.memory_ptr(rs1, imm, opcode, pc_value, count);
+                let data = self.read_reg(rs2);
+                let value = match opcode {
+                    Rv32LoadStoreOpcode::STOREW => data,
+                    Rv32LoadStoreOpcode::STOREH | Rv32LoadStoreOpcode::STOREB => {
+                        let mask = if opcode == Rv32LoadStoreOpcode::STOREH {
+                            0xffff
+                        } else {
+                            0xff
+                        };
+                        let prev = self.call(self.read_word, &[word_ptr]);
+                        let shift = self.byte_shift(ptr);
+                        let mask = self.iconst(mask);
+                        let mask = self.builder.ins().ishl(mask, shift);
+                        let data = self.builder.ins().ishl(data, shift);
+                        let data = self.builder.ins().band(data, mask);
+                        let prev = self.builder.ins().band_not(prev, mask);
+                        self.builder.ins().bor(prev, data)
+                    }
+                    _ => unreachable!("not a store: {opcode:?}"),
+                };
+                let status = self.call(self.write_word, &[word_ptr, value]);
+                let next_pc_value = self.iconst(next_pc);
+                self.exit_if(status, next_pc_value, next_count, STATUS_MEMORY_LIMIT);
+            }
+            Op::BranchEqual {
+                opcode,
+                rs1,
+                rs2,
+                to_pc,
+            } => {
+                let cc = match opcode {
+                    BranchEqualOpcode::BEQ => IntCC::Equal,
+                    BranchEqualOpcode::BNE => IntCC::NotEqual,
+                };
+                self.branch(cc, rs1, rs2, to_pc, next_pc);
+                return;
+            }
+            Op::BranchLessThan {
+                opcode,
+                rs1,
+                rs2,
+                to_pc,
+            } => {
+                let cc = match opcode {
+                    BranchLessThanOpcode::BLT => IntCC::SignedLessThan,
+                    BranchLessThanOpcode::BLTU => IntCC::UnsignedLessThan,
+                    BranchLessThanOpcode::BGE => IntCC::SignedGreaterThanOrEqual,
+                    BranchLessThanOpcode::BGEU => IntCC::UnsignedGreaterThanOrEqual,
+                };
+                self.branch(cc, rs1, rs2, to_pc, next_pc);
+                return;
+            }
+            Op::Jal { rd, to_pc } => {
+                if let Some(rd) = rd {
+                    let value = self.iconst(next_pc);
+                    self.write_reg(rd, value);
+                }
+                self.jump(to_pc);
+                return;
+            }
+            Op::Jalr { rd, rs1, imm } => {
+                let x = self.read_reg(rs1);
+                let imm = self.iconst(imm);
+                let to_pc = self.builder.ins().iadd(x, imm);
+                let to_pc = self.builder.ins().band_imm(to_pc, !1);
+                // The interpreter panics on an out of range pc.
+                let out_of_range = self.builder.ins().icmp_imm(
+                    IntCC::UnsignedGreaterThanOrEqual,
+                    to_pc,
+                    1i64 << PC_BITS,
+                );
+                self.exit_if(out_of_range, pc_value, count, STATUS_INTERPRET);
+                if let Some(rd) = rd {
+                    let value = self.iconst(next_pc);
+                    self.write_reg(rd, value);
+                }
+                self.builder.def_var(self.next_pc, to_pc);
+                self.builder.ins().jump(self.dispatch, &[]);
+                return;
+            }
+            Op::SetReg { rd, value } => {
+                if let Some(rd) = rd {
+                    let value = self.iconst(value);
+                    self.write_reg(rd, value);
+                }
+            }
+            Op::Mul { rd, rs1, rs2 } => {
+                let x = self.read_reg(rs1);
+                let y = self.read_reg(rs2);
+                let value = self.builder.ins().imul(x, y);
+                self.write_reg(rd, value);
+            }
+            Op::MulH {
+                opcode,
+                rd,
+                rs1,
+                rs2,
+            } => {
+                let x = self.read_reg(rs1);
+                let y = self.read_reg(rs2);
+                let (x_signed, y_signed) = match opcode {
+                    MulHOpcode::MULH => (true, true),
+                    MulHOpcode::MULHSU => (true, false),
+                    MulHOpcode::MULHU => (false, false),
+                };
+                let x = self.extend(x, x_signed);
+                let y = self.extend(y, y_signed);
+                let product = self.builder.ins().imul(x, y);
+                let high = self.builder.ins().ushr_imm(product, 32);
+                let value = self.builder.ins().ireduce(types::I32, high);
+                self.write_reg(rd, value);
+            }
+            Op::DivRem {
+                opcode,
+                rd,
+                rs1,
+                rs2,
+            } => {
+                let x = self.read_reg(rs1);
+                let y = self.read_reg(rs2);
+                let opcode = self.iconst(opcode as u32);
+                let call = self.builder.ins().call(self.div_rem, &[opcode, x, y]);
+                let value = self.builder.inst_results(call)[0];
+                self.write_reg(rd, value);
+            }
+        }
+        self.jump(next_pc);
+    }
+
+    fn iconst(&mut self, value: u32) -> Value {
+        self.builder.ins().iconst(types::I32, i64::from(value))
+    }
+
+    fn extend(&mut self, value: Value, signed: bool) -> Value {
+        if signed {
+            self.builder.ins().sextend(types::I64, value)
+        } else {
+            self.builder.ins().uextend(types::I64, value)
+        }
+    }
+
+    fn load(&mut self, ty: types::Type, offset: i32) -> Value {
+        self.builder
+            .ins()
+            .load(ty, MemFlags::trusted(), self.ctx, offset)
+    }
+
+    fn read_reg(&mut self, reg: Reg) -> Value {
+        self.load(types::I32, REGS_OFFSET + 4 * reg as i32)
+    }
+
+    fn write_reg(&mut self, reg: Reg, value: Value) {
+        self.builder.ins().store(
+            MemFlags::trusted(),
+            value,
+            self.ctx,
+            REGS_OFFSET + 4 * reg as i32,
+        );
+    }
+
+    fn operand(&mut self, operand: Operand) -> Value {
+        match operand {
+            Operand::Reg(reg) => self.read_reg(reg),
+            Operand::Imm(imm) => self.iconst(imm),
+        }
+    }
+
+    /// Calls a runtime memory function, which takes the context and address space first.
+    fn call(&mut self, func: FuncRef, args: &[Value]) -> Value {
+        let address_space = self.iconst(RV32_MEMORY_AS);
+        let args = [&[self.ctx, address_space], args].concat();
+        let call = self.builder.ins().call(func, &args);
+        self.builder.inst_results(call)[0]
+    }
+
+    /// Computes the pointer of a load or store and its aligned word pointer, and returns to the
+    /// interpreter if it is out of bounds or misaligned.
+    fn memory_ptr(
+        &mut self,
+        rs1: Reg,
+        imm: u32,
+        opcode: Rv32LoadStoreOpcode,
+        pc: Value,
+        count: Value,
+    ) -> (Value, Value) {
+        let x = self.read_reg(rs1);
+        let imm = self.iconst(imm);
+        let ptr = self.builder.ins().iadd(x, imm);
+        let alignment_mask = match opcode {
+            Rv32LoadStoreOpcode::LOADW | Rv32LoadStoreOpcode::STOREW => 3,
+            Rv32LoadStoreOpcode::LOADH
+            | Rv32LoadStoreOpcode::LOADHU
+            | Rv32LoadStoreOpcode::STOREH => 1,
+            _ => 0,
+        };
+        let misaligned = self.builder.ins().band_imm(ptr, alignment_mask);
+        let mut invalid = self.builder.ins().icmp_imm(IntCC::NotEqual, misaligned, 0);
+        if self.pointer_max_bits < 32 {
+            let out_of_bounds = self.builder.ins().icmp_imm(
+                IntCC::UnsignedGreaterThanOrEqual,
+                ptr,
+                1i64 << self.pointer_max_bits,
+            );
+            invalid = self.builder.ins().bor(invalid, out_of_bounds);
+        }
+        self.exit_if(invalid, pc, count, STATUS_INTERPRET);
+        let word_ptr = self.builder.ins().band_imm(ptr, !3);
+        (ptr, word_ptr)
+    }
+
+    /// Bit offset of the byte `ptr` points to within its word.
+    fn byte_shift(&mut self, ptr: Value) -> Value {
+        let byte = self.builder.ins().band_imm(ptr, 3);
+        self.builder.ins().ishl_imm(byte, 3)
+    }
+
+    fn branch(&mut self, cc: IntCC, rs1: Reg, rs2: Reg, to_pc: u32, next_pc: u32) {
+        let x = self.read_reg(rs1);
+        let y = self.read_reg(rs2);
+        let taken = self.builder.ins().icmp(cc, x, y);
+        let taken_block = self.target_block(to_pc);
+        let next_block = self.target_block(next_pc);
+        self.builder
+            .ins()
+            .brif(taken, taken_block, &[], next_block, &[]);
+    }
+
+    fn jump(&mut self, to_pc: u32) {
+        let block = self.target_block(to_pc);
+        self.builder.ins().jump(block, &[]);
+    }
+
+    /// Block of the instruction at `pc`, or a block returning to the interpreter if `pc` is
+    /// outside the program.
+    fn target_block(&mut self, pc: u32) -> Block {
+        let block = pc
+            .checked_sub(self.pc_base)
+            .filter(|offset| offset % DEFAULT_PC_STEP == 0)
+            .and_then(|offset| self.blocks.get((offset / DEFAULT_PC_STEP) as usize));
+        if let Some(&block) = block {
+            return block;
+        }
+        let block = self.builder.create_block();
+        self.builder.set_cold_block(block);
+        self.exits.push((block, pc));
+        block
+    }
+
+    /// Returns from native code with the given state.
+    fn exit(&mut self, pc: Value, count: Value, status: u32) {
+        self.builder
+            .ins()
+            .store(MemFlags::trusted(), pc, self.ctx, PC_OFFSET);
+        self.builder.ins().store(
+            MemFlags::trusted(),
+            count,
+            self.ctx,
+            NUM_INSTRUCTIONS_OFFSET,
+        );
+        let status = self.iconst(status);
+        self.builder.ins().return_(&[status]);
+    }
+
+    fn exit_if(&mut self, cond: Value, pc: Value, count: Value, status: u32) {
+        let exit = self.builder.create_block();
+        let next = self.builder.create_block();
+        self.builder.set_cold_block(exit);
+        self.builder.ins().brif(cond, exit, &[], next, &[]);
+        self.builder.switch_to_block(exit);
+        self.exit(pc, count, status);
+        self.builder.switch_to_block(next);
+    }
+}
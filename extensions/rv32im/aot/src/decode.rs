@@ -0,0 +1,281 @@
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_IMM_AS, RV32_MEMORY_AS, RV32_REGISTER_AS},
+    LocalOpcode, SysPhantom, SystemOpcode, VmOpcode,
+};
+use openvm_rv32im_transpiler::{
+    BaseAluOpcode, BranchEqualOpcode, BranchLessThanOpcode, DivRemOpcode, LessThanOpcode,
+    MulHOpcode, MulOpcode, Rv32AuipcOpcode, Rv32JalLuiOpcode, Rv32JalrOpcode, Rv32LoadStoreOpcode,
+    ShiftOpcode,
+};
+use openvm_stark_backend::p3_field::{FieldAlgebra, PrimeField32};
+
+/// Index of a register `x0..x31`.
+pub(crate) type Reg = usize;
+
+pub(crate) const NUM_REGISTERS: usize = 32;
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Operand {
+    Reg(Reg),
+    Imm(u32),
+}
+
+/// An instruction that is translated to native code, with all operands resolved. Immediates
+/// are sign-extended to 32 bits.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Op {
+    Nop,
+    BaseAlu {
+        opcode: BaseAluOpcode,
+        rd: Reg,
+        rs1: Reg,
+        rs2: Operand,
+    },
+    Shift {
+        opcode: ShiftOpcode,
+        rd: Reg,
+        rs1: Reg,
+        rs2: Operand,
+    },
+    LessThan {
+        opcode: LessThanOpcode,
+        rd: Reg,
+        rs1: Reg,
+        rs2: Operand,
+    },
+    Load {
+        opcode: Rv32LoadStoreOpcode,
+        rd: Reg,
+        rs1: Reg,
+        imm: u32,
+    },
+    Store {
+        opcode: Rv32LoadStoreOpcode,
+        rs1: Reg,
+        rs2: Reg,
+        imm: u32,
+    },
+    BranchEqual {
+        opcode: BranchEqualOpcode,
+        rs1: Reg,
+        rs2: Reg,
+        to_pc: u32,
+    },
+    BranchLessThan {
+        opcode: BranchLessThanOpcode,
+        rs1: Reg,
+        rs2: Reg,
+        to_pc: u32,
+    },
+    Jal {
+        rd: Option<Reg>,
+        to_pc: u32,
+    },
+    Jalr {
+        rd: Option<Reg>,
+        rs1: Reg,
+        imm: u32,
+    },
+    /// `LUI` and `AUIPC`, whose result is known at compile time.
+    SetReg {
+        rd: Option<Reg>,
+        value: u32,
+    },
+    Mul {
+        rd: Reg,
+        rs1: Reg,
+        rs2: Reg,
+    },
+    MulH {
+        opcode: MulHOpcode,
+        rd: Reg,
+        rs1: Reg,
+        rs2: Reg,
+    },
+    DivRem {
+        opcode: DivRemOpcode,
+        rd: Reg,
+        rs1: Reg,
+        rs2: Reg,
+    },
+}
+
+fn local_opcode<T: LocalOpcode>(opcode: VmOpcode, from_repr: fn(usize) -> Option<T>) -> Option<T> {
+    opcode
+        .as_usize()
+        .checked_sub(T::CLASS_OFFSET)
+        .and_then(from_repr)
+}
+
+fn reg<F: PrimeField32>(ptr: F) -> Option<Reg> {
+    let ptr = ptr.as_canonical_u32() as usize;
+    (ptr % 4 == 0 && ptr < 4 * NUM_REGISTERS).then_some(ptr / 4)
+}
+
+/// Register written by instructions with an `f` flag enabling the write.
+fn cond_reg<F: PrimeField32>(ptr: F, enabled: F) -> Option<Option<Reg>> {
+    if enabled.is_zero() {
+        Some(None)
+    } else {
+        reg(ptr).map(Some)
+    }
+}
+
+/// Second operand of ALU instructions: a register, or a 24-bit immediate whose top byte is
+/// repeated to make 32 bits.
+fn alu_operand<F: PrimeField32>(c: F, e: F) -> Option<Operand> {
+    match e.as_canonical_u32() {
+        RV32_REGISTER_AS => reg(c).map(Operand::Reg),
+        RV32_IMM_AS => {
+            let c = c.as_canonical_u32();
+            (c >> 24 == 0).then_some(Operand::Imm(c | ((c >> 16) << 24)))
+        }
+        _ => None,
+    }
+}
+
+/// 16-bit immediate of loads, stores and `JALR`, sign-extended.
+fn imm16<F: PrimeField32>(c: F) -> Option<u32> {
+    let c = c.as_canonical_u32();
+    (c >> 16 == 0).then_some(c as u16 as i16 as i32 as u32)
+}
+
+/// Decodes the instruction at `pc`. Returns `None` if it is not translated, in which case the
+/// interpreter executes it, including any error or panic it causes.
+pub(crate) fn decode<F: PrimeField32>(pc: u32, instruction: &Instruction<F>) -> Option<Op> {
+    let &Instruction {
+        opcode,
+        a,
+        b,
+        c,
+        d,
+        e,
+        f,
+        ..
+    } = instruction;
+    if opcode == SystemOpcode::PHANTOM.global_opcode() {
+        let discriminant = c.as_canonical_u32() as u16;
+        return (discriminant == SysPhantom::Nop as u16).then_some(Op::Nop);
+    }
+    if d.as_canonical_u32() != RV32_REGISTER_AS {
+        return None;
+    }
+
+    if let Some(opcode) = local_opcode(opcode, BaseAluOpcode::from_repr) {
+        return Some(Op::BaseAlu {
+            opcode,
+            rd: reg(a)?,
+            rs1: reg(b)?,
+            rs2: alu_operand(c, e)?,
+        });
+    }
+    if let Some(opcode) = local_opcode(opcode, ShiftOpcode::from_repr) {
+        return Some(Op::Shift {
+            opcode,
+            rd: reg(a)?,
+            rs1: reg(b)?,
+            rs2: alu_operand(c, e)?,
+        });
+    }
+    if let Some(opcode) = local_opcode(opcode, LessThanOpcode::from_repr) {
+        return Some(Op::LessThan {
+            opcode,
+            rd: reg(a)?,
+            rs1: reg(b)?,
+            rs2: alu_operand(c, e)?,
+        });
+    }
+    if let Some(opcode) = local_opcode(opcode, Rv32LoadStoreOpcode::from_repr) {
+        // Other address spaces, e.g. public values for `reveal`, are left to the interpreter.
+        if e.as_canonical_u32() != RV32_MEMORY_AS {
+            return None;
+        }
+        let imm = imm16(c)?;
+        return Some(match opcode {
+            Rv32LoadStoreOpcode::STOREW
+            | Rv32LoadStoreOpcode::STOREH
+            | Rv32LoadStoreOpcode::STOREB => Op::Store {
+                opcode,
+                rs1: reg(b)?,
+                rs2: reg(a)?,
+                imm,
+            },
+            _ => Op::Load {
+                opcode,
+                rd: reg(a)?,
+                rs1: reg(b)?,
+                imm,
+            },
+        });
+    }
+    if let Some(opcode) = local_opcode(opcode, BranchEqualOpcode::from_repr) {
+        return Some(Op::BranchEqual {
+            opcode,
+            rs1: reg(a)?,
+            rs2: reg(b)?,
+            to_pc: (F::from_canonical_u32(pc) + c).as_canonical_u32(),
+        });
+    }
+    if let Some(opcode) = local_opcode(opcode, BranchLessThanOpcode::from_repr) {
+        return Some(Op::BranchLessThan {
+            opcode,
+            rs1: reg(a)?,
+            rs2: reg(b)?,
+            to_pc: (F::from_canonical_u32(pc) + c).as_canonical_u32(),
+        });
+    }
+    if let Some(opcode) = local_opcode(opcode, Rv32JalLuiOpcode::from_repr) {
+        let rd = cond_reg(a, f)?;
+        return Some(match opcode {
+            Rv32JalLuiOpcode::JAL => {
+                // Same decoding of the signed immediate as the JAL core chip.
+                const HALF: u32 = 1 << 20;
+                let imm = (c + F::from_canonical_u32(HALF)).as_canonical_u32() as i64 - HALF as i64;
+                let to_pc = u32::try_from(pc as i64 + imm).ok()?;
+                Op::Jal { rd, to_pc }
+            }
+            Rv32JalLuiOpcode::LUI => Op::SetReg {
+                rd,
+                value: c.as_canonical_u32() << 12,
+            },
+        });
+    }
+    if local_opcode(opcode, Rv32JalrOpcode::from_repr).is_some() {
+        return Some(Op::Jalr {
+            rd: cond_reg(a, f)?,
+            rs1: reg(b)?,
+            imm: imm16(c)?,
+        });
+    }
+    if local_opcode(opcode, Rv32AuipcOpcode::from_repr).is_some() {
+        return Some(Op::SetReg {
+            rd: Some(reg(a)?),
+            value: pc.wrapping_add(c.as_canonical_u32() << 8),
+        });
+    }
+    if local_opcode(opcode, MulOpcode::from_repr).is_some() {
+        return Some(Op::Mul {
+            rd: reg(a)?,
+            rs1: reg(b)?,
+            rs2: reg(c)?,
+        });
+    }
+    if let Some(opcode) = local_opcode(opcode, MulHOpcode::from_repr) {
+        return Some(Op::MulH {
+            opcode,
+            rd: reg(a)?,
+            rs1: reg(b)?,
+            rs2: reg(c)?,
+        });
+    }
+    if let Some(opcode) = local_opcode(opcode, DivRemOpcode::from_repr) {
+        return Some(Op::DivRem {
+            opcode,
+            rd: reg(a)?,
+            rs1: reg(b)?,
+            rs2: reg(c)?,
+        });
+    }
+    None
+}
@@ -0,0 +1,265 @@
+//! Ahead-of-time compilation of RV32IM programs to host machine code, for fast execution
+//! without proving.
+//!
+//! The RV32IM instructions of a [VmExe] are translated with Cranelift into a single native
+//! function that keeps the registers in a context struct and accesses
+//! memory through the execution segment's [MemoryController]. Every other instruction, e.g. a
+//! custom opcode of another extension, a phantom instruction or `TERMINATE`, is executed by
+//! the interpreter of [VmExecutor] and therefore by the extension's own executor, after which
+//! native execution resumes. The final memory, public values, exit code and instruction count
+//! are the same as those of [VmExecutor::execute_only], including errors on execution limits.
+//!
+//! With [AotExecutor::new_with_segmentation], execution also computes where continuation
+//! segments start. Native code counts the rows of the executors of the instructions it runs, and
+//! the driver consults the [SegmentationStrategy] of the VM with these counts on top of the
+//! heights recorded by memory and the interpreted executors, at the same instructions as
+//! [VmExecutor::execute_segments] does.
+//!
+//! [MemoryController]: openvm_circuit::system::memory::MemoryController
+
+use openvm_circuit::arch::{
+    ExecutionError, ExecutionSegment, Streams, VmConfig, VmExecuteOnlyResult, VmExecutor,
+    VmInventoryError, SEGMENT_CHECK_INTERVAL,
+};
+use openvm_instructions::{exe::VmExe, program::DEFAULT_PC_STEP};
+use openvm_stark_backend::p3_field::PrimeField32;
+
+mod codegen;
+mod decode;
+mod runtime;
+
+use codegen::{CompiledProgram, HostFunctions};
+use runtime::*;
+
+#[derive(thiserror::Error, Debug)]
+pub enum AotError {
+    #[error("program pc step must be {DEFAULT_PC_STEP}, got {0}")]
+    UnsupportedPcStep(u32),
+    #[error("failed to create chip complex: {0}")]
+    Inventory(#[from] VmInventoryError),
+    #[error("code generation failed: {0}")]
+    Codegen(String),
+    #[error("segment boundaries require continuations")]
+    SegmentationWithoutContinuations,
+}
+
+pub struct AotExecutionResult<F> {
+    pub result: VmExecuteOnlyResult<F>,
+    /// Instruction counts at which a new segment starts, when segment boundaries are computed.
+    /// See [AotExecutor::new_with_segmentation].
+    pub segment_boundaries: Vec<u64>,
+}
+
+/// Executes a [VmExe] by running its RV32IM instructions as native code.
+pub struct AotExecutor<F: PrimeField32, VC: VmConfig<F>> {
+    executor: VmExecutor<F, VC>,
+    exe: VmExe<F>,
+    compiled: CompiledProgram,
+    /// Whether each instruction runs as native code.
+    is_native: Vec<bool>,
+    num_executors: usize,
+    segmentation: bool,
+}
+
+impl<F: PrimeField32, VC: VmConfig<F>> AotExecutor<F, VC> {
+    /// Compiles `exe` for the VM with the given config.
+    pub fn new(config: VC, exe: impl Into<VmExe<F>>) -> Result<Self, AotError> {
+        Self::new_impl(config, exe.into(), false)
+    }
+
+    /// Like [Self::new], but execution also computes the instruction counts at which
+    /// [VmExecutor::execute_segments] would start a new segment under the segmentation strategy
+    /// of `config`. This is slower than pure execution, since memory accesses and the
+    /// interpreted instructions are recorded for the strategy.
+    pub fn new_with_segmentation(config: VC, exe: impl Into<VmExe<F>>) -> Result<Self, AotError> {
+        if !config.system().continuation_enabled {
+            return Err(AotError::SegmentationWithoutContinuations);
+        }
+        Self::new_impl(config, exe.into(), true)
+    }
+
+    fn new_impl(config: VC, exe: VmExe<F>, segmentation: bool) -> Result<Self, AotError> {
+        let program = &exe.program;
+        if program.step != DEFAULT_PC_STEP {
+            return Err(AotError::UnsupportedPcStep(program.step));
+        }
+        let chip_complex = config.create_chip_complex()?;
+        let inventory = &chip_complex.inventory;
+        let (ops, executor_indices): (Vec<_>, Vec<_>) = program
+            .instructions_and_debug_infos
+            .iter()
+            .enumerate()
+            .map(|(i, instruction)| {
+                let Some((instruction, _)) = instruction else {
+                    return (None, None);
+                };
+                let pc = program.pc_base + i as u32 * DEFAULT_PC_STEP;
+                let executor_idx = inventory.executor_idx(instruction.opcode);
+                let op = executor_idx.and_then(|_| decode::decode(pc, instruction));
+                (op, executor_idx)
+            })
+            .unzip();
+        let is_native = ops.iter().map(Option::is_some).collect();
+        let num_executors = inventory.executors().len();
+
+        let compiled = codegen::compile(
+            &ops,
+            program.pc_base,
+            segmentation.then_some(executor_indices.as_slice()),
+            config.system().memory_config.pointer_max_bits,
+            &HostFunctions {
+                read_word: read_word::<F> as *const u8,
+                write_word: write_word::<F> as *const u8,
+                div_rem: div_rem as *const u8,
+            },
+        )?;
+        Ok(Self {
+            executor: VmExecutor::new(config),
+            exe,
+            compiled,
+            is_native,
+            num_executors,
+            segmentation,
+        })
+    }
+
+    fn is_native(&self, pc: u32) -> bool {
+        pc.checked_sub(self.exe.program.pc_base)
+            .filter(|offset| offset % DEFAULT_PC_STEP == 0)
+            .and_then(|offset| self.is_native.get((offset / DEFAULT_PC_STEP) as usize))
+            .is_some_and(|&is_native| is_native)
+    }
+
+    pub fn execute(
+        &self,
+        input: impl Into<Streams<F>>,
+    ) -> Result<AotExecutionResult<F>, ExecutionError> {
+        let limits = self.executor.config.system().limits;
+        let max_cycles = limits.max_cycles.unwrap_or(u64::MAX);
+        let mut segment = if self.segmentation {
+            self.executor
+                .create_first_segment(self.exe.clone(), input)?
+        } else {
+            self.executor
+                .create_execute_only_segment(self.exe.clone(), input)?
+        };
+        let mut heights = vec![0u64; self.num_executors];
+        let check_interval = SEGMENT_CHECK_INTERVAL as u64 + 1;
+        let mut next_segment_check = check_interval;
+        let mut segment_boundaries = vec![];
+
+        let mut synced_regs = read_registers(segment.chip_complex.memory_controller());
+        let mut ctx = AotContext {
+            regs: synced_regs,
+            pc: self.exe.pc_start,
+            num_instructions: 0,
+            max_instructions: max_cycles,
+            max_memory_pages: limits
+                .max_memory_pages
+                .map_or(u64::MAX, |pages| pages as u64),
+            memory: std::ptr::null_mut(),
+            executor_heights: std::ptr::null_mut(),
+        };
+        loop {
+            if self.segmentation {
+                ctx.max_instructions = max_cycles.min(next_segment_check);
+            }
+            ctx.memory = &mut segment.chip_complex.base.memory_controller as *mut _ as *mut _;
+            ctx.executor_heights = heights.as_mut_ptr();
+            // SAFETY: the memory controller and heights are not accessed while native code runs.
+            let status = unsafe { self.compiled.run(&mut ctx) };
+            match status {
+                STATUS_INTERPRET => {}
+                STATUS_MEMORY_LIMIT => {
+                    return Err(ExecutionError::MemoryLimitExceeded {
+                        pc: ctx.pc,
+                        pages: segment
+                            .chip_complex
+                            .memory_controller()
+                            .num_allocated_pages(),
+                        cycles: ctx.num_instructions,
+                    });
+                }
+                _ => unreachable!("unknown status {status}"),
+            }
+
+            if self.segmentation && ctx.num_instructions == next_segment_check {
+                next_segment_check += check_interval;
+                let extra_rows = heights.iter().map(|&h| h as usize).collect::<Vec<_>>();
+                if segment.should_segment_with_extra_rows(&extra_rows) {
+                    write_registers(
+                        &mut segment.chip_complex.base.memory_controller,
+                        &ctx.regs,
+                        &synced_regs,
+                    );
+                    synced_regs = ctx.regs;
+                    segment = self.next_segment(segment, segment_boundaries.len() + 1, &ctx)?;
+                    segment_boundaries.push(ctx.num_instructions);
+                    heights.fill(0);
+                    next_segment_check = ctx.num_instructions + check_interval;
+                }
+            }
+            // Native code also returns before instructions it runs, to let the driver consult the
+            // segmentation strategy.
+            if self.is_native(ctx.pc) && ctx.num_instructions < max_cycles {
+                continue;
+            }
+
+            write_registers(
+                &mut segment.chip_complex.base.memory_controller,
+                &ctx.regs,
+                &synced_regs,
+            );
+            segment.cycles_before = ctx.num_instructions;
+            let state = segment.execute_instructions_from_pc(ctx.pc, 1)?;
+            ctx.num_instructions += state.num_instructions;
+            if state.is_terminated {
+                break;
+            }
+            ctx.pc = state.pc;
+            synced_regs = read_registers(segment.chip_complex.memory_controller());
+            ctx.regs = synced_regs;
+        }
+
+        Ok(AotExecutionResult {
+            result: self
+                .executor
+                .execute_only_result(segment, ctx.num_instructions),
+            segment_boundaries,
+        })
+    }
+
+    /// Ends `segment` at the current state and starts segment number `num_segments`, like
+    /// [VmExecutor::execute_until_segment].
+    fn next_segment(
+        &self,
+        mut segment: ExecutionSegment<F, VC>,
+        num_segments: usize,
+        ctx: &AotContext,
+    ) -> Result<ExecutionSegment<F, VC>, ExecutionError> {
+        if let Some(max_segments) = self.executor.config.system().limits.max_segments {
+            if num_segments >= max_segments {
+                return Err(ExecutionError::SegmentLimitExceeded {
+                    pc: ctx.pc,
+                    segments: num_segments,
+                    cycles: ctx.num_instructions,
+                });
+            }
+        }
+        let memory = segment
+            .chip_complex
+            .memory_controller()
+            .memory_image()
+            .clone();
+        let streams = segment.chip_complex.take_streams();
+        let mut next = ExecutionSegment::new(
+            &self.executor.config,
+            self.exe.program.clone(),
+            streams,
+            Some(memory),
+            self.exe.fn_bounds.clone(),
+        )?;
+        next.cycles_before = ctx.num_instructions;
+        Ok(next)
+    }
+}
@@ -0,0 +1,131 @@
+use std::{ffi::c_void, mem::offset_of};
+
+use openvm_circuit::system::memory::MemoryController;
+use openvm_instructions::riscv::{RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS};
+use openvm_rv32im_transpiler::DivRemOpcode;
+use openvm_stark_backend::p3_field::{FieldAlgebra, PrimeField32};
+
+use crate::decode::NUM_REGISTERS;
+
+/// Native code returns to the driver to interpret the instruction at `pc`.
+pub(crate) const STATUS_INTERPRET: u32 = 0;
+/// The instruction before `pc` exceeded the memory page limit.
+pub(crate) const STATUS_MEMORY_LIMIT: u32 = 1;
+
+/// State shared between native code and the driver. Native code keeps the registers here and
+/// only the driver synchronizes them with register memory.
+#[repr(C)]
+pub(crate) struct AotContext {
+    pub regs: [u32; NUM_REGISTERS],
+    pub pc: u32,
+    /// Total number of instructions executed.
+    pub num_instructions: u64,
+    /// Native code returns to the driver once `num_instructions` reaches this, so that the
+    /// interpreter reports the cycle limit error or the driver consults the segmentation
+    /// strategy.
+    pub max_instructions: u64,
+    pub max_memory_pages: u64,
+    /// The `MemoryController<F>` of the execution segment.
+    pub memory: *mut c_void,
+    /// Rows of each executor in the current segment that native code counted instead of the
+    /// executor, indexed by executor index. Only used when computing segment boundaries.
+    pub executor_heights: *mut u64,
+}
+
+pub(crate) const REGS_OFFSET: i32 = offset_of!(AotContext, regs) as i32;
+pub(crate) const PC_OFFSET: i32 = offset_of!(AotContext, pc) as i32;
+pub(crate) const NUM_INSTRUCTIONS_OFFSET: i32 = offset_of!(AotContext, num_instructions) as i32;
+pub(crate) const MAX_INSTRUCTIONS_OFFSET: i32 = offset_of!(AotContext, max_instructions) as i32;
+pub(crate) const EXECUTOR_HEIGHTS_OFFSET: i32 = offset_of!(AotContext, executor_heights) as i32;
+
+/// # Safety
+/// `ctx.memory` must point to a `MemoryController<F>` that is not otherwise borrowed.
+unsafe fn memory<'a, F: PrimeField32>(ctx: *mut AotContext) -> &'a mut MemoryController<F> {
+    &mut *((*ctx).memory as *mut MemoryController<F>)
+}
+
+fn compose<F: PrimeField32>(data: [F; RV32_REGISTER_NUM_LIMBS]) -> u32 {
+    data.iter().enumerate().fold(0, |acc, (i, limb)| {
+        acc.wrapping_add(limb.as_canonical_u32() << (8 * i))
+    })
+}
+
+fn decompose<F: PrimeField32>(value: u32) -> [F; RV32_REGISTER_NUM_LIMBS] {
+    value.to_le_bytes().map(F::from_canonical_u8)
+}
+
+/// Reads the aligned word at `ptr`. Called from native code.
+pub(crate) unsafe extern "C" fn read_word<F: PrimeField32>(
+    ctx: *mut AotContext,
+    address_space: u32,
+    ptr: u32,
+) -> u32 {
+    let memory = memory::<F>(ctx);
+    let (_, data) = memory.read(
+        F::from_canonical_u32(address_space),
+        F::from_canonical_u32(ptr),
+    );
+    compose(data)
+}
+
+/// Writes the aligned word at `ptr` and returns a non-zero status if the memory page limit is
+/// exceeded. Called from native code.
+pub(crate) unsafe extern "C" fn write_word<F: PrimeField32>(
+    ctx: *mut AotContext,
+    address_space: u32,
+    ptr: u32,
+    value: u32,
+) -> u32 {
+    let memory = memory::<F>(ctx);
+    memory.write(
+        F::from_canonical_u32(address_space),
+        F::from_canonical_u32(ptr),
+        decompose(value),
+    );
+    if memory.num_allocated_pages() as u64 > (*ctx).max_memory_pages {
+        STATUS_MEMORY_LIMIT
+    } else {
+        STATUS_INTERPRET
+    }
+}
+
+/// RISC-V division and remainder, including division by zero and signed overflow. Called from
+/// native code.
+pub(crate) extern "C" fn div_rem(opcode: u32, x: u32, y: u32) -> u32 {
+    match DivRemOpcode::from_repr(opcode as usize).expect("invalid DivRemOpcode") {
+        DivRemOpcode::DIV if y == 0 => u32::MAX,
+        DivRemOpcode::DIV => (x as i32).wrapping_div(y as i32) as u32,
+        DivRemOpcode::DIVU => x.checked_div(y).unwrap_or(u32::MAX),
+        DivRemOpcode::REM if y == 0 => x,
+        DivRemOpcode::REM => (x as i32).wrapping_rem(y as i32) as u32,
+        DivRemOpcode::REMU => x.checked_rem(y).unwrap_or(x),
+    }
+}
+
+pub(crate) fn read_registers<F: PrimeField32>(
+    memory: &MemoryController<F>,
+) -> [u32; NUM_REGISTERS] {
+    std::array::from_fn(|i| {
+        compose(memory.unsafe_read(
+            F::from_canonical_u32(RV32_REGISTER_AS),
+            F::from_canonical_usize(i * RV32_REGISTER_NUM_LIMBS),
+        ))
+    })
+}
+
+/// Writes the registers that differ from `synced` to register memory.
+pub(crate) fn write_registers<F: PrimeField32>(
+    memory: &mut MemoryController<F>,
+    regs: &[u32; NUM_REGISTERS],
+    synced: &[u32; NUM_REGISTERS],
+) {
+    for (i, (&value, &synced)) in regs.iter().zip(synced).enumerate() {
+        if value != synced {
+            memory.write(
+                F::from_canonical_u32(RV32_REGISTER_AS),
+                F::from_canonical_usize(i * RV32_REGISTER_NUM_LIMBS),
+                decompose(value),
+            );
+        }
+    }
+}
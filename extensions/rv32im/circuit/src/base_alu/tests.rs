@@ -2,9 +2,10 @@ use std::borrow::BorrowMut;
 
 use openvm_circuit::{
     arch::{
-        testing::{TestAdapterChip, VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
+        testing::{MutationConfig, TestAdapterChip, VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
         ExecutionBridge, VmAdapterChip, VmChipWrapper,
     },
+    system::memory::offline_checker::MemoryReadAuxCols,
     utils::generate_long_number,
 };
 use openvm_circuit_primitives::bitwise_op_lookup::{
@@ -24,11 +25,13 @@ use openvm_stark_backend::{
     ChipUsageGetter,
 };
 use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
-use rand::Rng;
+use rand::{rngs::StdRng, Rng};
 
 use super::{core::run_alu, BaseAluCoreChip, Rv32BaseAluChip};
 use crate::{
-    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    adapters::{
+        Rv32BaseAluAdapterChip, Rv32BaseAluAdapterCols, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS,
+    },
     base_alu::BaseAluCoreCols,
     test_utils::{generate_rv32_is_type_immediate, rv32_rand_write_register_or_imm},
 };
@@ -42,13 +45,15 @@ type F = BabyBear;
 // passes all constraints.
 //////////////////////////////////////////////////////////////////////////////////////
 
-fn run_rv32_alu_rand_test(opcode: BaseAluOpcode, num_ops: usize) {
-    let mut rng = create_seeded_rng();
+fn create_test_chip(
+    tester: &VmChipTestBuilder<F>,
+) -> (
+    Rv32BaseAluChip<F>,
+    SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+) {
     let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
     let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
-
-    let mut tester = VmChipTestBuilder::default();
-    let mut chip = Rv32BaseAluChip::<F>::new(
+    let chip = Rv32BaseAluChip::<F>::new(
         Rv32BaseAluAdapterChip::new(
             tester.execution_bus(),
             tester.program_bus(),
@@ -57,6 +62,48 @@ fn run_rv32_alu_rand_test(opcode: BaseAluOpcode, num_ops: usize) {
         BaseAluCoreChip::new(bitwise_chip.clone(), BaseAluOpcode::CLASS_OFFSET),
         tester.offline_memory_mutex_arc(),
     );
+    (chip, bitwise_chip)
+}
+
+/// Executes `opcode` on random operands, with `c` read from a register or an immediate, and
+/// checks the result.
+fn set_and_execute(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut Rv32BaseAluChip<F>,
+    rng: &mut StdRng,
+    opcode: BaseAluOpcode,
+    is_imm: bool,
+) {
+    let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(rng);
+    let (c_imm, c) = if is_imm {
+        let (imm, c) = generate_rv32_is_type_immediate(rng);
+        (Some(imm), c)
+    } else {
+        (
+            None,
+            generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(rng),
+        )
+    };
+
+    let (instruction, rd) = rv32_rand_write_register_or_imm(
+        tester,
+        b,
+        c,
+        c_imm,
+        opcode.global_opcode().as_usize(),
+        rng,
+    );
+    tester.execute(chip, &instruction);
+
+    let a = run_alu::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(opcode, &b, &c)
+        .map(F::from_canonical_u32);
+    assert_eq!(a, tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd))
+}
+
+fn run_rv32_alu_rand_test(opcode: BaseAluOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut chip, bitwise_chip) = create_test_chip(&tester);
 
     for _ in 0..num_ops {
         let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
@@ -262,6 +309,51 @@ fn rv32_alu_and_wrong_negative_test() {
     );
 }
 
+//////////////////////////////////////////////////////////////////////////////////////
+// MUTATION TESTS
+//
+// Execute ADD and XOR, which cover the arithmetic and the bitwise lookup constraints, with a
+// register and with an immediate operand. Then mutate every cell of the resulting rows and check
+// that each mutated trace is rejected.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn rv32_alu_mutation_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut chip, bitwise_chip) = create_test_chip(&tester);
+
+    let mut num_rows = 0;
+    let mut imm_rows = vec![];
+    for opcode in [BaseAluOpcode::ADD, BaseAluOpcode::XOR] {
+        for is_imm in [false, true] {
+            set_and_execute(&mut tester, &mut chip, &mut rng, opcode, is_imm);
+            if is_imm {
+                imm_rows.push(num_rows);
+            }
+            num_rows += 1;
+        }
+    }
+    // The aux columns of the rs2 read are unused when rs2 is an immediate.
+    let aux_width = MemoryReadAuxCols::<F>::width();
+    let rs2_aux_columns = (aux_width..2 * aux_width).map(|i| format!("reads_aux[{i}]"));
+
+    tester.assert_mutations_rejected(
+        chip,
+        [
+            Rv32BaseAluAdapterCols::<F>::column_names(),
+            BaseAluCoreCols::<F, RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>::column_names(),
+        ]
+        .concat(),
+        |tester| tester.load(bitwise_chip),
+        &MutationConfig {
+            rows: (0..num_rows).collect(),
+            ..Default::default()
+        }
+        .with_dont_care(imm_rows, rs2_aux_columns),
+    );
+}
+
 ///////////////////////////////////////////////////////////////////////////////////////
 /// SANITY TESTS
 ///
@@ -456,6 +456,12 @@ impl<F: PrimeField32> VmExtension<F> for Rv32Io {
             Rv32HintStoreOpcode::CLASS_OFFSET,
         );
         hintstore_chip.set_streams(builder.streams().clone());
+        if let Some(input_commit_air) = builder.system_base().input_commit_air() {
+            hintstore_chip.set_input_commit_air(
+                input_commit_air,
+                builder.system_base().range_checker_chip.clone(),
+            );
+        }
 
         inventory.add_executor(
             hintstore_chip,
@@ -477,7 +483,7 @@ mod phantom {
     use openvm_stark_backend::p3_field::{Field, PrimeField32};
     use rand::{rngs::OsRng, Rng};
 
-    use crate::adapters::unsafe_read_rv32_register;
+    use crate::{adapters::unsafe_read_rv32_register, rv32_input_hint};
 
     pub struct Rv32HintInputSubEx;
     pub struct Rv32HintRandomSubEx {
@@ -500,23 +506,13 @@ mod phantom {
             _: F,
             _: u16,
         ) -> eyre::Result<()> {
-            let mut hint = match streams.input_stream.pop_front() {
+            let hint = match streams.input_stream.pop_front() {
                 Some(hint) => hint,
                 None => {
                     bail!("EndOfInputStream");
                 }
             };
-            streams.hint_stream.clear();
-            streams.hint_stream.extend(
-                (hint.len() as u32)
-                    .to_le_bytes()
-                    .iter()
-                    .map(|b| F::from_canonical_u8(*b)),
-            );
-            // Extend by 0 for 4 byte alignment
-            let capacity = hint.len().div_ceil(4) * 4;
-            hint.resize(capacity, F::ZERO);
-            streams.hint_stream.extend(hint);
+            streams.set_hint_stream(rv32_input_hint(&hint));
             Ok(())
         }
     }
@@ -532,8 +528,7 @@ mod phantom {
             _: u16,
         ) -> eyre::Result<()> {
             let len = unsafe_read_rv32_register(memory, a) as usize;
-            streams.hint_stream.clear();
-            streams.hint_stream.extend(
+            streams.set_hint_stream(
                 std::iter::repeat_with(|| F::from_canonical_u8(self.rng.gen::<u8>())).take(len * 4),
             );
             Ok(())
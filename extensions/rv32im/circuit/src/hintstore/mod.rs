@@ -1,16 +1,23 @@
 use std::{
     borrow::{Borrow, BorrowMut},
+    mem::offset_of,
     sync::{Arc, Mutex, OnceLock},
 };
 
 use openvm_circuit::{
     arch::{
-        ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor, Streams,
+        hasher::poseidon2::{vm_poseidon2_hasher, Poseidon2Hasher},
+        ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor,
+        Streams,
     },
     system::{
+        input_commitment::{
+            compute_input_commitment, InputCommitCols, InputCommitIo, InputCommitRecord,
+            InputCommitSubAir,
+        },
         memory::{
             offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
-            MemoryAddress, MemoryAuxColsFactory, MemoryController, OfflineMemory, RecordId,
+            MemoryAddress, MemoryAuxColsFactory, MemoryController, OfflineMemory, RecordId, CHUNK,
         },
         program::ProgramBus,
     },
@@ -18,6 +25,8 @@ use openvm_circuit::{
 use openvm_circuit_primitives::{
     bitwise_op_lookup::{BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip},
     utils::next_power_of_two_or_zero,
+    var_range::SharedVariableRangeCheckerChip,
+    SubAir,
 };
 use openvm_circuit_primitives_derive::AlignedBorrow;
 use openvm_instructions::{
@@ -70,17 +79,26 @@ pub struct Rv32HintStoreCols<T> {
     pub num_words_aux_cols: MemoryReadAuxCols<T>,
 }
 
+/// Column of `from_state.timestamp` in [Rv32HintStoreCols].
+const TIMESTAMP_COL: usize =
+    offset_of!(Rv32HintStoreCols<u8>, from_state) + offset_of!(ExecutionState<u8>, timestamp);
+
 #[derive(Copy, Clone, Debug)]
 pub struct Rv32HintStoreAir {
     pub execution_bridge: ExecutionBridge,
     pub memory_bridge: MemoryBridge,
     pub bitwise_operation_lookup_bus: BitwiseOperationLookupBus,
     pub offset: usize,
+    /// Commits to the hinted words, with [InputCommitCols] after [Rv32HintStoreCols].
+    pub input_commit: Option<InputCommitSubAir>,
 }
 
 impl<F: Field> BaseAir<F> for Rv32HintStoreAir {
     fn width(&self) -> usize {
         Rv32HintStoreCols::<F>::width()
+            + self
+                .input_commit
+                .map_or(0, |_| InputCommitCols::<F>::width())
     }
 }
 
@@ -90,10 +108,11 @@ impl<F: Field> PartitionedBaseAir<F> for Rv32HintStoreAir {}
 impl<AB: InteractionBuilder> Air<AB> for Rv32HintStoreAir {
     fn eval(&self, builder: &mut AB) {
         let main = builder.main();
+        let width = Rv32HintStoreCols::<AB::Var>::width();
         let local = main.row_slice(0);
-        let local_cols: &Rv32HintStoreCols<AB::Var> = (*local).borrow();
+        let local_cols: &Rv32HintStoreCols<AB::Var> = local[..width].borrow();
         let next = main.row_slice(1);
-        let next_cols: &Rv32HintStoreCols<AB::Var> = (*next).borrow();
+        let next_cols: &Rv32HintStoreCols<AB::Var> = next[..width].borrow();
 
         let timestamp: AB::Var = local_cols.from_state.timestamp;
         let mut timestamp_delta: usize = 0;
@@ -226,6 +245,20 @@ impl<AB: InteractionBuilder> Air<AB> for Rv32HintStoreAir {
             timestamp + AB::F::from_canonical_usize(timestamp_delta),
             next_cols.from_state.timestamp,
         );
+
+        if let Some(input_commit) = &self.input_commit {
+            let io = InputCommitIo {
+                data: local_cols.data.map(Into::into).to_vec(),
+                is_valid,
+                next_is_valid: next_cols.is_single + next_cols.is_buffer,
+                timestamp: timestamp.into(),
+                next_timestamp: next_cols.from_state.timestamp.into(),
+            };
+            input_commit.eval(
+                builder,
+                (io, local[width..].borrow(), next[width..].borrow()),
+            );
+        }
     }
 }
 
@@ -240,6 +273,7 @@ pub struct Rv32HintStoreRecord<F: Field> {
 
     pub num_words_read: Option<RecordId>,
     pub hints: Vec<([F; RV32_REGISTER_NUM_LIMBS], RecordId)>,
+    pub input_commit: Option<InputCommitRecord<F>>,
 }
 
 pub struct Rv32HintStoreChip<F: Field> {
@@ -249,6 +283,8 @@ pub struct Rv32HintStoreChip<F: Field> {
     offline_memory: Arc<Mutex<OfflineMemory<F>>>,
     pub streams: OnceLock<Arc<Mutex<Streams<F>>>>,
     bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+    input_commit_hasher: Option<Poseidon2Hasher<F>>,
+    range_checker_chip: Option<SharedVariableRangeCheckerChip>,
 }
 
 impl<F: PrimeField32> Rv32HintStoreChip<F> {
@@ -265,6 +301,7 @@ impl<F: PrimeField32> Rv32HintStoreChip<F> {
             memory_bridge,
             bitwise_operation_lookup_bus: bitwise_lookup_chip.bus(),
             offset,
+            input_commit: None,
         };
         Self {
             records: vec![],
@@ -273,11 +310,24 @@ impl<F: PrimeField32> Rv32HintStoreChip<F> {
             offline_memory,
             streams: OnceLock::new(),
             bitwise_lookup_chip,
+            input_commit_hasher: None,
+            range_checker_chip: None,
         }
     }
     pub fn set_streams(&mut self, streams: Arc<Mutex<Streams<F>>>) {
         self.streams.set(streams).unwrap();
     }
+    /// Commits to the words from the input stream, see
+    /// [input_commitment](openvm_circuit::system::input_commitment).
+    pub fn set_input_commit_air(
+        &mut self,
+        input_commit_air: InputCommitSubAir,
+        range_checker_chip: SharedVariableRangeCheckerChip,
+    ) {
+        self.air.input_commit = Some(input_commit_air);
+        self.input_commit_hasher = Some(vm_poseidon2_hasher());
+        self.range_checker_chip = Some(range_checker_chip);
+    }
 }
 
 impl<F: PrimeField32> InstructionExecutor<F> for Rv32HintStoreChip<F> {
@@ -325,6 +375,10 @@ impl<F: PrimeField32> InstructionExecutor<F> for Rv32HintStoreChip<F> {
             num_words,
             num_words_read,
             hints: vec![],
+            input_commit: self
+                .input_commit_hasher
+                .as_ref()
+                .map(|_| InputCommitRecord::new(streams.input_commitment.digest)),
         };
 
         for word_index in 0..num_words {
@@ -335,6 +389,15 @@ impl<F: PrimeField32> InstructionExecutor<F> for Rv32HintStoreChip<F> {
 
             let data: [F; RV32_REGISTER_NUM_LIMBS] =
                 std::array::from_fn(|_| streams.hint_stream.pop_front().unwrap());
+            if let (Some(hasher), Some(input_commit)) =
+                (&self.input_commit_hasher, &mut record.input_commit)
+            {
+                let digest =
+                    streams
+                        .input_commitment
+                        .absorb(hasher, &data, !memory.is_execute_only());
+                input_commit.digests.push(digest);
+            }
             let (write, _) = memory.write(
                 e,
                 F::from_canonical_u32(mem_ptr + (RV32_REGISTER_NUM_LIMBS as u32 * word_index)),
@@ -343,8 +406,10 @@ impl<F: PrimeField32> InstructionExecutor<F> for Rv32HintStoreChip<F> {
             record.hints.push((data, write));
         }
 
-        self.height += record.hints.len();
-        self.records.push(record);
+        if !memory.is_execute_only() {
+            self.height += record.hints.len();
+            self.records.push(record);
+        }
 
         let next_state = ExecutionState {
             pc: from_state.pc + DEFAULT_PC_STEP,
@@ -374,7 +439,7 @@ impl<F: Field> ChipUsageGetter for Rv32HintStoreChip<F> {
     }
 
     fn trace_width(&self) -> usize {
-        Rv32HintStoreCols::<F>::width()
+        BaseAir::<F>::width(&self.air)
     }
 }
 
@@ -384,6 +449,7 @@ impl<F: PrimeField32> Rv32HintStoreChip<F> {
         record: Rv32HintStoreRecord<F>,
         aux_cols_factory: &MemoryAuxColsFactory<F>,
         slice: &mut [F],
+        row_width: usize,
         memory: &OfflineMemory<F>,
         bitwise_lookup_chip: &SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
     ) -> usize {
@@ -425,7 +491,11 @@ impl<F: PrimeField32> Rv32HintStoreChip<F> {
                 );
             }
 
-            let cols: &mut Rv32HintStoreCols<F> = slice[used_u32s..used_u32s + width].borrow_mut();
+            let row = &mut slice[used_u32s..used_u32s + row_width];
+            if let Some(input_commit) = &record.input_commit {
+                input_commit.fill_row(i, row[width..].borrow_mut());
+            }
+            let cols: &mut Rv32HintStoreCols<F> = row[..width].borrow_mut();
             cols.from_state.timestamp =
                 F::from_canonical_u32(record.from_state.timestamp + (3 * i as u32));
             cols.data = data;
@@ -435,7 +505,7 @@ impl<F: PrimeField32> Rv32HintStoreChip<F> {
             if i != 0 {
                 cols.is_buffer = F::ONE;
             }
-            used_u32s += width;
+            used_u32s += row_width;
             mem_ptr += RV32_REGISTER_NUM_LIMBS as u32;
             rem_words -= 1;
         }
@@ -458,11 +528,24 @@ impl<F: PrimeField32> Rv32HintStoreChip<F> {
                 record,
                 &aux_cols_factory,
                 &mut flat_trace[used_u32s..],
+                width,
                 &memory,
                 &self.bitwise_lookup_chip,
             );
         }
-        // padding rows can just be all zeros
+        // padding rows can just be all zeros, except for the input commitment
+        if let (Some(input_commit), Some(range_checker_chip)) =
+            (&self.air.input_commit, &self.range_checker_chip)
+        {
+            input_commit.finalize_trace(
+                range_checker_chip.as_ref(),
+                &mut flat_trace,
+                width,
+                Rv32HintStoreCols::<F>::width(),
+                TIMESTAMP_COL,
+                self.height,
+            );
+        }
         RowMajorMatrix::new(flat_trace, width)
     }
 }
@@ -478,3 +561,28 @@ where
         AirProofInput::simple_no_pis(self.generate_trace())
     }
 }
+
+/// Returns the hint stream of an input read by the guest: its length as 4 little-endian bytes,
+/// followed by the input padded with zeros to a multiple of 4 elements.
+pub fn rv32_input_hint<F: Field>(input: &[F]) -> Vec<F> {
+    let len_bytes = (input.len() as u32).to_le_bytes();
+    let mut hint: Vec<F> = len_bytes.iter().map(|b| F::from_canonical_u8(*b)).collect();
+    hint.extend_from_slice(input);
+    hint.resize(
+        RV32_REGISTER_NUM_LIMBS + input.len().div_ceil(4) * 4,
+        F::ZERO,
+    );
+    hint
+}
+
+/// Returns the input commitment of a guest that reads all of `inputs` in order, see
+/// [input_commitment](openvm_circuit::system::input_commitment).
+pub fn rv32_input_commitment<F: PrimeField32, I: AsRef<[F]>>(
+    inputs: impl IntoIterator<Item = I>,
+) -> [F; CHUNK] {
+    let hints: Vec<F> = inputs
+        .into_iter()
+        .flat_map(|input| rv32_input_hint(input.as_ref()))
+        .collect();
+    compute_input_commitment(hints.chunks_exact(RV32_REGISTER_NUM_LIMBS))
+}
@@ -1,15 +1,27 @@
 use std::{
     array,
     borrow::BorrowMut,
+    iter,
     sync::{Arc, Mutex},
 };
 
-use openvm_circuit::arch::{
-    testing::{memory::gen_pointer, VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
-    Streams,
+use openvm_circuit::{
+    arch::{
+        hasher::HasherChip,
+        testing::{
+            memory::gen_pointer, VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS, POSEIDON2_DIRECT_BUS,
+        },
+        vm_poseidon2_config, Streams,
+    },
+    system::{
+        input_commitment::{InputCommitBus, InputCommitCols, InputCommitSubAir},
+        memory::{merkle::DirectCompressionBus, CHUNK},
+        poseidon2::Poseidon2PeripheryChip,
+    },
 };
-use openvm_circuit_primitives::bitwise_op_lookup::{
-    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+use openvm_circuit_primitives::{
+    assert_less_than::AssertLtSubAir,
+    bitwise_op_lookup::{BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip},
 };
 use openvm_instructions::{
     instruction::Instruction,
@@ -23,16 +35,26 @@ use openvm_stark_backend::{
         dense::{DenseMatrix, RowMajorMatrix},
         Matrix,
     },
+    prover::types::AirProofInput,
     utils::disable_debug_builder,
     verifier::VerificationError,
+    AirRef,
+};
+use openvm_stark_sdk::{
+    config::{baby_bear_blake3::BabyBearBlake3Config, setup_tracing},
+    dummy_airs::interaction::dummy_interaction_air::DummyInteractionAir,
+    p3_baby_bear::BabyBear,
+    utils::create_seeded_rng,
 };
-use openvm_stark_sdk::{config::setup_tracing, p3_baby_bear::BabyBear, utils::create_seeded_rng};
 use rand::{rngs::StdRng, Rng};
 
 use super::{Rv32HintStoreChip, Rv32HintStoreCols};
 use crate::adapters::decompose;
 
 type F = BabyBear;
+type SC = BabyBearBlake3Config;
+
+const INPUT_COMMIT_BUS: usize = 13;
 
 fn set_and_execute(
     tester: &mut VmChipTestBuilder<F>,
@@ -125,6 +147,38 @@ fn set_and_execute_buffer(
     }
 }
 
+fn setup_input_commit(
+    tester: &VmChipTestBuilder<F>,
+    chip: &mut Rv32HintStoreChip<F>,
+) -> Poseidon2PeripheryChip<F> {
+    let range_checker_chip = tester.range_checker();
+    let clk_max_bits = tester
+        .memory_controller()
+        .borrow()
+        .mem_config()
+        .clk_max_bits;
+    let input_commit_air = InputCommitSubAir {
+        bus: InputCommitBus(INPUT_COMMIT_BUS),
+        compression_bus: DirectCompressionBus(POSEIDON2_DIRECT_BUS),
+        timestamp_lt: AssertLtSubAir::new(range_checker_chip.bus(), clk_max_bits),
+    };
+    chip.set_input_commit_air(input_commit_air, range_checker_chip);
+    Poseidon2PeripheryChip::new(vm_poseidon2_config(), POSEIDON2_DIRECT_BUS, 3)
+}
+
+/// Balances the input commitment of a segment from `prev_digest` to `digest` on the
+/// [InputCommitBus], in place of the connector chip.
+fn input_commit_boundaries(
+    prev_digest: [F; CHUNK],
+    digest: [F; CHUNK],
+) -> [(AirRef<SC>, AirProofInput<SC>); 2] {
+    [(prev_digest, true), (digest, false)].map(|(digest, is_send)| {
+        let air: AirRef<SC> = Arc::new(DummyInteractionAir::new(CHUNK, is_send, INPUT_COMMIT_BUS));
+        let trace = RowMajorMatrix::new(iter::once(F::ONE).chain(digest).collect(), CHUNK + 1);
+        (air, AirProofInput::simple_no_pis(trace))
+    })
+}
+
 ///////////////////////////////////////////////////////////////////////////////////////
 /// POSITIVE TESTS
 ///
@@ -166,6 +220,53 @@ fn rand_hintstore_test() {
     tester.simple_test().expect("Verification failed");
 }
 
+#[test]
+fn rand_hintstore_input_commit_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+
+    let mut chip = Rv32HintStoreChip::<F>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        bitwise_chip.clone(),
+        tester.memory_bridge(),
+        tester.offline_memory_mutex_arc(),
+        0,
+    );
+    chip.set_streams(Arc::new(Mutex::new(Streams::default())));
+    let mut poseidon2_chip = setup_input_commit(&tester, &mut chip);
+
+    let num_tests: usize = 8;
+    for _ in 0..num_tests {
+        if rng.gen_bool(0.5) {
+            set_and_execute(&mut tester, &mut chip, &mut rng, HINT_STOREW);
+        } else {
+            set_and_execute_buffer(&mut tester, &mut chip, &mut rng, HINT_BUFFER);
+        }
+    }
+
+    let digest = {
+        let mut streams = chip.streams.get().unwrap().lock().unwrap();
+        for (lhs, rhs) in streams.input_commitment.take_pending() {
+            poseidon2_chip.compress_and_record(&lhs, &rhs);
+        }
+        streams.input_commitment.digest
+    };
+    let [initial, last] = input_commit_boundaries([F::ZERO; CHUNK], digest);
+    let tester = tester
+        .build()
+        .load(chip)
+        .load(bitwise_chip)
+        .load(poseidon2_chip)
+        .load_air_proof_input(initial)
+        .load_air_proof_input(last)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
 //////////////////////////////////////////////////////////////////////////////////////
 // NEGATIVE TESTS
 //
@@ -227,6 +328,76 @@ fn negative_hintstore_tests() {
         VerificationError::ChallengePhaseError,
     );
 }
+
+// Swaps the rows of two instructions and rechains the input commitment in the swapped order, so
+// that only the order of the timestamps is wrong.
+#[test]
+fn negative_hintstore_swapped_input_commit_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+
+    let mut chip = Rv32HintStoreChip::<F>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        bitwise_chip.clone(),
+        tester.memory_bridge(),
+        tester.offline_memory_mutex_arc(),
+        0,
+    );
+    chip.set_streams(Arc::new(Mutex::new(Streams::default())));
+    let mut poseidon2_chip = setup_input_commit(&tester, &mut chip);
+
+    set_and_execute(&mut tester, &mut chip, &mut rng, HINT_STOREW);
+    set_and_execute(&mut tester, &mut chip, &mut rng, HINT_STOREW);
+
+    let pending = chip
+        .streams
+        .get()
+        .unwrap()
+        .lock()
+        .unwrap()
+        .input_commitment
+        .take_pending();
+    let [(_, first_word), (_, second_word)]: [_; 2] = pending.try_into().unwrap();
+    let digests = {
+        let digest = poseidon2_chip.compress_and_record(&[F::ZERO; CHUNK], &second_word);
+        [
+            ([F::ZERO; CHUNK], digest),
+            (
+                digest,
+                poseidon2_chip.compress_and_record(&digest, &first_word),
+            ),
+        ]
+    };
+
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let width = Rv32HintStoreCols::<F>::width();
+        let mut rows: Vec<Vec<F>> = (0..2).map(|i| trace.row_slice(i).to_vec()).collect();
+        let (first, second) = rows.split_at_mut(1);
+        first[0][..width].swap_with_slice(&mut second[0][..width]);
+        for (row, (prev_digest, digest)) in rows.iter_mut().zip(digests) {
+            let cols: &mut InputCommitCols<F> = row[width..].borrow_mut();
+            cols.prev_digest = prev_digest;
+            cols.digest = digest;
+        }
+        *trace = RowMajorMatrix::new(rows.concat(), trace.width());
+    };
+
+    let [initial, last] = input_commit_boundaries([F::ZERO; CHUNK], digests[1].1);
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_prank_trace(chip, modify_trace)
+        .load(bitwise_chip)
+        .load(poseidon2_chip)
+        .load_air_proof_input(initial)
+        .load_air_proof_input(last)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::OodEvaluationMismatch);
+}
 ///////////////////////////////////////////////////////////////////////////////////////
 /// SANITY TESTS
 ///
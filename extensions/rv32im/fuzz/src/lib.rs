@@ -0,0 +1,161 @@
+//! Differential fuzzing of RV32IM execution.
+//!
+//! [fuzz_program] generates random RV32IM programs together with their initial registers and
+//! data memory. Each program is encoded to RISC-V machine code, transpiled with the RV32IM
+//! [Transpiler] extensions and executed by [VmExecutor], and the final registers and memory are
+//! compared to those of an independent [reference] emulator. [FuzzProver] additionally proves
+//! the execution, to catch trace generation bugs that do not affect execution.
+//!
+//! The property tests live in `tests/`. The number of cases of the execution test can be raised
+//! with the `PROPTEST_CASES` environment variable, and failing cases are shrunk to a minimal
+//! program by proptest.
+
+use eyre::{eyre, Result};
+use openvm_circuit::arch::{VirtualMachine, VmExecutor, VmMemoryState};
+use openvm_instructions::{
+    exe::VmExe,
+    program::{Program, DEFAULT_PC_STEP},
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS},
+};
+use openvm_rv32im_circuit::Rv32ImConfig;
+use openvm_rv32im_transpiler::{
+    Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
+};
+use openvm_stark_backend::{keygen::types::MultiStarkProvingKey, p3_field::PrimeField32};
+use openvm_stark_sdk::{
+    config::{
+        baby_bear_poseidon2::{BabyBearPoseidon2Config, BabyBearPoseidon2Engine},
+        FriParameters,
+    },
+    engine::StarkFriEngine,
+    p3_baby_bear::BabyBear,
+};
+use openvm_transpiler::{
+    elf::ELF_DEFAULT_MAX_NUM_PUBLIC_VALUES, transpiler::Transpiler,
+    util::elf_memory_image_to_openvm_memory_image,
+};
+
+mod program;
+pub mod reference;
+
+pub use program::*;
+
+type F = BabyBear;
+
+/// Registers and data window contents at termination.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MachineState {
+    pub regs: [u32; 32],
+    pub memory: Vec<u8>,
+}
+
+impl MachineState {
+    fn from_memory(memory: &VmMemoryState<F>, program: &FuzzProgram) -> Self {
+        let byte = |address_space, ptr| {
+            memory
+                .get(&(address_space, ptr))
+                .map_or(0, |byte| byte.as_canonical_u32() as u8)
+        };
+        let regs = std::array::from_fn(|i| {
+            let ptr = (i * RV32_REGISTER_NUM_LIMBS) as u32;
+            u32::from_le_bytes(std::array::from_fn(|j| {
+                byte(RV32_REGISTER_AS, ptr + j as u32)
+            }))
+        });
+        let start = program.data_start();
+        Self {
+            regs,
+            memory: (0..DATA_WINDOW)
+                .map(|i| byte(RV32_MEMORY_AS, start + i))
+                .collect(),
+        }
+    }
+}
+
+/// Transpiles `program` into an executable that starts at [PC_BASE].
+pub fn transpile(program: &FuzzProgram) -> Result<VmExe<F>> {
+    let transpiler = Transpiler::<F>::default()
+        .with_extension(Rv32ITranspilerExtension)
+        .with_extension(Rv32MTranspilerExtension)
+        .with_extension(Rv32IoTranspilerExtension);
+    let instructions = transpiler.transpile(&program.encode())?;
+    Ok(VmExe {
+        program: Program::new_without_debug_infos_with_option(
+            &instructions,
+            DEFAULT_PC_STEP,
+            PC_BASE,
+            ELF_DEFAULT_MAX_NUM_PUBLIC_VALUES,
+        ),
+        pc_start: PC_BASE,
+        init_memory: elf_memory_image_to_openvm_memory_image(program.memory_image()),
+        fn_bounds: Default::default(),
+    })
+}
+
+/// Runs `program` on the reference emulator. Returns the final state and the number of
+/// instructions executed, excluding the final `TERMINATE`.
+pub fn execute_reference(program: &FuzzProgram) -> (MachineState, u64) {
+    reference::execute(
+        &program.encode(),
+        PC_BASE,
+        &program.memory_image(),
+        program.data_start(),
+        DATA_WINDOW,
+    )
+}
+
+/// Runs `program` with [VmExecutor]. Returns the final state and the number of instructions
+/// executed, excluding the final `TERMINATE`.
+pub fn execute_openvm(program: &FuzzProgram) -> Result<(MachineState, u64)> {
+    let exe = transpile(program)?;
+    let result = VmExecutor::<F, _>::new(Rv32ImConfig::default()).execute_only(exe, vec![])?;
+    Ok((
+        MachineState::from_memory(&result.final_memory, program),
+        result.num_instructions,
+    ))
+}
+
+/// Proves executions of [Rv32ImConfig] with FRI parameters that are fast but insecure, reusing
+/// one proving key for all programs.
+pub struct FuzzProver {
+    vm: VirtualMachine<BabyBearPoseidon2Config, BabyBearPoseidon2Engine, Rv32ImConfig>,
+    pk: MultiStarkProvingKey<BabyBearPoseidon2Config>,
+}
+
+impl FuzzProver {
+    pub fn new() -> Self {
+        let config = Rv32ImConfig::default();
+        let mut log_blowup = 1;
+        while config.system.max_constraint_degree > (1 << log_blowup) + 1 {
+            log_blowup += 1;
+        }
+        let engine = BabyBearPoseidon2Engine::new(FriParameters {
+            log_blowup,
+            log_final_poly_len: 0,
+            num_queries: 2,
+            proof_of_work_bits: 0,
+        });
+        let vm = VirtualMachine::new(engine, config);
+        let pk = vm.keygen();
+        Self { vm, pk }
+    }
+
+    /// Executes, proves and verifies `program`, and returns the final state.
+    pub fn prove(&self, program: &FuzzProgram) -> Result<MachineState> {
+        let exe = transpile(program)?;
+        let mut result = self.vm.execute_and_generate(exe, vec![])?;
+        let final_memory = result
+            .final_memory
+            .take()
+            .ok_or_else(|| eyre!("final memory is only kept with continuations enabled"))?;
+        let proofs = self.vm.prove(&self.pk, result.per_segment);
+        self.vm.verify(&self.pk.get_vk(), proofs)?;
+        Ok(MachineState::from_memory(&final_memory, program))
+    }
+}
+
+impl Default for FuzzProver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
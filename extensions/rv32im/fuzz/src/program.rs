@@ -0,0 +1,506 @@
+use std::collections::BTreeMap;
+
+use proptest::{prelude::*, sample::select};
+
+/// Address of the first instruction.
+pub const PC_BASE: u32 = 0x0020_0000;
+/// Register holding the base address of every load and store. Generated instructions never
+/// write it, so that all memory accesses stay within the data window.
+pub const BASE_REG: usize = 2;
+/// Size in bytes of the data window `[base - DATA_WINDOW / 2, base + DATA_WINDOW / 2)`, which
+/// covers every address reachable with a 12-bit offset from the base register.
+pub const DATA_WINDOW: u32 = 1 << 12;
+/// Maximum number of instructions skipped by a jump or taken branch. Control flow only goes
+/// forward, so every program terminates.
+const MAX_SKIP: usize = 15;
+
+const OPCODE_LUI: u32 = 0b0110111;
+const OPCODE_AUIPC: u32 = 0b0010111;
+const OPCODE_JAL: u32 = 0b1101111;
+const OPCODE_JALR: u32 = 0b1100111;
+const OPCODE_BRANCH: u32 = 0b1100011;
+const OPCODE_LOAD: u32 = 0b0000011;
+const OPCODE_STORE: u32 = 0b0100011;
+const OPCODE_ALU_IMM: u32 = 0b0010011;
+const OPCODE_ALU: u32 = 0b0110011;
+/// `custom-0` with funct3 0, i.e. `TERMINATE` with exit code 0.
+const TERMINATE: u32 = 0b0001011;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BranchOp {
+    Beq,
+    Bne,
+    Blt,
+    Bge,
+    Bltu,
+    Bgeu,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoadOp {
+    Lb,
+    Lh,
+    Lw,
+    Lbu,
+    Lhu,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StoreOp {
+    Sb,
+    Sh,
+    Sw,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AluImmOp {
+    Addi,
+    Slti,
+    Sltiu,
+    Xori,
+    Ori,
+    Andi,
+    Slli,
+    Srli,
+    Srai,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AluOp {
+    Add,
+    Sub,
+    Sll,
+    Slt,
+    Sltu,
+    Xor,
+    Srl,
+    Sra,
+    Or,
+    And,
+    Mul,
+    Mulh,
+    Mulhsu,
+    Mulhu,
+    Div,
+    Divu,
+    Rem,
+    Remu,
+}
+
+impl BranchOp {
+    const ALL: [Self; 6] = [
+        Self::Beq,
+        Self::Bne,
+        Self::Blt,
+        Self::Bge,
+        Self::Bltu,
+        Self::Bgeu,
+    ];
+
+    fn funct3(self) -> u32 {
+        match self {
+            Self::Beq => 0b000,
+            Self::Bne => 0b001,
+            Self::Blt => 0b100,
+            Self::Bge => 0b101,
+            Self::Bltu => 0b110,
+            Self::Bgeu => 0b111,
+        }
+    }
+}
+
+impl LoadOp {
+    const ALL: [Self; 5] = [Self::Lb, Self::Lh, Self::Lw, Self::Lbu, Self::Lhu];
+
+    fn funct3(self) -> u32 {
+        match self {
+            Self::Lb => 0b000,
+            Self::Lh => 0b001,
+            Self::Lw => 0b010,
+            Self::Lbu => 0b100,
+            Self::Lhu => 0b101,
+        }
+    }
+
+    fn width(self) -> i32 {
+        match self {
+            Self::Lb | Self::Lbu => 1,
+            Self::Lh | Self::Lhu => 2,
+            Self::Lw => 4,
+        }
+    }
+}
+
+impl StoreOp {
+    const ALL: [Self; 3] = [Self::Sb, Self::Sh, Self::Sw];
+
+    fn funct3(self) -> u32 {
+        match self {
+            Self::Sb => 0b000,
+            Self::Sh => 0b001,
+            Self::Sw => 0b010,
+        }
+    }
+
+    fn width(self) -> i32 {
+        1 << self.funct3()
+    }
+}
+
+impl AluImmOp {
+    const ALL: [Self; 9] = [
+        Self::Addi,
+        Self::Slti,
+        Self::Sltiu,
+        Self::Xori,
+        Self::Ori,
+        Self::Andi,
+        Self::Slli,
+        Self::Srli,
+        Self::Srai,
+    ];
+
+    /// Returns `funct3` and, for shifts, `funct7`.
+    fn funct(self) -> (u32, Option<u32>) {
+        match self {
+            Self::Addi => (0b000, None),
+            Self::Slti => (0b010, None),
+            Self::Sltiu => (0b011, None),
+            Self::Xori => (0b100, None),
+            Self::Ori => (0b110, None),
+            Self::Andi => (0b111, None),
+            Self::Slli => (0b001, Some(0)),
+            Self::Srli => (0b101, Some(0)),
+            Self::Srai => (0b101, Some(0b0100000)),
+        }
+    }
+}
+
+impl AluOp {
+    const ALL: [Self; 18] = [
+        Self::Add,
+        Self::Sub,
+        Self::Sll,
+        Self::Slt,
+        Self::Sltu,
+        Self::Xor,
+        Self::Srl,
+        Self::Sra,
+        Self::Or,
+        Self::And,
+        Self::Mul,
+        Self::Mulh,
+        Self::Mulhsu,
+        Self::Mulhu,
+        Self::Div,
+        Self::Divu,
+        Self::Rem,
+        Self::Remu,
+    ];
+
+    /// Returns `funct3` and `funct7`.
+    fn funct(self) -> (u32, u32) {
+        match self {
+            Self::Add => (0b000, 0),
+            Self::Sub => (0b000, 0b0100000),
+            Self::Sll => (0b001, 0),
+            Self::Slt => (0b010, 0),
+            Self::Sltu => (0b011, 0),
+            Self::Xor => (0b100, 0),
+            Self::Srl => (0b101, 0),
+            Self::Sra => (0b101, 0b0100000),
+            Self::Or => (0b110, 0),
+            Self::And => (0b111, 0),
+            Self::Mul => (0b000, 1),
+            Self::Mulh => (0b001, 1),
+            Self::Mulhsu => (0b010, 1),
+            Self::Mulhu => (0b011, 1),
+            Self::Div => (0b100, 1),
+            Self::Divu => (0b101, 1),
+            Self::Rem => (0b110, 1),
+            Self::Remu => (0b111, 1),
+        }
+    }
+}
+
+/// A generated instruction. Jump and branch targets are given as a number of following
+/// instructions to skip, and loads and stores address memory relative to [BASE_REG].
+#[derive(Clone, Copy, Debug)]
+pub enum Insn {
+    Lui {
+        rd: usize,
+        imm: u32,
+    },
+    Auipc {
+        rd: usize,
+        imm: u32,
+    },
+    Jal {
+        rd: usize,
+        skip: usize,
+    },
+    /// `auipc rt, 0` followed by `jalr rd, rt, offset`.
+    Jalr {
+        rd: usize,
+        rt: usize,
+        skip: usize,
+    },
+    Branch {
+        op: BranchOp,
+        rs1: usize,
+        rs2: usize,
+        skip: usize,
+    },
+    Load {
+        op: LoadOp,
+        rd: usize,
+        offset: i32,
+    },
+    Store {
+        op: StoreOp,
+        rs2: usize,
+        offset: i32,
+    },
+    AluImm {
+        op: AluImmOp,
+        rd: usize,
+        rs1: usize,
+        imm: i32,
+    },
+    Alu {
+        op: AluOp,
+        rd: usize,
+        rs1: usize,
+        rs2: usize,
+    },
+}
+
+impl Insn {
+    /// Number of encoded words.
+    fn len(&self) -> usize {
+        match self {
+            Self::Jalr { .. } => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// A random RV32IM program with its initial registers and data memory.
+#[derive(Clone, Debug)]
+pub struct FuzzProgram {
+    /// Initial values of `x1..x31`, set by a prologue. `regs[0]` is ignored.
+    pub regs: [u32; 32],
+    /// Initial bytes of the data window.
+    pub memory: Vec<u8>,
+    pub insns: Vec<Insn>,
+}
+
+impl FuzzProgram {
+    /// Lowest address of the data window.
+    pub fn data_start(&self) -> u32 {
+        self.regs[BASE_REG] - DATA_WINDOW / 2
+    }
+
+    /// Initial data memory as aligned words.
+    pub fn memory_image(&self) -> BTreeMap<u32, u32> {
+        let start = self.data_start();
+        self.memory
+            .chunks_exact(4)
+            .enumerate()
+            .map(|(i, word)| {
+                (
+                    start + 4 * i as u32,
+                    u32::from_le_bytes(word.try_into().unwrap()),
+                )
+            })
+            .collect()
+    }
+
+    /// Encodes the program as RISC-V words to be placed at [PC_BASE]: a prologue that sets the
+    /// initial registers, the generated instructions and a final `TERMINATE`.
+    pub fn encode(&self) -> Vec<u32> {
+        let mut words = vec![];
+        for (rd, &value) in self.regs.iter().enumerate().skip(1) {
+            let hi = value.wrapping_add(0x800) >> 12;
+            let lo = value.wrapping_sub(hi << 12) as i32;
+            words.push(u_type(OPCODE_LUI, rd, hi));
+            words.push(i_type(OPCODE_ALU_IMM, 0b000, rd, rd, lo));
+        }
+
+        // Word index of each instruction, and of the final `TERMINATE`.
+        let mut starts = Vec::with_capacity(self.insns.len() + 1);
+        let mut idx = words.len();
+        for insn in &self.insns {
+            starts.push(idx);
+            idx += insn.len();
+        }
+        starts.push(idx);
+        let offset_to = |from: usize, skip: usize| {
+            let to = starts[(from + 1 + skip).min(self.insns.len())];
+            4 * (to as i32 - starts[from] as i32)
+        };
+
+        for (i, &insn) in self.insns.iter().enumerate() {
+            match insn {
+                Insn::Lui { rd, imm } => words.push(u_type(OPCODE_LUI, rd, imm)),
+                Insn::Auipc { rd, imm } => words.push(u_type(OPCODE_AUIPC, rd, imm)),
+                Insn::Jal { rd, skip } => words.push(j_type(rd, offset_to(i, skip))),
+                Insn::Jalr { rd, rt, skip } => {
+                    words.push(u_type(OPCODE_AUIPC, rt, 0));
+                    words.push(i_type(OPCODE_JALR, 0b000, rd, rt, offset_to(i, skip)));
+                }
+                Insn::Branch { op, rs1, rs2, skip } => {
+                    words.push(b_type(op.funct3(), rs1, rs2, offset_to(i, skip)))
+                }
+                Insn::Load { op, rd, offset } => {
+                    words.push(i_type(OPCODE_LOAD, op.funct3(), rd, BASE_REG, offset))
+                }
+                Insn::Store { op, rs2, offset } => {
+                    words.push(s_type(op.funct3(), BASE_REG, rs2, offset))
+                }
+                Insn::AluImm { op, rd, rs1, imm } => {
+                    let imm = match op.funct() {
+                        (_, Some(funct7)) => (funct7 << 5) as i32 | (imm & 0x1f),
+                        (_, None) => imm,
+                    };
+                    words.push(i_type(OPCODE_ALU_IMM, op.funct().0, rd, rs1, imm));
+                }
+                Insn::Alu { op, rd, rs1, rs2 } => {
+                    let (funct3, funct7) = op.funct();
+                    words.push(r_type(funct3, funct7, rd, rs1, rs2));
+                }
+            }
+        }
+        words.push(TERMINATE);
+        words
+    }
+}
+
+fn r_type(funct3: u32, funct7: u32, rd: usize, rs1: usize, rs2: usize) -> u32 {
+    (funct7 << 25)
+        | ((rs2 as u32) << 20)
+        | ((rs1 as u32) << 15)
+        | (funct3 << 12)
+        | ((rd as u32) << 7)
+        | OPCODE_ALU
+}
+
+fn i_type(opcode: u32, funct3: u32, rd: usize, rs1: usize, imm: i32) -> u32 {
+    ((imm as u32 & 0xfff) << 20)
+        | ((rs1 as u32) << 15)
+        | (funct3 << 12)
+        | ((rd as u32) << 7)
+        | opcode
+}
+
+fn s_type(funct3: u32, rs1: usize, rs2: usize, imm: i32) -> u32 {
+    let imm = imm as u32;
+    (((imm >> 5) & 0x7f) << 25)
+        | ((rs2 as u32) << 20)
+        | ((rs1 as u32) << 15)
+        | (funct3 << 12)
+        | ((imm & 0x1f) << 7)
+        | OPCODE_STORE
+}
+
+fn b_type(funct3: u32, rs1: usize, rs2: usize, offset: i32) -> u32 {
+    let imm = offset as u32;
+    (((imm >> 12) & 1) << 31)
+        | (((imm >> 5) & 0x3f) << 25)
+        | ((rs2 as u32) << 20)
+        | ((rs1 as u32) << 15)
+        | (funct3 << 12)
+        | (((imm >> 1) & 0xf) << 8)
+        | (((imm >> 11) & 1) << 7)
+        | OPCODE_BRANCH
+}
+
+fn u_type(opcode: u32, rd: usize, imm: u32) -> u32 {
+    ((imm & 0xfffff) << 12) | ((rd as u32) << 7) | opcode
+}
+
+fn j_type(rd: usize, offset: i32) -> u32 {
+    let imm = offset as u32;
+    (((imm >> 20) & 1) << 31)
+        | (((imm >> 1) & 0x3ff) << 21)
+        | (((imm >> 11) & 1) << 20)
+        | (((imm >> 12) & 0xff) << 12)
+        | ((rd as u32) << 7)
+        | OPCODE_JAL
+}
+
+/// Any register.
+fn reg() -> impl Strategy<Value = usize> {
+    0..32usize
+}
+
+/// A register that generated instructions may write, including `x0`.
+fn dest_reg() -> impl Strategy<Value = usize> {
+    reg().prop_filter("base register is reserved", |&r| r != BASE_REG)
+}
+
+/// A 32-bit value that is often at the edge of a signed or unsigned range.
+fn value() -> impl Strategy<Value = u32> {
+    prop_oneof![
+        3 => any::<u32>(),
+        1 => select(vec![0, 1, 2, 31, 32, 0x7fff_ffff, 0x8000_0000, u32::MAX, u32::MAX - 1]),
+    ]
+}
+
+fn imm12() -> impl Strategy<Value = i32> {
+    prop_oneof![
+        3 => -2048..2048i32,
+        1 => select(vec![0, 1, -1, 31, 2047, -2048]),
+    ]
+}
+
+/// An offset from the base register that keeps an access of `width` bytes aligned.
+fn offset(width: i32) -> impl Strategy<Value = i32> {
+    (-2048..2048i32).prop_map(move |offset| offset & !(width - 1))
+}
+
+fn skip() -> impl Strategy<Value = usize> {
+    0..=MAX_SKIP
+}
+
+pub fn insn() -> impl Strategy<Value = Insn> {
+    prop_oneof![
+        1 => (dest_reg(), 0..1u32 << 20).prop_map(|(rd, imm)| Insn::Lui { rd, imm }),
+        1 => (dest_reg(), 0..1u32 << 20).prop_map(|(rd, imm)| Insn::Auipc { rd, imm }),
+        1 => (dest_reg(), skip()).prop_map(|(rd, skip)| Insn::Jal { rd, skip }),
+        1 => (dest_reg(), dest_reg().prop_filter("jalr base must not be x0", |&r| r != 0), skip())
+            .prop_map(|(rd, rt, skip)| Insn::Jalr { rd, rt, skip }),
+        3 => (select(BranchOp::ALL.to_vec()), reg(), reg(), skip())
+            .prop_map(|(op, rs1, rs2, skip)| Insn::Branch { op, rs1, rs2, skip }),
+        3 => select(LoadOp::ALL.to_vec())
+            .prop_flat_map(|op| (Just(op), dest_reg(), offset(op.width())))
+            .prop_map(|(op, rd, offset)| Insn::Load { op, rd, offset }),
+        3 => select(StoreOp::ALL.to_vec())
+            .prop_flat_map(|op| (Just(op), reg(), offset(op.width())))
+            .prop_map(|(op, rs2, offset)| Insn::Store { op, rs2, offset }),
+        4 => (select(AluImmOp::ALL.to_vec()), dest_reg(), reg(), imm12())
+            .prop_map(|(op, rd, rs1, imm)| Insn::AluImm { op, rd, rs1, imm }),
+        6 => (select(AluOp::ALL.to_vec()), dest_reg(), reg(), reg())
+            .prop_map(|(op, rd, rs1, rs2)| Insn::Alu { op, rd, rs1, rs2 }),
+    ]
+}
+
+/// Random programs of up to `max_len` instructions.
+pub fn fuzz_program(max_len: usize) -> impl Strategy<Value = FuzzProgram> {
+    (
+        proptest::array::uniform32(value()),
+        // Word-aligned, and far enough from zero that the data window does not wrap.
+        (DATA_WINDOW..1 << 24).prop_map(|base| base & !3),
+        proptest::collection::vec(any::<u8>(), DATA_WINDOW as usize),
+        proptest::collection::vec(insn(), 1..=max_len),
+    )
+        .prop_map(|(mut regs, base, memory, insns)| {
+            regs[0] = 0;
+            regs[BASE_REG] = base;
+            FuzzProgram {
+                regs,
+                memory,
+                insns,
+            }
+        })
+}
@@ -0,0 +1,213 @@
+//! A straightforward RV32IM emulator, written from the RISC-V specification independently of
+//! the transpiler and the VM executors.
+
+use std::collections::BTreeMap;
+
+use crate::MachineState;
+
+/// Upper bound on the number of executed instructions, as a safeguard against non-terminating
+/// programs.
+const MAX_STEPS: u64 = 1 << 20;
+
+struct Emulator {
+    regs: [u32; 32],
+    memory: BTreeMap<u32, u8>,
+    pc: u32,
+}
+
+fn sign_extend(value: u32, bits: u32) -> u32 {
+    let shift = 32 - bits;
+    (((value << shift) as i32) >> shift) as u32
+}
+
+impl Emulator {
+    fn read(&self, address: u32, width: u32) -> u32 {
+        (0..width).fold(0, |acc, i| {
+            let byte = self.memory.get(&(address + i)).copied().unwrap_or(0);
+            acc | ((byte as u32) << (8 * i))
+        })
+    }
+
+    fn write(&mut self, address: u32, width: u32, value: u32) {
+        for i in 0..width {
+            self.memory.insert(address + i, (value >> (8 * i)) as u8);
+        }
+    }
+
+    fn set_reg(&mut self, rd: usize, value: u32) {
+        if rd != 0 {
+            self.regs[rd] = value;
+        }
+    }
+
+    /// Executes the instruction at `pc`. Returns `false` on `TERMINATE`.
+    fn step(&mut self, word: u32) -> bool {
+        let opcode = word & 0x7f;
+        let rd = ((word >> 7) & 0x1f) as usize;
+        let funct3 = (word >> 12) & 0x7;
+        let rs1 = ((word >> 15) & 0x1f) as usize;
+        let rs2 = ((word >> 20) & 0x1f) as usize;
+        let funct7 = word >> 25;
+        let x = self.regs[rs1];
+        let y = self.regs[rs2];
+
+        let imm_i = sign_extend(word >> 20, 12);
+        let imm_s = sign_extend(((word >> 25) << 5) | ((word >> 7) & 0x1f), 12);
+        let imm_b = sign_extend(
+            ((word >> 31) << 12)
+                | (((word >> 7) & 1) << 11)
+                | (((word >> 25) & 0x3f) << 5)
+                | (((word >> 8) & 0xf) << 1),
+            13,
+        );
+        let imm_u = word & 0xffff_f000;
+        let imm_j = sign_extend(
+            ((word >> 31) << 20)
+                | (((word >> 12) & 0xff) << 12)
+                | (((word >> 20) & 1) << 11)
+                | (((word >> 21) & 0x3ff) << 1),
+            21,
+        );
+
+        let mut next_pc = self.pc.wrapping_add(4);
+        match opcode {
+            0b0110111 => self.set_reg(rd, imm_u),
+            0b0010111 => self.set_reg(rd, self.pc.wrapping_add(imm_u)),
+            0b1101111 => {
+                self.set_reg(rd, next_pc);
+                next_pc = self.pc.wrapping_add(imm_j);
+            }
+            0b1100111 => {
+                self.set_reg(rd, next_pc);
+                next_pc = x.wrapping_add(imm_i) & !1;
+            }
+            0b1100011 => {
+                let taken = match funct3 {
+                    0b000 => x == y,
+                    0b001 => x != y,
+                    0b100 => (x as i32) < (y as i32),
+                    0b101 => (x as i32) >= (y as i32),
+                    0b110 => x < y,
+                    0b111 => x >= y,
+                    _ => panic!("invalid branch {word:#010x}"),
+                };
+                if taken {
+                    next_pc = self.pc.wrapping_add(imm_b);
+                }
+            }
+            0b0000011 => {
+                let address = x.wrapping_add(imm_i);
+                let value = match funct3 {
+                    0b000 => sign_extend(self.read(address, 1), 8),
+                    0b001 => sign_extend(self.read(address, 2), 16),
+                    0b010 => self.read(address, 4),
+                    0b100 => self.read(address, 1),
+                    0b101 => self.read(address, 2),
+                    _ => panic!("invalid load {word:#010x}"),
+                };
+                self.set_reg(rd, value);
+            }
+            0b0100011 => {
+                let address = x.wrapping_add(imm_s);
+                match funct3 {
+                    0b000..=0b010 => self.write(address, 1 << funct3, y),
+                    _ => panic!("invalid store {word:#010x}"),
+                }
+            }
+            0b0010011 => {
+                let shamt = imm_i & 0x1f;
+                let value = match funct3 {
+                    0b000 => x.wrapping_add(imm_i),
+                    0b010 => ((x as i32) < (imm_i as i32)) as u32,
+                    0b011 => (x < imm_i) as u32,
+                    0b100 => x ^ imm_i,
+                    0b110 => x | imm_i,
+                    0b111 => x & imm_i,
+                    0b001 => x << shamt,
+                    0b101 if funct7 == 0 => x >> shamt,
+                    0b101 => ((x as i32) >> shamt) as u32,
+                    _ => unreachable!(),
+                };
+                self.set_reg(rd, value);
+            }
+            0b0110011 if funct7 == 1 => {
+                let value = match funct3 {
+                    0b000 => x.wrapping_mul(y),
+                    0b001 => ((x as i32 as i64 * y as i32 as i64) >> 32) as u32,
+                    0b010 => ((x as i32 as i64 * y as i64) >> 32) as u32,
+                    0b011 => ((x as u64 * y as u64) >> 32) as u32,
+                    0b100 if y == 0 => u32::MAX,
+                    0b100 => (x as i32).wrapping_div(y as i32) as u32,
+                    0b101 => x.checked_div(y).unwrap_or(u32::MAX),
+                    0b110 if y == 0 => x,
+                    0b110 => (x as i32).wrapping_rem(y as i32) as u32,
+                    0b111 => x.checked_rem(y).unwrap_or(x),
+                    _ => unreachable!(),
+                };
+                self.set_reg(rd, value);
+            }
+            0b0110011 => {
+                let shamt = y & 0x1f;
+                let value = match (funct3, funct7) {
+                    (0b000, 0) => x.wrapping_add(y),
+                    (0b000, _) => x.wrapping_sub(y),
+                    (0b001, _) => x << shamt,
+                    (0b010, _) => ((x as i32) < (y as i32)) as u32,
+                    (0b011, _) => (x < y) as u32,
+                    (0b100, _) => x ^ y,
+                    (0b101, 0) => x >> shamt,
+                    (0b101, _) => ((x as i32) >> shamt) as u32,
+                    (0b110, _) => x | y,
+                    (0b111, _) => x & y,
+                    _ => unreachable!(),
+                };
+                self.set_reg(rd, value);
+            }
+            0b0001011 if funct3 == 0 => return false,
+            _ => panic!("unsupported instruction {word:#010x}"),
+        }
+        self.pc = next_pc;
+        true
+    }
+}
+
+/// Runs `words`, placed at `pc_base`, from `pc_base` until `TERMINATE`, and returns the final
+/// registers, the bytes of memory in `[data_start, data_start + data_len)` and the number of
+/// instructions executed, excluding `TERMINATE`.
+pub fn execute(
+    words: &[u32],
+    pc_base: u32,
+    memory_image: &BTreeMap<u32, u32>,
+    data_start: u32,
+    data_len: u32,
+) -> (MachineState, u64) {
+    let mut emulator = Emulator {
+        regs: [0; 32],
+        memory: BTreeMap::new(),
+        pc: pc_base,
+    };
+    for (&address, &word) in memory_image {
+        emulator.write(address, 4, word);
+    }
+
+    let mut num_instructions = 0;
+    loop {
+        let idx = (emulator.pc.wrapping_sub(pc_base) / 4) as usize;
+        let word = *words
+            .get(idx)
+            .unwrap_or_else(|| panic!("pc {:#x} is outside the program", emulator.pc));
+        if !emulator.step(word) {
+            break;
+        }
+        num_instructions += 1;
+        assert!(num_instructions < MAX_STEPS, "program does not terminate");
+    }
+
+    let state = MachineState {
+        regs: emulator.regs,
+        memory: (0..data_len)
+            .map(|i| emulator.read(data_start + i, 1) as u8)
+            .collect(),
+    };
+    (state, num_instructions)
+}
@@ -0,0 +1,25 @@
+use openvm_rv32im_fuzz::{execute_openvm, execute_reference, fuzz_program, FuzzProver};
+use proptest::{prelude::*, test_runner::TestRunner};
+
+proptest! {
+    #[test]
+    fn fuzz_execution(program in fuzz_program(64)) {
+        let expected = execute_reference(&program);
+        let actual = execute_openvm(&program).expect("execution should succeed");
+        prop_assert_eq!(actual, expected);
+    }
+}
+
+#[test]
+fn fuzz_proving() {
+    let prover = FuzzProver::new();
+    let mut runner = TestRunner::new(ProptestConfig::with_cases(4));
+    runner
+        .run(&fuzz_program(32), |program| {
+            let (expected, _) = execute_reference(&program);
+            let actual = prover.prove(&program).expect("proving should succeed");
+            prop_assert_eq!(actual, expected);
+            Ok(())
+        })
+        .unwrap();
+}
@@ -0,0 +1,39 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+extern crate alloc;
+use openvm::io::{read_aligned, read_archived, read_into};
+use rkyv::util::AlignedVec;
+
+openvm::entry!(main);
+
+#[derive(rkyv::Archive)]
+struct Foo {
+    bar: u32,
+    baz: alloc::vec::Vec<u32>,
+}
+
+#[allow(clippy::disallowed_names)]
+pub fn main() {
+    let mut buf = AlignedVec::<16>::with_capacity(256);
+    buf.resize(256, 0);
+
+    // The input length is not a multiple of the word size.
+    let len = read_into(&mut buf[..]);
+    if buf[..len] != *b"hello, openvm" {
+        openvm::process::panic();
+    }
+
+    let words: [u32; 4] = read_aligned();
+    if words != [1, 2, 3, 4] {
+        openvm::process::panic();
+    }
+
+    // SAFETY: the host writes an archive of `Foo`.
+    let foo = unsafe { read_archived::<ArchivedFoo>(&mut buf[..]) };
+    if foo.bar.to_native() != 42 {
+        openvm::process::panic();
+    }
+    if !foo.baz.iter().map(|x| x.to_native()).eq(0..4) {
+        openvm::process::panic();
+    }
+}
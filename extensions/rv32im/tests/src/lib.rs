@@ -1,17 +1,31 @@
 #[cfg(test)]
 mod tests {
+    use std::borrow::Borrow;
+
     use eyre::Result;
     use openvm_circuit::{
-        arch::{hasher::poseidon2::vm_poseidon2_hasher, VmExecutor},
-        system::memory::tree::public_values::UserPublicValuesProof,
+        arch::{
+            hasher::poseidon2::vm_poseidon2_hasher, ExecutionError, JournalRecorder, Streams,
+            VirtualMachine, VmExecutor, VmExecutorNextSegmentState, CONNECTOR_AIR_ID,
+        },
+        system::{
+            connector::VmConnectorPvs,
+            memory::tree::{data_segments::DataSegment, public_values::UserPublicValuesProof},
+        },
         utils::{air_test, air_test_with_min_segments},
     };
     use openvm_instructions::exe::VmExe;
-    use openvm_rv32im_circuit::{Rv32IConfig, Rv32ImConfig};
+    use openvm_rv32im_aot::AotExecutor;
+    use openvm_rv32im_circuit::{rv32_input_commitment, Rv32IConfig, Rv32ImConfig};
     use openvm_rv32im_transpiler::{
         Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
     };
-    use openvm_stark_sdk::{openvm_stark_backend::p3_field::FieldAlgebra, p3_baby_bear::BabyBear};
+    use openvm_stark_sdk::{
+        config::{baby_bear_poseidon2::BabyBearPoseidon2Engine, FriParameters},
+        engine::StarkFriEngine,
+        openvm_stark_backend::p3_field::FieldAlgebra,
+        p3_baby_bear::BabyBear,
+    };
     use openvm_toolchain_tests::{
         build_example_program_at_path, build_example_program_at_path_with_features,
         get_programs_dir,
@@ -100,7 +114,54 @@ mod tests {
                 .with_extension(Rv32IoTranspilerExtension),
         )?;
         let config = Rv32IConfig::default();
+        air_test_with_min_segments(config, exe, vec![read_input()], 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_aligned() -> Result<()> {
+        #[derive(rkyv::Archive, rkyv::Serialize)]
+        struct Foo {
+            bar: u32,
+            baz: Vec<u32>,
+        }
+
+        let elf = build_example_program_at_path_with_features(
+            get_programs_dir!(),
+            "read_aligned",
+            ["rkyv"],
+        )?;
+        let exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension),
+        )?;
+        let config = Rv32IConfig::default();
+        let foo = Foo {
+            bar: 42,
+            baz: vec![0, 1, 2, 3],
+        };
+        let archive = rkyv::to_bytes::<rkyv::rancor::Error>(&foo).unwrap();
+        let words: Vec<u8> = [1u32, 2, 3, 4]
+            .into_iter()
+            .flat_map(u32::to_le_bytes)
+            .collect();
+        let input = [b"hello, openvm".as_slice(), &words[..], &archive[..]]
+            .map(|bytes| {
+                bytes
+                    .iter()
+                    .map(|&b| F::from_canonical_u8(b))
+                    .collect::<Vec<_>>()
+            })
+            .to_vec();
+        air_test_with_min_segments(config, exe, input, 1);
+        Ok(())
+    }
 
+    /// Input expected by the `read` example.
+    fn read_input() -> Vec<F> {
         #[derive(serde::Serialize)]
         struct Foo {
             bar: u32,
@@ -111,12 +172,54 @@ mod tests {
             baz: vec![0, 1, 2, 3],
         };
         let serialized_foo = openvm::serde::to_vec(&foo).unwrap();
-        let input = serialized_foo
+        serialized_foo
             .into_iter()
             .flat_map(|w| w.to_le_bytes())
             .map(F::from_canonical_u8)
-            .collect();
-        air_test_with_min_segments(config, exe, vec![input], 1);
+            .collect()
+    }
+
+    #[test]
+    fn test_read_with_input_commitment() -> Result<()> {
+        let elf = build_example_program_at_path(get_programs_dir!(), "read")?;
+        let exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension),
+        )?;
+        let mut config = Rv32IConfig::default();
+        config.system = config
+            .system
+            .with_input_commitment()
+            .with_max_segment_len(200);
+
+        // The guest hints nothing but its input.
+        let input = read_input();
+        let input_commitment = VmExecutor::<F, _>::new(config.clone())
+            .execute_only(exe.clone(), vec![input.clone()])?
+            .input_commitment
+            .unwrap();
+        assert_eq!(input_commitment.digest, rv32_input_commitment(&[&input]));
+
+        let engine = BabyBearPoseidon2Engine::new(FriParameters::standard_fast());
+        let vm = VirtualMachine::new(engine, config);
+        let pk = vm.keygen();
+        let result = vm.execute_and_generate(exe, vec![input.clone()])?;
+        let (_, connector) = result
+            .per_segment
+            .last()
+            .unwrap()
+            .per_air
+            .iter()
+            .find(|(air_id, _)| *air_id == CONNECTOR_AIR_ID)
+            .unwrap();
+        let pvs: &VmConnectorPvs<F> = connector.raw.public_values.as_slice().borrow();
+        assert_eq!(pvs.final_input_commit, rv32_input_commitment(&[input]));
+
+        let proofs = vm.prove(&pk, result.per_segment);
+        vm.verify(&pk.get_vk(), proofs)?;
         Ok(())
     }
 
@@ -151,6 +254,66 @@ mod tests {
         Ok(())
     }
 
+    #[test_case("fibonacci")]
+    #[test_case("collatz")]
+    #[test_case("reveal")]
+    fn test_rv32im_aot(example_name: &str) -> Result<()> {
+        let elf = build_example_program_at_path(get_programs_dir!(), example_name)?;
+        let exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension),
+        )?;
+        let config = Rv32ImConfig::default();
+        let expected = VmExecutor::<F, _>::new(config.clone()).execute_only(exe.clone(), vec![])?;
+        let actual = AotExecutor::new(config, exe)?.execute(vec![])?.result;
+        assert_eq!(actual.num_instructions, expected.num_instructions);
+        assert_eq!(actual.exit_code, expected.exit_code);
+        assert_eq!(actual.public_values, expected.public_values);
+        assert_eq!(
+            actual.final_memory.items().collect::<Vec<_>>(),
+            expected.final_memory.items().collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test_case("fibonacci")]
+    #[test_case("collatz")]
+    fn test_rv32im_aot_segment_boundaries(example_name: &str) -> Result<()> {
+        let elf = build_example_program_at_path(get_programs_dir!(), example_name)?;
+        let exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension),
+        )?;
+        let mut config = Rv32ImConfig::default();
+        config.rv32i.system = config.rv32i.system.with_max_segment_len(1 << 8);
+
+        let executor = VmExecutor::<F, _>::new(config.clone());
+        let mut expected = vec![];
+        let mut state = VmExecutorNextSegmentState::new(
+            executor.initial_memory(&exe, &[])?,
+            vec![],
+            exe.pc_start,
+        );
+        while let Some(next_state) = executor
+            .execute_until_segment(exe.clone(), state)?
+            .next_state
+        {
+            expected.push(next_state.num_instructions);
+            state = next_state;
+        }
+        assert!(expected.len() > 1);
+
+        let actual = AotExecutor::new_with_segmentation(config, exe)?.execute(vec![])?;
+        assert_eq!(actual.segment_boundaries, expected);
+        Ok(())
+    }
+
     #[test]
     fn test_print() -> Result<()> {
         let elf = build_example_program_at_path(get_programs_dir!(), "print")?;
@@ -182,6 +345,114 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_hashmap_with_input_commitment() -> Result<()> {
+        // `HashMap` seeds its hasher with `HintRandom`, which the input commitment would absorb.
+        let elf =
+            build_example_program_at_path_with_features(get_programs_dir!(), "hashmap", ["std"])?;
+        let exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension),
+        )?;
+        let mut config = Rv32ImConfig::default();
+        config.rv32i.system = config.rv32i.system.with_input_commitment();
+        let result = VmExecutor::<F, _>::new(config).execute_only(exe, vec![]);
+        assert!(matches!(result, Err(ExecutionError::NonInputHint { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_replay() -> Result<()> {
+        // `HashMap` seeds its hasher with `HintRandom`.
+        let elf =
+            build_example_program_at_path_with_features(get_programs_dir!(), "hashmap", ["std"])?;
+        let transpiler = Transpiler::<F>::default()
+            .with_extension(Rv32ITranspilerExtension)
+            .with_extension(Rv32MTranspilerExtension)
+            .with_extension(Rv32IoTranspilerExtension);
+        let exe = VmExe::from_elf(elf, transpiler.clone())?;
+        let config = Rv32ImConfig::with_public_values_and_segment_len(32, 1 << 10);
+
+        let executor = VmExecutor::<F, _>::new(config.clone());
+        let (expected, journal) = executor.execute_only_and_record(exe.clone(), vec![]);
+        let expected = expected?;
+        assert!(journal
+            .phantoms
+            .iter()
+            .any(|phantom| phantom.effect.is_some()));
+        let actual = executor.replay(exe.clone(), journal)?;
+        assert_eq!(actual.num_instructions, expected.num_instructions);
+        assert_eq!(
+            actual.final_memory.items().collect::<Vec<_>>(),
+            expected.final_memory.items().collect::<Vec<_>>()
+        );
+
+        // Record with continuations, and replay the same segments.
+        let recorder = JournalRecorder::record().shared();
+        let mut executor = VmExecutor::<F, _>::new(config.clone());
+        executor.set_journal(recorder.clone());
+        let expected = executor.execute(exe.clone(), vec![])?.unwrap();
+        let journal = recorder.lock().unwrap().journal().clone();
+        assert!(!journal.segments.is_empty());
+        let mut executor = VmExecutor::<F, _>::new(config.clone());
+        executor.set_journal(JournalRecorder::replay(journal.clone()).shared());
+        let actual = executor.execute(exe, vec![])?.unwrap();
+        assert_eq!(
+            actual.items().collect::<Vec<_>>(),
+            expected.items().collect::<Vec<_>>()
+        );
+
+        // Replaying the journal of another program fails.
+        let elf = build_example_program_at_path(get_programs_dir!(), "fibonacci")?;
+        let exe = VmExe::from_elf(elf, transpiler)?;
+        let result = VmExecutor::<F, _>::new(config).replay(exe, journal);
+        assert!(matches!(
+            result,
+            Err(ExecutionError::JournalMismatch { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_replay_with_data_segments() -> Result<()> {
+        let elf = build_example_program_at_path(get_programs_dir!(), "fibonacci")?;
+        let exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension),
+        )?;
+        let config = Rv32ImConfig::with_public_values_and_segment_len(32, 1 << 10);
+        let data_segments = vec![DataSegment::new("table", (0..=255).collect())];
+        let mut input = Streams::default();
+        input.data_segments = data_segments.clone();
+
+        let executor = VmExecutor::<F, _>::new(config.clone());
+        let (expected, journal) = executor.execute_only_and_record(exe.clone(), input.clone());
+        let expected = expected?;
+        assert_eq!(journal.data_segments, data_segments);
+        // The data segments are mapped into memory on replay too.
+        let actual = executor.replay(exe.clone(), journal)?;
+        assert_eq!(
+            actual.final_memory.items().collect::<Vec<_>>(),
+            expected.final_memory.items().collect::<Vec<_>>()
+        );
+
+        // Data segments are an error without continuations.
+        let mut config = config;
+        config.rv32i.system = config.rv32i.system.without_continuations();
+        let result = VmExecutor::<F, _>::new(config).execute_only(exe, input);
+        assert!(matches!(
+            result,
+            Err(ExecutionError::DataSegmentsWithoutContinuations)
+        ));
+        Ok(())
+    }
+
     #[test]
     fn test_tiny_mem_test() -> Result<()> {
         let elf = build_example_program_at_path_with_features(
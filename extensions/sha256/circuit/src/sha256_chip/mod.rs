@@ -175,15 +175,17 @@ impl<F: PrimeField32> InstructionExecutor<F> for Sha256VmChip<F> {
             digest.map(|b| F::from_canonical_u8(b)),
         );
 
-        self.records.push(Sha256Record {
-            from_state: from_state.map(F::from_canonical_u32),
-            dst_read,
-            src_read,
-            len_read,
-            input_records,
-            input_message,
-            digest_write,
-        });
+        if !memory.is_execute_only() {
+            self.records.push(Sha256Record {
+                from_state: from_state.map(F::from_canonical_u32),
+                dst_read,
+                src_read,
+                len_read,
+                input_records,
+                input_message,
+                digest_write,
+            });
+        }
 
         Ok(ExecutionState {
             pc: from_state.pc + DEFAULT_PC_STEP,